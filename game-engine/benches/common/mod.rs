@@ -0,0 +1,81 @@
+//! Positions shared by the MCTS benchmarks.
+
+use meeple_game_engine::engine::models::{Action, GameConfig, Player};
+use meeple_game_engine::engine::plugin::TypedGamePlugin;
+use meeple_game_engine::engine::simulator::{apply_action_and_resolve, SimulationState};
+use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
+use meeple_game_engine::games::carcassonne::types::CarcassonneState;
+
+pub fn make_players() -> Vec<Player> {
+    (0..2)
+        .map(|i| Player {
+            player_id: format!("p{}", i + 1),
+            display_name: format!("Player {}", i + 1),
+            seat_index: i,
+            is_bot: false,
+            bot_id: None,
+        })
+        .collect()
+}
+
+/// The first `place_tile` position with at least 40 valid actions in a seeded
+/// random game; same search as `test_mcts_tree_stats_comparison`.
+pub fn mid_game_state(plugin: &CarcassonnePlugin, players: &[Player]) -> SimulationState<CarcassonneState> {
+    let mut best_sim = None;
+    let mut best_count = 0;
+
+    for seed in 40..100 {
+        let config = GameConfig { random_seed: Some(seed), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(players, &config);
+        let mut sim = SimulationState {
+            state,
+            phase,
+            players: players.into(),
+            scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
+            game_over: None,
+            history: None,
+        };
+
+        let mut rng = seed * 31337;
+        for _ in 0..200 {
+            if sim.game_over.is_some() {
+                break;
+            }
+            while sim.phase.auto_resolve && sim.game_over.is_none() {
+                let action_type = sim.phase.name.clone();
+                let action = Action { action_type, player_id: "system".into(), payload: serde_json::json!({}) };
+                apply_action_and_resolve(plugin, &mut sim, &action).unwrap();
+            }
+            if sim.game_over.is_some() {
+                break;
+            }
+
+            let acting_pid = sim.phase.expected_actions[0].player_id.clone();
+            let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &acting_pid);
+            if valid.is_empty() {
+                break;
+            }
+            if sim.phase.name == "place_tile" && valid.len() > best_count {
+                best_count = valid.len();
+                best_sim = Some(sim.clone());
+                if best_count >= 40 {
+                    break;
+                }
+            }
+
+            rng = rng.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let idx = (rng >> 33) as usize % valid.len();
+            let action = Action {
+                action_type: sim.phase.expected_actions[0].action_type.clone(),
+                player_id: acting_pid,
+                payload: valid[idx].clone(),
+            };
+            apply_action_and_resolve(plugin, &mut sim, &action).unwrap();
+        }
+        if best_count >= 40 {
+            break;
+        }
+    }
+
+    best_sim.expect("a state with many valid actions")
+}
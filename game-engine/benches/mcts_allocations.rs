@@ -0,0 +1,89 @@
+//! Criterion benchmark: allocations behind an MCTS iteration on the
+//! high-branching mid-game Carcassonne state from `common::mid_game_state`.
+//!
+//! Run with:
+//!     cargo bench --bench mcts_allocations
+//!
+//! Every iteration starts from a clone of the root `SimulationState`. Players
+//! are shared (`Arc<[Player]>`), so that clone no longer copies the player
+//! list; `player_vec` times the old layout next to it. Allocation counts per
+//! clone and per search iteration are printed before the timings.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use meeple_game_engine::engine::mcts::{mcts_search, MctsParams};
+use meeple_game_engine::engine::models::{Phase, Player};
+use meeple_game_engine::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
+use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
+use meeple_game_engine::games::carcassonne::types::CarcassonneState;
+
+use common::{make_players, mid_game_state};
+
+const NUM_SIMULATIONS: usize = 200;
+
+/// The system allocator, counting every allocation.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations_during<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn bench_allocations(c: &mut Criterion) {
+    let plugin = CarcassonnePlugin;
+    let players = make_players();
+    let sim = mid_game_state(&plugin, &players);
+    let acting_pid = sim.phase.expected_actions[0].player_id.clone();
+    let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
+    let eval_ref: Option<&(dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Sync)> =
+        Some(eval_fn.as_ref());
+    let params = MctsParams {
+        num_simulations: NUM_SIMULATIONS,
+        time_limit_ms: 999999.0,
+        num_determinizations: 1,
+        ..Default::default()
+    };
+
+    let shared = allocations_during(|| sim.clone());
+    let player_vec = allocations_during(|| (sim.clone(), sim.players.to_vec()));
+    let search = allocations_during(|| mcts_search(&sim.state, &sim.phase, &acting_pid, &plugin, &players, &params, eval_ref));
+    println!("allocations per root clone: {shared} shared players, {player_vec} with a player Vec");
+    println!("allocations per search iteration: {:.0}", search as f64 / NUM_SIMULATIONS as f64);
+
+    let mut group = c.benchmark_group("simulation_state_clone");
+    group.bench_function(BenchmarkId::new("clone", "shared_players"), |b| b.iter(|| sim.clone()));
+    group.bench_function(BenchmarkId::new("clone", "player_vec"), |b| {
+        b.iter(|| (sim.clone(), sim.players.to_vec()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_allocations);
+criterion_main!(benches);
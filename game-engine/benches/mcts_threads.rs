@@ -0,0 +1,51 @@
+//! Criterion benchmark: MCTS throughput with one tree searched by several
+//! threads (`threads_per_tree`) versus the default one tree per determinization.
+//!
+//! Run with:
+//!     cargo bench --bench mcts_threads
+//!
+//! Uses the high-branching mid-game Carcassonne state from `common::mid_game_state`.
+
+mod common;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use meeple_game_engine::engine::mcts::{mcts_search, MctsParams};
+use meeple_game_engine::engine::models::{Phase, Player};
+use meeple_game_engine::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
+use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
+use meeple_game_engine::games::carcassonne::types::CarcassonneState;
+
+use common::{make_players, mid_game_state};
+
+const NUM_SIMULATIONS: usize = 500;
+
+fn bench_threads_per_tree(c: &mut Criterion) {
+    let plugin = CarcassonnePlugin;
+    let players = make_players();
+    let sim = mid_game_state(&plugin, &players);
+    let acting_pid = sim.phase.expected_actions[0].player_id.clone();
+    let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
+    let eval_ref: Option<&(dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Sync)> =
+        Some(eval_fn.as_ref());
+
+    let default = MctsParams::default();
+    let configs = [
+        ("default", default.clone()),
+        ("1_det_8_threads", MctsParams { num_determinizations: 1, threads_per_tree: 8, ..default.clone() }),
+    ];
+
+    let mut group = c.benchmark_group("mcts_threads_per_tree");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(NUM_SIMULATIONS as u64));
+    for (label, params) in configs {
+        let params = MctsParams { num_simulations: NUM_SIMULATIONS, time_limit_ms: 999999.0, ..params };
+        group.bench_with_input(BenchmarkId::new("search", label), &params, |b, params| {
+            b.iter(|| mcts_search(&sim.state, &sim.phase, &acting_pid, &plugin, &players, params, eval_ref));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_threads_per_tree);
+criterion_main!(benches);
@@ -11,11 +11,12 @@ use std::sync::Arc;
 
 use clap::Parser;
 
-use meeple_game_engine::engine::arena::run_arena;
+use meeple_game_engine::engine::arena::{run_arena, run_arena_parallel, DEFAULT_MAX_MOVES_PER_GAME};
 use meeple_game_engine::engine::bot_profiles::{load_default_profiles, load_profiles, BotProfilesFile};
 use meeple_game_engine::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
-use meeple_game_engine::engine::mcts::MctsParams;
+use meeple_game_engine::engine::mcts::{FpuMode, MctsParams};
 use meeple_game_engine::engine::models::{Phase, Player};
+use meeple_game_engine::engine::plugin::TypedGamePlugin;
 use meeple_game_engine::games::carcassonne::evaluator::*;
 use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
 use meeple_game_engine::games::carcassonne::types::CarcassonneState;
@@ -39,6 +40,18 @@ struct Cli {
     #[arg(long)]
     profiles: Option<PathBuf>,
 
+    /// Play games in parallel (same results as sequential for the same seed)
+    #[arg(long)]
+    parallel: bool,
+
+    /// With --parallel, at most this many games at once (0 = one per CPU)
+    #[arg(long, default_value_t = 0)]
+    parallel_games: usize,
+
+    /// Write each game's move log (replayable JSON) into this directory (not with --parallel)
+    #[arg(long)]
+    save_logs: Option<PathBuf>,
+
     // --- Player 1 ---
     /// P1 display name
     #[arg(long, default_value = "p1")]
@@ -96,6 +109,10 @@ struct Cli {
     #[arg(long)]
     p1_rave_fpu: bool,
 
+    /// P1 RAVE FPU mode: infinity, amaf_optimistic or parent_value (overrides --p1-rave-fpu)
+    #[arg(long)]
+    p1_fpu_mode: Option<FpuMode>,
+
     /// P1 enable tile-aware AMAF
     #[arg(long)]
     p1_tile_aware_amaf: bool,
@@ -157,6 +174,10 @@ struct Cli {
     #[arg(long)]
     p2_rave_fpu: bool,
 
+    /// P2 RAVE FPU mode: infinity, amaf_optimistic or parent_value (overrides --p2-rave-fpu)
+    #[arg(long)]
+    p2_fpu_mode: Option<FpuMode>,
+
     /// P2 enable tile-aware AMAF
     #[arg(long)]
     p2_tile_aware_amaf: bool,
@@ -167,25 +188,23 @@ fn resolve_eval(
     custom_weights: Option<&EvalWeights>,
 ) -> Option<Box<dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Send + Sync>> {
     if let Some(w) = custom_weights {
-        return Some(make_carcassonne_eval_owned(*w));
+        return Some(make_carcassonne_eval(*w));
     }
-    match eval_profile {
-        "aggressive" => Some(make_carcassonne_eval(&AGGRESSIVE_WEIGHTS)),
-        "field_heavy" => Some(make_carcassonne_eval(&FIELD_HEAVY_WEIGHTS)),
-        "conservative" => Some(make_carcassonne_eval(&CONSERVATIVE_WEIGHTS)),
-        "default" => Some(make_carcassonne_eval(&DEFAULT_WEIGHTS)),
-        "" => None,
-        other => {
-            eprintln!("Warning: unknown eval profile '{}', using default", other);
-            Some(make_carcassonne_eval(&DEFAULT_WEIGHTS))
-        }
+    if eval_profile.is_empty() {
+        return None;
     }
+    CarcassonnePlugin.make_eval(eval_profile).or_else(|| {
+        eprintln!("Warning: unknown eval profile '{}', using default", eval_profile);
+        Some(make_carcassonne_eval(DEFAULT_WEIGHTS))
+    })
 }
 
 struct PlayerConfig {
     name: String,
     strategy_type: String,
     params: MctsParams,
+    /// Per-phase params from the profile; CLI overrides only change `params`.
+    phase_params: HashMap<String, MctsParams>,
     eval_profile: String,
     custom_weights: Option<EvalWeights>,
 }
@@ -204,7 +223,7 @@ fn build_player_config(
     rave: bool,
     rave_k: Option<f64>,
     max_amaf_depth: Option<usize>,
-    rave_fpu: bool,
+    fpu_mode: Option<FpuMode>,
     tile_aware_amaf: bool,
     profiles: &BotProfilesFile,
 ) -> PlayerConfig {
@@ -231,7 +250,7 @@ fn build_player_config(
         if rave { params.use_rave = true; }
         if let Some(v) = rave_k { params.rave_k = v; }
         if let Some(v) = max_amaf_depth { params.max_amaf_depth = v; }
-        if rave_fpu { params.rave_fpu = true; }
+        if let Some(v) = fpu_mode { params.fpu_mode = v; }
         if tile_aware_amaf { params.tile_aware_amaf = true; }
 
         let display_name = if name == "p1" || name == "p2" {
@@ -244,6 +263,7 @@ fn build_player_config(
             name: display_name,
             strategy_type: profile.strategy_type.clone(),
             params,
+            phase_params: profile.phase_mcts_params(),
             eval_profile,
             custom_weights,
         };
@@ -261,14 +281,16 @@ fn build_player_config(
         use_rave: rave,
         rave_k: rave_k.unwrap_or(d.rave_k),
         max_amaf_depth: max_amaf_depth.unwrap_or(d.max_amaf_depth),
-        rave_fpu,
+        fpu_mode: fpu_mode.unwrap_or(FpuMode::Infinity),
         tile_aware_amaf,
+        ..d
     };
 
     PlayerConfig {
         name: name.to_string(),
         strategy_type: strategy_type.to_string(),
         params,
+        phase_params: HashMap::new(),
         eval_profile: eval.unwrap_or("default").to_string(),
         custom_weights: None,
     }
@@ -281,13 +303,11 @@ fn build_strategy(
         "random" => Box::new(RandomStrategy),
         "mcts" | _ => {
             let eval_fn = resolve_eval(&config.eval_profile, config.custom_weights.as_ref());
-            match eval_fn {
-                Some(f) => Box::new(MctsStrategy::<CarcassonnePlugin>::with_eval(
-                    config.params.clone(),
-                    f,
-                )),
-                None => Box::new(MctsStrategy::<CarcassonnePlugin>::new(config.params.clone())),
-            }
+            let strategy = match eval_fn {
+                Some(f) => MctsStrategy::<CarcassonnePlugin>::with_eval(config.params.clone(), f),
+                None => MctsStrategy::<CarcassonnePlugin>::new(config.params.clone()),
+            };
+            Box::new(strategy.with_phase_params(config.phase_params.clone()))
         }
     }
 }
@@ -306,10 +326,10 @@ fn print_config(label: &str, config: &PlayerConfig) {
         if config.custom_weights.is_some() { " [custom weights]" } else { "" },
     );
     if config.params.use_rave {
-        eprintln!("         rave_k={}, max_amaf_depth={}, rave_fpu={}, tile_aware_amaf={}",
+        eprintln!("         rave_k={}, max_amaf_depth={}, fpu_mode={:?}, tile_aware_amaf={}",
             config.params.rave_k,
             config.params.max_amaf_depth,
-            config.params.rave_fpu,
+            config.params.fpu_mode,
             config.params.tile_aware_amaf,
         );
     }
@@ -333,7 +353,8 @@ fn main() {
         cli.p1_sims, cli.p1_time, cli.p1_dets, cli.p1_eval.as_deref(),
         cli.p1_exploration, cli.p1_pw_c, cli.p1_pw_alpha,
         cli.p1_rave, cli.p1_rave_k, cli.p1_max_amaf_depth,
-        cli.p1_rave_fpu, cli.p1_tile_aware_amaf,
+        cli.p1_fpu_mode.or(cli.p1_rave_fpu.then_some(FpuMode::AmafOptimistic)),
+        cli.p1_tile_aware_amaf,
         &profiles,
     );
 
@@ -342,7 +363,8 @@ fn main() {
         cli.p2_sims, cli.p2_time, cli.p2_dets, cli.p2_eval.as_deref(),
         cli.p2_exploration, cli.p2_pw_c, cli.p2_pw_alpha,
         cli.p2_rave, cli.p2_rave_k, cli.p2_max_amaf_depth,
-        cli.p2_rave_fpu, cli.p2_tile_aware_amaf,
+        cli.p2_fpu_mode.or(cli.p2_rave_fpu.then_some(FpuMode::AmafOptimistic)),
+        cli.p2_tile_aware_amaf,
         &profiles,
     );
 
@@ -368,17 +390,52 @@ fn main() {
     };
 
     let plugin = CarcassonnePlugin;
-    let result = run_arena(
-        &plugin,
-        &strategies,
-        cli.games,
-        cli.seed,
-        2,
-        None,
-        cli.alternate_seats,
-        Some(&progress_cb),
-    );
+    let result = if cli.parallel {
+        run_arena_parallel(
+            &plugin,
+            &strategies,
+            cli.games,
+            cli.seed,
+            2,
+            None,
+            cli.alternate_seats,
+            cli.save_logs.is_some(),
+            DEFAULT_MAX_MOVES_PER_GAME,
+            cli.parallel_games,
+            Some(&progress_cb),
+        )
+    } else {
+        run_arena(
+            &plugin,
+            &strategies,
+            cli.games,
+            cli.seed,
+            2,
+            None,
+            cli.alternate_seats,
+            cli.save_logs.is_some(),
+            DEFAULT_MAX_MOVES_PER_GAME,
+            Some(&progress_cb),
+        )
+    };
 
     eprintln!("\r                                    "); // clear progress line
     println!("{}", result.summary());
+
+    if let Some(dir) = &cli.save_logs {
+        let written = std::fs::create_dir_all(dir).and_then(|()| {
+            for (i, log) in result.game_logs.iter().enumerate() {
+                let json = serde_json::to_string_pretty(&log.to_json()).unwrap_or_default();
+                std::fs::write(dir.join(format!("game_{:04}.json", i)), json)?;
+            }
+            Ok(())
+        });
+        match written {
+            Ok(()) => eprintln!("Wrote {} game logs to {}", result.game_logs.len(), dir.display()),
+            Err(e) => {
+                eprintln!("Error writing game logs to {}: {}", dir.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -52,7 +52,7 @@ fn resolve_auto(
             player_id: pid,
             payload: serde_json::json!({}),
         };
-        apply_action_and_resolve(plugin, sim, &synthetic);
+        apply_action_and_resolve(plugin, sim, &synthetic).unwrap();
     }
 }
 
@@ -75,9 +75,10 @@ fn main() {
         let mut sim = SimulationState {
             state,
             phase,
-            players: players.clone(),
+            players: players.clone().into(),
             scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
             game_over: None,
+            history: None,
         };
 
         // Resolve initial auto phases (draws first tile)
@@ -156,7 +157,7 @@ fn main() {
                 player_id: acting_pid,
                 payload: valid[0].clone(),
             };
-            apply_action_and_resolve(&plugin, &mut sim, &action);
+            apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
         }
 
         // Report any checkpoints we couldn't reach
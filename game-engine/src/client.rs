@@ -0,0 +1,326 @@
+//! In-process client for `GameEngineServer`.
+//!
+//! Calls the `GameEngineService` methods directly (no socket, no tonic transport)
+//! and converts between engine models and protobuf messages, so tests and tools
+//! can drive the server with the same types the plugins use.
+
+use tonic::{Request, Status};
+
+use crate::engine::models;
+use crate::server::proto::game_engine_service_server::GameEngineService;
+use crate::server::proto::*;
+use crate::server::{phase_to_proto, proto_to_phase, GameEngineServer};
+
+/// `Status` boxed, since it is too large to return by value on every call.
+pub type ClientResult<T> = Result<T, Box<Status>>;
+
+/// Wraps a `GameEngineServer` and exposes its RPCs with engine types.
+pub struct EngineClient {
+    server: GameEngineServer,
+}
+
+impl EngineClient {
+    pub fn new(server: GameEngineServer) -> Self {
+        Self { server }
+    }
+
+    pub async fn create_initial_state(
+        &self,
+        game_id: &str,
+        players: &[models::Player],
+        config: &models::GameConfig,
+    ) -> ClientResult<(serde_json::Value, models::Phase, Vec<models::Event>)> {
+        let resp = self
+            .server
+            .create_initial_state(Request::new(CreateInitialStateRequest {
+                game_id: game_id.into(),
+                players: players_to_proto(players),
+                config: Some(config_to_proto(config)),
+                game_data_format: GameDataFormat::Json.into(),
+            }))
+            .await?
+            .into_inner();
+        Ok((
+            json_from_bytes(&resp.game_data_json)?,
+            resp.phase.as_ref().map(proto_to_phase).ok_or_else(missing("phase"))?,
+            resp.events.iter().map(proto_to_event).collect(),
+        ))
+    }
+
+    pub async fn get_valid_actions(
+        &self,
+        game_id: &str,
+        game_data: &serde_json::Value,
+        phase: &models::Phase,
+        player_id: &str,
+    ) -> ClientResult<Vec<serde_json::Value>> {
+        let resp = self
+            .server
+            .get_valid_actions(Request::new(GetValidActionsRequest {
+                game_id: game_id.into(),
+                game_data_json: json_to_bytes(game_data),
+                phase: Some(phase_to_proto(phase)),
+                player_id: player_id.into(),
+                include_details: false,
+                game_data_format: GameDataFormat::Json.into(),
+            }))
+            .await?
+            .into_inner();
+        resp.actions_json.iter().map(|a| json_from_bytes(a)).collect()
+    }
+
+    pub async fn apply_action(
+        &self,
+        game_id: &str,
+        game_data: &serde_json::Value,
+        phase: &models::Phase,
+        action: &models::Action,
+        players: &[models::Player],
+    ) -> ClientResult<models::TransitionResult> {
+        let resp = self
+            .server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: game_id.into(),
+                game_data_json: json_to_bytes(game_data),
+                phase: Some(phase_to_proto(phase)),
+                action: Some(action_to_proto(action)),
+                players: players_to_proto(players),
+                game_data_format: GameDataFormat::Json.into(),
+            }))
+            .await?
+            .into_inner();
+        let result = resp.result.ok_or_else(missing("result"))?;
+        proto_to_transition(&result)
+    }
+
+    /// Run MctsSearch for `player_id`. Search settings (simulations, eval profile,
+    /// bot profile, ...) are taken from `settings`; the game fields are filled in here.
+    pub async fn mcts_search(
+        &self,
+        game_id: &str,
+        game_data: &serde_json::Value,
+        phase: &models::Phase,
+        player_id: &str,
+        players: &[models::Player],
+        settings: MctsSearchRequest,
+    ) -> ClientResult<serde_json::Value> {
+        let resp = self
+            .server
+            .mcts_search(Request::new(MctsSearchRequest {
+                game_id: game_id.into(),
+                game_data_json: json_to_bytes(game_data),
+                phase: Some(phase_to_proto(phase)),
+                player_id: player_id.into(),
+                players: players_to_proto(players),
+                game_data_format: GameDataFormat::Json.into(),
+                ..settings
+            }))
+            .await?
+            .into_inner();
+        json_from_bytes(&resp.action_json)
+    }
+}
+
+// --- Conversion helpers: engine types -> protobuf and back ---
+
+fn missing(field: &'static str) -> impl FnOnce() -> Status {
+    move || Status::internal(format!("server response is missing `{}`", field))
+}
+
+fn json_from_bytes(bytes: &[u8]) -> ClientResult<serde_json::Value> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| Status::internal(format!("invalid JSON in server response: {}", e)).into())
+}
+
+fn json_to_bytes(value: &serde_json::Value) -> Vec<u8> {
+    serde_json::to_vec(value).unwrap_or_default()
+}
+
+fn players_to_proto(players: &[models::Player]) -> Vec<Player> {
+    players
+        .iter()
+        .map(|p| Player {
+            player_id: p.player_id.clone(),
+            display_name: p.display_name.clone(),
+            seat_index: p.seat_index,
+            is_bot: p.is_bot,
+            bot_id: p.bot_id.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn config_to_proto(config: &models::GameConfig) -> GameConfig {
+    // Option values travel as JSON strings; the server parses them back.
+    let options = config
+        .options
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .map(|(k, v)| (k.clone(), serde_json::to_string(v).unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default();
+    GameConfig {
+        options,
+        random_seed: config.random_seed.map(|s| s as i64),
+        ..Default::default()
+    }
+}
+
+fn action_to_proto(action: &models::Action) -> Action {
+    Action {
+        action_type: action.action_type.clone(),
+        player_id: action.player_id.clone(),
+        payload_json: json_to_bytes(&action.payload),
+    }
+}
+
+fn proto_to_event(event: &Event) -> models::Event {
+    models::Event {
+        event_type: event.event_type.clone(),
+        player_id: event.player_id.clone(),
+        payload: serde_json::from_slice(&event.payload_json).unwrap_or(serde_json::json!({})),
+    }
+}
+
+fn proto_to_transition(tr: &TransitionResult) -> ClientResult<models::TransitionResult> {
+    Ok(models::TransitionResult {
+        game_data: json_from_bytes(&tr.game_data_json)?,
+        events: tr.events.iter().map(proto_to_event).collect(),
+        next_phase: tr.next_phase.as_ref().map(proto_to_phase).ok_or_else(missing("next_phase"))?,
+        scores: tr.scores.clone(),
+        game_over: tr.game_over.as_ref().map(|gr| models::GameResult {
+            winners: gr.winners.clone(),
+            final_scores: gr.final_scores.clone(),
+            reason: gr.reason.clone(),
+            details: Default::default(),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::JsonAdapter;
+    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+    use crate::games::GameRegistry;
+
+    fn make_client() -> EngineClient {
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+        EngineClient::new(GameEngineServer::new(registry))
+    }
+
+    fn make_players() -> Vec<models::Player> {
+        (0..2)
+            .map(|i| models::Player {
+                player_id: format!("p{}", i),
+                display_name: format!("Player {}", i),
+                seat_index: i,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    fn action(phase: &models::Phase, player_id: &str, payload: serde_json::Value) -> models::Action {
+        models::Action {
+            action_type: phase.name.clone(),
+            player_id: player_id.into(),
+            payload,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_carcassonne_turn() {
+        let client = make_client();
+        let players = make_players();
+        let config = models::GameConfig {
+            options: serde_json::json!({"tile_count": 10}),
+            random_seed: Some(42),
+        };
+
+        let (game_data, phase, _) =
+            client.create_initial_state("carcassonne", &players, &config).await.unwrap();
+        assert_eq!(phase.name, "draw_tile");
+        assert_eq!(game_data["tile_bag"].as_array().unwrap().len(), 10);
+
+        let drawn = client
+            .apply_action("carcassonne", &game_data, &phase, &action(&phase, "p0", serde_json::json!({})), &players)
+            .await
+            .unwrap();
+        assert_eq!(drawn.next_phase.name, "place_tile");
+
+        let placements = client
+            .get_valid_actions("carcassonne", &drawn.game_data, &drawn.next_phase, "p0")
+            .await
+            .unwrap();
+        assert!(!placements.is_empty());
+        let placed = client
+            .apply_action(
+                "carcassonne",
+                &drawn.game_data,
+                &drawn.next_phase,
+                &action(&drawn.next_phase, "p0", placements[0].clone()),
+                &players,
+            )
+            .await
+            .unwrap();
+        assert_eq!(placed.next_phase.name, "place_meeple");
+        assert!(placed.events.iter().any(|e| e.event_type == "tile_placed"));
+
+        let settings = MctsSearchRequest {
+            num_simulations: 20,
+            num_determinizations: 1,
+            ..Default::default()
+        };
+        let meeple = client
+            .mcts_search("carcassonne", &placed.game_data, &placed.next_phase, "p0", &players, settings)
+            .await
+            .unwrap();
+        let valid = client
+            .get_valid_actions("carcassonne", &placed.game_data, &placed.next_phase, "p0")
+            .await
+            .unwrap();
+        assert!(valid.contains(&meeple));
+
+        let claimed = client
+            .apply_action(
+                "carcassonne",
+                &placed.game_data,
+                &placed.next_phase,
+                &action(&placed.next_phase, "p0", meeple),
+                &players,
+            )
+            .await
+            .unwrap();
+        assert_eq!(claimed.next_phase.name, "score_check");
+
+        let scored = client
+            .apply_action(
+                "carcassonne",
+                &claimed.game_data,
+                &claimed.next_phase,
+                &action(&claimed.next_phase, "p0", serde_json::json!({})),
+                &players,
+            )
+            .await
+            .unwrap();
+        assert_eq!(scored.next_phase.name, "draw_tile");
+        assert_eq!(scored.next_phase.metadata["player_index"], 1);
+        assert!(scored.game_over.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_passed_through() {
+        let client = make_client();
+        let err = client
+            .create_initial_state("no_such_game", &make_players(), &models::GameConfig {
+                options: serde_json::json!({}),
+                random_seed: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+}
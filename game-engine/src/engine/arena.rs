@@ -2,12 +2,28 @@
 //! Mirrors backend/src/engine/arena.py.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
+use rayon::prelude::*;
+
 use crate::engine::bot_strategy::BotStrategy;
 use crate::engine::models::*;
-use crate::engine::plugin::TypedGamePlugin;
-use crate::engine::simulator::{apply_action_and_resolve, SimulationState};
+use crate::engine::plugin::{metadata_player, TypedGamePlugin};
+use crate::engine::replay::GameLog;
+use crate::engine::rng::GameRng;
+use crate::engine::simulator::{
+    apply_action_and_resolve, apply_action_and_resolve_with_events, SimulationState,
+};
+
+/// Default `max_moves_per_game`: far more bot decisions than any real game needs.
+pub const DEFAULT_MAX_MOVES_PER_GAME: usize = 2000;
+
+/// `GameResult::reason` of games adjudicated on score at the move limit.
+pub const MOVE_LIMIT_REASON: &str = "move_limit";
+
+/// Largest Elo difference `ArenaResult::elo_ratings` reports (a 99% score is ~800).
+pub const MAX_ELO_DIFF: f64 = 800.0;
 
 /// Aggregated results from an arena run.
 pub struct ArenaResult {
@@ -16,6 +32,25 @@ pub struct ArenaResult {
     pub draws: usize,
     pub total_scores: HashMap<String, Vec<f64>>,
     pub game_durations_ms: Vec<f64>,
+    /// Per strategy, games played from each seat (index = seat).
+    pub seat_games: HashMap<String, Vec<usize>>,
+    /// Per strategy, wins from each seat. A large gap between seats points at a
+    /// seat-bias bug rather than playing strength.
+    pub seat_wins: HashMap<String, Vec<usize>>,
+    /// Per strategy, total wall time spent in `choose_action` across all games.
+    pub think_time_ms: HashMap<String, f64>,
+    /// Per strategy, number of `choose_action` calls across all games.
+    pub moves: HashMap<String, usize>,
+    /// Per strategy, for each game it won outright: its final score minus the
+    /// best opposing score. Negative only in games not won on points.
+    pub win_margins: HashMap<String, Vec<f64>>,
+    /// Outcome of the SPRT, for runs made with `run_arena_sprt`.
+    pub sprt_verdict: Option<SprtVerdict>,
+    /// Move log of each game, in game order, when the run recorded moves.
+    pub game_logs: Vec<GameLog>,
+    /// Games stopped at the move limit and decided on score (also counted in
+    /// `wins` / `draws`). Nonzero usually means a plugin that never ends.
+    pub adjudicated: usize,
 }
 
 impl ArenaResult {
@@ -23,6 +58,47 @@ impl ArenaResult {
         *self.wins.get(name).unwrap_or(&0) as f64 / self.num_games.max(1) as f64
     }
 
+    /// Win rate of `name` in each seat it played (0.0 for seats it never held).
+    pub fn seat_win_rates(&self, name: &str) -> Vec<f64> {
+        match (self.seat_wins.get(name), self.seat_games.get(name)) {
+            (Some(wins), Some(games)) => wins
+                .iter()
+                .zip(games)
+                .map(|(&w, &g)| w as f64 / g.max(1) as f64)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mean time `name` spent choosing a move, in ms.
+    pub fn avg_think_ms(&self, name: &str) -> f64 {
+        let moves = self.moves.get(name).copied().unwrap_or(0);
+        self.think_time_ms.get(name).copied().unwrap_or(0.0) / moves.max(1) as f64
+    }
+
+    /// Mean winning margin of `name` over the games it won (0.0 if none).
+    pub fn avg_win_margin(&self, name: &str) -> f64 {
+        match self.win_margins.get(name) {
+            Some(m) if !m.is_empty() => m.iter().sum::<f64>() / m.len() as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// Median winning margin of `name` over the games it won (0.0 if none).
+    pub fn median_win_margin(&self, name: &str) -> f64 {
+        let mut margins = match self.win_margins.get(name) {
+            Some(m) if !m.is_empty() => m.clone(),
+            _ => return 0.0,
+        };
+        margins.sort_by(f64::total_cmp);
+        let mid = margins.len() / 2;
+        if margins.len() % 2 == 0 {
+            (margins[mid - 1] + margins[mid]) / 2.0
+        } else {
+            margins[mid]
+        }
+    }
+
     pub fn avg_score(&self, name: &str) -> f64 {
         let scores = self.total_scores.get(name);
         match scores {
@@ -54,6 +130,27 @@ impl ArenaResult {
         ((center - margin).max(0.0), (center + margin).min(1.0))
     }
 
+    /// Elo of each strategy relative to `anchor` (rated 0), or an empty map if
+    /// `anchor` is not in the arena. Fits a Bradley-Terry (Luce) model to who won
+    /// each game; draws split a game's point evenly. Every strategy plays every
+    /// game, so the MM fit converges in one step: strengths are proportional to
+    /// points. Differences are capped at `MAX_ELO_DIFF`, e.g. for a 100% record.
+    pub fn elo_ratings(&self, anchor: &str) -> HashMap<String, f64> {
+        let num_players = self.wins.len().max(1) as f64;
+        let points = |name: &str| self.wins.get(name).map(|&w| w as f64 + self.draws as f64 / num_players);
+        let Some(anchor_points) = points(anchor) else {
+            return HashMap::new();
+        };
+        self.wins
+            .keys()
+            .map(|name| {
+                let diff = 400.0 * (points(name).unwrap() / anchor_points).log10();
+                let diff = if diff.is_nan() { 0.0 } else { diff.clamp(-MAX_ELO_DIFF, MAX_ELO_DIFF) };
+                (name.clone(), diff)
+            })
+            .collect()
+    }
+
     #[allow(dead_code)]
     pub fn summary(&self) -> String {
         let mut lines = vec![format!("Arena Results ({} games)", self.num_games)];
@@ -73,8 +170,32 @@ impl ArenaResult {
                 avg,
                 std,
             ));
+            let by_seat: Vec<String> = self
+                .seat_win_rates(name)
+                .iter()
+                .zip(&self.seat_games[name])
+                .enumerate()
+                .map(|(seat, (wr, games))| format!("seat {}: {:5.1}% ({})", seat, wr * 100.0, games))
+                .collect();
+            lines.push(format!("  {:>12}  {}", "", by_seat.join("  ")));
+            lines.push(format!(
+                "  {:>12}  think: {:.1}s total, {:.1}ms/move ({} moves)",
+                "",
+                self.think_time_ms[name] / 1000.0,
+                self.avg_think_ms(name),
+                self.moves[name],
+            ));
+            lines.push(format!(
+                "  {:>12}  win margin: mean {:.1}, median {:.1}",
+                "",
+                self.avg_win_margin(name),
+                self.median_win_margin(name),
+            ));
         }
         lines.push(format!("  {:>12}: {}", "Draws", self.draws));
+        if self.adjudicated > 0 {
+            lines.push(format!("  {:>12}: {} (hit the move limit)", "Adjudicated", self.adjudicated));
+        }
         if !self.game_durations_ms.is_empty() {
             let avg_ms = self.game_durations_ms.iter().sum::<f64>() / self.game_durations_ms.len() as f64;
             let total_s = self.game_durations_ms.iter().sum::<f64>() / 1000.0;
@@ -84,7 +205,19 @@ impl ArenaResult {
     }
 }
 
+/// One applied action and the events it produced, including those of the
+/// auto-resolve phases that followed it.
+pub struct TranscriptEntry {
+    pub action: Action,
+    pub events: Vec<Event>,
+}
+
 /// Run `num_games` between the given typed strategies and return aggregated stats.
+/// Seeds follow the contract on `game_seed`, so the result matches `run_arena_parallel`.
+/// With `record_moves`, each game's moves are kept in `ArenaResult::game_logs`.
+/// A game still running after `max_moves_per_game` bot decisions is adjudicated:
+/// the highest score wins, a tie is a draw.
+#[allow(clippy::too_many_arguments)]
 pub fn run_arena<P: TypedGamePlugin>(
     plugin: &P,
     strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
@@ -93,95 +226,527 @@ pub fn run_arena<P: TypedGamePlugin>(
     num_players: usize,
     game_options: Option<serde_json::Value>,
     alternate_seats: bool,
+    record_moves: bool,
+    max_moves_per_game: usize,
+    progress_callback: Option<&dyn Fn(usize, usize)>,
+) -> ArenaResult {
+    run_arena_until(
+        plugin,
+        strategies,
+        num_games,
+        base_seed,
+        num_players,
+        game_options,
+        alternate_seats,
+        record_moves,
+        max_moves_per_game,
+        progress_callback,
+        |_| false,
+    )
+}
+
+/// Sequential probability ratio test for a two-strategy arena: is `candidate`
+/// at least `elo1` stronger than its opponent (H1), or at most `elo0` (H0)?
+/// `alpha` and `beta` are the false-H1 and false-H0 rates.
+#[derive(Debug, Clone)]
+pub struct SprtParams {
+    pub candidate: String,
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtVerdict {
+    H0,
+    H1,
+    /// The game limit was reached before either bound.
+    Inconclusive,
+}
+
+impl SprtVerdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SprtVerdict::H0 => "h0",
+            SprtVerdict::H1 => "h1",
+            SprtVerdict::Inconclusive => "inconclusive",
+        }
+    }
+}
+
+/// Expected score against an opponent `elo` points weaker.
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+impl SprtParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.elo0.is_finite() && self.elo1.is_finite() && self.elo0 < self.elo1) {
+            return Err(format!("elo0 ({}) must be below elo1 ({})", self.elo0, self.elo1));
+        }
+        for (name, rate) in [("alpha", self.alpha), ("beta", self.beta)] {
+            if !(rate > 0.0 && rate < 1.0) {
+                return Err(format!("{name} must be in (0, 1), got {rate}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Log-likelihood ratio of H1 over H0 after the candidate's `wins`, `draws`
+    /// and `losses`. A draw counts as half a win and half a loss.
+    pub fn llr(&self, wins: usize, draws: usize, losses: usize) -> f64 {
+        let (s0, s1) = (elo_to_score(self.elo0), elo_to_score(self.elo1));
+        let win = (s1 / s0).ln();
+        let loss = ((1.0 - s1) / (1.0 - s0)).ln();
+        wins as f64 * win + losses as f64 * loss + draws as f64 * (win + loss) / 2.0
+    }
+
+    /// H1 or H0 once `llr` crosses the matching bound, else None (keep playing).
+    pub fn verdict(&self, llr: f64) -> Option<SprtVerdict> {
+        if llr >= ((1.0 - self.beta) / self.alpha).ln() {
+            Some(SprtVerdict::H1)
+        } else if llr <= (self.beta / (1.0 - self.alpha)).ln() {
+            Some(SprtVerdict::H0)
+        } else {
+            None
+        }
+    }
+
+    /// `llr` of the candidate's record so far in `result`.
+    pub fn result_llr(&self, result: &ArenaResult) -> f64 {
+        let played = result.game_durations_ms.len();
+        let wins = result.wins.get(&self.candidate).copied().unwrap_or(0);
+        self.llr(wins, result.draws, played - wins - result.draws)
+    }
+}
+
+/// `run_arena` for two strategies that stops as soon as `sprt` reaches a verdict,
+/// playing at most `max_games`. `num_games` in the result is the games played.
+#[allow(clippy::too_many_arguments)]
+pub fn run_arena_sprt<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    sprt: &SprtParams,
+    max_games: usize,
+    base_seed: u64,
+    game_options: Option<serde_json::Value>,
+    alternate_seats: bool,
+    progress_callback: Option<&dyn Fn(usize, usize)>,
+) -> ArenaResult {
+    assert!(strategies.contains_key(&sprt.candidate), "unknown SPRT candidate {}", sprt.candidate);
+    let mut verdict = None;
+    let mut result = run_arena_until(
+        plugin,
+        strategies,
+        max_games,
+        base_seed,
+        2,
+        game_options,
+        alternate_seats,
+        false,
+        DEFAULT_MAX_MOVES_PER_GAME,
+        progress_callback,
+        |result| {
+            verdict = sprt.verdict(sprt.result_llr(result));
+            verdict.is_some()
+        },
+    );
+    result.sprt_verdict = Some(verdict.unwrap_or(SprtVerdict::Inconclusive));
+    result
+}
+
+/// Play up to `num_games` in order, stopping early once `stop` holds for the
+/// results so far.
+#[allow(clippy::too_many_arguments)]
+fn run_arena_until<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    num_games: usize,
+    base_seed: u64,
+    num_players: usize,
+    game_options: Option<serde_json::Value>,
+    alternate_seats: bool,
+    record_moves: bool,
+    max_moves_per_game: usize,
     progress_callback: Option<&dyn Fn(usize, usize)>,
+    mut stop: impl FnMut(&ArenaResult) -> bool,
 ) -> ArenaResult {
     let strategy_names: Vec<String> = strategies.keys().cloned().collect();
-    assert_eq!(strategy_names.len(), num_players);
+    let mut result = empty_result(&strategy_names, num_games, num_players);
+    let options = game_options.unwrap_or(serde_json::json!({}));
+
+    for game_idx in 0..num_games {
+        let game = play_arena_game(
+            plugin,
+            strategies,
+            &strategy_names,
+            game_idx,
+            base_seed,
+            &options,
+            alternate_seats,
+            record_moves,
+            max_moves_per_game,
+        );
+        record_game(&mut result, &strategy_names, game);
+
+        if let Some(cb) = progress_callback {
+            cb(game_idx + 1, num_games);
+        }
+        if stop(&result) {
+            result.num_games = game_idx + 1;
+            break;
+        }
+    }
+
+    result
+}
+
+/// `run_arena` with up to `parallel_games` games at a time (0 = one per CPU), in a
+/// rayon pool of that size that the games' searches share. Wins, draws and scores
+/// are identical to a sequential run with the same `base_seed`, for any
+/// `parallel_games`; only timings differ. The callback may run on any pool thread.
+#[allow(clippy::too_many_arguments)]
+pub fn run_arena_parallel<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    num_games: usize,
+    base_seed: u64,
+    num_players: usize,
+    game_options: Option<serde_json::Value>,
+    alternate_seats: bool,
+    record_moves: bool,
+    max_moves_per_game: usize,
+    parallel_games: usize,
+    progress_callback: Option<&(dyn Fn(usize, usize) + Sync)>,
+) -> ArenaResult {
+    let strategy_names: Vec<String> = strategies.keys().cloned().collect();
+    let mut result = empty_result(&strategy_names, num_games, num_players);
+    let options = game_options.unwrap_or(serde_json::json!({}));
+    let completed = AtomicUsize::new(0);
 
-    let mut result = ArenaResult {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(parallel_games)
+        .build()
+        .expect("failed to build arena thread pool");
+    let games: Vec<ArenaGame> = pool.install(|| {
+        (0..num_games)
+            .into_par_iter()
+            .map(|game_idx| {
+                let game = play_arena_game(
+                    plugin,
+                    strategies,
+                    &strategy_names,
+                    game_idx,
+                    base_seed,
+                    &options,
+                    alternate_seats,
+                    record_moves,
+                    max_moves_per_game,
+                );
+                let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Some(cb) = progress_callback {
+                    cb(done, num_games);
+                }
+                game
+            })
+            .collect()
+    });
+    for game in games {
+        record_game(&mut result, &strategy_names, game);
+    }
+
+    result
+}
+
+/// Head-to-head record of a tournament pairing `(a, b)`; seats alternate by game.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PairwiseRecord {
+    pub games: usize,
+    pub wins_a: usize,
+    pub wins_b: usize,
+    pub draws: usize,
+}
+
+#[allow(dead_code)]
+impl PairwiseRecord {
+    /// Score of `a` against `b`, counting draws as half a win.
+    pub fn score_a(&self) -> f64 {
+        (self.wins_a as f64 + self.draws as f64 / 2.0) / self.games.max(1) as f64
+    }
+}
+
+/// Cross-table of a round-robin tournament, keyed by `(a, b)` with `a < b`.
+#[allow(dead_code)]
+pub struct TournamentResult {
+    pub pairings: HashMap<(String, String), PairwiseRecord>,
+}
+
+#[allow(dead_code)]
+impl TournamentResult {
+    /// Score of `a` against `b` (None if they never met).
+    pub fn score(&self, a: &str, b: &str) -> Option<f64> {
+        if let Some(record) = self.pairings.get(&(a.to_string(), b.to_string())) {
+            return Some(record.score_a());
+        }
+        self.pairings.get(&(b.to_string(), a.to_string())).map(|r| 1.0 - r.score_a())
+    }
+}
+
+/// Two-player round robin: every pair of `strategies` plays `games_per_pairing`
+/// games, alternating seats. Each pairing replays the same game seeds (the
+/// `run_arena` seeds for `base_seed`), so all pairings face the same deals.
+#[allow(dead_code)]
+pub fn run_tournament<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    games_per_pairing: usize,
+    base_seed: u64,
+    game_options: Option<serde_json::Value>,
+    progress_callback: Option<&dyn Fn(usize, usize)>,
+) -> TournamentResult {
+    let mut names: Vec<String> = strategies.keys().cloned().collect();
+    names.sort();
+    let pairs: Vec<[String; 2]> = names
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| names[i + 1..].iter().map(move |b| [a.clone(), b.clone()]))
+        .collect();
+    let options = game_options.unwrap_or(serde_json::json!({}));
+    let total = pairs.len() * games_per_pairing;
+
+    let mut pairings = HashMap::new();
+    for (pair_idx, pair) in pairs.into_iter().enumerate() {
+        let mut result = empty_result(&pair, games_per_pairing, 2);
+        for game_idx in 0..games_per_pairing {
+            let game = play_arena_game(
+                plugin, strategies, &pair, game_idx, base_seed, &options, true, false, DEFAULT_MAX_MOVES_PER_GAME,
+            );
+            record_game(&mut result, &pair, game);
+            if let Some(cb) = progress_callback {
+                cb(pair_idx * games_per_pairing + game_idx + 1, total);
+            }
+        }
+        let record = PairwiseRecord {
+            games: games_per_pairing,
+            wins_a: result.wins[&pair[0]],
+            wins_b: result.wins[&pair[1]],
+            draws: result.draws,
+        };
+        let [a, b] = pair;
+        pairings.insert((a, b), record);
+    }
+    TournamentResult { pairings }
+}
+
+/// Arena seed contract. Game `game_idx` of a run is created with this
+/// `random_seed`, which drives its setup shuffle. The strategy choosing the
+/// game's `m`-th move gets `move_rng(game_seed, m)`, from which `MctsStrategy`
+/// draws its determinization and search seeds. A game's outcome depends only on
+/// (`base_seed`, `game_idx`), whatever order or thread it runs on.
+pub fn game_seed(base_seed: u64, game_idx: usize) -> u64 {
+    base_seed.wrapping_add(game_idx as u64)
+}
+
+/// RNG handed to the strategy for move `move_idx` (0-based, bot decisions
+/// only) of the game seeded with `game_seed`. Stream 0 of the game seed is
+/// the plugin's setup shuffle, so moves start at stream 1.
+pub fn move_rng(game_seed: u64, move_idx: usize) -> GameRng {
+    GameRng::stream(game_seed, STRATEGY_RNG_STREAM + move_idx as u64)
+}
+
+fn empty_result(strategy_names: &[String], num_games: usize, num_players: usize) -> ArenaResult {
+    assert_eq!(strategy_names.len(), num_players);
+    ArenaResult {
         num_games,
         wins: strategy_names.iter().map(|n| (n.clone(), 0)).collect(),
         draws: 0,
         total_scores: strategy_names.iter().map(|n| (n.clone(), Vec::new())).collect(),
         game_durations_ms: Vec::new(),
+        seat_games: strategy_names.iter().map(|n| (n.clone(), vec![0; num_players])).collect(),
+        seat_wins: strategy_names.iter().map(|n| (n.clone(), vec![0; num_players])).collect(),
+        think_time_ms: strategy_names.iter().map(|n| (n.clone(), 0.0)).collect(),
+        moves: strategy_names.iter().map(|n| (n.clone(), 0)).collect(),
+        win_margins: strategy_names.iter().map(|n| (n.clone(), Vec::new())).collect(),
+        sprt_verdict: None,
+        game_logs: Vec::new(),
+        adjudicated: 0,
+    }
+}
+
+/// One finished arena game, before it is folded into an `ArenaResult`.
+struct ArenaGame {
+    players: Vec<Player>,
+    /// Strategy name in each seat.
+    seat_assignment: Vec<String>,
+    game_result: Option<GameResult>,
+    elapsed_ms: f64,
+    timings: HashMap<String, ThinkTime>,
+    log: Option<GameLog>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_arena_game<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    strategy_names: &[String],
+    game_idx: usize,
+    base_seed: u64,
+    options: &serde_json::Value,
+    alternate_seats: bool,
+    record_moves: bool,
+    max_moves: usize,
+) -> ArenaGame {
+    let num_players = strategy_names.len();
+    let seat_assignment: Vec<String> = if alternate_seats {
+        (0..num_players)
+            .map(|i| strategy_names[(i + game_idx) % num_players].clone())
+            .collect()
+    } else {
+        strategy_names.to_vec()
     };
 
-    for game_idx in 0..num_games {
-        let seed = base_seed + game_idx as u64;
+    let players: Vec<Player> = (0..num_players)
+        .map(|i| Player {
+            player_id: format!("p{}", i),
+            display_name: seat_assignment[i].clone(),
+            seat_index: i as i32,
+            is_bot: true,
+            bot_id: Some(seat_assignment[i].clone()),
+        })
+        .collect();
 
-        let seat_assignment: Vec<String> = if alternate_seats {
-            (0..num_players)
-                .map(|i| strategy_names[(i + game_idx) % num_players].clone())
-                .collect()
-        } else {
-            strategy_names[..num_players].to_vec()
-        };
+    let pid_to_strategy: HashMap<String, &dyn BotStrategy<P>> = (0..num_players)
+        .map(|i| (format!("p{}", i), strategies[&seat_assignment[i]].as_ref()))
+        .collect();
 
-        let players: Vec<Player> = (0..num_players)
-            .map(|i| Player {
-                player_id: format!("p{}", i),
-                display_name: seat_assignment[i].clone(),
-                seat_index: i as i32,
-                is_bot: true,
-                bot_id: Some(seat_assignment[i].clone()),
-            })
-            .collect();
+    let config = GameConfig {
+        random_seed: Some(game_seed(base_seed, game_idx)),
+        options: options.clone(),
+    };
 
-        let pid_to_strategy: HashMap<String, &dyn BotStrategy<P>> = (0..num_players)
-            .map(|i| (format!("p{}", i), strategies[&seat_assignment[i]].as_ref()))
-            .collect();
+    let mut log = record_moves.then(|| GameLog {
+        game_id: plugin.game_id().to_string(),
+        description: format!("arena game {}: {}", game_idx, seat_assignment.join(" vs ")),
+        seed: game_seed(base_seed, game_idx),
+        players: players.iter().map(|p| p.player_id.clone()).collect(),
+        options: options.clone(),
+        actions: Vec::new(),
+        move_scores: Vec::new(),
+        expected_scores: HashMap::new(),
+    });
 
-        let pid_to_name: HashMap<String, String> = (0..num_players)
-            .map(|i| (format!("p{}", i), seat_assignment[i].clone()))
-            .collect();
+    let t0 = Instant::now();
+    let mut timings = HashMap::new();
+    let game_result =
+        play_game(plugin, &players, &config, &pid_to_strategy, max_moves, None, &mut timings, log.as_mut());
+    ArenaGame {
+        players,
+        seat_assignment,
+        game_result,
+        elapsed_ms: t0.elapsed().as_secs_f64() * 1000.0,
+        timings,
+        log,
+    }
+}
 
-        let config = GameConfig {
-            random_seed: Some(seed),
-            options: game_options.clone().unwrap_or(serde_json::json!({})),
-        };
+fn record_game(result: &mut ArenaResult, strategy_names: &[String], game: ArenaGame) {
+    let seat_of = |pid: &str| game.players.iter().position(|p| p.player_id == pid);
+    let name_of = |pid: &str| seat_of(pid).and_then(|seat| game.seat_assignment.get(seat));
 
-        let t0 = Instant::now();
-        let game_result = play_one_game(plugin, &players, &config, &pid_to_strategy);
-        let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
-        result.game_durations_ms.push(elapsed_ms);
+    for (seat, name) in game.seat_assignment.iter().enumerate() {
+        result.seat_games.get_mut(name).unwrap()[seat] += 1;
+    }
+    result.game_durations_ms.push(game.elapsed_ms);
+    result.game_logs.extend(game.log);
+    for (pid, timing) in &game.timings {
+        let name = name_of(pid).unwrap();
+        *result.think_time_ms.get_mut(name).unwrap() += timing.total_ms;
+        *result.moves.get_mut(name).unwrap() += timing.moves;
+    }
 
-        match game_result {
-            None => {
-                result.draws += 1;
-                for name in &strategy_names {
-                    result.total_scores.get_mut(name).unwrap().push(0.0);
-                }
+    match game.game_result {
+        None => {
+            result.draws += 1;
+            for name in strategy_names {
+                result.total_scores.get_mut(name).unwrap().push(0.0);
+            }
+        }
+        Some(gr) => {
+            if gr.reason == MOVE_LIMIT_REASON {
+                result.adjudicated += 1;
             }
-            Some(gr) => {
-                for (pid, score) in &gr.final_scores {
-                    if let Some(name) = pid_to_name.get(pid) {
-                        result.total_scores.get_mut(name).unwrap().push(*score);
-                    }
+            for (pid, score) in &gr.final_scores {
+                if let Some(name) = name_of(pid) {
+                    result.total_scores.get_mut(name).unwrap().push(*score);
                 }
+            }
 
-                if gr.winners.len() == 1 {
-                    if let Some(name) = pid_to_name.get(&gr.winners[0]) {
-                        *result.wins.get_mut(name).unwrap() += 1;
-                    }
-                } else {
-                    result.draws += 1;
+            if gr.winners.len() == 1 {
+                if let Some(name) = name_of(&gr.winners[0]) {
+                    *result.wins.get_mut(name).unwrap() += 1;
+                    result.win_margins.get_mut(name).unwrap().push(win_margin(&gr));
+                    let seat = seat_of(&gr.winners[0]).unwrap();
+                    result.seat_wins.get_mut(name).unwrap()[seat] += 1;
                 }
+            } else {
+                result.draws += 1;
             }
         }
-
-        if let Some(cb) = progress_callback {
-            cb(game_idx + 1, num_games);
-        }
     }
+}
 
-    result
+/// Score of the sole winner minus the best score among the other players.
+fn win_margin(result: &GameResult) -> f64 {
+    let winner = &result.winners[0];
+    let winner_score = result.final_scores.get(winner).copied().unwrap_or(0.0);
+    let runner_up = result
+        .final_scores
+        .iter()
+        .filter(|(pid, _)| *pid != winner)
+        .map(|(_, &score)| score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if runner_up.is_finite() { winner_score - runner_up } else { 0.0 }
 }
 
-fn play_one_game<P: TypedGamePlugin>(
+/// First `GameRng` stream of the game seed handed to strategies; move m uses this + m.
+const STRATEGY_RNG_STREAM: u64 = 1;
+
+/// Time one player spent in `choose_action` during a game.
+#[derive(Default)]
+struct ThinkTime {
+    total_ms: f64,
+    moves: usize,
+}
+
+/// Play a single game between the strategies in `pid_to_strategy`. When
+/// `transcript` is given, every applied action (auto-resolve ones included) is
+/// appended to it with its events. `None` if the game did not finish (including
+/// a plugin stuck in an auto-resolve loop, which is logged).
+pub fn play_one_game<P: TypedGamePlugin>(
     plugin: &P,
     players: &[Player],
     config: &GameConfig,
     pid_to_strategy: &HashMap<String, &dyn BotStrategy<P>>,
+    transcript: Option<&mut Vec<TranscriptEntry>>,
+) -> Option<GameResult> {
+    play_game(plugin, players, config, pid_to_strategy, DEFAULT_MAX_MOVES_PER_GAME, transcript, &mut HashMap::new(), None)
+}
+
+/// `play_one_game` with a `max_moves` limit, recording each player's thinking time
+/// into `timings` (keyed by player_id) and, when `log` is given, each player action
+/// and the scores after it.
+#[allow(clippy::too_many_arguments)]
+fn play_game<P: TypedGamePlugin>(
+    plugin: &P,
+    players: &[Player],
+    config: &GameConfig,
+    pid_to_strategy: &HashMap<String, &dyn BotStrategy<P>>,
+    max_moves: usize,
+    transcript: Option<&mut Vec<TranscriptEntry>>,
+    timings: &mut HashMap<String, ThinkTime>,
+    mut log: Option<&mut GameLog>,
 ) -> Option<GameResult> {
     debug_assert!(
         players.iter().enumerate().all(|(i, p)| p.seat_index == i as i32),
@@ -190,32 +755,71 @@ fn play_one_game<P: TypedGamePlugin>(
     );
 
     let (state, phase, _) = plugin.create_initial_state(players, config);
+    let seed = config.random_seed.unwrap_or(0);
 
     let mut sim = SimulationState {
         state,
         phase,
-        players: players.to_vec(),
+        players: players.into(),
         scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
         game_over: None,
+        history: None,
     };
 
+    let outcome = run_game(
+        plugin,
+        players,
+        &mut sim,
+        pid_to_strategy,
+        seed,
+        max_moves,
+        transcript,
+        timings,
+        log.as_deref_mut(),
+    );
+    if let Some(log) = log {
+        log.expected_scores = sim.scores.clone();
+    }
+    match outcome {
+        Ok(()) => sim.game_over,
+        Err(e) => {
+            tracing::warn!(error = %e, "arena game aborted");
+            None
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_game<P: TypedGamePlugin>(
+    plugin: &P,
+    players: &[Player],
+    sim: &mut SimulationState<P::State>,
+    pid_to_strategy: &HashMap<String, &dyn BotStrategy<P>>,
+    seed: u64,
+    max_moves: usize,
+    mut transcript: Option<&mut Vec<TranscriptEntry>>,
+    timings: &mut HashMap<String, ThinkTime>,
+    mut log: Option<&mut GameLog>,
+) -> Result<(), String> {
     // Resolve initial auto-resolve phases
-    resolve_auto(plugin, &mut sim);
+    resolve_auto(plugin, sim, transcript.as_deref_mut())?;
 
-    let max_iterations = 500;
-    for _ in 0..max_iterations {
+    let mut move_idx = 0;
+    loop {
         if sim.game_over.is_some() {
             break;
         }
+        if move_idx >= max_moves {
+            sim.game_over = Some(adjudicate(&sim.scores));
+            break;
+        }
 
         if sim.phase.auto_resolve {
-            resolve_auto(plugin, &mut sim);
+            resolve_auto(plugin, sim, transcript.as_deref_mut())?;
             continue;
         }
 
-        let acting_pid = if !sim.phase.expected_actions.is_empty() {
-            sim.phase.expected_actions[0].player_id.clone()
-        } else {
+        let Some(acting_pid) = plugin.acting_player(&sim.state, &sim.phase, &sim.players) else {
             break;
         };
 
@@ -224,61 +828,95 @@ fn play_one_game<P: TypedGamePlugin>(
             None => break,
         };
 
+        let t0 = Instant::now();
         let chosen = strategy.choose_action(
             &sim.state,
             &sim.phase,
             &acting_pid,
             plugin,
             players,
+            &mut move_rng(seed, move_idx),
         );
+        move_idx += 1;
+        let timing = timings.entry(acting_pid.clone()).or_default();
+        timing.total_ms += t0.elapsed().as_secs_f64() * 1000.0;
+        timing.moves += 1;
 
-        let action_type = sim.phase.expected_actions[0].action_type.clone();
         let action = Action {
-            action_type,
+            action_type: plugin.action_type_for(&sim.phase, &chosen),
             player_id: acting_pid,
             payload: chosen,
         };
-        apply_action_and_resolve(plugin, &mut sim, &action);
+        if let Some(log) = log.as_deref_mut() {
+            log.actions.push(action.clone());
+        }
+        step(plugin, sim, action, transcript.as_deref_mut())?;
+        if let Some(log) = log.as_deref_mut() {
+            log.move_scores.push(sim.scores.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Result of a game stopped at the move limit: the top scorers win (all of them,
+/// i.e. a draw, on a tie).
+fn adjudicate(scores: &HashMap<String, f64>) -> GameResult {
+    let best = scores.values().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mut winners: Vec<String> =
+        scores.iter().filter(|(_, &s)| s == best).map(|(pid, _)| pid.clone()).collect();
+    winners.sort();
+    GameResult {
+        winners,
+        final_scores: scores.clone(),
+        reason: MOVE_LIMIT_REASON.into(),
+        details: HashMap::new(),
     }
+}
 
-    sim.game_over
+fn step<P: TypedGamePlugin>(
+    plugin: &P,
+    sim: &mut SimulationState<P::State>,
+    action: Action,
+    transcript: Option<&mut Vec<TranscriptEntry>>,
+) -> Result<(), String> {
+    match transcript {
+        Some(entries) => {
+            let events = apply_action_and_resolve_with_events(plugin, sim, &action)?;
+            entries.push(TranscriptEntry { action, events });
+        }
+        None => apply_action_and_resolve(plugin, sim, &action)?,
+    }
+    Ok(())
 }
 
 fn resolve_auto<P: TypedGamePlugin>(
     plugin: &P,
     state: &mut SimulationState<P::State>,
-) {
-    let mut max_auto = 50;
-    while state.phase.auto_resolve && state.game_over.is_none() && max_auto > 0 {
-        max_auto -= 1;
-
-        let pid = if let Some(pi) = state.phase.metadata.get("player_index").and_then(|v| v.as_u64()) {
-            let idx = pi as usize;
-            if idx < state.players.len() {
-                state.players[idx].player_id.clone()
-            } else {
-                "system".into()
-            }
-        } else {
-            "system".into()
-        };
+    mut transcript: Option<&mut Vec<TranscriptEntry>>,
+) -> Result<(), String> {
+    // `step` resolves any chain that follows, up to the plugin's cap.
+    while state.phase.auto_resolve && state.game_over.is_none() {
+        let pid = metadata_player(&state.phase, &state.players).unwrap_or_else(|| "system".into());
 
         let synthetic = Action {
             action_type: state.phase.name.clone(),
             player_id: pid,
             payload: serde_json::json!({}),
         };
-        apply_action_and_resolve(plugin, state, &synthetic);
+        step(plugin, state, synthetic, transcript.as_deref_mut())?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::bot_strategy::{MctsStrategy, RandomStrategy};
-    use crate::engine::mcts::MctsParams;
+    use crate::engine::bot_strategy::{AlphaBetaStrategy, GreedyStrategy, MctsStrategy, RandomStrategy};
+    use crate::engine::plugin::TypedTransitionResult;
+    use crate::engine::mcts::{FpuMode, MctsParams};
     use crate::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
     use crate::games::carcassonne::plugin::CarcassonnePlugin;
+    use rand::RngCore;
 
     #[test]
     fn test_arena_random_vs_random() {
@@ -295,6 +933,8 @@ mod tests {
             2,
             Some(serde_json::json!({"tile_count": 10})),
             true,
+            false,
+            DEFAULT_MAX_MOVES_PER_GAME,
             None,
         );
 
@@ -303,6 +943,642 @@ mod tests {
         assert_eq!(total_outcomes, 3);
     }
 
+    #[test]
+    fn test_greedy_beats_random() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("greedy".into(), Box::new(GreedyStrategy::new(make_carcassonne_eval(DEFAULT_WEIGHTS))));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+
+        let result = run_arena(
+            &plugin,
+            &strategies,
+            10,
+            7,
+            2,
+            Some(serde_json::json!({"tile_count": 30})),
+            true,
+            false,
+            DEFAULT_MAX_MOVES_PER_GAME,
+            None,
+        );
+        assert!(result.wins["greedy"] > 7, "greedy won {:?}", result.wins);
+    }
+
+    /// Plays `alpha_beta` from `seat` against every possible reply and returns
+    /// the number of games that reached the end, panicking on any loss.
+    fn explore_replies(
+        alpha_beta: &AlphaBetaStrategy<crate::engine::test_games::TicTacToe>,
+        sim: &SimulationState<[u8; 9]>,
+        seat: usize,
+    ) -> usize {
+        use crate::engine::test_games::TicTacToe;
+
+        if let Some(result) = &sim.game_over {
+            let me = &sim.players[seat].player_id;
+            assert!(result.winners.contains(me), "alpha-beta lost as seat {seat}: {:?}", sim.state);
+            return 1;
+        }
+        let mover = TicTacToe.acting_player(&sim.state, &sim.phase, &sim.players).unwrap();
+        let payloads = if mover == sim.players[seat].player_id {
+            let mut rng = GameRng::from_seed(sim.state.iter().map(|&c| c as u64).sum());
+            vec![alpha_beta.choose_action(&sim.state, &sim.phase, &mover, &TicTacToe, &sim.players, &mut rng)]
+        } else {
+            TicTacToe.get_valid_actions(&sim.state, &sim.phase, &mover)
+        };
+        payloads
+            .into_iter()
+            .map(|payload| {
+                let action = Action { action_type: "play".into(), player_id: mover.clone(), payload };
+                let mut next = sim.clone();
+                apply_action_and_resolve(&TicTacToe, &mut next, &action).unwrap();
+                explore_replies(alpha_beta, &next, seat)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_alpha_beta_never_loses_tic_tac_toe() {
+        use crate::engine::test_games::TicTacToe;
+
+        assert!(AlphaBetaStrategy::new(&CarcassonnePlugin, Some(2), None).is_err());
+        let players: Vec<Player> = (0..2)
+            .map(|i| Player {
+                player_id: format!("p{}", i),
+                display_name: format!("p{}", i),
+                seat_index: i,
+                is_bot: true,
+                bot_id: None,
+            })
+            .collect();
+        let config = GameConfig { options: serde_json::json!({}), random_seed: None };
+        let (state, phase, _) = TicTacToe.create_initial_state(&players, &config);
+        let start = SimulationState { state, phase, players: players.into(), scores: HashMap::new(), game_over: None, history: None };
+
+        let alpha_beta = AlphaBetaStrategy::new(&TicTacToe, None, None).unwrap();
+        for seat in 0..2 {
+            assert!(explore_replies(&alpha_beta, &start, seat) > 0);
+        }
+
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<TicTacToe>>> = HashMap::new();
+        strategies.insert("alpha_beta_a".into(), Box::new(AlphaBetaStrategy::new(&TicTacToe, None, None).unwrap()));
+        strategies.insert("alpha_beta_b".into(), Box::new(AlphaBetaStrategy::new(&TicTacToe, None, None).unwrap()));
+        let result = run_arena(&TicTacToe, &strategies, 6, 3, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+        assert_eq!(result.draws, 6, "{:?}", result.wins);
+    }
+
+    /// Never places a meeple, so it can never score.
+    struct NoMeeples;
+
+    impl BotStrategy<CarcassonnePlugin> for NoMeeples {
+        fn choose_action(
+            &self,
+            state: &<CarcassonnePlugin as TypedGamePlugin>::State,
+            phase: &Phase,
+            player_id: &str,
+            plugin: &CarcassonnePlugin,
+            _players: &[Player],
+            _rng: &mut GameRng,
+        ) -> serde_json::Value {
+            let valid = plugin.get_valid_actions(state, phase, player_id);
+            valid.iter().find(|a| a.get("meeple_spot").is_none()).or(valid.first()).cloned().unwrap_or_default()
+        }
+    }
+
+    /// Claims every feature it can from seat 0, plays like `NoMeeples` from any other seat.
+    struct SeatBiased;
+
+    impl BotStrategy<CarcassonnePlugin> for SeatBiased {
+        fn choose_action(
+            &self,
+            state: &<CarcassonnePlugin as TypedGamePlugin>::State,
+            phase: &Phase,
+            player_id: &str,
+            plugin: &CarcassonnePlugin,
+            players: &[Player],
+            rng: &mut GameRng,
+        ) -> serde_json::Value {
+            if players[0].player_id != player_id {
+                return NoMeeples.choose_action(state, phase, player_id, plugin, players, rng);
+            }
+            let valid = plugin.get_valid_actions(state, phase, player_id);
+            valid.iter().find(|a| a.get("meeple_spot").is_some()).or(valid.first()).cloned().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn test_seat_breakdown_reveals_seat_bias() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("biased".into(), Box::new(SeatBiased));
+        strategies.insert("no_meeples".into(), Box::new(NoMeeples));
+
+        let result = run_arena(&plugin, &strategies, 6, 42, 2, Some(serde_json::json!({"tile_count": 10})), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+
+        assert_eq!(result.seat_games["biased"], vec![3, 3]);
+        assert_eq!(result.seat_games["no_meeples"], vec![3, 3]);
+        // Overall it looks like a 50% strategy; by seat it wins every game from seat 0 and none from seat 1
+        assert_eq!(result.win_rate("biased"), 0.5);
+        assert_eq!(result.seat_win_rates("biased"), vec![1.0, 0.0]);
+        assert_eq!(result.seat_win_rates("no_meeples"), vec![0.0, 0.0]);
+        assert!(result.summary().contains("seat 0: 100.0% (3)"));
+    }
+
+    #[test]
+    fn test_think_time_tracked_per_strategy() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::<CarcassonnePlugin>::new(MctsParams {
+            num_simulations: 50,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            ..Default::default()
+        })));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+
+        let result = run_arena(&plugin, &strategies, 2, 42, 2, Some(serde_json::json!({"tile_count": 6})), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+
+        // Each player makes a tile and a meeple decision per turn
+        assert!(result.moves["mcts"] >= 6, "moves: {:?}", result.moves);
+        assert!(result.moves["random"] >= 6, "moves: {:?}", result.moves);
+        assert!(result.think_time_ms["mcts"] > 0.0);
+        assert!(
+            result.think_time_ms["random"] < result.think_time_ms["mcts"] * 0.1,
+            "random should think ~instantly: {:?}",
+            result.think_time_ms
+        );
+        let game_ms: f64 = result.game_durations_ms.iter().sum();
+        assert!(result.think_time_ms.values().sum::<f64>() <= game_ms);
+        assert!(result.summary().contains("ms/move"));
+    }
+
+    #[test]
+    fn test_win_margins_match_winners() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("random_a".into(), Box::new(RandomStrategy));
+        strategies.insert("random_b".into(), Box::new(RandomStrategy));
+
+        let result = run_arena(&plugin, &strategies, 8, 7, 2, Some(serde_json::json!({"tile_count": 20})), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+
+        for name in ["random_a", "random_b"] {
+            let margins = &result.win_margins[name];
+            assert_eq!(margins.len(), result.wins[name], "one margin per outright win");
+            // Carcassonne is won on points, and ties are draws
+            assert!(margins.iter().all(|&m| m > 0.0), "{}: {:?}", name, margins);
+            let mut sorted = margins.clone();
+            sorted.sort_by(f64::total_cmp);
+            let median = result.median_win_margin(name);
+            assert!(sorted.is_empty() || (sorted[0]..=sorted[sorted.len() - 1]).contains(&median));
+        }
+        assert_eq!(result.win_margins.values().map(Vec::len).sum::<usize>() + result.draws, 8);
+        assert!(result.summary().contains("win margin"));
+    }
+
+    #[test]
+    fn test_parallel_arena_matches_sequential() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::<CarcassonnePlugin>::new(MctsParams {
+            num_simulations: 30,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            ..Default::default()
+        })));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+        let options = Some(serde_json::json!({"tile_count": 8}));
+
+        let sequential = run_arena(&plugin, &strategies, 6, 42, 2, options.clone(), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+        let parallel = run_arena_parallel(&plugin, &strategies, 6, 42, 2, options, true, false, DEFAULT_MAX_MOVES_PER_GAME, 0, None);
+
+        assert_eq!(parallel.wins, sequential.wins);
+        assert_eq!(parallel.draws, sequential.draws);
+        assert_eq!(parallel.total_scores, sequential.total_scores);
+        assert_eq!(parallel.seat_wins, sequential.seat_wins);
+        assert_eq!(parallel.moves, sequential.moves);
+        // Move seeds are independent of each other and of earlier games
+        assert_eq!(move_rng(game_seed(42, 3), 5).next_u64(), move_rng(45, 5).next_u64());
+        assert_ne!(move_rng(45, 5).next_u64(), move_rng(45, 6).next_u64());
+    }
+
+    #[test]
+    fn test_win_margin_stats() {
+        let gr = GameResult {
+            winners: vec!["p1".into()],
+            final_scores: HashMap::from([("p0".into(), 12.0), ("p1".into(), 20.0), ("p2".into(), 15.0)]),
+            reason: "normal".into(),
+            details: HashMap::new(),
+        };
+        assert_eq!(win_margin(&gr), 5.0);
+
+        let result = ArenaResult {
+            num_games: 4,
+            wins: HashMap::from([("a".into(), 4)]),
+            draws: 0,
+            total_scores: HashMap::new(),
+            game_durations_ms: Vec::new(),
+            seat_games: HashMap::new(),
+            seat_wins: HashMap::new(),
+            think_time_ms: HashMap::new(),
+            moves: HashMap::new(),
+            win_margins: HashMap::from([("a".into(), vec![9.0, 1.0, 2.0, 4.0])]),
+            sprt_verdict: None,
+            game_logs: Vec::new(),
+            adjudicated: 0,
+        };
+        assert_eq!(result.avg_win_margin("a"), 4.0);
+        assert_eq!(result.median_win_margin("a"), 3.0);
+        assert_eq!(result.median_win_margin("b"), 0.0);
+    }
+
+    #[test]
+    fn test_sprt_stops_early_on_clear_winner() {
+        use crate::engine::test_games::TicTacToe;
+
+        let sprt = SprtParams { candidate: "mcts".into(), elo0: 0.0, elo1: 200.0, alpha: 0.05, beta: 0.05 };
+        assert!(sprt.validate().is_ok());
+        assert!(SprtParams { elo1: -10.0, ..sprt.clone() }.validate().is_err());
+        assert!(sprt.llr(1, 0, 0) > 0.0 && sprt.llr(0, 0, 1) < 0.0 && sprt.llr(0, 1, 0) < 0.0);
+        assert_eq!(sprt.verdict(sprt.llr(3, 0, 0)), None);
+        assert_eq!(sprt.verdict(sprt.llr(0, 0, 5)), Some(SprtVerdict::H0));
+
+        let params = MctsParams {
+            num_simulations: 200,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            rollout_depth: 9,
+            seed: Some(3),
+            ..Default::default()
+        };
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<TicTacToe>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::new(params)));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+        let result = run_arena_sprt(&TicTacToe, &strategies, &sprt, 100, 7, None, true, None);
+
+        assert_eq!(result.sprt_verdict, Some(SprtVerdict::H1));
+        assert!(result.num_games < 100, "took {} games", result.num_games);
+        assert_eq!(result.game_durations_ms.len(), result.num_games);
+        assert!(sprt.result_llr(&result) >= (0.95f64 / 0.05).ln());
+    }
+
+    #[test]
+    fn test_elo_ratings() {
+        let result = |wins: &[(&str, usize)], draws: usize| ArenaResult {
+            num_games: wins.iter().map(|w| w.1).sum::<usize>() + draws,
+            wins: wins.iter().map(|&(name, w)| (name.to_string(), w)).collect(),
+            draws,
+            total_scores: HashMap::new(),
+            game_durations_ms: Vec::new(),
+            seat_games: HashMap::new(),
+            seat_wins: HashMap::new(),
+            think_time_ms: HashMap::new(),
+            moves: HashMap::new(),
+            win_margins: HashMap::new(),
+            sprt_verdict: None,
+            game_logs: Vec::new(),
+            adjudicated: 0,
+        };
+
+        let elo = result(&[("strong", 70), ("weak", 20)], 10).elo_ratings("weak");
+        assert_eq!(elo["weak"], 0.0);
+        assert!((elo["strong"] - 200.0).abs() < 15.0, "{elo:?}");
+        let flipped = result(&[("strong", 70), ("weak", 20)], 10).elo_ratings("strong");
+        assert!((flipped["weak"] + elo["strong"]).abs() < 1e-9);
+
+        let shutout = result(&[("a", 10), ("b", 0)], 0);
+        assert_eq!(shutout.elo_ratings("b")["a"], MAX_ELO_DIFF);
+        assert_eq!(shutout.elo_ratings("a")["b"], -MAX_ELO_DIFF);
+        assert!(shutout.elo_ratings("nobody").is_empty());
+    }
+
+    #[test]
+    fn test_round_robin_tournament() {
+        use crate::engine::test_games::TicTacToe;
+
+        let params = MctsParams {
+            num_simulations: 200,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            rollout_depth: 9,
+            seed: Some(3),
+            ..Default::default()
+        };
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<TicTacToe>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::new(params)));
+        strategies.insert("random_a".into(), Box::new(RandomStrategy));
+        strategies.insert("random_b".into(), Box::new(RandomStrategy));
+        let progress = std::sync::Mutex::new(Vec::new());
+        let result = run_tournament(&TicTacToe, &strategies, 4, 7, None, Some(&|done, total| {
+            progress.lock().unwrap().push((done, total));
+        }));
+
+        let mut keys: Vec<_> = result.pairings.keys().cloned().collect();
+        keys.sort();
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(keys, vec![pair("mcts", "random_a"), pair("mcts", "random_b"), pair("random_a", "random_b")]);
+        for record in result.pairings.values() {
+            assert_eq!(record.games, 4);
+            assert_eq!(record.wins_a + record.wins_b + record.draws, 4);
+        }
+        assert_eq!(result.pairings[&pair("mcts", "random_a")].wins_b, 0);
+        assert_eq!(result.score("random_a", "mcts"), Some(1.0 - result.score("mcts", "random_a").unwrap()));
+        assert_eq!(progress.into_inner().unwrap().last(), Some(&(12, 12)));
+    }
+
+    #[test]
+    fn test_parallel_games_reproducible() {
+        use crate::engine::test_games::TicTacToe;
+
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<TicTacToe>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::new(MctsParams {
+            num_simulations: 50,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            rollout_depth: 9,
+            ..Default::default()
+        })));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+
+        let run = |parallel_games: usize| {
+            let calls = AtomicUsize::new(0);
+            let result = run_arena_parallel(&TicTacToe, &strategies, 12, 5, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, parallel_games, Some(&|_, total| {
+                assert_eq!(total, 12);
+                calls.fetch_add(1, Ordering::Relaxed);
+            }));
+            assert_eq!(calls.into_inner(), 12);
+            result
+        };
+        let one = run(1);
+        // Same base seed, same games: on a rerun and across thread counts
+        for other in [run(1), run(4)] {
+            assert_eq!(other.wins, one.wins);
+            assert_eq!(other.draws, one.draws);
+            assert_eq!(other.total_scores, one.total_scores);
+            assert_eq!(other.seat_wins, one.seat_wins);
+            assert_eq!(other.moves, one.moves);
+            assert_eq!(other.win_margins, one.win_margins);
+        }
+    }
+
+    #[test]
+    fn test_recorded_game_logs_replay() {
+        let plugin = CarcassonnePlugin;
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::<CarcassonnePlugin>::new(MctsParams {
+            num_simulations: 10,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            ..Default::default()
+        })));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+        let options = Some(serde_json::json!({"tile_count": 8}));
+
+        let unrecorded = run_arena(&plugin, &strategies, 2, 9, 2, options.clone(), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+        assert!(unrecorded.game_logs.is_empty());
+        let result = run_arena(&plugin, &strategies, 2, 9, 2, options, true, true, DEFAULT_MAX_MOVES_PER_GAME, None);
+        assert_eq!(result.game_logs.len(), 2);
+
+        for (game_idx, log) in result.game_logs.iter().enumerate() {
+            let json = log.to_json();
+            assert_eq!(json["seed"], game_seed(9, game_idx));
+            assert_eq!(json["options"]["tile_count"], 8);
+            let log: GameLog = serde_json::from_value(json).unwrap();
+            assert!(!log.actions.is_empty());
+            assert_eq!(log.move_scores.len(), log.actions.len());
+
+            let players = log.make_players();
+            let (state, phase, _) = plugin.create_initial_state(&players, &log.config());
+            let mut sim = SimulationState {
+                state,
+                phase,
+                players: players.clone().into(),
+                scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
+                game_over: None,
+                history: None,
+            };
+            resolve_auto(&plugin, &mut sim, None).unwrap();
+            for (action, scores) in log.actions.iter().zip(&log.move_scores) {
+                apply_action_and_resolve(&plugin, &mut sim, action).unwrap();
+                assert_eq!(&sim.scores, scores);
+            }
+            assert!(sim.game_over.is_some());
+            assert_eq!(sim.scores, log.expected_scores);
+        }
+    }
+
+    /// Buggy plugin: players take turns scoring a point each, and the game never ends.
+    struct Stalling;
+
+    fn stalling_phase(players: &[Player], moves: u32) -> Phase {
+        let player_index = moves as usize % players.len();
+        Phase {
+            name: "score".into(),
+            concurrent_mode: None,
+            expected_actions: vec![ExpectedAction {
+                player_id: players[player_index].player_id.clone(),
+                action_type: "score".into(),
+                constraints: HashMap::new(),
+                timeout_ms: None,
+            }],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": player_index}),
+        }
+    }
+
+    impl TypedGamePlugin for Stalling {
+        /// Moves made so far.
+        type State = u32;
+
+        fn game_id(&self) -> &str { "stalling" }
+        fn display_name(&self) -> &str { "Stalling" }
+        fn min_players(&self) -> u32 { 2 }
+        fn max_players(&self) -> u32 { 2 }
+        fn description(&self) -> &str { "" }
+        fn disconnect_policy(&self) -> &str { "abandon_all" }
+        fn decode_state(&self, _game_data: &serde_json::Value) -> u32 { 0 }
+        fn encode_state(&self, state: &u32) -> serde_json::Value { serde_json::json!(state) }
+
+        fn create_initial_state(&self, players: &[Player], _config: &GameConfig) -> (u32, Phase, Vec<Event>) {
+            (0, stalling_phase(players, 0), vec![])
+        }
+
+        fn get_valid_actions(&self, _state: &u32, _phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+            vec![serde_json::json!({})]
+        }
+
+        fn validate_action(&self, _state: &u32, _phase: &Phase, _action: &Action) -> Option<String> {
+            None
+        }
+
+        fn apply_action(&self, state: &u32, _phase: &Phase, _action: &Action, players: &[Player]) -> Result<TypedTransitionResult<u32>, String> {
+            let moves = state + 1;
+            Ok(TypedTransitionResult {
+                state: moves,
+                events: vec![],
+                next_phase: stalling_phase(players, moves),
+                scores: players
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (p.player_id.clone(), ((moves as usize + 1 - i) / 2) as f64))
+                    .collect(),
+                game_over: None,
+            })
+        }
+
+        fn get_player_view(&self, _state: &u32, _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+            serde_json::json!({})
+        }
+
+        fn get_scores(&self, _state: &u32) -> HashMap<String, f64> {
+            HashMap::new()
+        }
+
+        fn parse_ai_action(&self, response: &serde_json::Value, phase: &Phase, player_id: &str) -> Action {
+            Action { action_type: phase.name.clone(), player_id: player_id.into(), payload: response.clone() }
+        }
+    }
+
+    #[test]
+    fn test_move_limit_adjudicates_stalled_games() {
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<Stalling>>> = HashMap::new();
+        strategies.insert("a".into(), Box::new(RandomStrategy));
+        strategies.insert("b".into(), Box::new(RandomStrategy));
+
+        // After 11 moves seat 0 leads 6-5, so whoever sits there wins.
+        let result = run_arena(&Stalling, &strategies, 4, 1, 2, None, true, true, 11, None);
+        assert_eq!(result.adjudicated, 4);
+        assert_eq!(result.wins["a"], 2);
+        assert_eq!(result.wins["b"], 2);
+        assert_eq!(result.seat_win_rates("a"), vec![1.0, 0.0]);
+        assert!(result.game_logs.iter().all(|log| log.actions.len() == 11));
+        // Parallel runs honor the same options
+        let parallel = run_arena_parallel(&Stalling, &strategies, 4, 1, 2, None, true, true, 11, 2, None);
+        assert_eq!(parallel.adjudicated, 4);
+        assert_eq!(parallel.wins, result.wins);
+        assert_eq!(parallel.game_logs.len(), 4);
+        assert!(parallel.game_logs.iter().all(|log| log.actions.len() == 11));
+
+        // After 10 moves the score is tied: a draw.
+        let result = run_arena(&Stalling, &strategies, 2, 1, 2, None, true, false, 10, None);
+        assert_eq!((result.adjudicated, result.draws), (2, 2));
+        assert!(result.summary().contains("Adjudicated"));
+    }
+
+    /// `Stalling` with phases that name the mover only in `metadata.player_index`.
+    struct Unannounced;
+
+    fn unannounced(mut phase: Phase) -> Phase {
+        phase.expected_actions.clear();
+        phase
+    }
+
+    impl TypedGamePlugin for Unannounced {
+        type State = u32;
+
+        fn game_id(&self) -> &str { "unannounced" }
+        fn display_name(&self) -> &str { "Unannounced" }
+        fn min_players(&self) -> u32 { 2 }
+        fn max_players(&self) -> u32 { 2 }
+        fn description(&self) -> &str { "" }
+        fn disconnect_policy(&self) -> &str { "abandon_all" }
+        fn decode_state(&self, game_data: &serde_json::Value) -> u32 { Stalling.decode_state(game_data) }
+        fn encode_state(&self, state: &u32) -> serde_json::Value { Stalling.encode_state(state) }
+
+        fn create_initial_state(&self, players: &[Player], config: &GameConfig) -> (u32, Phase, Vec<Event>) {
+            let (state, phase, events) = Stalling.create_initial_state(players, config);
+            (state, unannounced(phase), events)
+        }
+
+        fn get_valid_actions(&self, state: &u32, phase: &Phase, player_id: &str) -> Vec<serde_json::Value> {
+            Stalling.get_valid_actions(state, phase, player_id)
+        }
+
+        fn validate_action(&self, state: &u32, phase: &Phase, action: &Action) -> Option<String> {
+            Stalling.validate_action(state, phase, action)
+        }
+
+        fn apply_action(&self, state: &u32, phase: &Phase, action: &Action, players: &[Player]) -> Result<TypedTransitionResult<u32>, String> {
+            let mut result = Stalling.apply_action(state, phase, action, players)?;
+            result.next_phase = unannounced(result.next_phase);
+            Ok(result)
+        }
+
+        fn get_player_view(&self, state: &u32, phase: &Phase, player_id: Option<&str>, players: &[Player]) -> serde_json::Value {
+            Stalling.get_player_view(state, phase, player_id, players)
+        }
+
+        fn get_scores(&self, state: &u32) -> HashMap<String, f64> {
+            Stalling.get_scores(state)
+        }
+
+        fn parse_ai_action(&self, response: &serde_json::Value, phase: &Phase, player_id: &str) -> Action {
+            Stalling.parse_ai_action(response, phase, player_id)
+        }
+    }
+
+    #[test]
+    fn test_mover_from_phase_metadata() {
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<Unannounced>>> = HashMap::new();
+        strategies.insert("a".into(), Box::new(RandomStrategy));
+        strategies.insert("b".into(), Box::new(RandomStrategy));
+
+        // The arena asks `acting_player`, which falls back to the phase metadata
+        let result = run_arena(&Unannounced, &strategies, 2, 1, 2, None, true, true, 11, None);
+        assert_eq!(result.adjudicated, 2);
+        for log in &result.game_logs {
+            assert_eq!(log.actions.len(), 11);
+            assert!(log.actions.windows(2).all(|w| w[0].player_id != w[1].player_id));
+        }
+    }
+
+    #[test]
+    fn test_same_seed_replays_identically() {
+        let plugin = CarcassonnePlugin;
+        let mcts = MctsStrategy::<CarcassonnePlugin>::with_eval(
+            MctsParams {
+                num_simulations: 40,
+                time_limit_ms: 999999.0,
+                num_determinizations: 3,
+                ..Default::default()
+            },
+            make_carcassonne_eval(DEFAULT_WEIGHTS),
+        );
+        let players: Vec<Player> = (0..2)
+            .map(|i| Player {
+                player_id: format!("p{}", i),
+                display_name: format!("p{}", i),
+                seat_index: i,
+                is_bot: true,
+                bot_id: None,
+            })
+            .collect();
+        let pid_to_strategy: HashMap<String, &dyn BotStrategy<CarcassonnePlugin>> =
+            [("p0".to_string(), &mcts as &dyn BotStrategy<_>), ("p1".to_string(), &RandomStrategy)]
+                .into_iter()
+                .collect();
+
+        let play = |seed: u64| {
+            let config = GameConfig {
+                random_seed: Some(seed),
+                options: serde_json::json!({"tile_count": 8}),
+            };
+            let mut transcript = Vec::new();
+            let result = play_one_game(&plugin, &players, &config, &pid_to_strategy, Some(&mut transcript));
+            let actions: Vec<String> = transcript
+                .iter()
+                .map(|e| serde_json::to_string(&e.action).unwrap())
+                .collect();
+            (actions, result.map(|r| (r.winners, r.final_scores)))
+        };
+
+        let first = play(11);
+        assert!(first.1.is_some());
+        for _ in 0..3 {
+            assert_eq!(play(11), first);
+        }
+        assert_ne!(play(12).0, first.0);
+    }
+
     #[test]
     #[ignore] // slow (~60s) — runs in nightly CI
     fn test_arena_pw_comparison() {
@@ -317,8 +1593,8 @@ mod tests {
         ];
 
         for (label, pw_c) in &pw_configs {
-            let eval_fn_a = make_carcassonne_eval(&DEFAULT_WEIGHTS);
-            let eval_fn_b = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+            let eval_fn_a = make_carcassonne_eval(DEFAULT_WEIGHTS);
+            let eval_fn_b = make_carcassonne_eval(DEFAULT_WEIGHTS);
 
             let strat_a = MctsStrategy::<CarcassonnePlugin>::with_eval(
                 MctsParams {
@@ -348,7 +1624,7 @@ mod tests {
             strategies.insert("B".into(), Box::new(strat_b));
 
             let t0 = std::time::Instant::now();
-            let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, None);
+            let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
             let elapsed = t0.elapsed();
 
             let avg_a = result.avg_score("A");
@@ -359,6 +1635,40 @@ mod tests {
         }
     }
 
+    #[test]
+    #[ignore] // slow (~60s) — runs in nightly CI
+    fn test_arena_fpu_mode_comparison() {
+        // Each RAVE FPU mode against the default (AMAF-optimistic) FPU
+        let plugin = CarcassonnePlugin;
+        let num_games = 4;
+        let options = Some(serde_json::json!({"tile_count": 24}));
+        let rave_params = |fpu_mode| MctsParams {
+            num_simulations: 200,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            use_rave: true,
+            fpu_mode,
+            ..Default::default()
+        };
+
+        for mode in [FpuMode::Infinity, FpuMode::AmafOptimistic, FpuMode::ParentValue] {
+            let mut strategies: HashMap<String, Box<dyn BotStrategy<CarcassonnePlugin>>> = HashMap::new();
+            strategies.insert("mode".into(), Box::new(MctsStrategy::<CarcassonnePlugin>::with_eval(
+                rave_params(mode),
+                make_carcassonne_eval(DEFAULT_WEIGHTS),
+            )));
+            strategies.insert("baseline".into(), Box::new(MctsStrategy::<CarcassonnePlugin>::with_eval(
+                rave_params(FpuMode::AmafOptimistic),
+                make_carcassonne_eval(DEFAULT_WEIGHTS),
+            )));
+
+            let result = run_arena(&plugin, &strategies, num_games, 42, 2, options.clone(), true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+            assert_eq!(result.num_games, num_games);
+            println!("{:?} vs AmafOptimistic: win rate {:.2}, avg score {:.1} vs {:.1}",
+                mode, result.win_rate("mode"), result.avg_score("mode"), result.avg_score("baseline"));
+        }
+    }
+
     #[test]
     #[ignore] // slow (~60s) — runs in nightly CI
     fn test_mcts_per_game_trace() {
@@ -368,8 +1678,8 @@ mod tests {
 
         for pw_c in [1.0f64, 2.0, 100.0] {
             println!("\n=== pw_c={} (1 det, 500 sims) ===", pw_c);
-            let eval_fn_a = make_carcassonne_eval(&DEFAULT_WEIGHTS);
-            let eval_fn_b = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+            let eval_fn_a = make_carcassonne_eval(DEFAULT_WEIGHTS);
+            let eval_fn_b = make_carcassonne_eval(DEFAULT_WEIGHTS);
 
             let strat_a = MctsStrategy::<CarcassonnePlugin>::with_eval(
                 MctsParams {
@@ -398,7 +1708,7 @@ mod tests {
             strategies.insert("A".into(), Box::new(strat_a));
             strategies.insert("B".into(), Box::new(strat_b));
 
-            let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, None);
+            let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
 
             let scores_a = result.total_scores.get("A").unwrap();
             let scores_b = result.total_scores.get("B").unwrap();
@@ -423,7 +1733,7 @@ mod tests {
         strategies.insert("A".into(), Box::new(RandomStrategy));
         strategies.insert("B".into(), Box::new(RandomStrategy));
 
-        let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, None);
+        let result = run_arena(&plugin, &strategies, num_games, 42, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
 
         println!("\nRandom play scores ({} games):", num_games);
         let scores_a = result.total_scores.get("A").unwrap();
@@ -6,8 +6,8 @@ use std::path::Path;
 
 use serde::Deserialize;
 
-use crate::engine::mcts::MctsParams;
-use crate::games::carcassonne::evaluator::EvalWeights;
+use crate::engine::mcts::{FinalSelection, FpuMode, MctsParams};
+use crate::games::carcassonne::evaluator::{EvalWeights, EVAL_PROFILES};
 
 /// A named bot profile combining MCTS parameters and evaluator configuration.
 #[derive(Debug, Deserialize, Clone)]
@@ -23,16 +23,48 @@ pub struct BotProfile {
     pub num_determinizations: Option<usize>,
     pub pw_c: Option<f64>,
     pub pw_alpha: Option<f64>,
+    pub pw_min_children: Option<usize>,
+    /// Phase name -> `[pw_c, pw_alpha]`.
+    pub pw_overrides: Option<HashMap<String, (f64, f64)>>,
     pub use_rave: Option<bool>,
     pub rave_k: Option<f64>,
     pub max_amaf_depth: Option<usize>,
+    /// Legacy switch: true = `amaf_optimistic`, false = `infinity`. `fpu_mode` wins.
     pub rave_fpu: Option<bool>,
+    pub fpu_mode: Option<FpuMode>,
     pub tile_aware_amaf: Option<bool>,
+    pub use_pruning_hints: Option<bool>,
+    pub selection_temperature: Option<f64>,
+    pub endgame_branching_threshold: Option<usize>,
+    pub endgame_sim_multiplier: Option<f64>,
+    pub weight_dets_by_iterations: Option<bool>,
+    pub max_tree_depth: Option<usize>,
+    pub opponent_rationality: Option<f64>,
+    pub max_parallel_determinizations: Option<usize>,
+    pub expand_all_root_children: Option<bool>,
+    pub use_transpositions: Option<bool>,
+    pub use_solver: Option<bool>,
+    pub final_selection: Option<FinalSelection>,
+    pub root_dirichlet_alpha: Option<f64>,
+    pub root_exploration_fraction: Option<f64>,
+    /// `determinization_seed` is the name from before the search had one seed.
+    #[serde(alias = "determinization_seed")]
+    pub seed: Option<u64>,
+    pub rollout_depth: Option<usize>,
+    pub threads_per_tree: Option<usize>,
+    pub early_stop: Option<bool>,
+    pub decisive_moves: Option<bool>,
 
     /// Named evaluator preset: "default", "aggressive", "field_heavy", "conservative".
     pub eval_profile: Option<String>,
     /// Custom eval weights (overrides eval_profile when present).
     pub eval_weights: Option<EvalWeights>,
+
+    /// Phase name -> MCTS param overrides on top of this profile's, e.g. a
+    /// `[profiles.strong.phases.place_meeple]` table with fewer simulations.
+    /// Only the MCTS fields of a phase table are used.
+    #[serde(default)]
+    pub phases: HashMap<String, BotProfile>,
 }
 
 fn default_strategy_type() -> String {
@@ -60,7 +92,30 @@ pub struct BotProfilesFile {
 impl BotProfile {
     /// Convert to MctsParams, using defaults for any unspecified fields.
     pub fn to_mcts_params(&self) -> MctsParams {
-        let d = MctsParams::default();
+        self.override_params(MctsParams::default())
+    }
+
+    /// Per-phase MctsParams: each `phases` table applied over `to_mcts_params`.
+    #[allow(dead_code)]
+    pub fn phase_mcts_params(&self) -> HashMap<String, MctsParams> {
+        let base = self.to_mcts_params();
+        self.phases
+            .iter()
+            .map(|(phase, overrides)| (phase.clone(), overrides.override_params(base.clone())))
+            .collect()
+    }
+
+    /// MctsParams for a search in `phase`: its `phases` table, if any, over `to_mcts_params`.
+    pub fn mcts_params_for(&self, phase: &str) -> MctsParams {
+        let base = self.to_mcts_params();
+        match self.phases.get(phase) {
+            Some(overrides) => overrides.override_params(base),
+            None => base,
+        }
+    }
+
+    /// `d` with every field this profile sets replaced.
+    fn override_params(&self, d: MctsParams) -> MctsParams {
         MctsParams {
             num_simulations: self.num_simulations.unwrap_or(d.num_simulations),
             time_limit_ms: self.time_limit_ms.unwrap_or(d.time_limit_ms),
@@ -68,11 +123,70 @@ impl BotProfile {
             num_determinizations: self.num_determinizations.unwrap_or(d.num_determinizations),
             pw_c: self.pw_c.unwrap_or(d.pw_c),
             pw_alpha: self.pw_alpha.unwrap_or(d.pw_alpha),
+            pw_min_children: self.pw_min_children.unwrap_or(d.pw_min_children),
+            pw_overrides: self.pw_overrides.clone().unwrap_or(d.pw_overrides),
             use_rave: self.use_rave.unwrap_or(d.use_rave),
             rave_k: self.rave_k.unwrap_or(d.rave_k),
             max_amaf_depth: self.max_amaf_depth.unwrap_or(d.max_amaf_depth),
-            rave_fpu: self.rave_fpu.unwrap_or(d.rave_fpu),
+            fpu_mode: self
+                .fpu_mode
+                .or(self.rave_fpu.map(FpuMode::from_rave_fpu))
+                .unwrap_or(d.fpu_mode),
             tile_aware_amaf: self.tile_aware_amaf.unwrap_or(d.tile_aware_amaf),
+            use_pruning_hints: self.use_pruning_hints.unwrap_or(d.use_pruning_hints),
+            selection_temperature: self.selection_temperature.unwrap_or(d.selection_temperature),
+            endgame_branching_threshold: self
+                .endgame_branching_threshold
+                .unwrap_or(d.endgame_branching_threshold),
+            endgame_sim_multiplier: self.endgame_sim_multiplier.unwrap_or(d.endgame_sim_multiplier),
+            weight_dets_by_iterations: self
+                .weight_dets_by_iterations
+                .unwrap_or(d.weight_dets_by_iterations),
+            max_tree_depth: self.max_tree_depth.or(d.max_tree_depth),
+            opponent_rationality: self.opponent_rationality.unwrap_or(d.opponent_rationality),
+            max_parallel_determinizations: self
+                .max_parallel_determinizations
+                .or(d.max_parallel_determinizations),
+            expand_all_root_children: self
+                .expand_all_root_children
+                .unwrap_or(d.expand_all_root_children),
+            use_transpositions: self.use_transpositions.unwrap_or(d.use_transpositions),
+            use_solver: self.use_solver.unwrap_or(d.use_solver),
+            final_selection: self.final_selection.unwrap_or(d.final_selection),
+            root_dirichlet_alpha: self.root_dirichlet_alpha.unwrap_or(d.root_dirichlet_alpha),
+            root_exploration_fraction: self
+                .root_exploration_fraction
+                .unwrap_or(d.root_exploration_fraction),
+            seed: self.seed.or(d.seed),
+            rollout_depth: self.rollout_depth.unwrap_or(d.rollout_depth),
+            threads_per_tree: self.threads_per_tree.unwrap_or(d.threads_per_tree),
+            early_stop: self.early_stop.unwrap_or(d.early_stop),
+            decisive_moves: self.decisive_moves.unwrap_or(d.decisive_moves),
+        }
+    }
+
+    /// Problems with this profile's settings, each prefixed with `name`.
+    fn check(&self, name: &str, problems: &mut Vec<String>) {
+        if self.num_simulations == Some(0) {
+            problems.push(format!("{name}: num_simulations must be at least 1"));
+        }
+        if self.num_determinizations == Some(0) {
+            problems.push(format!("{name}: num_determinizations must be at least 1"));
+        }
+        if let Some(t) = self.time_limit_ms.filter(|t| t.is_nan() || *t <= 0.0) {
+            problems.push(format!("{name}: time_limit_ms must be positive, got {t}"));
+        }
+        if let Some(profile) = self.eval_profile.as_deref() {
+            if !profile.is_empty() && !EVAL_PROFILES.contains(&profile) {
+                problems.push(format!(
+                    "{name}: unknown eval_profile '{profile}' (expected one of {EVAL_PROFILES:?} or \"\")"
+                ));
+            }
+        }
+        let mut phases: Vec<_> = self.phases.iter().collect();
+        phases.sort_by(|a, b| a.0.cmp(b.0));
+        for (phase, overrides) in phases {
+            overrides.check(&format!("{name}.phases.{phase}"), problems);
         }
     }
 
@@ -82,11 +196,41 @@ impl BotProfile {
     }
 }
 
-/// Load profiles from a TOML file at the given path.
+impl BotProfilesFile {
+    /// Reject settings that parse but can't work: no simulations or
+    /// determinizations, a non-positive time limit, an unknown eval profile, or
+    /// a production tier naming a missing profile. Lists every problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        let mut names: Vec<_> = self.profiles.keys().collect();
+        names.sort();
+        for name in names {
+            self.profiles[name].check(&format!("profiles.{name}"), &mut problems);
+        }
+        let prod = &self.production;
+        for (tier, profile) in [("easy", &prod.easy), ("medium", &prod.medium), ("hard", &prod.hard), ("default", &prod.default)] {
+            if let Some(profile) = profile.as_deref().filter(|p| !self.profiles.contains_key(*p)) {
+                problems.push(format!("production.{tier}: unknown profile '{profile}'"));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// Load and validate profiles from a TOML file at the given path.
 pub fn load_profiles(path: &Path) -> Result<BotProfilesFile, String> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
-    toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    let profiles: BotProfilesFile =
+        toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    profiles
+        .validate()
+        .map_err(|e| format!("Invalid bot profiles in {}: {}", path.display(), e))?;
+    Ok(profiles)
 }
 
 /// Try to load profiles from well-known paths, returning a default if none found.
@@ -128,3 +272,87 @@ impl ProductionConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::bot_strategy::MctsStrategy;
+    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+    fn load(toml: &str) -> Result<BotProfilesFile, String> {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(toml.as_bytes()).unwrap();
+        load_profiles(file.path())
+    }
+
+    #[test]
+    fn test_invalid_profiles_rejected() {
+        let err = load(
+            r#"
+            [profiles.fast]
+            num_determinizations = 0
+            eval_profile = "reckless"
+            [profiles.fast.phases.place_meeple]
+            time_limit_ms = -1.0
+            [production]
+            hard = "missing"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.starts_with("Invalid bot profiles in "), "{err}");
+        for expected in [
+            "profiles.fast: num_determinizations must be at least 1",
+            "profiles.fast: unknown eval_profile 'reckless'",
+            "profiles.fast.phases.place_meeple: time_limit_ms must be positive, got -1",
+            "production.hard: unknown profile 'missing'",
+        ] {
+            assert!(err.contains(expected), "missing {expected:?} in {err}");
+        }
+
+        // Negative counts don't even parse, and the error points at the field
+        let err = load("[profiles.fast]\nnum_simulations = -5\n").unwrap_err();
+        assert!(err.contains("Failed to parse") && err.contains("num_simulations"), "{err}");
+
+        assert!(load("[profiles.ok]\nnum_simulations = 10\neval_profile = \"\"\n[production]\ndefault = \"ok\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_repo_profiles_are_valid() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("bot_profiles.toml");
+        load_profiles(&path).unwrap();
+    }
+
+    #[test]
+    fn test_phase_overrides() {
+        let file: BotProfilesFile = toml::from_str(
+            r#"
+            [profiles.strong]
+            num_simulations = 800
+            time_limit_ms = 5000
+            [profiles.strong.phases.place_meeple]
+            num_simulations = 100
+            "#,
+        )
+        .unwrap();
+        let profile = &file.profiles["strong"];
+        let phases = profile.phase_mcts_params();
+        assert_eq!(phases.len(), 1);
+        // Unset fields come from the profile, not from MctsParams::default()
+        assert_eq!(phases["place_meeple"].time_limit_ms, 5000.0);
+
+        let strategy = MctsStrategy::<CarcassonnePlugin>::new(profile.to_mcts_params()).with_phase_params(phases);
+        let tile = strategy.params_for("place_tile").num_simulations;
+        let meeple = strategy.params_for("place_meeple").num_simulations;
+        assert_eq!((tile, meeple), (800, 100));
+        assert_eq!(profile.mcts_params_for("place_meeple").num_simulations, 100);
+        assert_eq!(profile.mcts_params_for("place_tile").num_simulations, 800);
+    }
+
+    #[test]
+    fn test_legacy_seed_name() {
+        let file: BotProfilesFile = toml::from_str("[profiles.seeded]\ndeterminization_seed = 7").unwrap();
+        assert_eq!(file.profiles["seeded"].to_mcts_params().seed, Some(7));
+    }
+}
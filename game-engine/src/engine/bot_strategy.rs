@@ -1,13 +1,20 @@
 //! Bot strategy trait and implementations.
 //! Mirrors backend/src/engine/bot_strategy.py.
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use rand::seq::SliceRandom;
+use rand::RngCore;
 
-use crate::engine::mcts::{mcts_search, MctsParams};
+use crate::engine::mcts::{mcts_search, mcts_search_reusing, MctsParams, SearchTrees};
 use crate::engine::models::*;
-use crate::engine::plugin::TypedGamePlugin;
+use crate::engine::plugin::{EvalFn, TypedGamePlugin};
+use crate::engine::rng::GameRng;
+use crate::engine::simulator::{apply_action_and_resolve, SimulationState};
 
 /// A bot strategy selects an action payload given the current typed game state.
+/// All randomness comes from `rng`, so a seeded caller gets reproducible choices.
 pub trait BotStrategy<P: TypedGamePlugin>: Send + Sync {
     fn choose_action(
         &self,
@@ -16,6 +23,7 @@ pub trait BotStrategy<P: TypedGamePlugin>: Send + Sync {
         player_id: &str,
         plugin: &P,
         players: &[Player],
+        rng: &mut GameRng,
     ) -> serde_json::Value;
 }
 
@@ -30,31 +38,290 @@ impl<P: TypedGamePlugin> BotStrategy<P> for RandomStrategy {
         player_id: &str,
         plugin: &P,
         _players: &[Player],
+        rng: &mut GameRng,
     ) -> serde_json::Value {
         let valid = plugin.get_valid_actions(state, phase, player_id);
         if valid.is_empty() {
             return serde_json::json!({});
         }
-        let mut rng = rand::thread_rng();
-        valid.choose(&mut rng).cloned().unwrap_or(serde_json::json!({}))
+        valid.choose(rng).cloned().unwrap_or(serde_json::json!({}))
+    }
+}
+
+/// Plays the valid action whose resulting position `eval_fn` rates best, one
+/// ply deep (auto-resolve phases included). Ties are broken at random.
+pub struct GreedyStrategy<P: TypedGamePlugin> {
+    pub eval_fn: EvalFn<P::State>,
+}
+
+impl<P: TypedGamePlugin> GreedyStrategy<P> {
+    #[allow(dead_code)]
+    pub fn new(eval_fn: EvalFn<P::State>) -> Self {
+        Self { eval_fn }
+    }
+}
+
+impl<P: TypedGamePlugin> BotStrategy<P> for GreedyStrategy<P> {
+    fn choose_action(
+        &self,
+        state: &P::State,
+        phase: &Phase,
+        player_id: &str,
+        plugin: &P,
+        players: &[Player],
+        rng: &mut GameRng,
+    ) -> serde_json::Value {
+        // Look ahead from a sampled state so hidden information (the draw order) isn't peeked at
+        let mut root_state = state.clone();
+        plugin.determinize(&mut root_state, rng);
+        let root = SimulationState {
+            state: root_state,
+            phase: phase.clone(),
+            players: players.into(),
+            scores: plugin.get_scores(state),
+            game_over: None,
+            history: None,
+        };
+        let mut best: Vec<serde_json::Value> = Vec::new();
+        let mut best_value = f64::NEG_INFINITY;
+        for payload in plugin.get_valid_actions(state, phase, player_id) {
+            let action = Action {
+                action_type: plugin.action_type_for(phase, &payload),
+                player_id: player_id.into(),
+                payload,
+            };
+            let mut sim = root.clone();
+            if apply_action_and_resolve(plugin, &mut sim, &action).is_err() {
+                continue;
+            }
+            let value = match &sim.game_over {
+                Some(result) => plugin.value_for(result, player_id),
+                None => (self.eval_fn)(&sim.state, &sim.phase, player_id, players),
+            };
+            if value > best_value {
+                best_value = value;
+                best.clear();
+            }
+            if value == best_value {
+                best.push(action.payload);
+            }
+        }
+        best.choose(rng).cloned().unwrap_or(serde_json::json!({}))
+    }
+}
+
+/// Exact minimax with alpha-beta pruning for perfect-information games.
+/// Searches `max_depth` decisions deep (the whole game when None), valuing
+/// finished games with `value_for` and cut-off positions with `eval_fn` (0.5
+/// without one). Every other player is treated as an opponent. Positions seen
+/// during a search are cached by `state_hash`. Ties are broken at random.
+pub struct AlphaBetaStrategy<P: TypedGamePlugin> {
+    pub max_depth: Option<usize>,
+    pub eval_fn: Option<EvalFn<P::State>>,
+}
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// A searched position: its value `depth` decisions deep, or a bound on it
+/// when the search was cut off.
+#[derive(Clone, Copy)]
+struct CacheEntry {
+    depth: usize,
+    value: f64,
+    bound: Bound,
+}
+
+type SearchCache = HashMap<(u64, String), CacheEntry>;
+
+impl<P: TypedGamePlugin> AlphaBetaStrategy<P> {
+    /// Fails for games with hidden information, where minimax would search
+    /// positions the player cannot actually see.
+    #[allow(dead_code)]
+    pub fn new(plugin: &P, max_depth: Option<usize>, eval_fn: Option<EvalFn<P::State>>) -> Result<Self, String> {
+        if !plugin.is_perfect_information() {
+            return Err(format!("{} is not a perfect-information game", plugin.game_id()));
+        }
+        Ok(Self { max_depth, eval_fn })
+    }
+
+    fn leaf_value(&self, sim: &SimulationState<P::State>, player_id: &str) -> f64 {
+        match &self.eval_fn {
+            Some(eval_fn) => eval_fn(&sim.state, &sim.phase, player_id, &sim.players),
+            None => 0.5,
+        }
+    }
+
+    /// Value of `sim` for `player_id`, searched `depth` decisions deep.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        plugin: &P,
+        sim: &SimulationState<P::State>,
+        player_id: &str,
+        depth: usize,
+        mut alpha: f64,
+        mut beta: f64,
+        cache: &mut SearchCache,
+    ) -> f64 {
+        if let Some(result) = &sim.game_over {
+            return plugin.value_for(result, player_id);
+        }
+        let Some(mover) = plugin.acting_player(&sim.state, &sim.phase, &sim.players) else {
+            return self.leaf_value(sim, player_id);
+        };
+        if depth == 0 {
+            return self.leaf_value(sim, player_id);
+        }
+        let key = (plugin.state_hash(&sim.state), mover);
+        if let Some(entry) = cache.get(&key).filter(|e| e.depth >= depth) {
+            match entry.bound {
+                Bound::Exact => return entry.value,
+                Bound::Lower => alpha = alpha.max(entry.value),
+                Bound::Upper => beta = beta.min(entry.value),
+            }
+            if alpha >= beta {
+                return entry.value;
+            }
+        }
+
+        let (alpha_in, beta_in) = (alpha, beta);
+        let maximizing = key.1 == player_id;
+        let mut best = if maximizing { f64::NEG_INFINITY } else { f64::INFINITY };
+        for payload in plugin.get_valid_actions(&sim.state, &sim.phase, &key.1) {
+            let Some(child) = play(plugin, sim, &key.1, payload) else {
+                continue;
+            };
+            let value = self.search(plugin, &child, player_id, depth - 1, alpha, beta, cache);
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(value);
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        if best.is_infinite() {
+            // Nothing playable: stuck, so value it as a cut-off
+            return self.leaf_value(sim, player_id);
+        }
+        let bound = if best <= alpha_in {
+            Bound::Upper
+        } else if best >= beta_in {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        cache.insert(key, CacheEntry { depth, value: best, bound });
+        best
+    }
+}
+
+/// `sim` after `player_id` plays `payload`, or None if it cannot be applied.
+fn play<P: TypedGamePlugin>(
+    plugin: &P,
+    sim: &SimulationState<P::State>,
+    player_id: &str,
+    payload: serde_json::Value,
+) -> Option<SimulationState<P::State>> {
+    let action = Action {
+        action_type: plugin.action_type_for(&sim.phase, &payload),
+        player_id: player_id.into(),
+        payload,
+    };
+    let mut child = sim.clone();
+    apply_action_and_resolve(plugin, &mut child, &action).ok()?;
+    Some(child)
+}
+
+impl<P: TypedGamePlugin> BotStrategy<P> for AlphaBetaStrategy<P> {
+    fn choose_action(
+        &self,
+        state: &P::State,
+        phase: &Phase,
+        player_id: &str,
+        plugin: &P,
+        players: &[Player],
+        rng: &mut GameRng,
+    ) -> serde_json::Value {
+        let root = SimulationState {
+            state: state.clone(),
+            phase: phase.clone(),
+            players: players.into(),
+            scores: plugin.get_scores(state),
+            game_over: None,
+            history: None,
+        };
+        let depth = self.max_depth.unwrap_or(usize::MAX).max(1);
+        let mut cache = SearchCache::new();
+        // Shuffled so equally good moves are picked at random: only a strictly
+        // better move replaces the first best one found
+        let mut actions = plugin.get_valid_actions(state, phase, player_id);
+        actions.shuffle(rng);
+        let mut best = None;
+        let mut best_value = f64::NEG_INFINITY;
+        for payload in actions {
+            let Some(child) = play(plugin, &root, player_id, payload.clone()) else {
+                continue;
+            };
+            let value = self.search(plugin, &child, player_id, depth - 1, best_value, f64::INFINITY, &mut cache);
+            if best.is_none() || value > best_value {
+                best_value = value;
+                best = Some(payload);
+            }
+        }
+        best.unwrap_or(serde_json::json!({}))
     }
 }
 
 /// Wraps the MCTS engine as a BotStrategy.
 pub struct MctsStrategy<P: TypedGamePlugin> {
     pub params: MctsParams,
+    /// Phase name -> params used instead of `params` in that phase.
+    pub phase_params: HashMap<String, MctsParams>,
     pub eval_fn: Option<Box<dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Send + Sync>>,
+    /// Trees from the last search, when tree reuse is on.
+    trees: Option<Mutex<SearchTrees<P::State>>>,
 }
 
 impl<P: TypedGamePlugin> MctsStrategy<P> {
     #[allow(dead_code)]
     pub fn new(params: MctsParams) -> Self {
-        Self { params, eval_fn: None }
+        Self { params, phase_params: HashMap::new(), eval_fn: None, trees: None }
     }
 
     #[allow(dead_code)]
     pub fn with_eval(params: MctsParams, eval_fn: Box<dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Send + Sync>) -> Self {
-        Self { params, eval_fn: Some(eval_fn) }
+        Self { params, phase_params: HashMap::new(), eval_fn: Some(eval_fn), trees: None }
+    }
+
+    /// Search with different params in some phases (e.g. fewer simulations for
+    /// meeple placement than for tile placement).
+    #[allow(dead_code)]
+    pub fn with_phase_params(mut self, phase_params: HashMap<String, MctsParams>) -> Self {
+        self.phase_params = phase_params;
+        self
+    }
+
+    /// Params for a search in `phase`: its override if any, else `params`.
+    pub fn params_for(&self, phase: &str) -> &MctsParams {
+        self.phase_params.get(phase).unwrap_or(&self.params)
+    }
+
+    /// Keep each search's trees and continue from the subtree of the position
+    /// reached on the next move (see `mcts_search_reusing`). Meant for one game
+    /// at a time: searches hold the trees' lock, so concurrent games serialize.
+    #[allow(dead_code)]
+    pub fn with_tree_reuse(mut self) -> Self {
+        self.trees = Some(Mutex::new(SearchTrees::default()));
+        self
     }
 }
 
@@ -66,10 +333,21 @@ impl<P: TypedGamePlugin> BotStrategy<P> for MctsStrategy<P> {
         player_id: &str,
         plugin: &P,
         players: &[Player],
+        rng: &mut GameRng,
     ) -> serde_json::Value {
         let eval_ref: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)> =
             self.eval_fn.as_ref().map(|f| f.as_ref() as &(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync));
-        let (action, _iterations) = mcts_search(state, phase, player_id, plugin, players, &self.params, eval_ref);
-        action
+        // A fixed seed in the params wins; otherwise draw one per move.
+        let mut params = self.params_for(&phase.name).clone();
+        params.seed.get_or_insert_with(|| rng.next_u64());
+        let action = match &self.trees {
+            Some(trees) => {
+                let mut trees = trees.lock().unwrap_or_else(|e| e.into_inner());
+                mcts_search_reusing(state, phase, player_id, plugin, players, &params, eval_ref, &mut trees)
+            }
+            None => mcts_search(state, phase, player_id, plugin, players, &params, eval_ref),
+        };
+        // A bot has no way to report a failed search; the plugin bug stops the game.
+        action.unwrap_or_else(|e| panic!("{e}")).0
     }
 }
@@ -1,7 +1,12 @@
 //! Monte Carlo Tree Search engine with determinization, progressive widening, and RAVE.
 //! Mirrors backend/src/engine/mcts.py.
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
 
 use rayon::prelude::*;
@@ -9,10 +14,82 @@ use rayon::prelude::*;
 use crate::engine::evaluator::default_eval;
 use crate::engine::models::*;
 use crate::engine::plugin::TypedGamePlugin;
+use crate::engine::rng::GameRng;
 use crate::engine::simulator::{apply_action_and_resolve, SimulationState};
 
+/// First-play urgency: the selection value RAVE gives a child with no visits yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FpuMode {
+    /// Always +inf, so every child is tried once before any is revisited.
+    Infinity,
+    /// `1.0 + amaf_q` from the parent's AMAF stats; +inf when it has none.
+    #[default]
+    AmafOptimistic,
+    /// The parent's mean value, from the child's acting player's side.
+    ParentValue,
+}
+
+impl FpuMode {
+    /// Mode selected by the legacy `rave_fpu` flag.
+    pub fn from_rave_fpu(rave_fpu: bool) -> Self {
+        if rave_fpu { FpuMode::AmafOptimistic } else { FpuMode::Infinity }
+    }
+}
+
+impl std::str::FromStr for FpuMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "infinity" => Ok(FpuMode::Infinity),
+            "amaf_optimistic" => Ok(FpuMode::AmafOptimistic),
+            "parent_value" => Ok(FpuMode::ParentValue),
+            _ => Err(format!("unknown FPU mode '{s}' (expected infinity, amaf_optimistic or parent_value)")),
+        }
+    }
+}
+
+/// How the final move is picked from the pooled root statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalSelection {
+    /// Most visits, ties broken on mean value.
+    #[default]
+    MaxVisits,
+    /// Highest mean value, ties broken on visits.
+    MaxValue,
+    /// Most visits among the moves whose mean value is within
+    /// `ROBUST_VALUE_MARGIN` of the best one.
+    Robust,
+}
+
+impl std::str::FromStr for FinalSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max_visits" => Ok(FinalSelection::MaxVisits),
+            "max_value" => Ok(FinalSelection::MaxValue),
+            "robust" => Ok(FinalSelection::Robust),
+            _ => Err(format!("unknown final selection '{s}' (expected max_visits, max_value or robust)")),
+        }
+    }
+}
+
+/// How far below the best mean value a move may be and still count as
+/// competitive for `FinalSelection::Robust`.
+const ROBUST_VALUE_MARGIN: f64 = 0.05;
+
+/// Iterations between `MctsParams::early_stop` checks.
+const EARLY_STOP_CHECK_INTERVAL: usize = 16;
+
+/// `MctsParams::decisive_moves` scans nodes above this depth (root = 0). Each scan
+/// applies every move and every reply, so it is kept to the top of the tree.
+const DECISIVE_MOVES_MAX_DEPTH: usize = 2;
+
 /// MCTS search parameters.
-#[derive(Clone)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct MctsParams {
     pub num_simulations: usize,
     pub time_limit_ms: f64,
@@ -20,11 +97,80 @@ pub struct MctsParams {
     pub num_determinizations: usize,
     pub pw_c: f64,
     pub pw_alpha: f64,
+    /// Progressive-widening floor: nodes may always expand at least this many children,
+    /// however few visits they have. 1 = plain widening.
+    pub pw_min_children: usize,
+    /// Per-phase `(pw_c, pw_alpha)`, keyed by the phase name at the widening
+    /// node, e.g. wide for Carcassonne's `place_tile` and narrow for
+    /// `place_meeple`. Phases not listed use `pw_c`/`pw_alpha`.
+    pub pw_overrides: HashMap<String, (f64, f64)>,
     pub use_rave: bool,
     pub rave_k: f64,
     pub max_amaf_depth: usize,
-    pub rave_fpu: bool,
+    pub fpu_mode: FpuMode,
     pub tile_aware_amaf: bool,
+    /// Expand actions from `TypedGamePlugin::prunable_actions` after all others.
+    pub use_pruning_hints: bool,
+    /// Sample the returned move from root visits^(1/T) instead of taking the
+    /// most-visited one. 0 = deterministic argmax.
+    pub selection_temperature: f64,
+    /// When the root has at most this many valid actions (endgames), scale the
+    /// simulation budget by `endgame_sim_multiplier`. 0 = disabled.
+    pub endgame_branching_threshold: usize,
+    pub endgame_sim_multiplier: f64,
+    /// Break visit ties on per-determinization mean values weighted by each
+    /// determinization's iteration count, instead of pooling all visits.
+    pub weight_dets_by_iterations: bool,
+    /// Nodes at this depth (root = 0) are not expanded; selection stops there and
+    /// the position is scored by the eval function. Bounds tree memory. None = unlimited.
+    pub max_tree_depth: Option<usize>,
+    /// How strongly opponents are assumed to pick their best reply, in [0, 1].
+    /// At opponent nodes the exploitation term is pulled toward 0.5 by this factor,
+    /// spreading selection over weaker replies. 1.0 = fully adversarial.
+    pub opponent_rationality: f64,
+    /// Run at most this many determinizations at once, in chunks, to bound peak
+    /// memory (one tree per running determinization). Results are unaffected.
+    /// None = as many as rayon schedules.
+    pub max_parallel_determinizations: Option<usize>,
+    /// Expand every root action before widening applies at the root, so each
+    /// legal move gets at least one simulation even on tiny budgets.
+    pub expand_all_root_children: bool,
+    /// Share one node between move orders that reach the same position, so their
+    /// visits pool instead of being split across duplicate subtrees. See `NodeArena`.
+    pub use_transpositions: bool,
+    /// MCTS-Solver: mark nodes whose outcome is proven (terminal wins/losses and
+    /// minimax over solved children), always take proven wins and skip proven
+    /// losses. Two-player games only; ignored otherwise.
+    pub use_solver: bool,
+    /// How the returned move is picked from the root statistics.
+    pub final_selection: FinalSelection,
+    /// Concentration of the Dirichlet noise mixed into root selection, for varied
+    /// self-play games. 0 = no noise.
+    pub root_dirichlet_alpha: f64,
+    /// Weight of the root noise bonus, in [0, 1]. 0 = no noise.
+    pub root_exploration_fraction: f64,
+    /// Seed for every RNG in the search: determinization sampling, root noise,
+    /// rollouts and temperature sampling each draw from their own stream of it
+    /// (see `seeded_rng`). None = fresh entropy per search.
+    pub seed: Option<u64>,
+    /// Moves played from each new leaf with `TypedGamePlugin::rollout_action`
+    /// before it is scored (by the game result if the rollout ends the game, else
+    /// by the eval function). 0 = score the leaf directly. Rollouts run inside the
+    /// determinization, so they play out its sampled hidden information (e.g. its
+    /// tile order) rather than fresh draws.
+    pub rollout_depth: usize,
+    /// Threads searching each determinization's tree together, kept on different
+    /// paths by virtual loss. Helps when `num_determinizations` is below the core
+    /// count. Above 1, results depend on thread timing even with fixed seeds.
+    pub threads_per_tree: usize,
+    /// Stop a determinization early once its most-visited root move can no longer
+    /// be overtaken by the iterations it has left (checked every
+    /// `EARLY_STOP_CHECK_INTERVAL` iterations). Only with `FinalSelection::MaxVisits`.
+    pub early_stop: bool,
+    /// When expanding a node less than `DECISIVE_MOVES_MAX_DEPTH` plies below the
+    /// root, keep only a move that wins outright if there is one (the solver then
+    /// proves it), else drop moves that let the next player win outright.
+    pub decisive_moves: bool,
 }
 
 impl Default for MctsParams {
@@ -36,21 +182,180 @@ impl Default for MctsParams {
             num_determinizations: 5,
             pw_c: 2.0,
             pw_alpha: 0.5,
+            pw_min_children: 1,
+            pw_overrides: HashMap::new(),
             use_rave: false,
             rave_k: 100.0,
             max_amaf_depth: 4,
-            rave_fpu: true,
+            fpu_mode: FpuMode::default(),
             tile_aware_amaf: false,
+            use_pruning_hints: false,
+            selection_temperature: 0.0,
+            endgame_branching_threshold: 0,
+            endgame_sim_multiplier: 1.0,
+            weight_dets_by_iterations: false,
+            max_tree_depth: None,
+            opponent_rationality: 1.0,
+            max_parallel_determinizations: None,
+            expand_all_root_children: false,
+            use_transpositions: false,
+            use_solver: false,
+            final_selection: FinalSelection::default(),
+            root_dirichlet_alpha: 0.0,
+            root_exploration_fraction: 0.0,
+            seed: None,
+            rollout_depth: 0,
+            threads_per_tree: 1,
+            early_stop: false,
+            decisive_moves: false,
+        }
+    }
+}
+
+impl MctsParams {
+    /// Fluent construction starting from the defaults, validated by `build`.
+    pub fn builder() -> MctsParamsBuilder {
+        MctsParamsBuilder { params: MctsParams::default() }
+    }
+
+    /// `(pw_c, pw_alpha)` for nodes in `phase`.
+    fn widening_for(&self, phase: &str) -> (f64, f64) {
+        self.pw_overrides.get(phase).copied().unwrap_or((self.pw_c, self.pw_alpha))
+    }
+
+    /// Reject parameter values the search cannot run with.
+    pub fn validate(&self) -> Result<(), String> {
+        let finite_at_least = |name: &str, v: f64, min: f64| {
+            if v.is_finite() && v >= min { Ok(()) } else { Err(format!("{name} must be >= {min}, got {v}")) }
+        };
+        let finite_positive = |name: &str, v: f64| {
+            if v.is_finite() && v > 0.0 { Ok(()) } else { Err(format!("{name} must be > 0, got {v}")) }
+        };
+        if self.num_simulations == 0 {
+            return Err("num_simulations must be >= 1".into());
+        }
+        if self.num_determinizations == 0 {
+            return Err("num_determinizations must be >= 1".into());
+        }
+        finite_positive("time_limit_ms", self.time_limit_ms)?;
+        finite_at_least("exploration_constant", self.exploration_constant, 0.0)?;
+        finite_positive("pw_c", self.pw_c)?;
+        if !(self.pw_alpha > 0.0 && self.pw_alpha <= 1.0) {
+            return Err(format!("pw_alpha must be in (0, 1], got {}", self.pw_alpha));
+        }
+        for (phase, &(pw_c, pw_alpha)) in &self.pw_overrides {
+            finite_positive(&format!("pw_overrides[{phase}] pw_c"), pw_c)?;
+            if !(pw_alpha > 0.0 && pw_alpha <= 1.0) {
+                return Err(format!("pw_overrides[{phase}] pw_alpha must be in (0, 1], got {pw_alpha}"));
+            }
+        }
+        finite_at_least("rave_k", self.rave_k, 0.0)?;
+        finite_at_least("selection_temperature", self.selection_temperature, 0.0)?;
+        finite_positive("endgame_sim_multiplier", self.endgame_sim_multiplier)?;
+        if !(0.0..=1.0).contains(&self.opponent_rationality) {
+            return Err(format!("opponent_rationality must be in [0, 1], got {}", self.opponent_rationality));
+        }
+        if self.max_tree_depth == Some(0) {
+            return Err("max_tree_depth must be >= 1 when set".into());
+        }
+        if self.max_parallel_determinizations == Some(0) {
+            return Err("max_parallel_determinizations must be >= 1 when set".into());
+        }
+        if self.threads_per_tree == 0 {
+            return Err("threads_per_tree must be >= 1".into());
+        }
+        finite_at_least("root_dirichlet_alpha", self.root_dirichlet_alpha, 0.0)?;
+        if !(0.0..=1.0).contains(&self.root_exploration_fraction) {
+            return Err(format!(
+                "root_exploration_fraction must be in [0, 1], got {}",
+                self.root_exploration_fraction
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Chainable setters for `MctsParams`; see the field docs there.
+#[derive(Clone)]
+pub struct MctsParamsBuilder {
+    params: MctsParams,
+}
+
+#[allow(dead_code)]
+impl MctsParamsBuilder {
+    pub fn simulations(mut self, v: usize) -> Self { self.params.num_simulations = v; self }
+    pub fn time_limit_ms(mut self, v: f64) -> Self { self.params.time_limit_ms = v; self }
+    pub fn exploration_constant(mut self, v: f64) -> Self { self.params.exploration_constant = v; self }
+    pub fn determinizations(mut self, v: usize) -> Self { self.params.num_determinizations = v; self }
+    pub fn pw_c(mut self, v: f64) -> Self { self.params.pw_c = v; self }
+    pub fn pw_alpha(mut self, v: f64) -> Self { self.params.pw_alpha = v; self }
+    pub fn pw_min_children(mut self, v: usize) -> Self { self.params.pw_min_children = v; self }
+    /// Widen nodes in `phase` with `pw_c`/`pw_alpha` instead of the global pair.
+    pub fn pw_override(mut self, phase: &str, pw_c: f64, pw_alpha: f64) -> Self {
+        self.params.pw_overrides.insert(phase.to_string(), (pw_c, pw_alpha));
+        self
+    }
+    pub fn rave(mut self, v: bool) -> Self { self.params.use_rave = v; self }
+    pub fn rave_k(mut self, v: f64) -> Self { self.params.rave_k = v; self }
+    pub fn max_amaf_depth(mut self, v: usize) -> Self { self.params.max_amaf_depth = v; self }
+    pub fn fpu_mode(mut self, v: FpuMode) -> Self { self.params.fpu_mode = v; self }
+    pub fn tile_aware_amaf(mut self, v: bool) -> Self { self.params.tile_aware_amaf = v; self }
+    pub fn use_pruning_hints(mut self, v: bool) -> Self { self.params.use_pruning_hints = v; self }
+    pub fn selection_temperature(mut self, v: f64) -> Self { self.params.selection_temperature = v; self }
+    pub fn endgame_branching_threshold(mut self, v: usize) -> Self { self.params.endgame_branching_threshold = v; self }
+    pub fn endgame_sim_multiplier(mut self, v: f64) -> Self { self.params.endgame_sim_multiplier = v; self }
+    pub fn weight_dets_by_iterations(mut self, v: bool) -> Self { self.params.weight_dets_by_iterations = v; self }
+    pub fn max_tree_depth(mut self, v: Option<usize>) -> Self { self.params.max_tree_depth = v; self }
+    pub fn opponent_rationality(mut self, v: f64) -> Self { self.params.opponent_rationality = v; self }
+    pub fn max_parallel_determinizations(mut self, v: Option<usize>) -> Self {
+        self.params.max_parallel_determinizations = v;
+        self
+    }
+    pub fn expand_all_root_children(mut self, v: bool) -> Self { self.params.expand_all_root_children = v; self }
+    pub fn use_transpositions(mut self, v: bool) -> Self { self.params.use_transpositions = v; self }
+    pub fn use_solver(mut self, v: bool) -> Self { self.params.use_solver = v; self }
+    pub fn final_selection(mut self, v: FinalSelection) -> Self { self.params.final_selection = v; self }
+    pub fn root_dirichlet_alpha(mut self, v: f64) -> Self { self.params.root_dirichlet_alpha = v; self }
+    pub fn root_exploration_fraction(mut self, v: f64) -> Self { self.params.root_exploration_fraction = v; self }
+    pub fn seed(mut self, v: Option<u64>) -> Self { self.params.seed = v; self }
+    pub fn rollout_depth(mut self, v: usize) -> Self { self.params.rollout_depth = v; self }
+    pub fn threads_per_tree(mut self, v: usize) -> Self { self.params.threads_per_tree = v; self }
+    pub fn early_stop(mut self, v: bool) -> Self { self.params.early_stop = v; self }
+    pub fn decisive_moves(mut self, v: bool) -> Self { self.params.decisive_moves = v; self }
+
+    pub fn build(self) -> Result<MctsParams, String> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
+
+/// Proven outcome of a node for the player who moved into it (`use_solver`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProofValue {
+    Win,
+    Loss,
+}
+
+impl ProofValue {
+    /// The same outcome seen by the other player.
+    fn flip(self) -> Self {
+        match self {
+            ProofValue::Win => ProofValue::Loss,
+            ProofValue::Loss => ProofValue::Win,
         }
     }
 }
 
 /// A node in the MCTS search tree.
+#[derive(Clone)]
 struct MctsNode {
     action_taken: Option<serde_json::Value>,
     parent: Option<usize>, // index into arena
     acting_player: Option<String>,
     children: Vec<usize>, // indices into arena
+    /// Children shared via the transposition table, with the move that reaches
+    /// them from this node (their own `action_taken` is from `parent`).
+    transposed_edges: Vec<TransposedEdge>,
     untried_actions: Option<Vec<serde_json::Value>>,
     visit_count: u32,
     total_value: f64,
@@ -58,6 +363,14 @@ struct MctsNode {
     amaf_visits: HashMap<String, u32>,
     amaf_values: HashMap<String, f64>,
     amaf_key: String,
+    proof: Option<ProofValue>,
+    /// Dirichlet noise on this root child's move; 0 below the root.
+    noise: f64,
+    /// In-flight iterations through this node (`threads_per_tree`), already
+    /// counted in `visit_count` as losses; see `add_virtual_loss`.
+    virtual_losses: u32,
+    /// Phase at this node, set when it is expanded; selects `pw_overrides`.
+    phase_name: String,
 }
 
 impl MctsNode {
@@ -67,47 +380,67 @@ impl MctsNode {
             parent,
             acting_player: None,
             children: Vec::new(),
+            transposed_edges: Vec::new(),
             untried_actions: None,
             visit_count: 0,
             total_value: 0.0,
             amaf_visits: HashMap::new(),
             amaf_values: HashMap::new(),
             amaf_key: String::new(),
+            proof: None,
+            noise: 0.0,
+            virtual_losses: 0,
+            phase_name: String::new(),
         }
     }
 
-    fn uct_value(&self, parent_visits: u32, c: f64) -> f64 {
+    fn uct_value(&self, parent_visits: u32, c: f64, rationality: f64) -> f64 {
         if self.visit_count == 0 {
             return f64::INFINITY;
         }
-        let exploit = self.total_value / self.visit_count as f64;
+        let exploit = soften(self.total_value / self.visit_count as f64, rationality);
         let explore = c * ((parent_visits as f64).ln() / self.visit_count as f64).sqrt();
         exploit + explore
     }
 
+    /// Root noise bonus: the noise stands in for a prior in the PUCT exploration
+    /// term, so it fades as this child gathers visits.
+    fn noise_bonus(&self, parent_visits: u32, c: f64, noise_weight: f64) -> f64 {
+        noise_weight * self.noise * c * (parent_visits as f64).sqrt() / (1.0 + self.visit_count as f64)
+    }
+
+    /// `action_k` is the AMAF key of the edge into this node from `parent`.
+    #[allow(clippy::too_many_arguments)]
     fn rave_value(
         &self,
+        action_k: &str,
         parent_visits: u32,
         c: f64,
         rave_k: f64,
-        rave_fpu: bool,
+        fpu_mode: FpuMode,
         parent: Option<&MctsNode>,
+        rationality: f64,
     ) -> f64 {
-        let action_k = if !self.amaf_key.is_empty() {
-            &self.amaf_key
-        } else {
-            &action_key_from_opt(&self.action_taken)
-        };
-
         if self.visit_count == 0 {
-            if rave_fpu {
-                if let Some(p) = parent {
-                    let amaf_n = p.amaf_visits.get(action_k.as_str()).copied().unwrap_or(0);
+            let Some(p) = parent else { return f64::INFINITY };
+            match fpu_mode {
+                FpuMode::Infinity => {}
+                FpuMode::AmafOptimistic => {
+                    let amaf_n = p.amaf_visits.get(action_k).copied().unwrap_or(0);
                     if amaf_n > 0 {
-                        let amaf_q = p.amaf_values.get(action_k.as_str()).copied().unwrap_or(0.0) / amaf_n as f64;
+                        let amaf_q = p.amaf_values.get(action_k).copied().unwrap_or(0.0) / amaf_n as f64;
                         return 1.0 + amaf_q;
                     }
                 }
+                FpuMode::ParentValue => {
+                    if p.visit_count > 0 {
+                        let parent_q = p.total_value / p.visit_count as f64;
+                        // Parent stats are kept for the player who moved into it;
+                        // the root (no acting player) is already the child's side.
+                        let same_side = p.acting_player.is_none() || p.acting_player == self.acting_player;
+                        return if same_side { parent_q } else { 1.0 - parent_q };
+                    }
+                }
             }
             return f64::INFINITY;
         }
@@ -115,9 +448,9 @@ impl MctsNode {
         let q_uct = self.total_value / self.visit_count as f64;
 
         let (_amaf_n, amaf_q) = if let Some(p) = parent {
-            let n = p.amaf_visits.get(action_k.as_str()).copied().unwrap_or(0);
+            let n = p.amaf_visits.get(action_k).copied().unwrap_or(0);
             if n > 0 {
-                (n, p.amaf_values.get(action_k.as_str()).copied().unwrap_or(0.0) / n as f64)
+                (n, p.amaf_values.get(action_k).copied().unwrap_or(0.0) / n as f64)
             } else {
                 (0, 0.5)
             }
@@ -128,20 +461,69 @@ impl MctsNode {
         // matching the Python implementation. As parent gets more visits,
         // β shrinks and we rely more on UCT than AMAF.
         let beta = (rave_k / (3.0 * parent_visits as f64 + rave_k)).sqrt();
-        let blended = (1.0 - beta) * q_uct + beta * amaf_q;
+        let blended = soften((1.0 - beta) * q_uct + beta * amaf_q, rationality);
         let explore = c * ((parent_visits as f64).ln() / self.visit_count as f64).sqrt();
         blended + explore
     }
 }
 
+/// Pull a win rate toward 0.5: 1.0 keeps it, 0.0 makes every move look equal.
+fn soften(q: f64, rationality: f64) -> f64 {
+    0.5 + rationality * (q - 0.5)
+}
+
+/// `opponent_rationality` when an opponent decides at this node, else 1.0.
+/// All children of a node share the acting player who chooses among them.
+fn selection_rationality(
+    arena: &NodeArena,
+    node_idx: usize,
+    searching_player: &str,
+    params: &MctsParams,
+) -> f64 {
+    let node = arena.get(node_idx);
+    let opponent_moves = node.children.first().is_some_and(|&ci| {
+        arena.get(ci).acting_player.as_deref().is_some_and(|p| p != searching_player)
+    });
+    if opponent_moves {
+        params.opponent_rationality.clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+/// Edge from a node to a child it shares with another parent.
+#[derive(Clone)]
+struct TransposedEdge {
+    child: usize,
+    action: serde_json::Value,
+    amaf_key: String,
+}
+
 /// Arena-allocated node storage for cache locality.
+///
+/// With `use_transpositions` the nodes form a DAG rather than a tree: a position
+/// reached by several move orders is one node listed in several parents'
+/// `children`. Its `parent` is only the node that allocated it, so each iteration
+/// backpropagates along the path it actually selected, and a shared node's stats
+/// pool the visits of all its parents.
 struct NodeArena {
     nodes: Vec<MctsNode>,
+    /// Transposition key (see `transposition_key`) -> node.
+    transpositions: HashMap<u64, usize>,
+    /// Root noise per action key (`root_dirichlet_alpha`); empty until sampled.
+    root_noise: HashMap<String, f64>,
+    /// `get_valid_actions` calls made while expanding nodes; each node asks once.
+    valid_action_calls: usize,
 }
 
 impl NodeArena {
     fn new() -> Self {
-        Self { nodes: Vec::with_capacity(1024) }
+        Self {
+            nodes: Vec::with_capacity(1024),
+            transpositions: HashMap::new(),
+            root_noise: HashMap::new(),
+            valid_action_calls: 0,
+        }
     }
 
     fn alloc(&mut self, node: MctsNode) -> usize {
@@ -158,17 +540,99 @@ impl NodeArena {
         &mut self.nodes[idx]
     }
 
-    fn best_child_uct(&self, node_idx: usize, c: f64) -> usize {
+    /// Move and AMAF key of the edge `parent_idx -> child_idx`.
+    fn edge(&self, parent_idx: usize, child_idx: usize) -> (Option<&serde_json::Value>, &str) {
+        match self.nodes[parent_idx].transposed_edges.iter().find(|e| e.child == child_idx) {
+            Some(e) => (Some(&e.action), &e.amaf_key),
+            None => {
+                let child = &self.nodes[child_idx];
+                (child.action_taken.as_ref(), &child.amaf_key)
+            }
+        }
+    }
+
+    /// AMAF key of the edge `parent_idx -> child_idx`, falling back to its action key.
+    fn edge_amaf_key(&self, parent_idx: usize, child_idx: usize) -> Cow<'_, str> {
+        match self.edge(parent_idx, child_idx) {
+            (_, amaf) if !amaf.is_empty() => Cow::Borrowed(amaf),
+            (action, _) => Cow::Owned(action.map(action_key).unwrap_or_default()),
+        }
+    }
+
+    /// Copy of the nodes reachable from `root_idx`, re-rooted there as node 0.
+    /// The new root has no parent or move and keeps its value from
+    /// `searching_player`'s side, like a fresh root. The transposition table
+    /// starts empty: its keys hold depths relative to the old root. Root noise is
+    /// dropped too, and resampled for the new root's moves.
+    fn subtree(&self, root_idx: usize, searching_player: &str) -> NodeArena {
+        // BFS order, with the node each one was first reached from
+        let mut order = vec![(root_idx, None)];
+        let mut new_idx = HashMap::from([(root_idx, 0)]);
+        let mut i = 0;
+        while i < order.len() {
+            let (old, _) = order[i];
+            for &child in &self.nodes[old].children {
+                if let std::collections::hash_map::Entry::Vacant(e) = new_idx.entry(child) {
+                    e.insert(order.len());
+                    order.push((child, Some(i)));
+                }
+            }
+            i += 1;
+        }
+
+        let mut arena = NodeArena::new();
+        for &(old, reached_from) in &order {
+            let mut node = self.nodes[old].clone();
+            // Shared nodes allocated outside the subtree adopt the parent they were reached from.
+            node.parent = node.parent.and_then(|p| new_idx.get(&p).copied()).or(reached_from);
+            node.children = node.children.iter().map(|c| new_idx[c]).collect();
+            for edge in &mut node.transposed_edges {
+                edge.child = new_idx[&edge.child];
+            }
+            arena.alloc(node);
+        }
+
+        let root = arena.get_mut(0);
+        if root.acting_player.as_deref().is_some_and(|p| p != searching_player) {
+            root.total_value = root.visit_count as f64 - root.total_value;
+        }
+        root.parent = None;
+        root.action_taken = None;
+        root.acting_player = None;
+        root.amaf_key.clear();
+        for node in &mut arena.nodes {
+            node.noise = 0.0;
+        }
+        arena
+    }
+
+    /// Children of `node_idx` open to selection: all of them, or with `skip_lost`
+    /// those not proven lost for their mover. Must leave at least one.
+    fn selectable_children(&self, node_idx: usize, skip_lost: bool) -> impl Iterator<Item = usize> + '_ {
+        self.nodes[node_idx]
+            .children
+            .iter()
+            .copied()
+            .filter(move |&ci| !(skip_lost && self.nodes[ci].proof == Some(ProofValue::Loss)))
+    }
+
+    /// `noise_weight` scales the root noise bonus; pass 0 below the root.
+    fn best_child_uct(&self, node_idx: usize, c: f64, rationality: f64, skip_lost: bool, noise_weight: f64) -> usize {
         let node = &self.nodes[node_idx];
         let parent_visits = node.visit_count;
         // Use first-max (not last-max) to match Python's max() tie-breaking.
         // This ensures the MCTS deepens the first-expanded (earliest) child
         // when UCT values tie, producing deeper trees that reach terminal
         // states faster.
-        let mut best_idx = node.children[0];
-        let mut best_val = self.nodes[best_idx].uct_value(parent_visits, c);
-        for &child_idx in &node.children[1..] {
-            let val = self.nodes[child_idx].uct_value(parent_visits, c);
+        let uct = |child_idx: usize| {
+            let child = &self.nodes[child_idx];
+            child.uct_value(parent_visits, c, rationality) + child.noise_bonus(parent_visits, c, noise_weight)
+        };
+        let mut children = self.selectable_children(node_idx, skip_lost);
+        let mut best_idx = children.next().expect("a selectable child");
+        let mut best_val = uct(best_idx);
+        for child_idx in children {
+            let val = uct(child_idx);
             if val > best_val {
                 best_val = val;
                 best_idx = child_idx;
@@ -177,13 +641,30 @@ impl NodeArena {
         best_idx
     }
 
-    fn best_child_rave(&self, node_idx: usize, c: f64, rave_k: f64, rave_fpu: bool) -> usize {
+    #[allow(clippy::too_many_arguments)]
+    fn best_child_rave(
+        &self,
+        node_idx: usize,
+        c: f64,
+        rave_k: f64,
+        fpu_mode: FpuMode,
+        rationality: f64,
+        skip_lost: bool,
+        noise_weight: f64,
+    ) -> usize {
         let node = &self.nodes[node_idx];
         let parent_visits = node.visit_count;
-        let mut best_idx = node.children[0];
-        let mut best_val = self.nodes[best_idx].rave_value(parent_visits, c, rave_k, rave_fpu, Some(node));
-        for &child_idx in &node.children[1..] {
-            let val = self.nodes[child_idx].rave_value(parent_visits, c, rave_k, rave_fpu, Some(node));
+        let mut children = self.selectable_children(node_idx, skip_lost);
+        let mut best_idx = children.next().expect("a selectable child");
+        let rave = |child_idx: usize| {
+            let key = self.edge_amaf_key(node_idx, child_idx);
+            let child = &self.nodes[child_idx];
+            child.rave_value(&key, parent_visits, c, rave_k, fpu_mode, Some(node), rationality)
+                + child.noise_bonus(parent_visits, c, noise_weight)
+        };
+        let mut best_val = rave(best_idx);
+        for child_idx in children {
+            let val = rave(child_idx);
             if val > best_val {
                 best_val = val;
                 best_idx = child_idx;
@@ -194,7 +675,76 @@ impl NodeArena {
 
 }
 
+/// Search trees kept from an earlier search so the next one can continue from the
+/// position actually reached (tree reuse); see `mcts_search_reusing`. Holds one
+/// tree per determinization, each rooted at node 0.
+pub struct SearchTrees<S: Clone> {
+    player_id: String,
+    dets: Vec<Option<DetTree<S>>>,
+}
+
+impl<S: Clone> Default for SearchTrees<S> {
+    fn default() -> Self {
+        Self { player_id: String::new(), dets: Vec::new() }
+    }
+}
+
+impl<S: Clone> SearchTrees<S> {
+    /// Root visits summed over the kept trees.
+    #[allow(dead_code)]
+    pub fn root_visits(&self) -> u32 {
+        self.dets.iter().flatten().map(|t| t.arena.get(0).visit_count).sum()
+    }
+}
+
+/// One determinization's tree and the (determinized) state at its root.
+struct DetTree<S: Clone> {
+    arena: NodeArena,
+    root_state: SimulationState<S>,
+}
+
+/// How many plies below the old root tree reuse looks for the new position.
+const REUSE_MAX_DEPTH: usize = 8;
+
+impl<S: Clone> DetTree<S> {
+    /// This tree re-rooted at the node whose position hashes to `target` (see
+    /// `position_hash`), or None if no node within `REUSE_MAX_DEPTH` plies matches.
+    /// Hidden information makes a determinized position differ from the real one,
+    /// so games with a non-trivial `determinize` mostly fall back to a fresh tree.
+    fn reroot<P: TypedGamePlugin<State = S>>(
+        self,
+        plugin: &P,
+        target: u64,
+        searching_player: &str,
+    ) -> Option<DetTree<S>> {
+        let mut queue = std::collections::VecDeque::from([(0, self.root_state.clone(), 0)]);
+        while let Some((idx, state, depth)) = queue.pop_front() {
+            if position_hash(plugin, &state.state, &state.phase) == target {
+                let arena = self.arena.subtree(idx, searching_player);
+                return Some(DetTree { arena, root_state: state });
+            }
+            if depth == REUSE_MAX_DEPTH || state.game_over.is_some() {
+                continue;
+            }
+            for &child_idx in &self.arena.get(idx).children {
+                let (action, _) = self.arena.edge(idx, child_idx);
+                let acting_player = self.arena.get(child_idx).acting_player.as_deref();
+                let mut child_state = state.clone();
+                if let (Some(action), Some(_)) = (action, acting_player) {
+                    // The fresh search replaying this move reports the error
+                    if apply_node_action(plugin, &mut child_state, action, acting_player).is_err() {
+                        continue;
+                    }
+                }
+                queue.push_back((child_idx, child_state, depth + 1));
+            }
+        }
+        None
+    }
+}
+
 /// Per-determinization results, collected and aggregated after parallel execution.
+#[derive(Clone)]
 struct DetResult {
     visits: HashMap<String, u32>,
     values: HashMap<String, f64>,
@@ -204,6 +754,7 @@ struct DetResult {
 
 /// Run MCTS on typed state and return the best action payload and total iterations run.
 /// Determinizations run in parallel via rayon for ~linear speedup with core count.
+/// Errors if the plugin fails to apply a move it listed as valid.
 pub fn mcts_search<P: TypedGamePlugin>(
     state: &P::State,
     phase: &Phase,
@@ -212,7 +763,135 @@ pub fn mcts_search<P: TypedGamePlugin>(
     players: &[Player],
     params: &MctsParams,
     eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
-) -> (serde_json::Value, usize) {
+) -> Result<(serde_json::Value, usize), String> {
+    let (action, iterations, _) =
+        search_root(state, phase, player_id, plugin, players, params, eval_fn, false, None, None)?;
+    Ok((action, iterations))
+}
+
+/// Like mcts_search, but continues from `trees` kept by the previous call: each
+/// determinization whose tree contains the current position searches on from that
+/// subtree, keeping its statistics; the others start fresh. `trees` is replaced
+/// by this search's trees. Trees kept for another `player_id` are discarded.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn mcts_search_reusing<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    trees: &mut SearchTrees<P::State>,
+) -> Result<(serde_json::Value, usize), String> {
+    let (action, iterations, _) =
+        search_root(state, phase, player_id, plugin, players, params, eval_fn, false, None, Some(trees))?;
+    Ok((action, iterations))
+}
+
+/// Root statistics after some determinizations have finished.
+#[derive(Debug, Clone)]
+pub struct MctsProgress {
+    /// Most-visited action so far.
+    pub best_action: serde_json::Value,
+    pub best_visits: u32,
+    pub best_mean_value: f64,
+    pub iterations: usize,
+    pub dets_completed: usize,
+    pub num_determinizations: usize,
+}
+
+/// Like mcts_search, calling `on_progress` each time a determinization finishes
+/// with the root stats pooled over the finished ones. Calls are serialized, with
+/// `dets_completed` increasing. The returned action is the one mcts_search picks.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn mcts_search_with_progress<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    on_progress: &(dyn Fn(MctsProgress) + Sync),
+) -> Result<(serde_json::Value, usize), String> {
+    let (action, iterations, _) =
+        search_root(state, phase, player_id, plugin, players, params, eval_fn, false, Some(on_progress), None)?;
+    Ok((action, iterations))
+}
+
+/// Root actions with their share of root visits, most-visited first.
+pub type RootPolicy = Vec<(serde_json::Value, f64)>;
+
+/// Like mcts_search but also returns the root policy: each root action with its share
+/// of root visits (summing to 1), most-visited first. Used as a self-play policy target.
+pub fn mcts_search_with_policy<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+) -> Result<(serde_json::Value, usize, RootPolicy), String> {
+    search_root(state, phase, player_id, plugin, players, params, eval_fn, true, None, None)
+}
+
+/// Best action plus the root visit distribution pooled over all determinizations,
+/// for self-play training targets. Visit fractions are raised to `1/temperature`
+/// and renormalised: 1.0 keeps them as they are, higher flattens them, and
+/// `temperature <= 0` puts all mass on the most-visited action. The policy is
+/// ordered like `mcts_search_with_policy`'s, most-visited first.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn mcts_search_policy<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    temperature: f64,
+) -> Result<(serde_json::Value, Vec<(serde_json::Value, f64)>), String> {
+    let (action, _, policy) = mcts_search_with_policy(state, phase, player_id, plugin, players, params, eval_fn)?;
+    Ok((action, temper_policy(policy, temperature)))
+}
+
+/// Raise each probability to `1/temperature` and renormalise (see `mcts_search_policy`).
+fn temper_policy(policy: Vec<(serde_json::Value, f64)>, temperature: f64) -> Vec<(serde_json::Value, f64)> {
+    if temperature <= 0.0 {
+        return policy
+            .into_iter()
+            .enumerate()
+            .map(|(i, (action, _))| (action, if i == 0 { 1.0 } else { 0.0 }))
+            .collect();
+    }
+    // Normalise by the max before exponentiating to keep low temperatures finite.
+    let max_p = policy.iter().map(|e| e.1).fold(0.0, f64::max);
+    if max_p <= 0.0 {
+        return policy;
+    }
+    let weights: Vec<f64> = policy.iter().map(|e| (e.1 / max_p).powf(1.0 / temperature)).collect();
+    let total: f64 = weights.iter().sum();
+    policy.into_iter().zip(weights).map(|((action, _), w)| (action, w / total)).collect()
+}
+
+/// Shared body of mcts_search / mcts_search_with_policy / mcts_search_with_progress /
+/// mcts_search_reusing. The policy is only built when `with_policy` is set; otherwise
+/// it is returned empty. With `trees`, searches continue from and replace them.
+#[allow(clippy::too_many_arguments)]
+fn search_root<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    with_policy: bool,
+    on_progress: Option<&(dyn Fn(MctsProgress) + Sync)>,
+    mut trees: Option<&mut SearchTrees<P::State>>,
+) -> Result<(serde_json::Value, usize, RootPolicy), String> {
     // Validate player ordering invariants — zero cost in release builds
     debug_assert!(
         !players.is_empty(),
@@ -232,185 +911,567 @@ pub fn mcts_search<P: TypedGamePlugin>(
 
     let valid_actions = plugin.get_valid_actions(state, phase, player_id);
     if valid_actions.len() <= 1 {
-        return (valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), 0);
+        let action = valid_actions.into_iter().next().unwrap_or(serde_json::json!({}));
+        let policy = if with_policy { vec![(action.clone(), 1.0)] } else { vec![] };
+        return Ok((action, 0, policy));
     }
 
-    let sims_per_det = (params.num_simulations / params.num_determinizations).max(1);
+    let num_simulations = effective_num_simulations(params, valid_actions.len());
+    let sims_per_det = (num_simulations / params.num_determinizations).max(1);
     let total_deadline = Instant::now() + std::time::Duration::from_millis(params.time_limit_ms as u64);
     let base_scores = plugin.get_scores(state);
+    // Determinizations finished so far, only kept when reporting progress
+    let finished: Mutex<Vec<DetResult>> = Mutex::new(Vec::new());
+    // Tree reuse: determinization i takes slot i's old tree and leaves its new one there
+    let keep_trees = trees.is_some();
+    let target = position_hash(plugin, state, phase);
+    let mut previous = match trees.as_deref_mut() {
+        Some(t) if t.player_id == player_id => std::mem::take(&mut t.dets),
+        _ => Vec::new(),
+    };
+    let slots = (0..params.num_determinizations)
+        .map(|i| Mutex::new(previous.get_mut(i).and_then(Option::take)))
+        .collect::<Vec<_>>();
 
     // Run determinizations in parallel
-    let det_results: Vec<DetResult> = (0..params.num_determinizations)
-        .into_par_iter()
-        .map(|_det_idx| {
-            if Instant::now() >= total_deadline {
-                return DetResult {
-                    visits: HashMap::new(),
-                    values: HashMap::new(),
-                    actions: HashMap::new(),
-                    iterations: 0,
-                };
-            }
+    let det_results = par_determinizations(params, |det_idx| {
+        if Instant::now() >= total_deadline {
+            return Ok(DetResult {
+                visits: HashMap::new(),
+                values: HashMap::new(),
+                actions: HashMap::new(),
+                iterations: 0,
+            });
+        }
 
+        let slot = &slots[det_idx];
+        let reused = slot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .and_then(|tree| tree.reroot(plugin, target, player_id));
+        let DetTree { mut arena, root_state } = reused.unwrap_or_else(|| {
             let mut det_state = state.clone();
-            plugin.determinize(&mut det_state);
-
+            plugin.determinize(&mut det_state, &mut det_rng(params, det_idx));
             let root_state = SimulationState {
                 state: det_state,
                 phase: phase.clone(),
-                players: players.to_vec(),
+                players: players.into(),
                 scores: base_scores.clone(),
                 game_over: None,
+                history: None,
             };
-
             let mut arena = NodeArena::new();
-            let root_idx = arena.alloc(MctsNode::new(None, None));
-            let mut iterations = 0;
-
-            for _sim_i in 0..sims_per_det {
-                if Instant::now() >= total_deadline {
-                    break;
-                }
-                iterations += 1;
-                run_one_iteration(
-                    &mut arena,
-                    root_idx,
-                    &root_state,
-                    player_id,
-                    players,
-                    plugin,
-                    params,
-                    eval_fn,
-                );
+            arena.alloc(MctsNode::new(None, None));
+            DetTree { arena, root_state }
+        });
+        let root_idx = 0;
+        let iterations = search_tree(
+            &mut arena,
+            &root_state,
+            player_id,
+            players,
+            plugin,
+            params,
+            eval_fn,
+            sims_per_det,
+            total_deadline,
+            det_idx,
+        )?;
+
+        let mut visits = HashMap::new();
+        let mut values = HashMap::new();
+        let mut actions = HashMap::new();
+
+        let root = arena.get(root_idx);
+        for &child_idx in &root.children {
+            let child = arena.get(child_idx);
+            if let Some(ref action) = child.action_taken {
+                let key = action_key(action);
+                actions.entry(key.clone()).or_insert_with(|| action.clone());
+                *visits.entry(key.clone()).or_insert(0) += child.visit_count;
+                *values.entry(key).or_insert(0.0) += child.total_value;
             }
+        }
 
-            let mut visits = HashMap::new();
-            let mut values = HashMap::new();
-            let mut actions = HashMap::new();
-
-            let root = arena.get(root_idx);
-            for &child_idx in &root.children {
-                let child = arena.get(child_idx);
-                if let Some(ref action) = child.action_taken {
-                    let key = action_key(action);
-                    actions.entry(key.clone()).or_insert_with(|| action.clone());
-                    *visits.entry(key.clone()).or_insert(0) += child.visit_count;
-                    *values.entry(key).or_insert(0.0) += child.total_value;
-                }
+        if keep_trees {
+            *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(DetTree { arena, root_state });
+        }
+
+        let det = DetResult { visits, values, actions, iterations };
+        if let Some(report) = on_progress {
+            let mut done = finished.lock().unwrap_or_else(|e| e.into_inner());
+            done.push(det.clone());
+            if let Some(progress) = progress_snapshot(&done, params) {
+                report(progress);
             }
+        }
+        Ok(det)
+    });
 
-            DetResult { visits, values, actions, iterations }
-        })
-        .collect();
+    if let Some(t) = trees {
+        t.player_id = player_id.to_string();
+        t.dets = slots.into_iter().map(|m| m.into_inner().unwrap_or_else(|e| e.into_inner())).collect();
+    }
 
     // Aggregate results from all determinizations
-    let mut action_visits: HashMap<String, u32> = HashMap::new();
-    let mut action_values: HashMap<String, f64> = HashMap::new();
-    let mut action_map: HashMap<String, serde_json::Value> = HashMap::new();
-    let mut total_iterations: usize = 0;
+    let DetAggregate {
+        visits: action_visits,
+        mean_values,
+        actions: mut action_map,
+        iterations: total_iterations,
+    } = aggregate_dets(det_results.into_iter().collect::<Result<Vec<_>, String>>()?, params.weight_dets_by_iterations);
+
+    if action_visits.is_empty() {
+        // No iteration finished in time: fall back to a uniform policy.
+        let policy = if with_policy {
+            let p = 1.0 / valid_actions.len() as f64;
+            valid_actions.iter().map(|a| (a.clone(), p)).collect()
+        } else {
+            vec![]
+        };
+        return Ok((valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), total_iterations, policy));
+    }
+
+    let best_key = select_best_action(&action_visits, &mean_values, params.final_selection);
+
+    let policy = if with_policy {
+        let total_visits = action_visits.values().map(|&v| v as f64).sum::<f64>().max(1.0);
+        let mut keys: Vec<&String> = action_visits.keys().collect();
+        // Argmax move first (it may tie on visits), then by visits, then key for determinism.
+        keys.sort_by(|a, b| {
+            (*b == &best_key)
+                .cmp(&(*a == &best_key))
+                .then(action_visits[*b].cmp(&action_visits[*a]))
+                .then(a.cmp(b))
+        });
+        keys.into_iter()
+            .map(|k| (action_map[k].clone(), action_visits[k] as f64 / total_visits))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let chosen_key = if params.selection_temperature > 0.0 {
+        sample_by_visits(&action_visits, params.selection_temperature, &mut seeded_rng(params, SELECTION_STREAM))
+    } else {
+        best_key
+    };
+
+    Ok((action_map.remove(&chosen_key).unwrap_or(serde_json::json!({})), total_iterations, policy))
+}
+
+/// Pooled root stats over the determinizations in `done`; None before any root visit.
+fn progress_snapshot(done: &[DetResult], params: &MctsParams) -> Option<MctsProgress> {
+    let agg = aggregate_dets(done.iter().cloned(), params.weight_dets_by_iterations);
+    if agg.visits.is_empty() {
+        return None;
+    }
+    let best_key = select_best_action(&agg.visits, &agg.mean_values, params.final_selection);
+    Some(MctsProgress {
+        best_action: agg.actions.get(&best_key).cloned().unwrap_or(serde_json::json!({})),
+        best_visits: agg.visits[&best_key],
+        best_mean_value: agg.mean_values.get(&best_key).copied().unwrap_or(0.0),
+        iterations: agg.iterations,
+        dets_completed: done.len(),
+        num_determinizations: params.num_determinizations,
+    })
+}
+
+/// Map `run_det` over all determinization indices in parallel, at most
+/// `max_parallel_determinizations` at a time. Results come back in index order.
+fn par_determinizations<T, F>(params: &MctsParams, run_det: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync + Send,
+{
+    let n = params.num_determinizations;
+    let chunk = params.max_parallel_determinizations.unwrap_or(n).max(1);
+    let mut results = Vec::with_capacity(n);
+    for start in (0..n).step_by(chunk) {
+        let batch: Vec<T> = (start..(start + chunk).min(n)).into_par_iter().map(&run_det).collect();
+        results.extend(batch);
+    }
+    results
+}
+
+/// `MctsParams::seed` streams: determinization i samples its hidden information
+/// with stream i, and its root noise and rollouts use `SEARCH_STREAMS + i`.
+const SEARCH_STREAMS: u64 = 1 << 32;
+/// Stream for sampling the returned move when `selection_temperature` > 0.
+const SELECTION_STREAM: u64 = u64::MAX;
+
+/// Stream `stream` of `MctsParams::seed`, or fresh entropy when unseeded.
+fn seeded_rng(params: &MctsParams, stream: u64) -> GameRng {
+    match params.seed {
+        Some(seed) => GameRng::stream(seed, stream),
+        None => GameRng::from_entropy(),
+    }
+}
+
+/// RNG for determinization `det_idx`'s sampled hidden information.
+fn det_rng(params: &MctsParams, det_idx: usize) -> GameRng {
+    seeded_rng(params, det_idx as u64)
+}
+
+/// RNG for determinization `det_idx`'s root noise and rollouts.
+fn search_rng(params: &MctsParams, det_idx: usize) -> GameRng {
+    seeded_rng(params, SEARCH_STREAMS + det_idx as u64)
+}
+
+/// Root statistics summed over determinizations.
+struct DetAggregate {
+    visits: HashMap<String, u32>,
+    /// Mean value per action, used to break visit ties.
+    mean_values: HashMap<String, f64>,
+    actions: HashMap<String, serde_json::Value>,
+    iterations: usize,
+}
+
+/// Sum visits across determinizations and compute each action's mean value.
+/// By default the mean pools all visits. With `weight_by_iterations`, each det's own
+/// mean is weighted by how many iterations it ran, so a det cut short by the
+/// deadline cannot swing the tiebreak with a handful of visits.
+fn aggregate_dets(
+    det_results: impl IntoIterator<Item = DetResult>,
+    weight_by_iterations: bool,
+) -> DetAggregate {
+    let mut visits: HashMap<String, u32> = HashMap::new();
+    let mut actions: HashMap<String, serde_json::Value> = HashMap::new();
+    let mut iterations: usize = 0;
+    // Per action: (weighted value sum, total weight)
+    let mut value_sums: HashMap<String, (f64, f64)> = HashMap::new();
 
     for det in det_results {
-        total_iterations += det.iterations;
-        for (key, count) in &det.visits {
-            *action_visits.entry(key.clone()).or_insert(0) += count;
-            if !action_map.contains_key(key) {
+        iterations += det.iterations;
+        for (key, &count) in &det.visits {
+            *visits.entry(key.clone()).or_insert(0) += count;
+            if !actions.contains_key(key) {
                 if let Some(action) = det.actions.get(key) {
-                    action_map.insert(key.clone(), action.clone());
+                    actions.insert(key.clone(), action.clone());
                 }
             }
-        }
-        for (key, value) in &det.values {
-            *action_values.entry(key.clone()).or_insert(0.0) += value;
+            let value = det.values.get(key).copied().unwrap_or(0.0);
+            let sums = value_sums.entry(key.clone()).or_insert((0.0, 0.0));
+            if weight_by_iterations {
+                if count > 0 {
+                    let weight = det.iterations as f64;
+                    sums.0 += weight * value / count as f64;
+                    sums.1 += weight;
+                }
+            } else {
+                sums.0 += value;
+                sums.1 += count as f64;
+            }
         }
     }
 
-    if action_visits.is_empty() {
-        return (valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), total_iterations);
+    let mean_values = value_sums
+        .into_iter()
+        .map(|(key, (sum, weight))| (key, if weight > 0.0 { sum / weight } else { 0.0 }))
+        .collect();
+    DetAggregate { visits, mean_values, actions, iterations }
+}
+
+/// Key of the move to play under `selection`, from pooled root visits and mean values.
+fn select_best_action(
+    action_visits: &HashMap<String, u32>,
+    mean_values: &HashMap<String, f64>,
+    selection: FinalSelection,
+) -> String {
+    match selection {
+        FinalSelection::MaxVisits => most_visited_key(action_visits, mean_values),
+        FinalSelection::MaxValue => highest_value_key(action_visits, mean_values),
+        FinalSelection::Robust => {
+            let value = |k: &String| mean_values.get(k).copied().unwrap_or(0.0);
+            let best_value = action_visits.keys().map(value).fold(f64::NEG_INFINITY, f64::max);
+            let competitive: HashMap<String, u32> = action_visits
+                .iter()
+                .filter(|(k, _)| value(k) >= best_value - ROBUST_VALUE_MARGIN)
+                .map(|(k, &v)| (k.clone(), v))
+                .collect();
+            most_visited_key(&competitive, mean_values)
+        }
     }
+}
+
+/// Highest mean value; ties go to more visits, then the smaller key.
+fn highest_value_key(action_visits: &HashMap<String, u32>, mean_values: &HashMap<String, f64>) -> String {
+    action_visits
+        .iter()
+        .max_by(|(a_key, a_visits), (b_key, b_visits)| {
+            let a_val = mean_values.get(*a_key).copied().unwrap_or(0.0);
+            let b_val = mean_values.get(*b_key).copied().unwrap_or(0.0);
+            a_val
+                .partial_cmp(&b_val)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a_visits.cmp(b_visits))
+                .then_with(|| b_key.cmp(a_key))
+        })
+        .map(|(k, _)| k.clone())
+        .unwrap_or_default()
+}
 
-    // Find the max visit count, then break ties by highest average value.
-    // When many children have similar visit counts (common with wide PW),
-    // the average value provides better differentiation than alphabetical order.
+/// Find the max visit count, then break ties by highest mean value.
+/// When many children have similar visit counts (common with wide PW),
+/// the mean value provides better differentiation than alphabetical order.
+/// Exact value ties go to the smallest key, so the pick never depends on
+/// HashMap iteration order.
+fn most_visited_key(action_visits: &HashMap<String, u32>, mean_values: &HashMap<String, f64>) -> String {
     let max_visits = action_visits.values().copied().max().unwrap_or(0);
-    let best_key = action_visits.iter()
+    action_visits
+        .iter()
         .filter(|(_, &v)| v == max_visits)
         .max_by(|(a_key, _), (b_key, _)| {
-            let a_val = action_values.get(*a_key).copied().unwrap_or(0.0) / max_visits.max(1) as f64;
-            let b_val = action_values.get(*b_key).copied().unwrap_or(0.0) / max_visits.max(1) as f64;
-            a_val.partial_cmp(&b_val).unwrap_or(std::cmp::Ordering::Equal)
+            let a_val = mean_values.get(*a_key).copied().unwrap_or(0.0);
+            let b_val = mean_values.get(*b_key).copied().unwrap_or(0.0);
+            a_val
+                .partial_cmp(&b_val)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b_key.cmp(a_key))
         })
         .map(|(k, _)| k.clone())
-        .unwrap();
+        .unwrap_or_default()
+}
 
-    (action_map.remove(&best_key).unwrap_or(serde_json::json!({})), total_iterations)
+/// Pick an action key with probability proportional to visits^(1/temperature).
+fn sample_by_visits(action_visits: &HashMap<String, u32>, temperature: f64, rng: &mut GameRng) -> String {
+    use rand::distributions::{Distribution, WeightedIndex};
+
+    // Sort so the same seed picks the same move regardless of HashMap order.
+    let mut entries: Vec<(&String, u32)> = action_visits.iter().map(|(k, &v)| (k, v)).collect();
+    entries.sort();
+    // Normalise by the max before exponentiating to keep low temperatures finite.
+    let max_visits = entries.iter().map(|e| e.1).max().unwrap_or(0).max(1) as f64;
+    let weights: Vec<f64> = entries
+        .iter()
+        .map(|&(_, v)| (v as f64 / max_visits).powf(1.0 / temperature))
+        .collect();
+    match WeightedIndex::new(&weights) {
+        Ok(dist) => entries[dist.sample(rng)].0.clone(),
+        // All weights underflowed to zero: fall back to the most-visited move.
+        Err(_) => entries.iter().max_by_key(|e| e.1).map(|e| e.0.clone()).unwrap_or_default(),
+    }
 }
 
-/// One MCTS iteration: select -> expand -> evaluate -> backpropagate.
-fn run_one_iteration<P: TypedGamePlugin>(
-    arena: &mut NodeArena,
-    root_idx: usize,
-    root_state: &SimulationState<P::State>,
-    searching_player: &str,
-    players: &[Player],
-    plugin: &P,
-    params: &MctsParams,
-    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
-) {
-    let mut node_idx = root_idx;
-    let mut state = root_state.clone();
+/// One draw from Gamma(alpha, 1) (Marsaglia-Tsang; alpha < 1 via the
+/// `U^(1/alpha)` boost).
+fn sample_gamma(rng: &mut impl rand::Rng, alpha: f64) -> f64 {
+    if alpha < 1.0 {
+        let u: f64 = rng.gen();
+        return sample_gamma(rng, alpha + 1.0) * u.powf(1.0 / alpha);
+    }
+    let d = alpha - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        // Standard normal via Box-Muller
+        let (u1, u2): (f64, f64) = (rng.gen(), rng.gen());
+        let x = (-2.0 * (1.0 - u1).ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.gen();
+        if (1.0 - u).ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// `n` weights from a symmetric Dirichlet(alpha), summing to 1.
+fn sample_dirichlet(rng: &mut impl rand::Rng, alpha: f64, n: usize) -> Vec<f64> {
+    let draws: Vec<f64> = (0..n).map(|_| sample_gamma(rng, alpha)).collect();
+    let total: f64 = draws.iter().sum();
+    if total > 0.0 {
+        draws.into_iter().map(|g| g / total).collect()
+    } else {
+        // Every draw underflowed (tiny alpha): put all mass on one move.
+        let hit = rng.gen_range(0..n.max(1));
+        (0..n).map(|i| if i == hit { 1.0 } else { 0.0 }).collect()
+    }
+}
+
+/// Sample Dirichlet noise over every root move, once the root's actions are
+/// known, and hand it to the root children. No-op if already sampled.
+fn add_root_noise(arena: &mut NodeArena, root_idx: usize, alpha: f64, rng: &mut GameRng) {
+    let root = arena.get(root_idx);
+    if !arena.root_noise.is_empty() || root.untried_actions.is_none() {
+        return;
+    }
+    let mut keys: Vec<String> = root
+        .children
+        .iter()
+        .filter_map(|&ci| arena.get(ci).action_taken.as_ref().map(action_key))
+        .collect();
+    keys.extend(root.untried_actions.iter().flatten().map(action_key));
+    if keys.is_empty() {
+        return;
+    }
+    let noise = sample_dirichlet(rng, alpha, keys.len());
+    arena.root_noise = keys.into_iter().zip(noise).collect();
+    for ci in arena.get(root_idx).children.clone() {
+        let key = action_key_from_opt(&arena.get(ci).action_taken);
+        arena.get_mut(ci).noise = arena.root_noise.get(&key).copied().unwrap_or(0.0);
+    }
+}
+
+/// Simulation budget for a root with `branching` valid actions. Low-branching roots get
+/// `endgame_sim_multiplier` times the budget; the time limit still applies.
+fn effective_num_simulations(params: &MctsParams, branching: usize) -> usize {
+    if branching <= params.endgame_branching_threshold && params.endgame_sim_multiplier > 1.0 {
+        (params.num_simulations as f64 * params.endgame_sim_multiplier) as usize
+    } else {
+        params.num_simulations
+    }
+}
+
+/// One MCTS iteration: select -> expand -> evaluate -> backpropagate.
+/// `rng` drives root noise and rollouts (see `search_rng`).
+#[allow(clippy::too_many_arguments)]
+fn run_one_iteration<P: TypedGamePlugin>(
+    arena: &mut NodeArena,
+    root_idx: usize,
+    root_state: &SimulationState<P::State>,
+    searching_player: &str,
+    players: &[Player],
+    plugin: &P,
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    rng: &mut GameRng,
+) -> Result<(), String> {
+    let mut descent = descend(arena, root_idx, root_state, searching_player, players, plugin, params, rng)?;
+    let value = leaf_value(&mut descent, searching_player, players, plugin, params, eval_fn, rng)?;
+    finish_iteration(arena, &descent, value, searching_player, players, params);
+    Ok(())
+}
+
+/// A leaf picked by `descend`, waiting for its value.
+struct Descent<S: Clone> {
+    /// Nodes from the root to the leaf.
+    path: Vec<usize>,
+    /// Position at the leaf.
+    state: SimulationState<S>,
+    played_actions: Vec<(String, Option<String>)>,
+    /// Value of a solved leaf (`use_solver`), which needs no evaluation.
+    proven: Option<f64>,
+}
+
+/// Select and expand: the tree-walking half of an iteration, which needs the arena.
+#[allow(clippy::too_many_arguments)]
+fn descend<P: TypedGamePlugin>(
+    arena: &mut NodeArena,
+    root_idx: usize,
+    root_state: &SimulationState<P::State>,
+    searching_player: &str,
+    players: &[Player],
+    plugin: &P,
+    params: &MctsParams,
+    rng: &mut GameRng,
+) -> Result<Descent<P::State>, String> {
+    if params.root_dirichlet_alpha > 0.0 && params.root_exploration_fraction > 0.0 {
+        add_root_noise(arena, root_idx, params.root_dirichlet_alpha, rng);
+    }
+
+    let mut node_idx = root_idx;
+    let mut path = vec![root_idx];
+    let mut state = root_state.clone();
     let mut played_actions: Vec<(String, Option<String>)> = Vec::new();
+    let mut depth = 0;
+    let at_depth_limit = |depth: usize| params.max_tree_depth.is_some_and(|max| depth >= max);
+    let solver = params.use_solver && players.len() == 2;
 
     // 1. SELECT
     loop {
         let node = arena.get(node_idx);
-        if node.children.is_empty() || !at_widening_limit(node, params.pw_c, params.pw_alpha) {
+        // A solved node is scored by its proof, like a terminal one.
+        if solver && node.proof.is_some() && node_idx != root_idx {
+            break;
+        }
+        let all_lost = solver && all_children_lost(arena, node_idx);
+        if node.children.is_empty()
+            || !at_widening_limit(node, params)
+            || at_depth_limit(depth)
+            || (all_lost && has_untried(node))
+        {
             break;
         }
 
-        let child_idx = if params.use_rave {
-            arena.best_child_rave(node_idx, params.exploration_constant, params.rave_k, params.rave_fpu)
+        let rationality = selection_rationality(arena, node_idx, searching_player, params);
+        let skip_lost = solver && !all_lost;
+        let noise_weight = if node_idx == root_idx { params.root_exploration_fraction } else { 0.0 };
+        let proven_win = if solver {
+            node.children.iter().copied().find(|&ci| arena.get(ci).proof == Some(ProofValue::Win))
         } else {
-            arena.best_child_uct(node_idx, params.exploration_constant)
+            None
+        };
+        let child_idx = if let Some(win) = proven_win {
+            win
+        } else if params.use_rave {
+            arena.best_child_rave(
+                node_idx,
+                params.exploration_constant,
+                params.rave_k,
+                params.fpu_mode,
+                rationality,
+                skip_lost,
+                noise_weight,
+            )
+        } else {
+            arena.best_child_uct(node_idx, params.exploration_constant, rationality, skip_lost, noise_weight)
         };
 
-        node_idx = child_idx;
-        let child = arena.get(child_idx);
-
-        if child.action_taken.is_some() && child.acting_player.is_some() {
-            let key = if !child.amaf_key.is_empty() {
-                child.amaf_key.clone()
-            } else {
-                action_key_from_opt(&child.action_taken)
-            };
-            played_actions.push((key, child.acting_player.clone()));
-            apply_node_action(plugin, &mut state, arena.get(child_idx));
+        let (action, amaf) = arena.edge(node_idx, child_idx);
+        let acting_player = arena.get(child_idx).acting_player.as_deref();
+        if let (Some(action), Some(_)) = (action, acting_player) {
+            let key = if !amaf.is_empty() { amaf.to_string() } else { action_key(action) };
+            played_actions.push((key, acting_player.map(String::from)));
+            apply_node_action(plugin, &mut state, action, acting_player)?;
         }
+
+        node_idx = child_idx;
+        path.push(child_idx);
+        depth += 1;
     }
 
     // 2. EXPAND
-    if state.game_over.is_none() {
+    let solved = solver && node_idx != root_idx && arena.get(node_idx).proof.is_some();
+    if state.game_over.is_none() && !at_depth_limit(depth) && !solved {
+        // The sorted list is cached in `untried_actions`: later visits only pop from it.
         let needs_expand = arena.get(node_idx).untried_actions.is_none();
         if needs_expand {
-            let acting_pid = get_acting_player(&state.phase, players);
+            let acting_pid = plugin.acting_player(&state.state, &state.phase, players);
             let actions = if let Some(ref pid) = acting_pid {
+                arena.valid_action_calls += 1;
                 let mut acts = plugin.get_valid_actions(&state.state, &state.phase, pid);
                 acts.sort_by(|a, b| action_sort_key(a).cmp(&action_sort_key(b)));
+                if params.use_pruning_hints {
+                    let prunable = plugin.prunable_actions(&state.state, &state.phase, pid);
+                    if !prunable.is_empty() {
+                        // Stable sort: hinted actions move to the back, order otherwise kept.
+                        acts.sort_by_key(|a| prunable.contains(a));
+                    }
+                }
+                if params.decisive_moves && depth < DECISIVE_MOVES_MAX_DEPTH {
+                    acts = decisive_moves(plugin, &state, pid, players, acts)?;
+                }
                 acts
             } else {
                 vec![]
             };
-            arena.get_mut(node_idx).untried_actions = Some(actions);
+            let node = arena.get_mut(node_idx);
+            node.untried_actions = Some(actions);
+            node.phase_name.clone_from(&state.phase.name);
         }
 
         let should_expand = {
             let node = arena.get(node_idx);
             state.game_over.is_none()
                 && node.untried_actions.as_ref().map_or(false, |u| !u.is_empty())
-                && !at_widening_limit(node, params.pw_c, params.pw_alpha)
+                && (!at_widening_limit(node, params) || (solver && all_children_lost(arena, node_idx)))
         };
 
         if should_expand {
-            let acting_pid = get_acting_player(&state.phase, players);
+            let acting_pid = plugin.acting_player(&state.state, &state.phase, players);
             let action_payload = arena.get_mut(node_idx)
                 .untried_actions.as_mut().unwrap()
                 .remove(0);
@@ -425,54 +1486,330 @@ fn run_one_iteration<P: TypedGamePlugin>(
                 String::new()
             };
 
-            let mut child = MctsNode::new(Some(action_payload.clone()), Some(node_idx));
-            child.acting_player = acting_pid.clone();
-            child.amaf_key = amaf_key_str;
-
-            let child_idx = arena.alloc(child);
-            arena.get_mut(node_idx).children.push(child_idx);
-            node_idx = child_idx;
-
             if acting_pid.is_some() {
-                let child = arena.get(child_idx);
-                let key = if !child.amaf_key.is_empty() {
-                    child.amaf_key.clone()
+                let key = if !amaf_key_str.is_empty() {
+                    amaf_key_str.clone()
                 } else {
                     action_key(&action_payload)
                 };
-                played_actions.push((key, acting_pid));
-                apply_node_action(plugin, &mut state, arena.get(child_idx));
+                played_actions.push((key, acting_pid.clone()));
+                apply_node_action(plugin, &mut state, &action_payload, acting_pid.as_deref())?;
             }
+
+            // Root children stay one node per move, so per-move root stats are exact.
+            let tt_key = (params.use_transpositions && depth > 0)
+                .then(|| transposition_key(plugin, &state, acting_pid.as_deref(), depth + 1));
+            let transposed = tt_key
+                .and_then(|key| arena.transpositions.get(&key).copied())
+                .filter(|idx| !arena.get(node_idx).children.contains(idx));
+
+            let child_idx = match transposed {
+                Some(idx) => {
+                    let parent = arena.get_mut(node_idx);
+                    parent.children.push(idx);
+                    parent.transposed_edges.push(TransposedEdge {
+                        child: idx,
+                        action: action_payload,
+                        amaf_key: amaf_key_str,
+                    });
+                    idx
+                }
+                None => {
+                    let noise = if node_idx == root_idx {
+                        arena.root_noise.get(&action_key(&action_payload)).copied().unwrap_or(0.0)
+                    } else {
+                        0.0
+                    };
+                    let mut child = MctsNode::new(Some(action_payload), Some(node_idx));
+                    child.acting_player = acting_pid;
+                    child.amaf_key = amaf_key_str;
+                    child.noise = noise;
+                    let idx = arena.alloc(child);
+                    arena.get_mut(node_idx).children.push(idx);
+                    if let Some(key) = tt_key {
+                        arena.transpositions.entry(key).or_insert(idx);
+                    }
+                    idx
+                }
+            };
+            path.push(child_idx);
+        }
+    }
+
+    // 3. SOLVE terminal leaves (evaluation is left to `leaf_value`)
+    let leaf_idx = *path.last().unwrap_or(&root_idx);
+    if solver && leaf_idx != root_idx && arena.get(leaf_idx).proof.is_none() {
+        if let (Some(result), Some(mover)) = (&state.game_over, arena.get(leaf_idx).acting_player.as_deref()) {
+            arena.get_mut(leaf_idx).proof = terminal_proof(plugin, result, mover, players);
         }
     }
+    let proven = if solver { proven_value(arena.get(leaf_idx), searching_player) } else { None };
+    Ok(Descent { path, state, played_actions, proven })
+}
+
+/// Value of the leaf for `searching_player`: its proof, else the rollout's end
+/// position scored by the game result or the eval function. Leaves the arena alone.
+#[allow(clippy::type_complexity)]
+fn leaf_value<P: TypedGamePlugin>(
+    descent: &mut Descent<P::State>,
+    searching_player: &str,
+    players: &[Player],
+    plugin: &P,
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    rng: &mut GameRng,
+) -> Result<f64, String> {
+    if let Some(proven) = descent.proven {
+        return Ok(proven);
+    }
+    let state = &mut descent.state;
+    if params.rollout_depth > 0 {
+        rollout(plugin, state, players, params.rollout_depth, rng)?;
+    }
 
-    // 3. EVALUATE
-    let value = if state.game_over.is_some() {
-        terminal_value(&state.game_over, searching_player)
+    Ok(if state.game_over.is_some() {
+        terminal_value(plugin, &state.game_over, searching_player)
     } else if let Some(eval) = eval_fn {
         eval(&state.state, &state.phase, searching_player, players)
     } else {
         // Default: sigmoid of score differential
         default_eval(plugin, &state.state, searching_player)
+    })
+}
+
+/// Backpropagate `value` along the descent's path, then its proofs.
+fn finish_iteration<S: Clone>(
+    arena: &mut NodeArena,
+    descent: &Descent<S>,
+    value: f64,
+    searching_player: &str,
+    players: &[Player],
+    params: &MctsParams,
+) {
+    let Descent { path, played_actions, .. } = descent;
+    backpropagate(arena, path, value, searching_player, played_actions, params.use_rave, params.max_amaf_depth);
+    if params.use_solver && players.len() == 2 {
+        propagate_proofs(arena, path);
+    }
+}
+
+/// Run up to `sims` iterations on one determinization's tree until `deadline`,
+/// with `threads_per_tree` threads sharing it. Returns the iterations run, or
+/// the first error applying a move (see `apply_node_action`), which stops all threads.
+///
+/// Shared trees sit behind one lock, held to descend and to backpropagate; leaf
+/// evaluation (rollout and eval function, usually the bulk of an iteration) runs
+/// unlocked. Each thread's path carries a virtual loss while it evaluates, so
+/// the others spread over different leaves instead of piling onto one.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn search_tree<P: TypedGamePlugin>(
+    arena: &mut NodeArena,
+    root_state: &SimulationState<P::State>,
+    searching_player: &str,
+    players: &[Player],
+    plugin: &P,
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    sims: usize,
+    deadline: Instant,
+    det_idx: usize,
+) -> Result<usize, String> {
+    let root_idx = 0;
+    let threads = params.threads_per_tree.max(1);
+    let early_stop = params.early_stop && params.final_selection == FinalSelection::MaxVisits;
+    let check_now = |done: usize| early_stop && done.is_multiple_of(EARLY_STOP_CHECK_INTERVAL);
+    if threads == 1 {
+        let mut rng = search_rng(params, det_idx);
+        let mut iterations = 0;
+        for _sim_i in 0..sims {
+            if Instant::now() >= deadline || (check_now(iterations) && root_decided(arena, root_idx, sims - iterations)) {
+                break;
+            }
+            iterations += 1;
+            run_one_iteration(
+                arena,
+                root_idx,
+                root_state,
+                searching_player,
+                players,
+                plugin,
+                params,
+                eval_fn,
+                &mut rng,
+            )?;
+        }
+        return Ok(iterations);
+    }
+
+    let shared = Mutex::new(std::mem::replace(arena, NodeArena::new()));
+    let claimed = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+    let error: Mutex<Option<String>> = Mutex::new(None);
+    let fail = |e: String| {
+        stopped.store(true, Ordering::Relaxed);
+        error.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(e);
     };
+    (0..threads).into_par_iter().for_each(|thread_idx| {
+        let mut rng = search_rng(params, det_idx * threads + thread_idx);
+        while Instant::now() < deadline
+            && !stopped.load(Ordering::Relaxed)
+            && claimed.fetch_add(1, Ordering::Relaxed) < sims
+        {
+            let mut descent = {
+                let mut arena = shared.lock().unwrap_or_else(|e| e.into_inner());
+                match descend(&mut arena, root_idx, root_state, searching_player, players, plugin, params, &mut rng) {
+                    Ok(descent) => {
+                        add_virtual_loss(&mut arena, &descent.path);
+                        descent
+                    }
+                    Err(e) => {
+                        fail(e);
+                        break;
+                    }
+                }
+            };
+            let value = leaf_value(&mut descent, searching_player, players, plugin, params, eval_fn, &mut rng);
+            let mut arena = shared.lock().unwrap_or_else(|e| e.into_inner());
+            remove_virtual_loss(&mut arena, &descent.path);
+            let value = match value {
+                Ok(value) => value,
+                Err(e) => {
+                    fail(e);
+                    break;
+                }
+            };
+            finish_iteration(&mut arena, &descent, value, searching_player, players, params);
+            let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            // In-flight iterations are in the visit counts (virtual loss) and in the
+            // remaining budget, so the check stays conservative.
+            if check_now(done) && root_decided(&arena, root_idx, sims.saturating_sub(done)) {
+                stopped.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    *arena = shared.into_inner().unwrap_or_else(|e| e.into_inner());
+    match error.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        Some(e) => Err(e),
+        None => Ok(completed.into_inner()),
+    }
+}
+
+/// Whether the most-visited root child leads the runner-up (or an unexpanded
+/// move, at 0 visits) by more than `remaining` iterations could make up.
+fn root_decided(arena: &NodeArena, root_idx: usize, remaining: usize) -> bool {
+    let mut visits: Vec<u32> = arena.get(root_idx).children.iter().map(|&ci| arena.get(ci).visit_count).collect();
+    visits.sort_unstable_by(|a, b| b.cmp(a));
+    let (best, second) = (visits.first().copied().unwrap_or(0), visits.get(1).copied().unwrap_or(0));
+    (best - second) as usize > remaining
+}
+
+/// Count an in-flight iteration as a visit worth 0 to each node's mover, making
+/// its path look worse to other threads until `remove_virtual_loss`.
+fn add_virtual_loss(arena: &mut NodeArena, path: &[usize]) {
+    for &idx in path {
+        let node = arena.get_mut(idx);
+        node.visit_count += 1;
+        node.virtual_losses += 1;
+    }
+}
+
+/// Undo `add_virtual_loss`, before the iteration's real backpropagation.
+fn remove_virtual_loss(arena: &mut NodeArena, path: &[usize]) {
+    for &idx in path {
+        let node = arena.get_mut(idx);
+        debug_assert!(node.virtual_losses > 0, "MCTS: virtual loss removed twice");
+        node.visit_count -= 1;
+        node.virtual_losses -= 1;
+    }
+}
+
+// ------------------------------------------------------------------ //
+//  Solver
+// ------------------------------------------------------------------ //
 
-    // 4. BACKPROPAGATE
-    backpropagate(arena, node_idx, value, searching_player, &played_actions, params.use_rave, params.max_amaf_depth);
+fn has_untried(node: &MctsNode) -> bool {
+    node.untried_actions.as_ref().is_some_and(|u| !u.is_empty())
 }
 
+/// Every child of `node_idx` is a proven loss for the player choosing among them.
+fn all_children_lost(arena: &NodeArena, node_idx: usize) -> bool {
+    let children = &arena.get(node_idx).children;
+    !children.is_empty() && children.iter().all(|&ci| arena.get(ci).proof == Some(ProofValue::Loss))
+}
+
+/// Proof for a terminal node: a clean win or loss (value 1 vs 0) for `mover`.
+/// Draws and shared wins stay unproven.
+fn terminal_proof<P: TypedGamePlugin>(
+    plugin: &P,
+    result: &GameResult,
+    mover: &str,
+    players: &[Player],
+) -> Option<ProofValue> {
+    let other = players.iter().find(|p| p.player_id != mover)?;
+    let mover_value = plugin.value_for(result, mover);
+    let other_value = plugin.value_for(result, &other.player_id);
+    if mover_value == 1.0 && other_value == 0.0 {
+        Some(ProofValue::Win)
+    } else if mover_value == 0.0 && other_value == 1.0 {
+        Some(ProofValue::Loss)
+    } else {
+        None
+    }
+}
+
+/// Value of a solved node for `searching_player`, in a two-player game.
+fn proven_value(node: &MctsNode, searching_player: &str) -> Option<f64> {
+    let won = node.proof? == ProofValue::Win;
+    let mover_is_searcher = node.acting_player.as_deref()? == searching_player;
+    Some(if won == mover_is_searcher { 1.0 } else { 0.0 })
+}
+
+/// Solve ancestors of a newly proven leaf along `path`, as far as proofs follow.
+/// A node is won for the player choosing among its children if any child is a
+/// proven win for them, and lost once every action is expanded and lost. Proofs
+/// are kept for the player who moved into each node, so they flip across a
+/// change of mover. With transpositions, other parents of a shared node pick its
+/// proof up the next time an iteration passes through them.
+fn propagate_proofs(arena: &mut NodeArena, path: &[usize]) {
+    for pair in path.windows(2).rev() {
+        let (parent_idx, child_idx) = (pair[0], pair[1]);
+        let child = arena.get(child_idx);
+        let (Some(_), Some(chooser)) = (child.proof, child.acting_player.clone()) else { break };
+        let parent = arena.get(parent_idx);
+        if parent.proof.is_some() {
+            continue;
+        }
+        // The root has no mover to prove anything for.
+        let Some(parent_mover) = parent.acting_player.as_deref() else { break };
+
+        let outcome = if parent.children.iter().any(|&ci| arena.get(ci).proof == Some(ProofValue::Win)) {
+            ProofValue::Win
+        } else if parent.untried_actions.is_some() && !has_untried(parent) && all_children_lost(arena, parent_idx) {
+            ProofValue::Loss
+        } else {
+            break;
+        };
+        let proof = if parent_mover == chooser { outcome } else { outcome.flip() };
+        arena.get_mut(parent_idx).proof = Some(proof);
+    }
+}
+
+/// Update every node on `path` (root first). Walking the selected path rather
+/// than `parent` links credits the parents this iteration went through when a
+/// node is shared via the transposition table.
 fn backpropagate(
     arena: &mut NodeArena,
-    leaf_idx: usize,
+    path: &[usize],
     value: f64,
     searching_player: &str,
     played_actions: &[(String, Option<String>)],
     use_rave: bool,
     max_amaf_depth: usize,
 ) {
-    let mut node_idx_opt = Some(leaf_idx);
     let mut depth = played_actions.len();
 
-    while let Some(idx) = node_idx_opt {
+    for &idx in path.iter().rev() {
         let node = arena.get_mut(idx);
         node.visit_count += 1;
 
@@ -504,73 +1841,140 @@ fn backpropagate(
         if depth > 0 {
             depth -= 1;
         }
-        node_idx_opt = arena.get(idx).parent;
     }
 }
 
-fn at_widening_limit(node: &MctsNode, pw_c: f64, pw_alpha: f64) -> bool {
+fn at_widening_limit(node: &MctsNode, params: &MctsParams) -> bool {
     if node.untried_actions.as_ref().map_or(true, |u| u.is_empty()) {
         return true;
     }
-    let limit = max_children(node.visit_count, pw_c, pw_alpha);
+    if params.expand_all_root_children && node.parent.is_none() {
+        return false;
+    }
+    let (pw_c, pw_alpha) = params.widening_for(&node.phase_name);
+    let limit = max_children(node.visit_count, pw_c, pw_alpha, params.pw_min_children);
     node.children.len() >= limit
 }
 
-fn max_children(visit_count: u32, pw_c: f64, pw_alpha: f64) -> usize {
-    (pw_c * (visit_count.max(1) as f64).powf(pw_alpha)).max(1.0) as usize
+fn max_children(visit_count: u32, pw_c: f64, pw_alpha: f64, min_children: usize) -> usize {
+    let widened = (pw_c * (visit_count.max(1) as f64).powf(pw_alpha)).max(1.0) as usize;
+    widened.max(min_children)
 }
 
 // ------------------------------------------------------------------ //
 //  Helpers
 // ------------------------------------------------------------------ //
 
+/// Hash of a position: the plugin's state hash plus the phase.
+fn position_hash<P: TypedGamePlugin>(plugin: &P, state: &P::State, phase: &Phase) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    plugin.state_hash(state).hash(&mut hasher);
+    serde_json::to_string(phase).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Transposition-table key: the position hash plus the player who just moved and
+/// the depth. Keying on depth means every edge goes exactly one ply deeper, so
+/// the DAG cannot cycle even in games that repeat positions.
+fn transposition_key<P: TypedGamePlugin>(
+    plugin: &P,
+    state: &SimulationState<P::State>,
+    mover: Option<&str>,
+    depth: usize,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    position_hash(plugin, &state.state, &state.phase).hash(&mut hasher);
+    mover.hash(&mut hasher);
+    depth.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn apply_node_action<P: TypedGamePlugin>(
     plugin: &P,
     state: &mut SimulationState<P::State>,
-    node: &MctsNode,
-) {
-    let action_type = if !state.phase.expected_actions.is_empty() {
-        state.phase.expected_actions[0].action_type.clone()
-    } else {
-        state.phase.name.clone()
-    };
+    payload: &serde_json::Value,
+    acting_player: Option<&str>,
+) -> Result<(), String> {
     let action = Action {
-        action_type,
-        player_id: node.acting_player.clone().unwrap_or_else(|| "system".into()),
-        payload: node.action_taken.clone().unwrap_or(serde_json::json!({})),
+        action_type: plugin.action_type_for(&state.phase, payload),
+        player_id: acting_player.unwrap_or("system").to_string(),
+        payload: payload.clone(),
     };
-    apply_action_and_resolve(plugin, state, &action);
+    // Any failure here (a rejected move, an auto-resolve loop) is a plugin bug; it ends the search.
+    apply_action_and_resolve(plugin, state, &action).map_err(|e| format!("MCTS: {e}"))
 }
 
-fn get_acting_player(phase: &Phase, players: &[Player]) -> Option<String> {
-    if !phase.expected_actions.is_empty() {
-        return Some(phase.expected_actions[0].player_id.clone());
+/// `MctsParams::decisive_moves` filter for `pid`'s `actions` at `state`: the first
+/// move that makes `pid` the sole winner, else the moves after which the next
+/// player has no such move. Returns `actions` unchanged if every move loses that way.
+fn decisive_moves<P: TypedGamePlugin>(
+    plugin: &P,
+    state: &SimulationState<P::State>,
+    pid: &str,
+    players: &[Player],
+    actions: Vec<serde_json::Value>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let sole_winner = |s: &SimulationState<P::State>, player: &str| {
+        s.game_over.as_ref().is_some_and(|r| r.winners.len() == 1 && r.winners[0] == player)
+    };
+    let after = |s: &SimulationState<P::State>, action: &serde_json::Value, player: &str| {
+        let mut next = s.clone();
+        apply_node_action(plugin, &mut next, action, Some(player)).map(|_| next)
+    };
+
+    let next_states = actions.iter().map(|a| after(state, a, pid)).collect::<Result<Vec<_>, _>>()?;
+    if let Some(i) = next_states.iter().position(|s| sole_winner(s, pid)) {
+        return Ok(vec![actions[i].clone()]);
     }
-    if let Some(pi) = phase.metadata.get("player_index").and_then(|v| v.as_u64()) {
-        let idx = pi as usize;
-        if idx < players.len() {
-            debug_assert_eq!(
-                players[idx].seat_index, idx as i32,
-                "get_acting_player: player at index {} has seat_index {}, expected {}. \
-                 Players may be misordered.",
-                idx, players[idx].seat_index, idx
-            );
-            return Some(players[idx].player_id.clone());
+    let opponent_wins = |s: &SimulationState<P::State>| -> Result<bool, String> {
+        if s.game_over.is_some() {
+            return Ok(false);
+        }
+        match plugin.acting_player(&s.state, &s.phase, players) {
+            Some(opp) if opp != pid => {
+                for reply in plugin.get_valid_actions(&s.state, &s.phase, &opp) {
+                    if sole_winner(&after(s, &reply, &opp)?, &opp) {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    };
+    let mut safe = Vec::new();
+    for (action, next) in actions.iter().zip(&next_states) {
+        if !opponent_wins(next)? {
+            safe.push(action.clone());
         }
     }
-    None
+    Ok(if safe.is_empty() { actions } else { safe })
 }
 
-fn terminal_value(game_over: &Option<GameResult>, player_id: &str) -> f64 {
+/// Play up to `depth` rollout moves from `state`, stopping early when the game
+/// ends or nobody is left to act.
+fn rollout<P: TypedGamePlugin>(
+    plugin: &P,
+    state: &mut SimulationState<P::State>,
+    players: &[Player],
+    depth: usize,
+    rng: &mut GameRng,
+) -> Result<(), String> {
+    for _ in 0..depth {
+        if state.game_over.is_some() {
+            break;
+        }
+        let Some(pid) = plugin.acting_player(&state.state, &state.phase, players) else { break };
+        let Some(action) = plugin.rollout_action(&state.state, &state.phase, &pid, rng) else { break };
+        apply_node_action(plugin, state, &action, Some(&pid))?;
+    }
+    Ok(())
+}
+
+fn terminal_value<P: TypedGamePlugin>(plugin: &P, game_over: &Option<GameResult>, player_id: &str) -> f64 {
     match game_over {
         None => 0.5,
-        Some(result) => {
-            if result.winners.iter().any(|w| w == player_id) {
-                if result.winners.len() == 1 { 1.0 } else { 0.8 }
-            } else {
-                0.0
-            }
-        }
+        Some(result) => plugin.value_for(result, player_id),
     }
 }
 
@@ -642,7 +2046,7 @@ fn action_sort_key(action: &serde_json::Value) -> (i32, i64) {
 }
 
 /// Tree statistics for diagnostics.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, serde::Serialize)]
 pub struct TreeStats {
     pub total_nodes: usize,
     pub max_depth: usize,
@@ -652,6 +2056,8 @@ pub struct TreeStats {
     pub terminal_count: usize, // tracked via a counter during search
     pub avg_leaf_depth: f64,
     pub root_child_visits: Vec<(String, u32, f64)>, // (action_key, visits, avg_value)
+    pub max_children_by_phase: HashMap<String, usize>, // widest node per phase
+    pub valid_action_calls: usize, // get_valid_actions calls during expansion
 }
 
 fn collect_tree_stats(arena: &NodeArena, root_idx: usize) -> TreeStats {
@@ -659,6 +2065,7 @@ fn collect_tree_stats(arena: &NodeArena, root_idx: usize) -> TreeStats {
     let root = arena.get(root_idx);
     stats.root_visit_count = root.visit_count;
     stats.root_children = root.children.len();
+    stats.valid_action_calls = arena.valid_action_calls;
 
     // Collect root children info
     let mut child_info: Vec<(String, u32, f64)> = root.children.iter().map(|&ci| {
@@ -670,17 +2077,25 @@ fn collect_tree_stats(arena: &NodeArena, root_idx: usize) -> TreeStats {
     child_info.sort_by(|a, b| b.1.cmp(&a.1)); // sort by visits desc
     stats.root_child_visits = child_info;
 
-    // BFS to count nodes, depth, leaves
+    // BFS to count nodes, depth, leaves (each shared transposition node once)
     let mut queue = std::collections::VecDeque::new();
     queue.push_back((root_idx, 0usize));
+    let mut seen = HashSet::new();
     let mut leaf_depths = Vec::new();
 
     while let Some((idx, depth)) = queue.pop_front() {
+        if !seen.insert(idx) {
+            continue;
+        }
         stats.total_nodes += 1;
         if depth > stats.max_depth {
             stats.max_depth = depth;
         }
         let node = arena.get(idx);
+        if !node.children.is_empty() {
+            let widest = stats.max_children_by_phase.entry(node.phase_name.clone()).or_insert(0);
+            *widest = (*widest).max(node.children.len());
+        }
         if node.children.is_empty() && node.visit_count > 0 {
             stats.leaf_evals += 1;
             leaf_depths.push(depth as f64);
@@ -706,173 +2121,854 @@ pub fn mcts_search_with_stats<P: TypedGamePlugin>(
     players: &[Player],
     params: &MctsParams,
     eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
-) -> (serde_json::Value, usize, Vec<TreeStats>) {
+) -> Result<(serde_json::Value, usize, Vec<TreeStats>), String> {
     let valid_actions = plugin.get_valid_actions(state, phase, player_id);
     if valid_actions.len() <= 1 {
-        return (valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), 0, vec![]);
+        return Ok((valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), 0, vec![]));
     }
 
-    let sims_per_det = (params.num_simulations / params.num_determinizations).max(1);
+    let num_simulations = effective_num_simulations(params, valid_actions.len());
+    let sims_per_det = (num_simulations / params.num_determinizations).max(1);
     let total_deadline = Instant::now() + std::time::Duration::from_millis(params.time_limit_ms as u64);
     let base_scores = plugin.get_scores(state);
 
-    let det_results: Vec<(DetResult, TreeStats)> = (0..params.num_determinizations)
-        .into_par_iter()
-        .map(|_det_idx| {
-            if Instant::now() >= total_deadline {
-                return (DetResult {
-                    visits: HashMap::new(),
-                    values: HashMap::new(),
-                    actions: HashMap::new(),
-                    iterations: 0,
-                }, TreeStats::default());
+    let det_results = par_determinizations(params, |det_idx| {
+        if Instant::now() >= total_deadline {
+            return Ok((DetResult {
+                visits: HashMap::new(),
+                values: HashMap::new(),
+                actions: HashMap::new(),
+                iterations: 0,
+            }, TreeStats::default()));
+        }
+
+        let mut det_state = state.clone();
+        plugin.determinize(&mut det_state, &mut det_rng(params, det_idx));
+
+        let root_state = SimulationState {
+            state: det_state,
+            phase: phase.clone(),
+            players: players.into(),
+            scores: base_scores.clone(),
+            game_over: None,
+            history: None,
+        };
+
+        let mut arena = NodeArena::new();
+        let root_idx = arena.alloc(MctsNode::new(None, None));
+        let iterations = search_tree(
+            &mut arena, &root_state, player_id, players, plugin, params, eval_fn,
+            sims_per_det, total_deadline, det_idx,
+        )?;
+
+        let stats = collect_tree_stats(&arena, root_idx);
+
+        let mut visits = HashMap::new();
+        let mut values = HashMap::new();
+        let mut actions = HashMap::new();
+
+        let root = arena.get(root_idx);
+        for &child_idx in &root.children {
+            let child = arena.get(child_idx);
+            if let Some(ref action) = child.action_taken {
+                let key = action_key(action);
+                actions.entry(key.clone()).or_insert_with(|| action.clone());
+                *visits.entry(key.clone()).or_insert(0) += child.visit_count;
+                *values.entry(key).or_insert(0.0) += child.total_value;
             }
+        }
 
-            let mut det_state = state.clone();
-            plugin.determinize(&mut det_state);
+        Ok((DetResult { visits, values, actions, iterations }, stats))
+    });
 
-            let root_state = SimulationState {
-                state: det_state,
-                phase: phase.clone(),
-                players: players.to_vec(),
-                scores: base_scores.clone(),
-                game_over: None,
-            };
+    let det_results = det_results.into_iter().collect::<Result<Vec<_>, String>>()?;
+    let (dets, all_stats): (Vec<DetResult>, Vec<TreeStats>) = det_results.into_iter().unzip();
+    let DetAggregate {
+        visits: action_visits,
+        mean_values,
+        actions: mut action_map,
+        iterations: total_iterations,
+    } = aggregate_dets(dets, params.weight_dets_by_iterations);
 
-            let mut arena = NodeArena::new();
-            let root_idx = arena.alloc(MctsNode::new(None, None));
-            let mut iterations = 0;
+    if action_visits.is_empty() {
+        return Ok((valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), total_iterations, all_stats));
+    }
 
-            for _sim_i in 0..sims_per_det {
-                if Instant::now() >= total_deadline {
-                    break;
-                }
-                iterations += 1;
-                run_one_iteration(
-                    &mut arena, root_idx, &root_state,
-                    player_id, players, plugin, params, eval_fn,
-                );
+    let best_key = select_best_action(&action_visits, &mean_values, params.final_selection);
+
+    Ok((action_map.remove(&best_key).unwrap_or(serde_json::json!({})), total_iterations, all_stats))
+}
+
+/// Run a search on a single determinization and render its tree as Graphviz DOT.
+/// Each node is labeled with its action key, visits and average value; only the
+/// `top_n` most-visited children of each node are drawn. Determinization 0 is
+/// used, so set `seed` to reproduce a tree.
+#[allow(dead_code, clippy::too_many_arguments, clippy::type_complexity)]
+pub fn mcts_tree_dot<P: TypedGamePlugin>(
+    state: &P::State,
+    phase: &Phase,
+    player_id: &str,
+    plugin: &P,
+    players: &[Player],
+    params: &MctsParams,
+    eval_fn: Option<&(dyn Fn(&P::State, &Phase, &str, &[Player]) -> f64 + Sync)>,
+    top_n: usize,
+) -> Result<String, String> {
+    let branching = plugin.get_valid_actions(state, phase, player_id).len();
+    let num_simulations = effective_num_simulations(params, branching);
+    let deadline = Instant::now() + std::time::Duration::from_millis(params.time_limit_ms as u64);
+
+    let mut det_state = state.clone();
+    plugin.determinize(&mut det_state, &mut det_rng(params, 0));
+    let root_state = SimulationState {
+        state: det_state,
+        phase: phase.clone(),
+        players: players.into(),
+        scores: plugin.get_scores(state),
+        game_over: None,
+        history: None,
+    };
+
+    let mut arena = NodeArena::new();
+    let root_idx = arena.alloc(MctsNode::new(None, None));
+    let mut rng = search_rng(params, 0);
+    for _ in 0..num_simulations {
+        if Instant::now() >= deadline {
+            break;
+        }
+        run_one_iteration(
+            &mut arena, root_idx, &root_state,
+            player_id, players, plugin, params, eval_fn, &mut rng,
+        )?;
+    }
+
+    Ok(tree_to_dot(&arena, root_idx, top_n))
+}
+
+#[allow(dead_code)]
+fn tree_to_dot(arena: &NodeArena, root_idx: usize, top_n: usize) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::from("digraph mcts {\n  node [shape=box, fontname=\"monospace\"];\n");
+    let mut stack = vec![root_idx];
+    let mut drawn = HashSet::new();
+
+    while let Some(idx) = stack.pop() {
+        if !drawn.insert(idx) {
+            continue;
+        }
+        let node = arena.get(idx);
+        let avg = if node.visit_count > 0 { node.total_value / node.visit_count as f64 } else { 0.0 };
+        let key = if idx == root_idx { "root".to_string() } else { action_key_from_opt(&node.action_taken) };
+        let key = key.replace('\\', "\\\\").replace('"', "\\\"");
+        let _ = writeln!(out, "  n{} [label=\"{}\\nN={} Q={:.3}\"];", idx, key, node.visit_count, avg);
+
+        let mut children = node.children.clone();
+        children.sort_by(|&a, &b| {
+            let (ca, cb) = (arena.get(a), arena.get(b));
+            cb.visit_count
+                .cmp(&ca.visit_count)
+                .then_with(|| action_key_from_opt(&ca.action_taken).cmp(&action_key_from_opt(&cb.action_taken)))
+        });
+        let hidden = children.len().saturating_sub(top_n);
+        children.truncate(top_n);
+        for &ci in &children {
+            let _ = writeln!(out, "  n{} -> n{};", idx, ci);
+        }
+        if hidden > 0 {
+            let _ = writeln!(out, "  n{}_more [label=\"+{} more\", shape=plaintext];", idx, hidden);
+            let _ = writeln!(out, "  n{} -> n{}_more [style=dashed];", idx, idx);
+        }
+        stack.extend(children.into_iter().rev());
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::{GamePlugin, JsonAdapter, TypedTransitionResult};
+    use crate::engine::test_games::{TicTacToe, TTT_LINES};
+    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+    fn make_players(n: u32) -> Vec<Player> {
+        (0..n)
+            .map(|i| Player {
+                player_id: format!("p{}", i + 1),
+                display_name: format!("Player {}", i + 1),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mcts_returns_valid_action() {
+        let plugin = CarcassonnePlugin;
+        let json_plugin = JsonAdapter(CarcassonnePlugin);
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"tile_count": 5}),
+        };
+
+        // Use JsonAdapter for initial state + draw action (returns JSON for apply_action)
+        let (game_data, phase, _) = json_plugin.create_initial_state(&players, &config);
+
+        // Advance to place_tile phase via JSON boundary
+        let draw_action = Action {
+            action_type: "draw_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players).unwrap();
+        let game_data = result.game_data;
+        let phase = result.next_phase;
+
+        // Decode to typed state for MCTS
+        let state = plugin.decode_state(&game_data);
+
+        let params = MctsParams {
+            num_simulations: 20,
+            time_limit_ms: 500.0,
+            num_determinizations: 2,
+            ..Default::default()
+        };
+
+        let (best, iterations) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+
+        // Should have x, y, rotation (tile placement)
+        assert!(best.get("x").is_some(), "MCTS should return an action with x");
+        assert!(best.get("y").is_some(), "MCTS should return an action with y");
+        assert!(best.get("rotation").is_some(), "MCTS should return an action with rotation");
+        assert!(iterations > 0, "Should have run at least one iteration");
+    }
+
+    /// One-move toy game: `take` wins for the mover, `pass` loses. The plugin lists
+    /// `pass` first and hints it as prunable.
+    struct PassOrTake;
+
+    impl TypedGamePlugin for PassOrTake {
+        type State = ();
+
+        fn game_id(&self) -> &str { "pass_or_take" }
+        fn display_name(&self) -> &str { "Pass or Take" }
+        fn min_players(&self) -> u32 { 2 }
+        fn max_players(&self) -> u32 { 2 }
+        fn description(&self) -> &str { "" }
+        fn disconnect_policy(&self) -> &str { "abandon_all" }
+        fn decode_state(&self, _game_data: &serde_json::Value) {}
+        fn encode_state(&self, _state: &()) -> serde_json::Value { serde_json::json!({}) }
+
+        fn create_initial_state(&self, players: &[Player], _config: &GameConfig) -> ((), Phase, Vec<Event>) {
+            let phase = Phase {
+                name: "choose".into(),
+                concurrent_mode: None,
+                expected_actions: vec![ExpectedAction {
+                    player_id: players[0].player_id.clone(),
+                    action_type: "choose".into(),
+                    constraints: HashMap::new(),
+                    timeout_ms: None,
+                }],
+                auto_resolve: false,
+                metadata: serde_json::json!({"player_index": 0}),
+            };
+            ((), phase, vec![])
+        }
+
+        fn get_valid_actions(&self, _state: &(), phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+            if phase.name != "choose" {
+                return vec![];
             }
+            vec![serde_json::json!({"pass": true}), serde_json::json!({"take": true})]
+        }
+
+        fn validate_action(&self, _state: &(), _phase: &Phase, _action: &Action) -> Option<String> {
+            None
+        }
 
-            let stats = collect_tree_stats(&arena, root_idx);
+        fn apply_action(&self, _state: &(), _phase: &Phase, action: &Action, players: &[Player]) -> Result<TypedTransitionResult<()>, String> {
+            let taken = action.payload.get("take").is_some();
+            let winner = if taken { &players[0] } else { &players[1] };
+            Ok(TypedTransitionResult {
+                state: (),
+                events: vec![],
+                next_phase: Phase {
+                    name: "game_over".into(),
+                    concurrent_mode: None,
+                    expected_actions: vec![],
+                    auto_resolve: false,
+                    metadata: serde_json::json!({}),
+                },
+                scores: HashMap::new(),
+                game_over: Some(GameResult {
+                    winners: vec![winner.player_id.clone()],
+                    final_scores: HashMap::new(),
+                    reason: "normal".into(),
+                    details: HashMap::new(),
+                }),
+            })
+        }
 
-            let mut visits = HashMap::new();
-            let mut values = HashMap::new();
-            let mut actions = HashMap::new();
+        fn get_player_view(&self, _state: &(), _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+            serde_json::json!({})
+        }
 
-            let root = arena.get(root_idx);
-            for &child_idx in &root.children {
-                let child = arena.get(child_idx);
-                if let Some(ref action) = child.action_taken {
-                    let key = action_key(action);
-                    actions.entry(key.clone()).or_insert_with(|| action.clone());
-                    *visits.entry(key.clone()).or_insert(0) += child.visit_count;
-                    *values.entry(key).or_insert(0.0) += child.total_value;
-                }
+        fn get_scores(&self, _state: &()) -> HashMap<String, f64> {
+            HashMap::new()
+        }
+
+        fn parse_ai_action(&self, response: &serde_json::Value, _phase: &Phase, player_id: &str) -> Action {
+            Action { action_type: "choose".into(), player_id: player_id.into(), payload: response.clone() }
+        }
+
+        fn prunable_actions(&self, _state: &(), _phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+            vec![serde_json::json!({"pass": true})]
+        }
+    }
+
+    /// Three interchangeable moves that all end the game in a draw.
+    struct ThreeWayDraw;
+
+    impl TypedGamePlugin for ThreeWayDraw {
+        type State = ();
+
+        fn game_id(&self) -> &str { "three_way_draw" }
+        fn display_name(&self) -> &str { "Three Way Draw" }
+        fn min_players(&self) -> u32 { 2 }
+        fn max_players(&self) -> u32 { 2 }
+        fn description(&self) -> &str { "" }
+        fn disconnect_policy(&self) -> &str { "abandon_all" }
+        fn decode_state(&self, _game_data: &serde_json::Value) {}
+        fn encode_state(&self, _state: &()) -> serde_json::Value { serde_json::json!({}) }
+
+        fn create_initial_state(&self, players: &[Player], config: &GameConfig) -> ((), Phase, Vec<Event>) {
+            PassOrTake.create_initial_state(players, config)
+        }
+
+        fn get_valid_actions(&self, _state: &(), phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+            if phase.name != "choose" {
+                return vec![];
             }
+            ["a", "b", "c"].iter().map(|m| serde_json::json!({"move": m})).collect()
+        }
 
-            (DetResult { visits, values, actions, iterations }, stats)
-        })
-        .collect();
+        fn validate_action(&self, _state: &(), _phase: &Phase, _action: &Action) -> Option<String> {
+            None
+        }
 
-    let mut action_visits: HashMap<String, u32> = HashMap::new();
-    let mut action_values: HashMap<String, f64> = HashMap::new();
-    let mut action_map: HashMap<String, serde_json::Value> = HashMap::new();
-    let mut total_iterations: usize = 0;
-    let mut all_stats = Vec::new();
-
-    for (det, stats) in det_results {
-        total_iterations += det.iterations;
-        all_stats.push(stats);
-        for (key, count) in &det.visits {
-            *action_visits.entry(key.clone()).or_insert(0) += count;
-            if !action_map.contains_key(key) {
-                if let Some(action) = det.actions.get(key) {
-                    action_map.insert(key.clone(), action.clone());
-                }
+        fn apply_action(&self, state: &(), phase: &Phase, action: &Action, players: &[Player]) -> Result<TypedTransitionResult<()>, String> {
+            let mut result = PassOrTake.apply_action(state, phase, action, players)?;
+            if let Some(ref mut gr) = result.game_over {
+                gr.winners = players.iter().map(|p| p.player_id.clone()).collect();
             }
+            Ok(result)
+        }
+
+        fn get_player_view(&self, _state: &(), _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+            serde_json::json!({})
+        }
+
+        fn get_scores(&self, _state: &()) -> HashMap<String, f64> {
+            HashMap::new()
         }
-        for (key, value) in &det.values {
-            *action_values.entry(key.clone()).or_insert(0.0) += value;
+
+        fn parse_ai_action(&self, response: &serde_json::Value, phase: &Phase, player_id: &str) -> Action {
+            PassOrTake.parse_ai_action(response, phase, player_id)
         }
     }
 
-    if action_visits.is_empty() {
-        return (valid_actions.into_iter().next().unwrap_or(serde_json::json!({})), total_iterations, all_stats);
+    /// PassOrTake whose `apply_action` rejects `take`, though it lists it as valid.
+    struct RejectsTake;
+
+    impl TypedGamePlugin for RejectsTake {
+        type State = ();
+
+        fn game_id(&self) -> &str { "rejects_take" }
+        fn display_name(&self) -> &str { "Rejects Take" }
+        fn min_players(&self) -> u32 { 2 }
+        fn max_players(&self) -> u32 { 2 }
+        fn description(&self) -> &str { "" }
+        fn disconnect_policy(&self) -> &str { "abandon_all" }
+        fn decode_state(&self, _game_data: &serde_json::Value) {}
+        fn encode_state(&self, _state: &()) -> serde_json::Value { serde_json::json!({}) }
+
+        fn create_initial_state(&self, players: &[Player], config: &GameConfig) -> ((), Phase, Vec<Event>) {
+            PassOrTake.create_initial_state(players, config)
+        }
+
+        fn get_valid_actions(&self, state: &(), phase: &Phase, player_id: &str) -> Vec<serde_json::Value> {
+            PassOrTake.get_valid_actions(state, phase, player_id)
+        }
+
+        fn validate_action(&self, _state: &(), _phase: &Phase, _action: &Action) -> Option<String> {
+            None
+        }
+
+        fn apply_action(&self, state: &(), phase: &Phase, action: &Action, players: &[Player]) -> Result<TypedTransitionResult<()>, String> {
+            if action.payload.get("take").is_some() {
+                return Err("take rejected".into());
+            }
+            PassOrTake.apply_action(state, phase, action, players)
+        }
+
+        fn get_player_view(&self, _state: &(), _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+            serde_json::json!({})
+        }
+
+        fn get_scores(&self, _state: &()) -> HashMap<String, f64> {
+            HashMap::new()
+        }
+
+        fn parse_ai_action(&self, response: &serde_json::Value, phase: &Phase, player_id: &str) -> Action {
+            PassOrTake.parse_ai_action(response, phase, player_id)
+        }
     }
 
-    let max_visits = action_visits.values().copied().max().unwrap_or(0);
-    let best_key = action_visits.iter()
-        .filter(|(_, &v)| v == max_visits)
-        .max_by(|(a_key, _), (b_key, _)| {
-            let a_val = action_values.get(*a_key).copied().unwrap_or(0.0) / max_visits.max(1) as f64;
-            let b_val = action_values.get(*b_key).copied().unwrap_or(0.0) / max_visits.max(1) as f64;
-            a_val.partial_cmp(&b_val).unwrap_or(std::cmp::Ordering::Equal)
-        })
-        .map(|(k, _)| k.clone())
-        .unwrap();
+    #[test]
+    fn test_search_reports_rejected_move() {
+        let plugin = RejectsTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
 
-    (action_map.remove(&best_key).unwrap_or(serde_json::json!({})), total_iterations, all_stats)
-}
+        for threads_per_tree in [1, 2] {
+            let params = MctsParams {
+                num_simulations: 50,
+                time_limit_ms: 999999.0,
+                num_determinizations: 2,
+                threads_per_tree,
+                seed: Some(1),
+                ..Default::default()
+            };
+            let err = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap_err();
+            assert!(err.contains("take rejected"), "unexpected error: {}", err);
+            let err = mcts_search_with_stats(&state, &phase, "p1", &plugin, &players, &params, None).unwrap_err();
+            assert!(err.contains("take rejected"), "unexpected error: {}", err);
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::engine::plugin::{GamePlugin, JsonAdapter};
-    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+    #[test]
+    fn test_tree_dot_export() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 200,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            seed: Some(1),
+            ..Default::default()
+        };
+
+        let dot = mcts_tree_dot(&state, &phase, "p0", &plugin, &players, &params, None, 3).unwrap();
+
+        assert!(dot.starts_with("digraph mcts {"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert!(dot.contains("n0 [label=\"root\\nN=200 "));
+        let root_children: Vec<&str> = dot
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("n0 -> "))
+            .filter_map(|l| l.strip_suffix(';'))
+            .filter(|target| !target.contains("_more"))
+            .collect();
+        assert_eq!(root_children.len(), 3);
+        // Each drawn child is labeled by its action key, with JSON quotes escaped.
+        for child in root_children {
+            let prefix = format!(r#"{} [label="{{\"cell\":"#, child);
+            assert!(dot.lines().any(|l| l.trim().starts_with(&prefix)), "no label for {}", child);
+        }
+        assert!(dot.contains("n0_more [label=\"+6 more\""));
+    }
+
+    #[test]
+    fn test_max_parallel_determinizations_keeps_results() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let base = MctsParams {
+            num_simulations: 300,
+            time_limit_ms: 999999.0,
+            num_determinizations: 7,
+            seed: Some(5),
+            ..Default::default()
+        };
+
+        let expected = mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &base, None).unwrap();
+        for cap in [1, 2, 3, 7, 20] {
+            let params = MctsParams { max_parallel_determinizations: Some(cap), ..base.clone() };
+            let got = mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+            assert_eq!(got, expected, "cap {}", cap);
+        }
+        let chunked = MctsParams { max_parallel_determinizations: Some(3), ..base };
+        assert_eq!(par_determinizations(&chunked, |i| i), (0..7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_exact_ties_break_by_key() {
+        let plugin = ThreeWayDraw;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 60,
+            time_limit_ms: 999999.0,
+            num_determinizations: 4,
+            ..Default::default()
+        };
+
+        // Every move has the same visits and value; the pick must not depend on
+        // HashMap order or thread scheduling.
+        for _ in 0..10 {
+            let (best, _) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+            assert_eq!(best, serde_json::json!({"move": "a"}));
+        }
+
+        let visits: HashMap<String, u32> =
+            ["c", "a", "b"].iter().map(|k| (k.to_string(), 7)).collect();
+        let values: HashMap<String, f64> = visits.keys().map(|k| (k.clone(), 0.5)).collect();
+        assert_eq!(most_visited_key(&visits, &values), "a");
+    }
+
+    #[test]
+    fn test_pruning_hints_reduce_visits_on_dominated_action() {
+        let plugin = PassOrTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let pass_key = action_key(&serde_json::json!({"pass": true}));
+
+        // Expansion order matters most when the budget is small.
+        let run = |use_pruning_hints: bool| {
+            let params = MctsParams {
+                num_simulations: 10,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                pw_c: 1.0,
+                pw_alpha: 0.5,
+                use_pruning_hints,
+                ..Default::default()
+            };
+            let (best, _, stats) =
+                mcts_search_with_stats(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+            let pass_visits = stats[0]
+                .root_child_visits
+                .iter()
+                .find(|(k, _, _)| *k == pass_key)
+                .map_or(0, |c| c.1);
+            (best, pass_visits)
+        };
+
+        let (best_plain, pass_plain) = run(false);
+        let (best_hinted, pass_hinted) = run(true);
+        assert_eq!(best_plain, serde_json::json!({"take": true}));
+        assert_eq!(best_hinted, best_plain, "hints must not change a clear best move");
+        assert!(
+            pass_hinted < pass_plain,
+            "hinted skip should get fewer visits: {} vs {}",
+            pass_hinted,
+            pass_plain
+        );
+    }
+
+    #[test]
+    fn test_root_policy_distribution() {
+        let plugin = PassOrTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 100,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            ..Default::default()
+        };
+
+        let (best, _, policy) =
+            mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+        assert_eq!(policy.len(), 2);
+        let total: f64 = policy.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9, "policy sums to {}", total);
+        assert_eq!(policy[0].0, best);
+        assert!(policy.iter().all(|(_, p)| *p <= policy[0].1));
+        assert_eq!(best, serde_json::json!({"take": true}));
+    }
+
+    #[test]
+    fn test_search_policy_temperature() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 300,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            seed: Some(4),
+            ..Default::default()
+        };
+        let policy_at = |temperature| {
+            mcts_search_policy(&state, &phase, "p1", &plugin, &players, &params, None, temperature).unwrap()
+        };
+
+        let (best, raw) = policy_at(1.0);
+        let (searched, _) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+        assert_eq!(best, searched);
+        assert_eq!(raw[0].0, best);
+        assert_eq!(raw.len(), 9);
+        assert!((raw.iter().map(|e| e.1).sum::<f64>() - 1.0).abs() < 1e-9);
+        let (_, _, unscaled) = mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+        assert_eq!(raw, unscaled);
+
+        // Higher temperature flattens, lower sharpens, <= 0 is one-hot.
+        let (_, hot) = policy_at(4.0);
+        let (_, cold) = policy_at(0.25);
+        assert!(hot[0].1 < raw[0].1 && raw[0].1 < cold[0].1);
+        assert!((hot.iter().map(|e| e.1).sum::<f64>() - 1.0).abs() < 1e-9);
+        let (_, greedy) = policy_at(0.0);
+        assert_eq!(greedy[0], (best, 1.0));
+        assert!(greedy[1..].iter().all(|e| e.1 == 0.0));
+    }
+
+    #[test]
+    fn test_root_dirichlet_noise() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let policy_with = |alpha: f64, seed| {
+            let params = MctsParams {
+                num_simulations: 300,
+                time_limit_ms: 999999.0,
+                num_determinizations: 2,
+                root_dirichlet_alpha: alpha,
+                root_exploration_fraction: 0.25,
+                seed: Some(seed),
+                ..Default::default()
+            };
+            mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &params, None).unwrap().2
+        };
+
+        assert_eq!(policy_with(0.3, 1), policy_with(0.3, 1));
+        assert_ne!(policy_with(0.3, 1), policy_with(0.3, 2));
+        // No noise: the seed is unused.
+        assert_eq!(policy_with(0.0, 1), policy_with(0.0, 2));
+        assert_ne!(policy_with(0.3, 1), policy_with(0.0, 1));
+
+        let mut rng = GameRng::from_seed(3);
+        for alpha in [0.03, 0.3, 2.5] {
+            let noise = sample_dirichlet(&mut rng, alpha, 9);
+            assert!((noise.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(noise.iter().all(|&x| (0.0..=1.0).contains(&x)));
+        }
+        assert!(MctsParams::builder().root_exploration_fraction(1.5).build().is_err());
+        assert!(MctsParams::builder().root_dirichlet_alpha(-1.0).build().is_err());
+    }
+
+    #[test]
+    fn test_rollout_stops_at_game_over() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        // One empty cell left, X (seat 0) to fill it.
+        let mut state = SimulationState {
+            state: [1, 2, 1, 1, 2, 2, 2, 1, 0],
+            phase: plugin.create_initial_state(&players, &config).1,
+            players: players.clone().into(),
+            scores: HashMap::new(),
+            game_over: None,
+            history: None,
+        };
+        rollout(&plugin, &mut state, &players, 5, &mut GameRng::from_seed(1)).unwrap();
+        assert_eq!(state.state[8], 1);
+        assert!(state.game_over.is_some());
+    }
+
+    #[test]
+    fn test_rollouts_beat_random() {
+        use crate::engine::arena::{run_arena, DEFAULT_MAX_MOVES_PER_GAME};
+        use crate::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
+
+        // No eval function: without rollouts every leaf scores 0.5.
+        let params = MctsParams {
+            num_simulations: 500,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            rollout_depth: 9,
+            seed: Some(3),
+            ..Default::default()
+        };
+        let mut strategies: HashMap<String, Box<dyn BotStrategy<TicTacToe>>> = HashMap::new();
+        strategies.insert("mcts".into(), Box::new(MctsStrategy::new(params)));
+        strategies.insert("random".into(), Box::new(RandomStrategy));
+        let result = run_arena(&TicTacToe, &strategies, 10, 7, 2, None, true, false, DEFAULT_MAX_MOVES_PER_GAME, None);
+        assert_eq!(result.wins["random"], 0, "{:?}", result.wins);
+        assert!(result.wins["mcts"] >= 5, "{:?}", result.wins);
+    }
 
-    fn make_players(n: u32) -> Vec<Player> {
-        (0..n)
-            .map(|i| Player {
-                player_id: format!("p{}", i + 1),
-                display_name: format!("Player {}", i + 1),
-                seat_index: i as i32,
-                is_bot: false,
-                bot_id: None,
-            })
-            .collect()
+    #[test]
+    fn test_virtual_loss_diverts_selection() {
+        let mut arena = NodeArena::new();
+        let root = arena.alloc(MctsNode::new(None, None));
+        arena.get_mut(root).visit_count = 20;
+        for cell in 0..2 {
+            let mut child = MctsNode::new(Some(serde_json::json!({"cell": cell})), Some(root));
+            child.acting_player = Some("p1".into());
+            child.visit_count = 10;
+            child.total_value = 6.0;
+            let idx = arena.alloc(child);
+            arena.get_mut(root).children.push(idx);
+        }
+        let (first, second) = (arena.get(root).children[0], arena.get(root).children[1]);
+        assert_eq!(arena.best_child_uct(root, 1.0, 1.0, false, 0.0), first);
+
+        add_virtual_loss(&mut arena, &[root, first]);
+        assert_eq!(arena.best_child_uct(root, 1.0, 1.0, false, 0.0), second);
+        remove_virtual_loss(&mut arena, &[root, first]);
+        assert_eq!(arena.get(first).visit_count, 10);
+        assert_eq!(arena.get(first).virtual_losses, 0);
+        assert_eq!(arena.best_child_uct(root, 1.0, 1.0, false, 0.0), first);
     }
 
     #[test]
-    fn test_mcts_returns_valid_action() {
-        let plugin = CarcassonnePlugin;
-        let json_plugin = JsonAdapter(CarcassonnePlugin);
+    fn test_threads_per_tree_shares_one_tree() {
+        let plugin = TicTacToe;
         let players = make_players(2);
-        let config = GameConfig {
-            random_seed: Some(42),
-            options: serde_json::json!({"tile_count": 5}),
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+        // X on 0 and 1, O on 3 and 4: X wins at 2.
+        for (i, cell) in [0, 3, 1, 4].into_iter().enumerate() {
+            let action = Action {
+                action_type: "play".into(),
+                player_id: players[i % 2].player_id.clone(),
+                payload: serde_json::json!({"cell": cell}),
+            };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            (state, phase) = (r.state, r.next_phase);
+        }
+        let params = MctsParams {
+            num_simulations: 300,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            threads_per_tree: 4,
+            ..Default::default()
         };
 
-        // Use JsonAdapter for initial state + draw action (returns JSON for apply_action)
-        let (game_data, phase, _) = json_plugin.create_initial_state(&players, &config);
+        // Own pool, so the workers really interleave even on a single-core runner.
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let (best, iterations, stats) =
+            pool.install(|| mcts_search_with_stats(&state, &phase, "p1", &plugin, &players, &params, None).unwrap());
+        assert_eq!(best, serde_json::json!({"cell": 2}));
+        assert_eq!(iterations, 300);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].root_visit_count, 300);
+        assert!(MctsParams::builder().threads_per_tree(0).build().is_err());
+    }
 
-        // Advance to place_tile phase via JSON boundary
-        let draw_action = Action {
-            action_type: "draw_tile".into(),
-            player_id: "p1".into(),
-            payload: serde_json::json!({}),
+    #[test]
+    fn test_early_stop_on_forced_win() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+        // X on 0 and 1, O on 3 and 4: X wins at 2.
+        for (i, cell) in [0, 3, 1, 4].into_iter().enumerate() {
+            let action = Action {
+                action_type: "play".into(),
+                player_id: players[i % 2].player_id.clone(),
+                payload: serde_json::json!({"cell": cell}),
+            };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            (state, phase) = (r.state, r.next_phase);
+        }
+        let search = |early_stop| {
+            let params = MctsParams {
+                num_simulations: 1000,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                early_stop,
+                ..Default::default()
+            };
+            mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap()
         };
-        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players);
-        let game_data = result.game_data;
-        let phase = result.next_phase;
 
-        // Decode to typed state for MCTS
-        let state = plugin.decode_state(&game_data);
+        let (full_action, full_iterations) = search(false);
+        let (action, iterations) = search(true);
+        assert_eq!(full_iterations, 1000);
+        assert_eq!(action, full_action);
+        assert_eq!(action, serde_json::json!({"cell": 2}));
+        assert!(iterations < 600, "early stop ran {iterations} iterations");
+        assert_eq!(iterations % EARLY_STOP_CHECK_INTERVAL, 0);
+    }
 
-        let params = MctsParams {
-            num_simulations: 20,
-            time_limit_ms: 500.0,
-            num_determinizations: 2,
-            ..Default::default()
+    #[test]
+    fn test_selection_temperature() {
+        let plugin = PassOrTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let search = |selection_temperature: f64, selection_seed: u64| {
+            let params = MctsParams {
+                num_simulations: 100,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                selection_temperature,
+                seed: Some(selection_seed),
+                ..Default::default()
+            };
+            mcts_search_with_policy(&state, &phase, "p1", &plugin, &players, &params, None).unwrap()
         };
 
-        let (best, iterations) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None);
+        // Temperature 0 returns the argmax of the visit distribution
+        for seed in 0..5 {
+            let (best, _, policy) = search(0.0, seed);
+            assert_eq!(best, policy[0].0);
+        }
 
-        // Should have x, y, rotation (tile placement)
-        assert!(best.get("x").is_some(), "MCTS should return an action with x");
-        assert!(best.get("y").is_some(), "MCTS should return an action with y");
-        assert!(best.get("rotation").is_some(), "MCTS should return an action with rotation");
-        assert!(iterations > 0, "Should have run at least one iteration");
+        // A high temperature spreads choices across seeds
+        let chosen: std::collections::HashSet<String> =
+            (0..30).map(|seed| action_key(&search(10.0, seed).0)).collect();
+        assert_eq!(chosen.len(), 2, "expected both moves to be sampled: {:?}", chosen);
+    }
+
+    #[test]
+    fn test_strategy_samples_per_move() {
+        use crate::engine::bot_strategy::{BotStrategy, MctsStrategy};
+
+        let plugin = PassOrTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let strategy: MctsStrategy<PassOrTake> = MctsStrategy::new(MctsParams {
+            num_simulations: 100,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            selection_temperature: 10.0,
+            ..Default::default()
+        });
+        // Each move draws its sampling seed from the game's RNG
+        let mut rng = GameRng::from_seed(1);
+        let chosen: std::collections::HashSet<String> = (0..30)
+            .map(|_| action_key(&strategy.choose_action(&state, &phase, "p1", &plugin, &players, &mut rng)))
+            .collect();
+        assert_eq!(chosen.len(), 2, "expected both moves to be sampled: {:?}", chosen);
+    }
+
+    #[test]
+    fn test_sample_by_visits_is_seeded() {
+        let visits: HashMap<String, u32> =
+            [("a".to_string(), 50), ("b".to_string(), 30), ("c".to_string(), 20)].into_iter().collect();
+        let sample = |temperature: f64, seed: u64| sample_by_visits(&visits, temperature, &mut GameRng::from_seed(seed));
+        for seed in 0..10 {
+            assert_eq!(sample(1.0, seed), sample(1.0, seed));
+        }
+        // Very low temperatures collapse onto the most-visited move
+        assert!((0..10).all(|seed| sample(0.01, seed) == "a"));
     }
 
     #[test]
@@ -888,7 +2984,7 @@ mod tests {
         let (state, phase, _) = plugin.create_initial_state(&players, &config);
 
         let params = MctsParams::default();
-        let (_action, _iters) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None);
+        let (_action, _iters) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
     }
 
     #[test]
@@ -900,7 +2996,7 @@ mod tests {
 
         let plugin = CarcassonnePlugin;
         let players = make_players(2);
-        let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+        let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
 
         let mut results: Vec<serde_json::Value> = Vec::new();
 
@@ -913,9 +3009,10 @@ mod tests {
             let mut sim = SimulationState {
                 state,
                 phase,
-                players: players.clone(),
+                players: players.clone().into(),
                 scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
                 game_over: None,
+                history: None,
             };
 
             let mut rng = seed;
@@ -928,7 +3025,7 @@ mod tests {
                     apply_action_and_resolve(&plugin, &mut sim, &Action {
                         action_type: at, player_id: "system".into(),
                         payload: serde_json::json!({}),
-                    });
+                    }).unwrap();
                 }
                 if sim.game_over.is_some() { break; }
 
@@ -961,7 +3058,7 @@ mod tests {
                     player_id: acting_pid,
                     payload: valid[idx].clone(),
                 };
-                apply_action_and_resolve(&plugin, &mut sim, &action);
+                apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
             }
         }
 
@@ -977,6 +3074,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pw_overrides_narrow_meeple_phase() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let mut sim = SimulationState {
+            state,
+            phase,
+            players: players.clone().into(),
+            scores: HashMap::new(),
+            game_over: None,
+            history: None,
+        };
+        // Draw the first tile.
+        let draw = Action {
+            action_type: sim.phase.name.clone(),
+            player_id: "system".into(),
+            payload: serde_json::json!({}),
+        };
+        apply_action_and_resolve(&plugin, &mut sim, &draw).unwrap();
+        let (state, phase) = (sim.state, sim.phase);
+        assert_eq!(phase.name, "place_tile");
+        let widest = |params: MctsParams| {
+            let params = MctsParams {
+                num_simulations: 300,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                seed: Some(1),
+                ..params
+            };
+            let (_, _, stats) = mcts_search_with_stats(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+            stats[0].max_children_by_phase.clone()
+        };
+
+        // No widening at all, then the same with place_meeple held to a trickle.
+        let base = widest(MctsParams { pw_c: 100.0, ..Default::default() });
+        let narrowed =
+            widest(MctsParams::builder().pw_c(100.0).pw_override("place_meeple", 1.0, 0.1).build().unwrap());
+        assert!(base["place_meeple"] > 1, "{base:?}");
+        assert_eq!(narrowed["place_meeple"], 1, "{narrowed:?}");
+        assert!(narrowed["place_tile"] > 1, "{narrowed:?}");
+
+        let bad = MctsParams::builder().pw_override("place_tile", 1.0, 1.5).build();
+        assert!(bad.unwrap_err().contains("place_tile"));
+    }
+
     #[test]
     fn test_mcts_tree_stats_comparison() {
         // Compare tree structure between pw_c=1 and pw_c=2 at a high-branching mid-game state
@@ -999,9 +3143,10 @@ mod tests {
             let mut sim = SimulationState {
                 state,
                 phase,
-                players: players.clone(),
+                players: players.clone().into(),
                 scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
                 game_over: None,
+                history: None,
             };
 
             let mut rng = seed * 31337;
@@ -1012,7 +3157,7 @@ mod tests {
                     apply_action_and_resolve(&plugin, &mut sim, &Action {
                         action_type: at, player_id: "system".into(),
                         payload: serde_json::json!({}),
-                    });
+                    }).unwrap();
                 }
                 if sim.game_over.is_some() { break; }
 
@@ -1033,7 +3178,7 @@ mod tests {
                     player_id: acting_pid,
                     payload: valid[idx].clone(),
                 };
-                apply_action_and_resolve(&plugin, &mut sim, &action);
+                apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
             }
             if best_count >= 40 { break; }
         }
@@ -1055,28 +3200,30 @@ mod tests {
             println!("  {:2}. {} sort_key={:?}", i, key, sort);
         }
 
-        let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+        let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
 
         let configs = [
-            ("pw_c=1", 1.0f64, 0.5f64),
-            ("pw_c=2", 2.0, 0.5),
-            ("pw_c=100 (no PW)", 100.0, 0.5),
+            ("pw_c=1", 1.0f64, 0.5f64, 1usize),
+            ("pw_c=1 floor=4", 1.0, 0.5, 4),
+            ("pw_c=2", 2.0, 0.5, 1),
+            ("pw_c=100 (no PW)", 100.0, 0.5, 1),
         ];
 
-        for (label, pw_c, pw_alpha) in &configs {
+        for (label, pw_c, pw_alpha, pw_min_children) in &configs {
             let params = MctsParams {
                 num_simulations: 500,
                 time_limit_ms: 999999.0,
                 num_determinizations: 1,
                 pw_c: *pw_c,
                 pw_alpha: *pw_alpha,
+                pw_min_children: *pw_min_children,
                 ..Default::default()
             };
 
             let (best_action, iters, stats) = mcts_search_with_stats(
                 &sim.state, &sim.phase, &acting_pid, &plugin, &players, &params,
                 Some(&|s, ph, pid, pl| eval_fn(s, ph, pid, pl)),
-            );
+            ).unwrap();
 
             println!("\n=== {} (iters={}) ===", label, iters);
             for (i, s) in stats.iter().enumerate() {
@@ -1093,6 +3240,537 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_det_weighting_ignores_near_empty_det() {
+        let det = |iterations: usize, stats: &[(&str, u32, f64)]| DetResult {
+            visits: stats.iter().map(|(k, v, _)| (k.to_string(), *v)).collect(),
+            values: stats.iter().map(|(k, _, val)| (k.to_string(), *val)).collect(),
+            actions: stats.iter().map(|(k, _, _)| (k.to_string(), serde_json::json!(k))).collect(),
+            iterations,
+        };
+        // x and y tie on total visits (250). The long det rates x (0.6) over y (0.5);
+        // the det cut short after 1 iteration saw y once, with a win.
+        let dets = || {
+            vec![
+                det(500, &[("x", 250, 150.0), ("y", 249, 124.5)]),
+                det(1, &[("y", 1, 1.0)]),
+            ]
+        };
+
+        let agg = aggregate_dets(dets(), true);
+        assert_eq!(agg.visits["x"], 250);
+        assert_eq!(agg.visits["y"], 250);
+        assert_eq!(agg.iterations, 501);
+        assert!((agg.mean_values["y"] - (500.0 * 0.5 + 1.0) / 501.0).abs() < 1e-12);
+        assert_eq!(most_visited_key(&agg.visits, &agg.mean_values), "x");
+
+        // An unweighted average of per-det means would let the 1-iteration det win it for y
+        let naive_y = (0.5 + 1.0) / 2.0;
+        assert!(naive_y > agg.mean_values["x"]);
+
+        // Pooled mode (the default) keeps the previous tiebreak
+        let pooled = aggregate_dets(dets(), false);
+        assert!((pooled.mean_values["y"] - 125.5 / 250.0).abs() < 1e-12);
+        assert_eq!(most_visited_key(&pooled.visits, &pooled.mean_values), "x");
+    }
+
+    #[test]
+    fn test_fpu_modes_for_unvisited_child() {
+        let mut parent = MctsNode::new(None, None);
+        parent.acting_player = Some("p1".into());
+        parent.visit_count = 10;
+        parent.total_value = 7.0;
+        parent.amaf_visits.insert("a".into(), 4);
+        parent.amaf_values.insert("a".into(), 1.0);
+
+        let mut child = MctsNode::new(Some(serde_json::json!({"x": 1})), Some(0));
+        child.amaf_key = "a".into();
+        child.acting_player = Some("p2".into());
+        let fpu = |child: &MctsNode, mode| child.rave_value(&child.amaf_key, 10, 1.41, 100.0, mode, Some(&parent), 1.0);
+
+        assert_eq!(fpu(&child, FpuMode::Infinity), f64::INFINITY);
+        assert!((fpu(&child, FpuMode::AmafOptimistic) - 1.25).abs() < 1e-12);
+        // Parent's 0.7 is p1's view; p2 sees 0.3
+        assert!((fpu(&child, FpuMode::ParentValue) - 0.3).abs() < 1e-12);
+        child.acting_player = Some("p1".into());
+        assert!((fpu(&child, FpuMode::ParentValue) - 0.7).abs() < 1e-12);
+
+        // Without AMAF stats, optimistic FPU falls back to infinity
+        child.amaf_key = "b".into();
+        assert_eq!(fpu(&child, FpuMode::AmafOptimistic), f64::INFINITY);
+
+        assert_eq!(MctsParams::default().fpu_mode, FpuMode::AmafOptimistic);
+        assert_eq!(FpuMode::from_rave_fpu(false), FpuMode::Infinity);
+        assert_eq!("parent_value".parse::<FpuMode>(), Ok(FpuMode::ParentValue));
+        assert!("optimistic".parse::<FpuMode>().is_err());
+    }
+
+    #[test]
+    fn test_opponent_rationality_softens_opponent_selection() {
+        // Root where p2 replies to the searcher p1: a strong reply that's
+        // been explored a lot, and a weak one tried a few times.
+        let mut arena = NodeArena::new();
+        let root = arena.alloc(MctsNode::new(None, None));
+        arena.get_mut(root).visit_count = 35;
+        for (visits, value) in [(5, 0.3), (30, 0.9)] {
+            let mut child = MctsNode::new(Some(serde_json::json!({"v": value})), Some(root));
+            child.acting_player = Some("p2".into());
+            child.visit_count = visits;
+            child.total_value = value * visits as f64;
+            let idx = arena.alloc(child);
+            arena.get_mut(root).children.push(idx);
+        }
+        let (weak, strong) = (arena.get(root).children[0], arena.get(root).children[1]);
+        let select = |searcher: &str, rationality: f64| {
+            let params = MctsParams { opponent_rationality: rationality, ..Default::default() };
+            let r = selection_rationality(&arena, root, searcher, &params);
+            // rave_k = 0 turns off AMAF blending so both selectors see the same values
+            let rave = arena.best_child_rave(root, 0.5, 0.0, FpuMode::Infinity, r, false, 0.0);
+            (arena.best_child_uct(root, 0.5, r, false, 0.0), rave)
+        };
+
+        // Adversarial (default): p2 keeps choosing its best reply.
+        assert_eq!(select("p1", 1.0), (strong, strong));
+        // A fully irrational opponent sees equal values, so exploration decides.
+        assert_eq!(select("p1", 0.0), (weak, weak));
+        // Out-of-range values are clamped.
+        assert_eq!(select("p1", -3.0), (weak, weak));
+        // The searcher's own choices are never softened.
+        assert_eq!(select("p2", 0.0), (strong, strong));
+        assert_eq!(MctsParams::default().opponent_rationality, 1.0);
+    }
+
+    #[test]
+    fn test_endgame_sim_multiplier() {
+        let params = MctsParams {
+            num_simulations: 40,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            endgame_branching_threshold: 3,
+            endgame_sim_multiplier: 4.0,
+            ..Default::default()
+        };
+        assert_eq!(effective_num_simulations(&params, 2), 160);
+        assert_eq!(effective_num_simulations(&params, 3), 160);
+        assert_eq!(effective_num_simulations(&params, 4), 40);
+
+        // Low branching: PassOrTake has 2 root actions
+        let plugin = PassOrTake;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let (_, narrow_iters) = mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+
+        // High branching: first tile placement of a Carcassonne game
+        let plugin = CarcassonnePlugin;
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let draw = Action { action_type: "draw_tile".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+        assert!(plugin.get_valid_actions(&r.state, &r.next_phase, "p1").len() > 3);
+        let (_, wide_iters) =
+            mcts_search(&r.state, &r.next_phase, "p1", &plugin, &players, &params, None).unwrap();
+
+        assert_eq!(narrow_iters, 160);
+        assert_eq!(wide_iters, 40);
+    }
+
+    #[test]
+    fn test_pw_min_children_floor() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let draw = Action { action_type: "draw_tile".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+        let num_valid = plugin.get_valid_actions(&r.state, &r.next_phase, "p1").len();
+        let floor = 4;
+        assert!(num_valid > floor, "need a wider position, got {} placements", num_valid);
+
+        let root_children = |pw_min_children: usize| {
+            let params = MctsParams {
+                num_simulations: 8,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                pw_c: 1.0,
+                pw_alpha: 0.5,
+                pw_min_children,
+                ..Default::default()
+            };
+            let (_, _, stats) =
+                mcts_search_with_stats(&r.state, &r.next_phase, "p1", &plugin, &players, &params, None).unwrap();
+            stats[0].root_children
+        };
+
+        assert!(root_children(1) < floor);
+        assert!(root_children(floor) >= floor);
+        assert_eq!(max_children(1, 1.0, 0.5, 1), 1);
+        assert_eq!(max_children(1, 1.0, 0.5, floor), floor);
+        assert_eq!(max_children(100, 1.0, 0.5, floor), 10);
+    }
+
+    #[test]
+    fn test_expand_all_root_children() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let draw = Action { action_type: "draw_tile".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+        let num_valid = plugin.get_valid_actions(&r.state, &r.next_phase, "p1").len();
+        assert!(num_valid > 4 && num_valid < 20, "need 5..19 placements, got {}", num_valid);
+
+        let visited_root_moves = |expand_all_root_children: bool| {
+            let params = MctsParams {
+                num_simulations: 20,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                seed: Some(1),
+                pw_c: 1.0,
+                expand_all_root_children,
+                ..Default::default()
+            };
+            let (_, _, policy) =
+                mcts_search_with_policy(&r.state, &r.next_phase, "p1", &plugin, &players, &params, None).unwrap();
+            policy.iter().filter(|(_, share)| *share > 0.0).count()
+        };
+
+        assert!(visited_root_moves(false) < num_valid);
+        assert_eq!(visited_root_moves(true), num_valid);
+    }
+
+    #[test]
+    fn test_final_selection() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+        // X on 0 and 1, O on 2 and 4: X must block at 6.
+        for (i, cell) in [0, 2, 1, 4].into_iter().enumerate() {
+            let action = Action {
+                action_type: "play".into(),
+                player_id: players[i % 2].player_id.clone(),
+                payload: serde_json::json!({"cell": cell}),
+            };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            (state, phase) = (r.state, r.next_phase);
+        }
+        let pick = |final_selection| {
+            let params = MctsParams {
+                num_simulations: 12,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                seed: Some(1),
+                final_selection,
+                ..Default::default()
+            };
+            mcts_search(&state, &phase, "p1", &plugin, &players, &params, None).unwrap().0
+        };
+
+        // Too few simulations to refute the non-blocking moves: they have the most
+        // visits, but the block keeps the best mean value.
+        let block = serde_json::json!({"cell": 6});
+        assert_ne!(pick(FinalSelection::MaxVisits), block);
+        assert_eq!(pick(FinalSelection::MaxValue), block);
+        assert_eq!(pick(FinalSelection::Robust), block);
+
+        let visits: HashMap<String, u32> = [("a".into(), 10), ("b".into(), 8), ("c".into(), 2)].into();
+        let values: HashMap<String, f64> = [("a".into(), 0.40), ("b".into(), 0.52), ("c".into(), 0.55)].into();
+        assert_eq!(select_best_action(&visits, &values, FinalSelection::MaxVisits), "a");
+        assert_eq!(select_best_action(&visits, &values, FinalSelection::MaxValue), "c");
+        // "a" is more than the margin behind "c"; "b" is within it.
+        assert_eq!(select_best_action(&visits, &values, FinalSelection::Robust), "b");
+        assert_eq!("robust".parse::<FinalSelection>(), Ok(FinalSelection::Robust));
+        assert!("best".parse::<FinalSelection>().is_err());
+    }
+
+    /// Empty cells completing a line of `mark`.
+    fn ttt_winning_cells(board: &[u8; 9], mark: u8) -> Vec<usize> {
+        (0..9)
+            .filter(|&c| board[c] == 0)
+            .filter(|&c| {
+                TTT_LINES.iter().any(|l| l.contains(&c) && l.iter().filter(|&&i| i != c).all(|&i| board[i] == mark))
+            })
+            .collect()
+    }
+
+    /// Distinct non-terminal tic-tac-toe positions with `marks` marks on the board,
+    /// for `marks` in `min_marks..=max_marks`, with the mark the mover plays and
+    /// the opponent's.
+    fn ttt_positions(players: &[Player], min_marks: usize, max_marks: usize) -> Vec<([u8; 9], Phase, u8, u8)> {
+        let plugin = TicTacToe;
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(players, &config);
+        let mut frontier = vec![(state, phase)];
+        let mut positions = Vec::new();
+        for marks in 0..max_marks {
+            let mut next = Vec::new();
+            for (board, phase) in frontier {
+                let mover = players[marks % 2].player_id.clone();
+                for action in plugin.get_valid_actions(&board, &phase, &mover) {
+                    let act = Action { action_type: "play".into(), player_id: mover.clone(), payload: action };
+                    let r = plugin.apply_action(&board, &phase, &act, players).unwrap();
+                    if r.game_over.is_none() && !next.iter().any(|(b, _)| *b == r.state) {
+                        next.push((r.state, r.next_phase));
+                    }
+                }
+            }
+            frontier = next;
+            if marks + 1 >= min_marks {
+                let (own, theirs) = if marks % 2 == 0 { (2, 1) } else { (1, 2) };
+                positions.extend(frontier.iter().map(|(b, p)| (*b, p.clone(), own, theirs)));
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn test_solver_finds_forced_moves() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let params = MctsParams {
+            num_simulations: 50,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            seed: Some(1),
+            use_solver: true,
+            ..Default::default()
+        };
+
+        // Every non-terminal position with 3 or 4 marks where the mover has a single
+        // forced move: the only win, or else the only block.
+        let mut forced = Vec::new();
+        for (board, phase, own, theirs) in ttt_positions(&players, 3, 4) {
+            let wins = ttt_winning_cells(&board, own);
+            let must = if wins.is_empty() { ttt_winning_cells(&board, theirs) } else { wins };
+            if must.len() == 1 {
+                forced.push((board, phase, must[0]));
+            }
+        }
+        assert!(forced.len() > 100, "only {} forced positions", forced.len());
+
+        // 50 simulations are too few for plain MCTS to get all of these right;
+        // proven losses let the solver rule out every other move.
+        for (board, phase, cell) in &forced {
+            let mover = &phase.expected_actions[0].player_id;
+            let (action, _) = mcts_search(board, phase, mover, &plugin, &players, &params, None).unwrap();
+            assert_eq!(action, serde_json::json!({"cell": cell}), "{:?}", board);
+        }
+    }
+
+    #[test]
+    fn test_decisive_moves_block_one_move_wins() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let params = MctsParams {
+            num_simulations: 20,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            seed: Some(1),
+            decisive_moves: true,
+            ..Default::default()
+        };
+
+        // The opponent threatens exactly one cell and the mover has no win of its own.
+        let mut threats = 0;
+        for (board, phase, own, theirs) in ttt_positions(&players, 3, 7) {
+            let block = ttt_winning_cells(&board, theirs);
+            if block.len() != 1 || !ttt_winning_cells(&board, own).is_empty() {
+                continue;
+            }
+            threats += 1;
+            let mover = &phase.expected_actions[0].player_id;
+            let (action, _) = mcts_search(&board, &phase, mover, &plugin, &players, &params, None).unwrap();
+            assert_eq!(action, serde_json::json!({"cell": block[0]}), "{:?}", board);
+        }
+        assert!(threats > 500, "only {threats} positions");
+
+        // A win on the board beats blocking.
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let phase = plugin.create_initial_state(&players, &config).1;
+        let board = [1, 1, 0, 2, 2, 0, 0, 0, 0];
+        let (action, _) = mcts_search(&board, &phase, "p1", &plugin, &players, &params, None).unwrap();
+        assert_eq!(action, serde_json::json!({"cell": 2}));
+    }
+
+    #[test]
+    fn test_tree_reuse_accumulates_visits() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 400,
+            time_limit_ms: 999999.0,
+            num_determinizations: 2,
+            seed: Some(3),
+            ..Default::default()
+        };
+        let play = |state: &[u8; 9], phase: &Phase, player_id: &str, payload: serde_json::Value| {
+            let action = Action { action_type: "play".into(), player_id: player_id.into(), payload };
+            let r = plugin.apply_action(state, phase, &action, &players).unwrap();
+            (r.state, r.next_phase)
+        };
+
+        let mut trees = SearchTrees::default();
+        let (x_move, _) = mcts_search_reusing(&state, &phase, "p1", &plugin, &players, &params, None, &mut trees).unwrap();
+        assert_eq!(trees.root_visits(), 400);
+        let (after_x, after_x_phase) = play(&state, &phase, "p1", x_move);
+        let (o_move, _) = mcts_search(&after_x, &after_x_phase, "p2", &plugin, &players, &params, None).unwrap();
+        let (after_o, after_o_phase) = play(&after_x, &after_x_phase, "p2", o_move);
+
+        // Same budget, but the reached subtree's visits carry over.
+        let mut fresh = SearchTrees::default();
+        mcts_search_reusing(&after_o, &after_o_phase, "p1", &plugin, &players, &params, None, &mut fresh).unwrap();
+        let (reused_move, _) =
+            mcts_search_reusing(&after_o, &after_o_phase, "p1", &plugin, &players, &params, None, &mut trees).unwrap();
+        assert_eq!(fresh.root_visits(), 400);
+        assert!(trees.root_visits() > 400, "reused root has {} visits", trees.root_visits());
+        assert!(plugin.get_valid_actions(&after_o, &after_o_phase, "p1").contains(&reused_move));
+
+        // A position the kept trees never reach falls back to a fresh search.
+        mcts_search_reusing(&state, &phase, "p1", &plugin, &players, &params, None, &mut trees).unwrap();
+        assert_eq!(trees.root_visits(), 400);
+    }
+
+    #[test]
+    fn test_transpositions_share_nodes() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        assert_eq!(plugin.state_hash(&[0; 9]), plugin.state_hash(&state));
+        assert_ne!(plugin.state_hash(&[1, 0, 0, 0, 0, 0, 0, 0, 0]), plugin.state_hash(&state));
+
+        let search = |state: &[u8; 9], phase: &Phase, use_transpositions: bool| {
+            let params = MctsParams {
+                num_simulations: 2000,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                seed: Some(1),
+                use_rave: true,
+                use_transpositions,
+                ..Default::default()
+            };
+            let (action, _, stats) = mcts_search_with_stats(state, phase, "p1", &plugin, &players, &params, None).unwrap();
+            (action, stats.into_iter().next().unwrap())
+        };
+
+        // Tic-tac-toe is full of move-order transpositions: the DAG must be smaller.
+        let (_, tree) = search(&state, &phase, false);
+        let (_, dag) = search(&state, &phase, true);
+        assert_eq!(tree.root_visit_count, dag.root_visit_count);
+        assert_eq!(dag.root_children, 9);
+        assert!(dag.total_nodes < tree.total_nodes, "dag {} vs tree {}", dag.total_nodes, tree.total_nodes);
+
+        // Shared stats must not mislead the search: X still takes the win at cell 2.
+        let (mut state, mut phase) = (state, phase);
+        for (i, cell) in [0, 3, 1, 4].into_iter().enumerate() {
+            let mover = &players[i % 2].player_id;
+            let action = Action {
+                action_type: "play".into(),
+                player_id: mover.clone(),
+                payload: serde_json::json!({"cell": cell}),
+            };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            (state, phase) = (r.state, r.next_phase);
+        }
+        for use_transpositions in [false, true] {
+            let (action, _) = search(&state, &phase, use_transpositions);
+            assert_eq!(action, serde_json::json!({"cell": 2}));
+        }
+    }
+
+    #[test]
+    fn test_valid_actions_computed_once_per_node() {
+        let plugin = TicTacToe;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: None, options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let params = MctsParams {
+            num_simulations: 2000,
+            time_limit_ms: 999999.0,
+            num_determinizations: 1,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let (_, iterations, stats) = mcts_search_with_stats(&state, &phase, "p1", &plugin, &players, &params, None).unwrap();
+        let stats = &stats[0];
+
+        // Recomputing on every visit would cost one call per node on each iteration's
+        // path; caching leaves one call per expanded node.
+        assert!(stats.valid_action_calls > 0);
+        assert!(stats.valid_action_calls <= stats.total_nodes, "{} calls, {} nodes", stats.valid_action_calls, stats.total_nodes);
+        assert!(stats.valid_action_calls < iterations / 2, "{} calls, {iterations} iterations", stats.valid_action_calls);
+    }
+
+    #[test]
+    fn test_params_builder() {
+        let params = MctsParams::builder()
+            .simulations(200)
+            .determinizations(3)
+            .pw_alpha(1.0)
+            .rave(true)
+            .rave_k(100.0)
+            .fpu_mode(FpuMode::ParentValue)
+            .seed(Some(9))
+            .build()
+            .unwrap();
+        assert_eq!(params.num_simulations, 200);
+        assert_eq!(params.num_determinizations, 3);
+        assert_eq!(params.pw_alpha, 1.0);
+        assert!(params.use_rave);
+        assert_eq!(params.rave_k, 100.0);
+        assert_eq!(params.fpu_mode, FpuMode::ParentValue);
+        assert_eq!(params.seed, Some(9));
+        assert_eq!(params.time_limit_ms, MctsParams::default().time_limit_ms);
+        assert!(MctsParams::default().validate().is_ok());
+
+        let err = |b: MctsParamsBuilder| b.build().unwrap_err();
+        assert!(err(MctsParams::builder().determinizations(0)).contains("num_determinizations"));
+        assert!(err(MctsParams::builder().simulations(0)).contains("num_simulations"));
+        assert!(err(MctsParams::builder().pw_alpha(0.0)).contains("pw_alpha"));
+        assert!(err(MctsParams::builder().pw_alpha(1.5)).contains("pw_alpha"));
+        assert!(err(MctsParams::builder().pw_alpha(f64::NAN)).contains("pw_alpha"));
+        assert!(err(MctsParams::builder().time_limit_ms(-1.0)).contains("time_limit_ms"));
+        assert!(err(MctsParams::builder().exploration_constant(f64::INFINITY)).contains("exploration_constant"));
+        assert!(err(MctsParams::builder().opponent_rationality(1.2)).contains("opponent_rationality"));
+        assert!(err(MctsParams::builder().max_parallel_determinizations(Some(0))).contains("max_parallel"));
+    }
+
+    #[test]
+    fn test_max_tree_depth_caps_tree() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let draw = Action { action_type: "draw_tile".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+
+        let search = |max_tree_depth: Option<usize>| {
+            let params = MctsParams {
+                num_simulations: 300,
+                time_limit_ms: 999999.0,
+                num_determinizations: 1,
+                pw_c: 1.0,
+                max_tree_depth,
+                ..Default::default()
+            };
+            let (best, _, stats) =
+                mcts_search_with_stats(&r.state, &r.next_phase, "p1", &plugin, &players, &params, None).unwrap();
+            (best, stats.into_iter().next().unwrap())
+        };
+
+        let (_, uncapped) = search(None);
+        assert!(uncapped.max_depth > 2, "uncapped search too shallow: {}", uncapped.max_depth);
+
+        for cap in [1, 2] {
+            let (best, stats) = search(Some(cap));
+            assert!(stats.max_depth <= cap, "depth {} exceeds cap {}", stats.max_depth, cap);
+            assert_eq!(stats.root_visit_count, 300);
+            assert!(plugin.get_valid_actions(&r.state, &r.next_phase, "p1").contains(&best));
+        }
+    }
+
     /// CI smoke test: 2 short games (10 tiles, 50 sims).
     /// Uses UUID-like IDs where alphabetical sort != seat order,
     /// so player-ordering bugs cause MCTS to lose.
@@ -1103,7 +3781,7 @@ mod tests {
         use rand::seq::SliceRandom;
 
         let plugin = CarcassonnePlugin;
-        let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+        let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
 
         let params = MctsParams {
             num_simulations: 50,
@@ -1139,12 +3817,16 @@ mod tests {
                 options: serde_json::json!({"tile_count": 10}),
             };
             let (state, phase, _) = plugin.create_initial_state(&players, &config);
+            // Seeded search and opponent so the smoke test can't flake
+            let params = MctsParams { seed: Some(seed), ..params.clone() };
+            let mut rng = GameRng::from_seed(seed);
             let mut sim = SimulationState {
                 state,
                 phase,
-                players: players.clone(),
+                players: players.clone().into(),
                 scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
                 game_over: None,
+                history: None,
             };
 
             for _ in 0..200 {
@@ -1160,7 +3842,7 @@ mod tests {
                         action_type: phase_name,
                         player_id: pid,
                         payload: serde_json::json!({}),
-                    });
+                    }).unwrap();
                 }
                 if sim.game_over.is_some() || sim.phase.expected_actions.is_empty() { break; }
 
@@ -1172,18 +3854,18 @@ mod tests {
                     let (action, _) = mcts_search(
                         &sim.state, &sim.phase, &acting_pid, &plugin,
                         &players, &params, eval_ref,
-                    );
+                    ).unwrap();
                     action
                 } else {
                     let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &acting_pid);
                     if valid.is_empty() { break; }
-                    valid.choose(&mut rand::thread_rng()).cloned().unwrap()
+                    valid.choose(&mut rng).cloned().unwrap()
                 };
 
                 let action_type = sim.phase.expected_actions[0].action_type.clone();
                 apply_action_and_resolve(&plugin, &mut sim, &Action {
                     action_type, player_id: acting_pid, payload: chosen,
-                });
+                }).unwrap();
             }
 
             mcts_total += sim.scores.get("zzz-mcts-bot").copied().unwrap_or(0.0);
@@ -1,8 +1,13 @@
 pub mod models;
 pub mod plugin;
+pub mod payload;
 pub mod simulator;
 pub mod mcts;
 pub mod arena;
 pub mod bot_strategy;
 pub mod bot_profiles;
 pub mod evaluator;
+pub mod replay;
+pub mod rng;
+#[cfg(test)]
+pub mod test_games;
@@ -24,6 +24,40 @@ pub struct GameConfig {
     pub random_seed: Option<u64>,
 }
 
+/// JSON type of a game option value.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionType {
+    Integer,
+    Number,
+    Boolean,
+    String,
+    /// A JSON array (e.g. a list of expansion names).
+    Array,
+}
+
+impl OptionType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OptionType::Integer => "integer",
+            OptionType::Number => "number",
+            OptionType::Boolean => "boolean",
+            OptionType::String => "string",
+            OptionType::Array => "array",
+        }
+    }
+}
+
+/// A key a game accepts in `GameConfig::options`, so clients can render a setup form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionSpec {
+    pub name: String,
+    pub option_type: OptionType,
+    /// Value used when the option is absent; null when absence has its own meaning.
+    pub default: serde_json::Value,
+    pub description: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ConcurrentMode {
@@ -100,3 +134,48 @@ pub struct TransitionResult {
     #[serde(default)]
     pub game_over: Option<GameResult>,
 }
+
+/// Wire encoding of `game_data`. JSON carries the game_data document; MessagePack
+/// carries the game's binary encoding of its typed state (see
+/// `TypedGamePlugin::encode_state_binary`), more compact for large states
+/// (e.g. Carcassonne's feature maps).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameDataFormat {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl GameDataFormat {
+    /// Encode the game_data document itself, in this format.
+    pub fn encode(self, game_data: &serde_json::Value) -> Result<Vec<u8>, String> {
+        match self {
+            GameDataFormat::Json => serde_json::to_vec(game_data).map_err(|e| format!("cannot encode game_data as JSON: {e}")),
+            GameDataFormat::MessagePack => to_msgpack(game_data),
+        }
+    }
+
+    /// Decode a game_data document encoded by `encode`.
+    pub fn decode(self, bytes: &[u8]) -> Result<serde_json::Value, String> {
+        match self {
+            GameDataFormat::Json => serde_json::from_slice(bytes).map_err(|e| format!("invalid game_data JSON: {e}")),
+            GameDataFormat::MessagePack => from_msgpack(bytes),
+        }
+    }
+}
+
+/// MessagePack of `value`, with structs as maps so optional fields may be omitted.
+pub fn to_msgpack<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, String> {
+    rmp_serde::to_vec_named(value).map_err(|e| format!("cannot encode game_data as MessagePack: {e}"))
+}
+
+/// Decode MessagePack written by `to_msgpack`.
+pub fn from_msgpack<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    let mut reader = std::io::Cursor::new(bytes);
+    let value = rmp_serde::from_read(&mut reader).map_err(|e| format!("invalid game_data MessagePack: {e}"))?;
+    // Like serde_json, reject trailing bytes (e.g. JSON sent as MessagePack)
+    if reader.position() != bytes.len() as u64 {
+        return Err("invalid game_data MessagePack: trailing bytes".into());
+    }
+    Ok(value)
+}
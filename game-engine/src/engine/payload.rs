@@ -0,0 +1,206 @@
+//! Action payload shape checks.
+//!
+//! Plugins declare, per action type, which payload fields they read and with what
+//! JSON type (`TypedGamePlugin::payload_schema`). `validate_payload_shape` runs at the
+//! JSON boundary before plugin validation and before any apply, so the `apply_*`
+//! helpers can rely on the fields being present and well-typed. The same schemas
+//! are published to clients as JSON Schema (`action_schema`).
+
+use serde_json::{json, Map, Value};
+
+/// JSON type expected for a payload field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    /// Non-negative integer.
+    UInt,
+    Int,
+    Bool,
+}
+
+impl FieldKind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            FieldKind::String => value.is_string(),
+            FieldKind::UInt => value.is_u64(),
+            FieldKind::Int => value.is_i64() || value.is_u64(),
+            FieldKind::Bool => value.is_boolean(),
+        }
+    }
+
+    fn json_schema(self) -> Value {
+        match self {
+            FieldKind::String => json!({"type": "string"}),
+            FieldKind::UInt => json!({"type": "integer", "minimum": 0}),
+            FieldKind::Int => json!({"type": "integer"}),
+            FieldKind::Bool => json!({"type": "boolean"}),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            FieldKind::String => "a string",
+            FieldKind::UInt => "a non-negative integer",
+            FieldKind::Int => "an integer",
+            FieldKind::Bool => "a boolean",
+        }
+    }
+}
+
+/// One field of an action payload. Fields not listed in a schema are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadField {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+impl PayloadField {
+    pub const fn required(name: &'static str, kind: FieldKind) -> Self {
+        Self { name, kind, required: true }
+    }
+
+    pub const fn optional(name: &'static str, kind: FieldKind) -> Self {
+        Self { name, kind, required: false }
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check `payload` against `schema`. Returns a description of the first problem found.
+pub fn validate_payload_shape(payload: &Value, schema: &[PayloadField]) -> Option<String> {
+    let Some(obj) = payload.as_object() else {
+        return Some(format!("Payload must be a JSON object, got {}", json_type_name(payload)));
+    };
+    for field in schema {
+        match obj.get(field.name) {
+            None | Some(Value::Null) if field.required => {
+                return Some(format!("Missing '{}' in payload", field.name));
+            }
+            None | Some(Value::Null) => {}
+            Some(value) if !field.kind.matches(value) => {
+                return Some(format!(
+                    "Field '{}' must be {}, got {}",
+                    field.name,
+                    field.kind.describe(),
+                    json_type_name(value)
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    None
+}
+
+/// JSON Schema of a payload with `fields`. Other fields are allowed, as in
+/// `validate_payload_shape`.
+pub fn payload_json_schema(fields: &[PayloadField]) -> Value {
+    let properties: Map<String, Value> =
+        fields.iter().map(|f| (f.name.to_string(), f.kind.json_schema())).collect();
+    let required: Vec<&str> = fields.iter().filter(|f| f.required).map(|f| f.name).collect();
+    json!({"type": "object", "properties": properties, "required": required})
+}
+
+/// JSON Schema document for a game's action payloads. `$defs` has one schema per
+/// action type (from `payload_schema`; unchecked payloads are any object) and
+/// `phases` one per phase, accepting any of the phase's action types: validate a
+/// payload for phase `p` against `#/phases/p`. The root accepts any payload.
+pub fn action_schema<'a>(
+    game_id: &str,
+    phases: &[(&str, &[&'a str])],
+    payload_schema: impl Fn(&str) -> Option<&'a [PayloadField]>,
+) -> Value {
+    let mut defs = Map::new();
+    let mut phase_schemas = Map::new();
+    for &(phase, action_types) in phases {
+        let refs: Vec<Value> = action_types
+            .iter()
+            .map(|&action_type| {
+                defs.entry(action_type).or_insert_with(|| match payload_schema(action_type) {
+                    Some(fields) => payload_json_schema(fields),
+                    None => json!({"type": "object"}),
+                });
+                json!({"$ref": format!("#/$defs/{action_type}")})
+            })
+            .collect();
+        phase_schemas.insert(phase.to_string(), json!({"anyOf": refs}));
+    }
+    let all: Vec<Value> = defs.keys().map(|t| json!({"$ref": format!("#/$defs/{t}")})).collect();
+
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{game_id} action payloads"),
+        "type": "object",
+        "$defs": defs,
+        "phases": phase_schemas,
+    });
+    if !all.is_empty() {
+        schema["anyOf"] = Value::Array(all);
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[PayloadField] = &[
+        PayloadField::required("hex", FieldKind::String),
+        PayloadField::required("orientation", FieldKind::UInt),
+        PayloadField::optional("skip", FieldKind::Bool),
+    ];
+
+    #[test]
+    fn test_validate_payload_shape() {
+        assert_eq!(validate_payload_shape(&json!({"hex": "0,0", "orientation": 3}), SCHEMA), None);
+        // Extra fields are fine
+        assert_eq!(
+            validate_payload_shape(&json!({"hex": "0,0", "orientation": 3, "action_type": "x"}), SCHEMA),
+            None
+        );
+
+        assert_eq!(
+            validate_payload_shape(&json!({"orientation": 3}), SCHEMA).unwrap(),
+            "Missing 'hex' in payload"
+        );
+        assert_eq!(
+            validate_payload_shape(&json!({"hex": 5, "orientation": 3}), SCHEMA).unwrap(),
+            "Field 'hex' must be a string, got integer"
+        );
+        assert_eq!(
+            validate_payload_shape(&json!({"hex": "0,0", "orientation": -1}), SCHEMA).unwrap(),
+            "Field 'orientation' must be a non-negative integer, got integer"
+        );
+        assert!(validate_payload_shape(&json!({"hex": "0,0", "orientation": 1, "skip": "yes"}), SCHEMA)
+            .unwrap()
+            .contains("'skip'"));
+        assert!(validate_payload_shape(&json!([1, 2]), SCHEMA).unwrap().contains("array"));
+    }
+    #[test]
+    fn test_action_schema() {
+        let phases: &[(&str, &[&str])] = &[("turn", &["place", "pass"])];
+        let schema = action_schema("demo", phases, |t| (t == "place").then_some(SCHEMA));
+        assert_eq!(schema["title"], "demo action payloads");
+        assert_eq!(schema["$defs"]["place"]["required"], json!(["hex", "orientation"]));
+        assert_eq!(schema["$defs"]["place"]["properties"]["orientation"], json!({"type": "integer", "minimum": 0}));
+        assert_eq!(schema["$defs"]["pass"], json!({"type": "object"}));
+        assert_eq!(
+            schema["phases"]["turn"],
+            json!({"anyOf": [{"$ref": "#/$defs/place"}, {"$ref": "#/$defs/pass"}]})
+        );
+
+        let empty = action_schema("demo", &[], |_| None);
+        assert_eq!(empty["phases"], json!({}));
+        assert!(empty.get("anyOf").is_none());
+    }
+}
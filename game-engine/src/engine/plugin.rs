@@ -5,7 +5,15 @@
 //! `GamePlugin` (JSON boundary) is auto-derived via `JsonAdapter`.
 
 use crate::engine::models::*;
+use crate::engine::payload::{action_schema, validate_payload_shape, PayloadField};
+use crate::engine::rng::GameRng;
+use crate::engine::simulator::DEFAULT_MAX_AUTO_RESOLVES;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// MCTS leaf evaluator: the value of a state for `player_id`.
+pub type EvalFn<S> = Box<dyn Fn(&S, &Phase, &str, &[Player]) -> f64 + Send + Sync>;
 
 /// Transition result with typed game state.
 pub struct TypedTransitionResult<S> {
@@ -18,7 +26,7 @@ pub struct TypedTransitionResult<S> {
 
 /// The primary trait every game implements. Uses strongly-typed state.
 pub trait TypedGamePlugin: Send + Sync {
-    type State: Clone + Send + Sync;
+    type State: Clone + Send + Sync + serde::de::DeserializeOwned;
 
     // --- Metadata ---
     fn game_id(&self) -> &str;
@@ -29,9 +37,43 @@ pub trait TypedGamePlugin: Send + Sync {
     fn disconnect_policy(&self) -> &str;
 
     // --- Serialization ---
-    fn decode_state(&self, game_data: &serde_json::Value) -> Self::State;
+    /// Decode `game_data` from a client, which may be malformed.
+    fn try_decode_state(&self, game_data: &serde_json::Value) -> Result<Self::State, String> {
+        serde_json::from_value(game_data.clone())
+            .map_err(|e| format!("invalid {} state: {}", self.game_id(), e))
+    }
+
+    /// Decode a state known to be well formed; panics otherwise.
+    fn decode_state(&self, game_data: &serde_json::Value) -> Self::State {
+        self.try_decode_state(game_data).unwrap_or_else(|e| panic!("{e}"))
+    }
     fn encode_state(&self, state: &Self::State) -> serde_json::Value;
 
+    /// `state` in the binary `GameDataFormat::MessagePack`. Default: MessagePack
+    /// of the `encode_state` document; games with large states override this
+    /// with a compact encoding of the typed state.
+    fn encode_state_binary(&self, state: &Self::State) -> Result<Vec<u8>, String> {
+        GameDataFormat::MessagePack.encode(&self.encode_state(state))
+    }
+
+    /// Decode `encode_state_binary` bytes from a client, which may be malformed.
+    fn decode_state_binary(&self, bytes: &[u8]) -> Result<Self::State, String> {
+        self.try_decode_state(&GameDataFormat::MessagePack.decode(bytes)?)
+    }
+
+    /// Check that `game_data` at `phase` is a state this game can resume from
+    /// (e.g. a hand-crafted mid-game position). Unlike `decode_state`, never
+    /// panics. Default: accept anything.
+    fn load_state(&self, _game_data: &serde_json::Value, _phase: &Phase) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Check `config.options` before `create_initial_state`, which may panic on
+    /// values this rejects. Default: accept anything.
+    fn validate_config(&self, _config: &GameConfig) -> Result<(), String> {
+        Ok(())
+    }
+
     // --- Core game logic ---
     fn create_initial_state(
         &self,
@@ -53,13 +95,15 @@ pub trait TypedGamePlugin: Send + Sync {
         action: &Action,
     ) -> Option<String>;
 
+    /// Apply `action`, which may not have been through `validate_action`.
+    /// Errs instead of panicking when the payload can't be applied.
     fn apply_action(
         &self,
         state: &Self::State,
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TypedTransitionResult<Self::State>;
+    ) -> Result<TypedTransitionResult<Self::State>, String>;
 
     fn get_player_view(
         &self,
@@ -73,6 +117,11 @@ pub trait TypedGamePlugin: Send + Sync {
 
     // --- Methods with defaults ---
 
+    /// Keys accepted in `GameConfig::options`.
+    fn supported_options(&self) -> Vec<OptionSpec> {
+        Vec::new()
+    }
+
     fn get_spectator_summary(
         &self,
         state: &Self::State,
@@ -109,15 +158,176 @@ pub trait TypedGamePlugin: Send + Sync {
         None
     }
 
+    /// Payload fields read for `action_type`, checked at the JSON boundary before
+    /// validation and apply. `None` means the payload is not inspected.
+    fn payload_schema(&self, _action_type: &str) -> Option<&'static [PayloadField]> {
+        None
+    }
+
+    /// Phases where players act and the action types each accepts, published by
+    /// `action_schema`. Default: none.
+    fn phase_action_types(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        &[]
+    }
+
+    /// JSON Schema of the action payloads each phase accepts, for client authors.
+    /// Default: built from `phase_action_types` and `payload_schema`.
+    fn action_schema(&self) -> serde_json::Value {
+        action_schema(self.game_id(), self.phase_action_types(), |t| self.payload_schema(t))
+    }
+
+    /// Per-feature completion estimates for `player_id` (UI hints, AI prompting).
+    /// `None` when the game has no such notion.
+    fn feature_outlook(&self, _state: &Self::State, _player_id: &str) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Details of the feature under a meeple spot (e.g. for a click on a meeple).
+    /// `None` when the spot does not exist or the game has no meeples.
+    fn describe_meeple(&self, _state: &Self::State, _position: &str, _spot: &str) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// One-line text form of a state, for logs and sharing positions.
+    /// `None` when the game has no notation.
+    fn export_notation(&self, _state: &Self::State) -> Option<String> {
+        None
+    }
+
+    /// Parse `export_notation` output. `None` when the game has no notation.
+    fn import_notation(&self, _notation: &str) -> Option<Result<Self::State, String>> {
+        None
+    }
+
+    /// Optional metadata explaining the valid actions (e.g. why a target is legal),
+    /// returned only when a client asks for it so the action list stays lean.
+    fn valid_action_details(
+        &self,
+        _state: &Self::State,
+        _phase: &Phase,
+        _player_id: &str,
+    ) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Cap on consecutive auto-resolve phases the simulator runs after one action
+    /// before reporting a loop. Raise it for games with legitimately long chains.
+    fn max_auto_resolves(&self) -> usize {
+        DEFAULT_MAX_AUTO_RESOLVES
+    }
+
     // --- MCTS-specific ---
 
+    /// Value in [0, 1] of a finished game for `player_id`, used by MCTS at terminal
+    /// nodes. For two players it should satisfy value(a) = 1 - value(b), since
+    /// backprop scores opponents as `1 - value`. Default: 1.0 sole win, 0.8 shared
+    /// win, 0.0 loss.
+    fn value_for(&self, result: &GameResult, player_id: &str) -> f64 {
+        if result.winners.iter().any(|w| w == player_id) {
+            if result.winners.len() == 1 { 1.0 } else { 0.8 }
+        } else {
+            0.0
+        }
+    }
+
+    /// Player who decides at `phase`, or None when nobody does (e.g. final scoring).
+    /// MCTS expands this player's valid actions. Default: the first expected
+    /// action's player, else the player at `metadata.player_index`.
+    fn acting_player(
+        &self,
+        _state: &Self::State,
+        phase: &Phase,
+        players: &[Player],
+    ) -> Option<String> {
+        match phase.expected_actions.first() {
+            Some(expected) => Some(expected.player_id.clone()),
+            None => metadata_player(phase, players),
+        }
+    }
+
+    /// `Action::action_type` for playing `payload` at `phase`, as MCTS and the
+    /// arena build actions from bare payloads. Default: the first expected
+    /// action's type, else the phase name.
+    fn action_type_for(&self, phase: &Phase, _payload: &serde_json::Value) -> String {
+        match phase.expected_actions.first() {
+            Some(expected) => expected.action_type.clone(),
+            None => phase.name.clone(),
+        }
+    }
+
+    /// Named eval profiles `make_eval` accepts, default first. Default: none.
+    fn eval_profiles(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// MCTS leaf evaluator for one of `eval_profiles`, or None if `profile` is
+    /// not one of them.
+    fn make_eval(&self, _profile: &str) -> Option<EvalFn<Self::State>> {
+        None
+    }
+
+    /// Whether every player sees the whole state and nothing is left to chance,
+    /// so exact search (`AlphaBetaStrategy`) applies. Default: false.
+    fn is_perfect_information(&self) -> bool {
+        false
+    }
+
     /// Randomize hidden information for MCTS determinization.
-    fn determinize(&self, _state: &mut Self::State) {}
+    fn determinize(&self, _state: &mut Self::State, _rng: &mut GameRng) {}
+
+    /// Hash identifying `state` in the MCTS transposition table
+    /// (`MctsParams::use_transpositions`); equal states must hash equal.
+    /// Default: hash of the encoded JSON. Override with something cheaper
+    /// (e.g. Zobrist keys) where encoding is slow.
+    fn state_hash(&self, state: &Self::State) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.encode_state(state).to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Move for `player_id` in an MCTS rollout (`MctsParams::rollout_depth`), or
+    /// None to end the rollout there. Default: uniformly random valid action.
+    /// Override with a cheap heuristic for stronger ("heavy") playouts.
+    fn rollout_action(
+        &self,
+        state: &Self::State,
+        phase: &Phase,
+        player_id: &str,
+        rng: &mut GameRng,
+    ) -> Option<serde_json::Value> {
+        use rand::seq::SliceRandom;
+        self.get_valid_actions(state, phase, player_id).choose(rng).cloned()
+    }
 
     /// Return context for AMAF key generation (e.g., current tile type).
     fn amaf_context(&self, _state: &Self::State) -> String {
         String::new()
     }
+
+    /// Actions that are (heuristically) dominated for `player_id` in this position.
+    /// With `MctsParams::use_pruning_hints`, MCTS expands them last. Hints never
+    /// remove actions, so search stays sound even if a hint is wrong.
+    fn prunable_actions(
+        &self,
+        _state: &Self::State,
+        _phase: &Phase,
+        _player_id: &str,
+    ) -> Vec<serde_json::Value> {
+        Vec::new()
+    }
+}
+
+/// The player at the phase's `metadata.player_index`, if present and in range.
+pub fn metadata_player(phase: &Phase, players: &[Player]) -> Option<String> {
+    let idx = phase.metadata.get("player_index")?.as_u64()? as usize;
+    let player = players.get(idx)?;
+    debug_assert_eq!(
+        player.seat_index, idx as i32,
+        "metadata_player: player at index {} has seat_index {}, expected {}. \
+         Players may be misordered.",
+        idx, player.seat_index, idx
+    );
+    Some(player.player_id.clone())
 }
 
 // =========================================================================
@@ -133,6 +343,19 @@ pub trait GamePlugin: Send + Sync {
     fn max_players(&self) -> u32;
     fn description(&self) -> &str;
     fn disconnect_policy(&self) -> &str;
+    fn supported_options(&self) -> Vec<OptionSpec>;
+    /// Check that `game_data` can be resumed at `phase` and return it decoded
+    /// and re-encoded, so fields left to their defaults are filled in.
+    fn load_state(&self, game_data: &serde_json::Value, phase: &Phase) -> Result<serde_json::Value, String>;
+    /// Check that `game_data` decodes as this game's state. The other methods
+    /// panic on a state that doesn't.
+    fn check_state(&self, game_data: &serde_json::Value) -> Result<(), String>;
+    fn validate_config(&self, config: &GameConfig) -> Result<(), String>;
+    /// `game_data` as wire bytes in `format`.
+    fn encode_game_data(&self, game_data: &serde_json::Value, format: GameDataFormat) -> Result<Vec<u8>, String>;
+    /// Wire bytes in `format` back to `game_data`. Binary formats decode through
+    /// the typed state; JSON is only parsed (see `check_state`).
+    fn decode_game_data(&self, bytes: &[u8], format: GameDataFormat) -> Result<serde_json::Value, String>;
 
     fn create_initial_state(
         &self,
@@ -147,6 +370,15 @@ pub trait GamePlugin: Send + Sync {
         player_id: &str,
     ) -> Vec<serde_json::Value>;
 
+    /// `get_valid_actions` for each of `player_ids`, decoding the state once.
+    /// Players the phase isn't waiting on get an empty list.
+    fn get_valid_actions_batch(
+        &self,
+        game_data: &serde_json::Value,
+        phase: &Phase,
+        player_ids: &[String],
+    ) -> Vec<Vec<serde_json::Value>>;
+
     fn validate_action(
         &self,
         game_data: &serde_json::Value,
@@ -154,13 +386,37 @@ pub trait GamePlugin: Send + Sync {
         action: &Action,
     ) -> Option<String>;
 
+    /// Check the action payload against the plugin's declared schema.
+    /// Callers must reject the action before `apply_action` if this returns an error.
+    fn validate_payload_shape(&self, action: &Action) -> Option<String>;
+
+    fn action_schema(&self) -> serde_json::Value;
+
+    fn feature_outlook(&self, game_data: &serde_json::Value, player_id: &str) -> Option<serde_json::Value>;
+
+    /// `TypedGamePlugin::eval_profiles`; building the evaluator needs the typed plugin.
+    fn eval_profiles(&self) -> Vec<&'static str>;
+
+    fn describe_meeple(&self, game_data: &serde_json::Value, position: &str, spot: &str) -> Option<serde_json::Value>;
+
+    fn export_notation(&self, game_data: &serde_json::Value) -> Option<String>;
+
+    fn import_notation(&self, notation: &str) -> Option<Result<serde_json::Value, String>>;
+
+    fn valid_action_details(
+        &self,
+        game_data: &serde_json::Value,
+        phase: &Phase,
+        player_id: &str,
+    ) -> Option<serde_json::Value>;
+
     fn apply_action(
         &self,
         game_data: &serde_json::Value,
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TransitionResult;
+    ) -> Result<TransitionResult, String>;
 
     fn get_player_view(
         &self,
@@ -216,6 +472,35 @@ impl<P: TypedGamePlugin> GamePlugin for JsonAdapter<P> {
     fn max_players(&self) -> u32 { self.0.max_players() }
     fn description(&self) -> &str { self.0.description() }
     fn disconnect_policy(&self) -> &str { self.0.disconnect_policy() }
+    fn supported_options(&self) -> Vec<OptionSpec> { self.0.supported_options() }
+
+    fn load_state(&self, game_data: &serde_json::Value, phase: &Phase) -> Result<serde_json::Value, String> {
+        let state = self.0.try_decode_state(game_data)?;
+        self.0.load_state(game_data, phase)?;
+        Ok(self.0.encode_state(&state))
+    }
+
+    fn check_state(&self, game_data: &serde_json::Value) -> Result<(), String> {
+        self.0.try_decode_state(game_data).map(|_| ())
+    }
+
+    fn validate_config(&self, config: &GameConfig) -> Result<(), String> {
+        self.0.validate_config(config)
+    }
+
+    fn encode_game_data(&self, game_data: &serde_json::Value, format: GameDataFormat) -> Result<Vec<u8>, String> {
+        match format {
+            GameDataFormat::Json => format.encode(game_data),
+            GameDataFormat::MessagePack => self.0.encode_state_binary(&self.0.try_decode_state(game_data)?),
+        }
+    }
+
+    fn decode_game_data(&self, bytes: &[u8], format: GameDataFormat) -> Result<serde_json::Value, String> {
+        match format {
+            GameDataFormat::Json => format.decode(bytes),
+            GameDataFormat::MessagePack => self.0.decode_state_binary(bytes).map(|state| self.0.encode_state(&state)),
+        }
+    }
 
     fn create_initial_state(
         &self,
@@ -236,32 +521,98 @@ impl<P: TypedGamePlugin> GamePlugin for JsonAdapter<P> {
         self.0.get_valid_actions(&state, phase, player_id)
     }
 
+    fn get_valid_actions_batch(
+        &self,
+        game_data: &serde_json::Value,
+        phase: &Phase,
+        player_ids: &[String],
+    ) -> Vec<Vec<serde_json::Value>> {
+        let state = self.0.decode_state(game_data);
+        player_ids
+            .iter()
+            .map(|pid| {
+                let acting = phase.expected_actions.is_empty()
+                    || phase.expected_actions.iter().any(|ea| &ea.player_id == pid);
+                if acting {
+                    self.0.get_valid_actions(&state, phase, pid)
+                } else {
+                    vec![]
+                }
+            })
+            .collect()
+    }
+
     fn validate_action(
         &self,
         game_data: &serde_json::Value,
         phase: &Phase,
         action: &Action,
     ) -> Option<String> {
+        if let Some(err) = self.validate_payload_shape(action) {
+            return Some(err);
+        }
         let state = self.0.decode_state(game_data);
         self.0.validate_action(&state, phase, action)
     }
 
+    fn validate_payload_shape(&self, action: &Action) -> Option<String> {
+        let schema = self.0.payload_schema(&action.action_type)?;
+        validate_payload_shape(&action.payload, schema)
+    }
+
+    fn action_schema(&self) -> serde_json::Value {
+        self.0.action_schema()
+    }
+
+    fn feature_outlook(&self, game_data: &serde_json::Value, player_id: &str) -> Option<serde_json::Value> {
+        let state = self.0.decode_state(game_data);
+        self.0.feature_outlook(&state, player_id)
+    }
+
+    fn eval_profiles(&self) -> Vec<&'static str> {
+        self.0.eval_profiles()
+    }
+
+    fn describe_meeple(&self, game_data: &serde_json::Value, position: &str, spot: &str) -> Option<serde_json::Value> {
+        let state = self.0.decode_state(game_data);
+        self.0.describe_meeple(&state, position, spot)
+    }
+
+    fn export_notation(&self, game_data: &serde_json::Value) -> Option<String> {
+        let state = self.0.decode_state(game_data);
+        self.0.export_notation(&state)
+    }
+
+    fn import_notation(&self, notation: &str) -> Option<Result<serde_json::Value, String>> {
+        self.0.import_notation(notation).map(|r| r.map(|state| self.0.encode_state(&state)))
+    }
+
+    fn valid_action_details(
+        &self,
+        game_data: &serde_json::Value,
+        phase: &Phase,
+        player_id: &str,
+    ) -> Option<serde_json::Value> {
+        let state = self.0.decode_state(game_data);
+        self.0.valid_action_details(&state, phase, player_id)
+    }
+
     fn apply_action(
         &self,
         game_data: &serde_json::Value,
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TransitionResult {
+    ) -> Result<TransitionResult, String> {
         let state = self.0.decode_state(game_data);
-        let typed = self.0.apply_action(&state, phase, action, players);
-        TransitionResult {
+        let typed = self.0.apply_action(&state, phase, action, players)?;
+        Ok(TransitionResult {
             game_data: self.0.encode_state(&typed.state),
             events: typed.events,
             next_phase: typed.next_phase,
             scores: typed.scores,
             game_over: typed.game_over,
-        }
+        })
     }
 
     fn get_player_view(
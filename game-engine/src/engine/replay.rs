@@ -0,0 +1,349 @@
+//! Game log replay — re-run a recorded game and check the engine reproduces it.
+//!
+//! A `GameLog` stores everything needed to replay a game deterministically:
+//! the seed, options, seat order and every player action (auto-resolve phases
+//! are not recorded; they are re-derived during replay). Logs dropped into
+//! `tests/fixtures/replay_corpus/` become permanent regression tests.
+
+// Used by integration tests and tooling; the server binary does not replay logs.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::models::*;
+use crate::engine::plugin::GamePlugin;
+use crate::engine::simulator::{phase_player_id, DEFAULT_MAX_AUTO_RESOLVES};
+
+/// A recorded game: initial conditions, player actions and the expected outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameLog {
+    pub game_id: String,
+    #[serde(default)]
+    pub description: String,
+    pub seed: u64,
+    /// Player ids in seat order.
+    pub players: Vec<PlayerId>,
+    #[serde(default = "empty_options")]
+    pub options: serde_json::Value,
+    /// Non-auto-resolve actions in the order they were applied.
+    pub actions: Vec<Action>,
+    /// Scores after each action (auto-resolve phases included), when recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub move_scores: Vec<HashMap<String, f64>>,
+    pub expected_scores: HashMap<String, f64>,
+}
+
+fn empty_options() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+impl GameLog {
+    pub fn make_players(&self) -> Vec<Player> {
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(i, pid)| Player {
+                player_id: pid.clone(),
+                display_name: pid.clone(),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    /// The log as a replayable JSON transcript, in the corpus file format.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("GameLog serializes to JSON")
+    }
+
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            options: self.options.clone(),
+            random_seed: Some(self.seed),
+        }
+    }
+}
+
+/// Final position reached by a replay.
+#[derive(Debug, Clone)]
+pub struct ReplayOutcome {
+    pub game_data: serde_json::Value,
+    pub phase: Phase,
+    pub scores: HashMap<String, f64>,
+    pub game_over: Option<GameResult>,
+}
+
+/// Mutable JSON-level game position, advanced through auto-resolve phases.
+struct JsonGame {
+    game_data: serde_json::Value,
+    phase: Phase,
+    players: Vec<Player>,
+    scores: HashMap<String, f64>,
+    game_over: Option<GameResult>,
+}
+
+impl JsonGame {
+    fn apply(&mut self, plugin: &dyn GamePlugin, action: &Action) -> Result<(), String> {
+        let result = plugin.apply_action(&self.game_data, &self.phase, action, &self.players)?;
+        self.game_data = result.game_data;
+        self.phase = result.next_phase;
+        if !result.scores.is_empty() {
+            self.scores = result.scores;
+        }
+        self.game_over = result.game_over;
+        Ok(())
+    }
+
+    fn resolve_auto(&mut self, plugin: &dyn GamePlugin) -> Result<(), String> {
+        let mut resolved = 0;
+        while self.phase.auto_resolve && self.game_over.is_none() {
+            if resolved == DEFAULT_MAX_AUTO_RESOLVES {
+                return Err(format!(
+                    "still in auto-resolve phase '{}' after {} consecutive auto-resolves",
+                    self.phase.name, resolved
+                ));
+            }
+            resolved += 1;
+            let synthetic = Action {
+                action_type: self.phase.name.clone(),
+                player_id: phase_player_id(&self.phase, &self.players),
+                payload: serde_json::json!({}),
+            };
+            self.apply(plugin, &synthetic)?;
+        }
+        Ok(())
+    }
+
+    fn start(plugin: &dyn GamePlugin, players: Vec<Player>, config: &GameConfig) -> Result<Self, String> {
+        plugin.validate_config(config)?;
+        let (game_data, phase, _) = plugin.create_initial_state(&players, config);
+        let scores = players.iter().map(|p| (p.player_id.clone(), 0.0)).collect();
+        let mut game = Self { game_data, phase, players, scores, game_over: None };
+        game.resolve_auto(plugin)?;
+        Ok(game)
+    }
+}
+
+/// Replay `log` through `plugin`, validating every recorded action.
+/// Returns an error describing the first divergence (rejected or extra action).
+pub fn replay_game(plugin: &dyn GamePlugin, log: &GameLog) -> Result<ReplayOutcome, String> {
+    if plugin.game_id() != log.game_id {
+        return Err(format!(
+            "log is for game '{}' but plugin is '{}'",
+            log.game_id,
+            plugin.game_id()
+        ));
+    }
+
+    let mut game = JsonGame::start(plugin, log.make_players(), &log.config())?;
+
+    for (i, action) in log.actions.iter().enumerate() {
+        if game.game_over.is_some() {
+            return Err(format!(
+                "action {} ({}) recorded after the game ended",
+                i, action.action_type
+            ));
+        }
+        if let Some(err) = plugin.validate_action(&game.game_data, &game.phase, action) {
+            return Err(format!(
+                "action {} ({} by {}) rejected in phase '{}': {}",
+                i, action.action_type, action.player_id, game.phase.name, err
+            ));
+        }
+        game.apply(plugin, action)
+            .and_then(|_| game.resolve_auto(plugin))
+            .map_err(|e| format!("action {} ({} by {}) failed to apply: {}", i, action.action_type, action.player_id, e))?;
+    }
+
+    Ok(ReplayOutcome {
+        game_data: game.game_data,
+        phase: game.phase,
+        scores: game.scores,
+        game_over: game.game_over,
+    })
+}
+
+/// Replay `log` and check the final scores match `expected_scores`.
+pub fn check_game_log(plugin: &dyn GamePlugin, log: &GameLog) -> Result<ReplayOutcome, String> {
+    let outcome = replay_game(plugin, log)?;
+
+    let mut mismatches = Vec::new();
+    for pid in &log.players {
+        let expected = log.expected_scores.get(pid).copied().unwrap_or(0.0);
+        let actual = outcome.scores.get(pid).copied().unwrap_or(0.0);
+        if (expected - actual).abs() > 1e-9 {
+            mismatches.push(format!("{}: expected {}, got {}", pid, expected, actual));
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(outcome)
+    } else {
+        Err(format!("final scores differ: {}", mismatches.join("; ")))
+    }
+}
+
+/// Play a game by letting `choose` pick an index into each decision's valid actions,
+/// and record it as a `GameLog` whose expected scores are the scores reached.
+pub fn record_game(
+    plugin: &dyn GamePlugin,
+    player_ids: &[&str],
+    seed: u64,
+    options: serde_json::Value,
+    max_actions: usize,
+    choose: &mut dyn FnMut(&[serde_json::Value]) -> usize,
+) -> GameLog {
+    let mut log = GameLog {
+        game_id: plugin.game_id().to_string(),
+        description: String::new(),
+        seed,
+        players: player_ids.iter().map(|p| p.to_string()).collect(),
+        options,
+        actions: Vec::new(),
+        move_scores: Vec::new(),
+        expected_scores: HashMap::new(),
+    };
+
+    let mut game = JsonGame::start(plugin, log.make_players(), &log.config())
+        .expect("auto-resolve phases apply");
+    while game.game_over.is_none() && log.actions.len() < max_actions {
+        let pid = phase_player_id(&game.phase, &game.players);
+        let valid = plugin.get_valid_actions(&game.game_data, &game.phase, &pid);
+        if valid.is_empty() {
+            break;
+        }
+        let payload = valid[choose(&valid).min(valid.len() - 1)].clone();
+        let action_type = payload
+            .get("action_type")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| game.phase.expected_actions.first().map(|ea| ea.action_type.clone()))
+            .unwrap_or_else(|| game.phase.name.clone());
+        let action = Action { action_type, player_id: pid, payload };
+
+        game.apply(plugin, &action)
+            .and_then(|_| game.resolve_auto(plugin))
+            .expect("valid actions apply");
+        log.actions.push(action);
+    }
+
+    log.expected_scores = game.scores;
+    log
+}
+
+/// Load every `*.json` game log in `dir`, sorted by file name.
+pub fn load_corpus(dir: &Path) -> Result<Vec<(PathBuf, GameLog)>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read corpus dir {}: {}", dir.display(), e))?;
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let log: GameLog = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+            Ok((path, log))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::JsonAdapter;
+    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+    #[test]
+    fn test_record_then_replay_reproduces_scores() {
+        let plugin = JsonAdapter(CarcassonnePlugin);
+        let mut turn = 0usize;
+        let log = record_game(
+            &plugin,
+            &["p0", "p1"],
+            42,
+            serde_json::json!({"tile_count": 10}),
+            500,
+            &mut |valid| {
+                turn += 1;
+                turn % valid.len()
+            },
+        );
+        assert!(!log.actions.is_empty());
+
+        let outcome = check_game_log(&plugin, &log).expect("replay should match recording");
+        assert!(outcome.game_over.is_some());
+    }
+
+    #[test]
+    fn test_replay_reports_score_mismatch() {
+        let plugin = JsonAdapter(CarcassonnePlugin);
+        let mut log = record_game(
+            &plugin,
+            &["p0", "p1"],
+            7,
+            serde_json::json!({"tile_count": 5}),
+            500,
+            &mut |_| 0,
+        );
+        *log.expected_scores.get_mut("p0").unwrap() += 1.0;
+
+        let err = check_game_log(&plugin, &log).unwrap_err();
+        assert!(err.contains("p0"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_replay_reports_rejected_action() {
+        let plugin = JsonAdapter(CarcassonnePlugin);
+        let mut log = record_game(
+            &plugin,
+            &["p0", "p1"],
+            7,
+            serde_json::json!({"tile_count": 5}),
+            500,
+            &mut |_| 0,
+        );
+        log.actions[0].payload = serde_json::json!({"x": 100, "y": 100, "rotation": 0});
+
+        let err = replay_game(&plugin, &log).unwrap_err();
+        assert!(err.starts_with("action 0"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_replay_reports_auto_resolve_loop() {
+        use crate::engine::test_games::Looping;
+
+        let log = GameLog {
+            game_id: "looping".into(),
+            description: String::new(),
+            seed: 1,
+            players: vec!["p0".into()],
+            options: serde_json::json!({}),
+            actions: vec![],
+            move_scores: vec![],
+            expected_scores: HashMap::new(),
+        };
+        let err = replay_game(&JsonAdapter(Looping { max_auto: None }), &log).unwrap_err();
+        assert!(err.contains("auto-resolve phase 'spin'"), "unexpected error: {}", err);
+        assert!(err.contains(&DEFAULT_MAX_AUTO_RESOLVES.to_string()), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_replay_rejects_invalid_options() {
+        let plugin = JsonAdapter(CarcassonnePlugin);
+        let mut log = record_game(&plugin, &["p0", "p1"], 7, serde_json::json!({"tile_count": 5}), 500, &mut |_| 0);
+        log.options = serde_json::json!({"meeples_per_player": 0});
+
+        assert!(replay_game(&plugin, &log).is_err());
+    }
+}
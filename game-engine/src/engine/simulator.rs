@@ -1,7 +1,8 @@
 //! Synchronous game simulator — advances game state through auto-resolve phases.
 //! Used by MCTS and Arena. Mirrors backend/src/engine/game_simulator.py.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 use crate::engine::models::*;
 use crate::engine::plugin::TypedGamePlugin;
@@ -11,19 +12,122 @@ use crate::engine::plugin::TypedGamePlugin;
 pub struct SimulationState<S: Clone> {
     pub state: S,
     pub phase: Phase,
-    pub players: Vec<Player>,
+    /// Shared, since players never change during a game: cloning a state for
+    /// a search iteration only bumps the refcount.
+    pub players: Arc<[Player]>,
     pub scores: HashMap<String, f64>,
     pub game_over: Option<GameResult>,
+    /// Positions `pop_last` can go back to; None (the default for search) records nothing.
+    pub history: Option<History<S>>,
 }
 
+/// Bounded record of the positions before each decision, for taking moves back.
+#[derive(Clone)]
+pub struct History<S: Clone> {
+    depth: usize,
+    snapshots: VecDeque<(S, Phase, HashMap<String, f64>)>,
+}
+
+#[allow(dead_code)]
+impl<S: Clone> History<S> {
+    /// Keep at most `depth` positions, dropping the oldest first.
+    pub fn new(depth: usize) -> Self {
+        Self { depth, snapshots: VecDeque::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+impl<S: Clone> SimulationState<S> {
+    /// Record up to `depth` decisions so they can be taken back with `pop_last`.
+    #[allow(dead_code)]
+    pub fn with_history(mut self, depth: usize) -> Self {
+        self.history = Some(History::new(depth));
+        self
+    }
+
+    /// Take back the last decision along with the auto-resolve phases it
+    /// triggered. Returns false when there is nothing to take back.
+    #[allow(dead_code)]
+    pub fn pop_last(&mut self) -> bool {
+        let Some((state, phase, scores)) = self.history.as_mut().and_then(|h| h.snapshots.pop_back()) else {
+            return false;
+        };
+        self.state = state;
+        self.phase = phase;
+        self.scores = scores;
+        self.game_over = None;
+        true
+    }
+
+    /// The current position, if history is on and it is a decision point.
+    /// Auto-resolve phases are not decisions: taking back lands before the
+    /// action that led into them.
+    fn history_snapshot(&self) -> Option<(S, Phase, HashMap<String, f64>)> {
+        let history = self.history.as_ref()?;
+        if self.phase.auto_resolve || history.depth == 0 {
+            return None;
+        }
+        Some((self.state.clone(), self.phase.clone(), self.scores.clone()))
+    }
+
+    fn record_history(&mut self, snapshot: Option<(S, Phase, HashMap<String, f64>)>) {
+        let (Some(history), Some(snapshot)) = (self.history.as_mut(), snapshot) else {
+            return;
+        };
+        if history.snapshots.len() == history.depth {
+            history.snapshots.pop_front();
+        }
+        history.snapshots.push_back(snapshot);
+    }
+}
+
+/// Default cap on consecutive auto-resolve phases after one action
+/// (`TypedGamePlugin::max_auto_resolves`).
+pub const DEFAULT_MAX_AUTO_RESOLVES: usize = 1000;
+
 /// Apply an action and auto-resolve all subsequent auto-resolve phases.
 /// Mutates `sim` in place.
+///
+/// Errors if the plugin keeps producing auto-resolve phases past its
+/// `max_auto_resolves` cap (a plugin bug); `sim` is left at the last phase reached.
 pub fn apply_action_and_resolve<P: TypedGamePlugin>(
     plugin: &P,
     sim: &mut SimulationState<P::State>,
     action: &Action,
-) {
-    let result = plugin.apply_action(&sim.state, &sim.phase, action, &sim.players);
+) -> Result<(), String> {
+    apply_and_resolve_inner(plugin, sim, action, |_| {})
+}
+
+/// Like `apply_action_and_resolve`, but also returns the events emitted by the
+/// action and by every auto-resolve phase that followed it, in order.
+pub fn apply_action_and_resolve_with_events<P: TypedGamePlugin>(
+    plugin: &P,
+    sim: &mut SimulationState<P::State>,
+    action: &Action,
+) -> Result<Vec<Event>, String> {
+    let mut events = Vec::new();
+    apply_and_resolve_inner(plugin, sim, action, |e| events.extend(e))?;
+    Ok(events)
+}
+
+fn apply_and_resolve_inner<P: TypedGamePlugin>(
+    plugin: &P,
+    sim: &mut SimulationState<P::State>,
+    action: &Action,
+    mut on_events: impl FnMut(Vec<Event>),
+) -> Result<(), String> {
+    // Snapshot first, but only record it once the action is accepted
+    let snapshot = sim.history_snapshot();
+    let result = plugin.apply_action(&sim.state, &sim.phase, action, &sim.players)?;
+    sim.record_history(snapshot);
+    on_events(result.events);
     sim.state = result.state;
     sim.phase = result.next_phase;
     if !result.scores.is_empty() {
@@ -32,12 +136,23 @@ pub fn apply_action_and_resolve<P: TypedGamePlugin>(
     sim.game_over = result.game_over;
 
     if sim.game_over.is_some() {
-        return;
+        return Ok(());
     }
 
-    let mut max_auto = 50;
-    while sim.phase.auto_resolve && sim.game_over.is_none() && max_auto > 0 {
-        max_auto -= 1;
+    let max_auto = plugin.max_auto_resolves();
+    let mut resolved = 0;
+    while sim.phase.auto_resolve && sim.game_over.is_none() {
+        if resolved == max_auto {
+            return Err(format!(
+                "{}: still in auto-resolve phase '{}' after {} consecutive auto-resolves (after '{}' by {})",
+                plugin.game_id(),
+                sim.phase.name,
+                resolved,
+                action.action_type,
+                action.player_id
+            ));
+        }
+        resolved += 1;
 
         let pid = phase_player_id(&sim.phase, &sim.players);
         let synthetic = Action {
@@ -46,7 +161,8 @@ pub fn apply_action_and_resolve<P: TypedGamePlugin>(
             payload: serde_json::json!({}),
         };
 
-        let result = plugin.apply_action(&sim.state, &sim.phase, &synthetic, &sim.players);
+        let result = plugin.apply_action(&sim.state, &sim.phase, &synthetic, &sim.players)?;
+        on_events(result.events);
         sim.state = result.state;
         sim.phase = result.next_phase;
         if !result.scores.is_empty() {
@@ -54,6 +170,7 @@ pub fn apply_action_and_resolve<P: TypedGamePlugin>(
         }
         sim.game_over = result.game_over;
     }
+    Ok(())
 }
 
 /// Extract the acting player from a phase, falling back to first player.
@@ -73,3 +190,107 @@ pub fn phase_player_id(phase: &Phase, players: &[Player]) -> PlayerId {
         "system".into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::test_games::Looping;
+
+    fn start(plugin: &Looping) -> SimulationState<u32> {
+        let players = vec![Player {
+            player_id: "p0".into(),
+            display_name: "p0".into(),
+            seat_index: 0,
+            is_bot: true,
+            bot_id: None,
+        }];
+        let (state, phase, _) = plugin.create_initial_state(&players, &GameConfig { options: serde_json::json!({}), random_seed: None });
+        SimulationState { state, phase, players: players.into(), scores: HashMap::new(), game_over: None, history: None }
+    }
+
+    #[test]
+    fn test_auto_resolve_loop_guard_triggers() {
+        let action = Action { action_type: "spin".into(), player_id: "p0".into(), payload: serde_json::json!({}) };
+
+        let plugin = Looping { max_auto: None };
+        let mut sim = start(&plugin);
+        let err = apply_action_and_resolve(&plugin, &mut sim, &action).unwrap_err();
+        assert!(err.contains("auto-resolve phase 'spin'"), "{}", err);
+        assert!(err.contains(&DEFAULT_MAX_AUTO_RESOLVES.to_string()), "{}", err);
+        // The action plus exactly `max` auto-resolves were applied before bailing out.
+        assert_eq!(sim.state as usize, DEFAULT_MAX_AUTO_RESOLVES + 1);
+
+        let plugin = Looping { max_auto: Some(5) };
+        let mut sim = start(&plugin);
+        assert!(apply_action_and_resolve_with_events(&plugin, &mut sim, &action).is_err());
+        assert_eq!(sim.state, 6);
+    }
+
+    #[test]
+    fn test_pop_last_takes_back_to_the_last_decision() {
+        use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+        let plugin = CarcassonnePlugin;
+        let players: Vec<Player> = (0..2)
+            .map(|i| Player {
+                player_id: format!("p{i}"),
+                display_name: format!("p{i}"),
+                seat_index: i,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect();
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(3) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let mut sim = SimulationState { state, phase, players: players.into(), scores: HashMap::new(), game_over: None, history: None }
+            .with_history(2);
+        let act = |sim: &mut SimulationState<_>, payload: serde_json::Value| {
+            let action = Action {
+                action_type: sim.phase.name.clone(),
+                player_id: phase_player_id(&sim.phase, &sim.players),
+                payload,
+            };
+            apply_action_and_resolve(&plugin, sim, &action).unwrap();
+        };
+
+        // The opening draw is an auto-resolve phase, so there is nothing to take back yet
+        act(&mut sim, serde_json::json!({}));
+        assert_eq!(sim.phase.name, "place_tile");
+        assert!(!sim.pop_last());
+
+        // A rejected action (onto the starting tile) is not recorded
+        let illegal = Action {
+            action_type: "place_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({"x": 0, "y": 0, "rotation": 0}),
+        };
+        assert!(apply_action_and_resolve(&plugin, &mut sim, &illegal).is_err());
+        assert!(sim.history.as_ref().unwrap().is_empty());
+
+        let before = plugin.get_valid_actions(&sim.state, &sim.phase, "p0");
+        act(&mut sim, before[0].clone());
+        assert_eq!(sim.phase.name, "place_meeple");
+        assert!(sim.pop_last());
+        assert_eq!(sim.phase.name, "place_tile");
+        assert_eq!(plugin.get_valid_actions(&sim.state, &sim.phase, "p0"), before);
+
+        // Skipping the meeple chains through scoring and the next draw; one
+        // takeback lands back on the meeple decision
+        act(&mut sim, before[0].clone());
+        act(&mut sim, serde_json::json!({"skip": true}));
+        assert_eq!(sim.phase.name, "place_tile");
+        assert_eq!(phase_player_id(&sim.phase, &sim.players), "p1");
+        assert!(sim.pop_last());
+        assert_eq!(sim.phase.name, "place_meeple");
+
+        // Only the last two decisions are kept
+        act(&mut sim, serde_json::json!({"skip": true}));
+        let next = plugin.get_valid_actions(&sim.state, &sim.phase, "p1");
+        act(&mut sim, next[0].clone());
+        assert_eq!(sim.history.as_ref().unwrap().len(), 2);
+        assert!(sim.pop_last());
+        assert!(sim.pop_last());
+        assert_eq!(sim.phase.name, "place_meeple");
+        assert!(!sim.pop_last());
+    }
+}
@@ -0,0 +1,179 @@
+//! Tiny games shared by unit tests that need a real, fast-to-play plugin.
+
+use std::collections::HashMap;
+
+use crate::engine::models::*;
+use crate::engine::plugin::{TypedGamePlugin, TypedTransitionResult};
+use crate::engine::simulator::DEFAULT_MAX_AUTO_RESOLVES;
+
+/// Tic-tac-toe on a 3x3 board; seat 0 plays X. Actions are `{"cell": 0..9}`.
+pub struct TicTacToe;
+
+/// Winning lines, as cell indices.
+pub const TTT_LINES: [[usize; 3]; 8] =
+    [[0, 1, 2], [3, 4, 5], [6, 7, 8], [0, 3, 6], [1, 4, 7], [2, 5, 8], [0, 4, 8], [2, 4, 6]];
+
+fn ttt_phase(players: &[Player], player_index: usize) -> Phase {
+    Phase {
+        name: "play".into(),
+        concurrent_mode: None,
+        expected_actions: vec![ExpectedAction {
+            player_id: players[player_index].player_id.clone(),
+            action_type: "play".into(),
+            constraints: HashMap::new(),
+            timeout_ms: None,
+        }],
+        auto_resolve: false,
+        metadata: serde_json::json!({"player_index": player_index}),
+    }
+}
+
+impl TypedGamePlugin for TicTacToe {
+    /// Cell owners: 0 = empty, else seat index + 1.
+    type State = [u8; 9];
+
+    fn game_id(&self) -> &str { "tic_tac_toe" }
+    fn display_name(&self) -> &str { "Tic-Tac-Toe" }
+    fn min_players(&self) -> u32 { 2 }
+    fn max_players(&self) -> u32 { 2 }
+    fn description(&self) -> &str { "" }
+    fn disconnect_policy(&self) -> &str { "abandon_all" }
+    fn decode_state(&self, _game_data: &serde_json::Value) -> [u8; 9] { [0; 9] }
+    fn encode_state(&self, state: &[u8; 9]) -> serde_json::Value { serde_json::json!(state) }
+
+    fn create_initial_state(&self, players: &[Player], _config: &GameConfig) -> ([u8; 9], Phase, Vec<Event>) {
+        ([0; 9], ttt_phase(players, 0), vec![])
+    }
+
+    fn get_valid_actions(&self, state: &[u8; 9], phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+        if phase.name != "play" {
+            return vec![];
+        }
+        (0..9).filter(|&i| state[i] == 0).map(|i| serde_json::json!({"cell": i})).collect()
+    }
+
+    fn validate_action(&self, _state: &[u8; 9], _phase: &Phase, _action: &Action) -> Option<String> {
+        None
+    }
+
+    fn apply_action(&self, state: &[u8; 9], phase: &Phase, action: &Action, players: &[Player]) -> Result<TypedTransitionResult<[u8; 9]>, String> {
+        let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
+        let cell = action.payload["cell"]
+            .as_u64()
+            .map(|c| c as usize)
+            .filter(|&c| c < 9 && state[c] == 0)
+            .ok_or_else(|| format!("no empty cell {}", action.payload["cell"]))?;
+        let mut board = *state;
+        board[cell] = player_index as u8 + 1;
+
+        let won = TTT_LINES.iter().any(|line| line.iter().all(|&c| board[c] == player_index as u8 + 1));
+        let winners = if won {
+            Some(vec![players[player_index].player_id.clone()])
+        } else if board.iter().all(|&c| c != 0) {
+            Some(players.iter().map(|p| p.player_id.clone()).collect())
+        } else {
+            None
+        };
+        let (next_phase, game_over) = match winners {
+            Some(winners) => (
+                Phase {
+                    name: "game_over".into(),
+                    concurrent_mode: None,
+                    expected_actions: vec![],
+                    auto_resolve: false,
+                    metadata: serde_json::json!({}),
+                },
+                Some(GameResult {
+                    winners,
+                    final_scores: HashMap::new(),
+                    reason: "normal".into(),
+                    details: HashMap::new(),
+                }),
+            ),
+            None => (ttt_phase(players, 1 - player_index), None),
+        };
+        Ok(TypedTransitionResult { state: board, events: vec![], next_phase, scores: HashMap::new(), game_over })
+    }
+
+    fn get_player_view(&self, state: &[u8; 9], _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+        self.encode_state(state)
+    }
+
+    fn get_scores(&self, _state: &[u8; 9]) -> HashMap<String, f64> {
+        HashMap::new()
+    }
+
+    fn is_perfect_information(&self) -> bool { true }
+
+    fn parse_ai_action(&self, response: &serde_json::Value, _phase: &Phase, player_id: &str) -> Action {
+        Action { action_type: "play".into(), player_id: player_id.into(), payload: response.clone() }
+    }
+}
+
+/// Buggy plugin: every action leads to an auto-resolve phase that leads to
+/// another one, forever.
+pub struct Looping {
+    /// `max_auto_resolves`; None for the engine default.
+    pub max_auto: Option<usize>,
+}
+
+fn looping_phase() -> Phase {
+    Phase {
+        name: "spin".into(),
+        concurrent_mode: None,
+        expected_actions: vec![],
+        auto_resolve: true,
+        metadata: serde_json::json!({}),
+    }
+}
+
+impl TypedGamePlugin for Looping {
+    type State = u32;
+
+    fn game_id(&self) -> &str { "looping" }
+    fn display_name(&self) -> &str { "Looping" }
+    fn min_players(&self) -> u32 { 1 }
+    fn max_players(&self) -> u32 { 1 }
+    fn description(&self) -> &str { "" }
+    fn disconnect_policy(&self) -> &str { "abandon_all" }
+    fn decode_state(&self, _game_data: &serde_json::Value) -> u32 { 0 }
+    fn encode_state(&self, state: &u32) -> serde_json::Value { serde_json::json!(state) }
+
+    fn create_initial_state(&self, _players: &[Player], _config: &GameConfig) -> (u32, Phase, Vec<Event>) {
+        (0, looping_phase(), vec![])
+    }
+
+    fn get_valid_actions(&self, _state: &u32, _phase: &Phase, _player_id: &str) -> Vec<serde_json::Value> {
+        vec![]
+    }
+
+    fn validate_action(&self, _state: &u32, _phase: &Phase, _action: &Action) -> Option<String> {
+        None
+    }
+
+    fn apply_action(&self, state: &u32, _phase: &Phase, _action: &Action, _players: &[Player]) -> Result<TypedTransitionResult<u32>, String> {
+        Ok(TypedTransitionResult {
+            state: state + 1,
+            events: vec![],
+            next_phase: looping_phase(),
+            scores: HashMap::new(),
+            game_over: None,
+        })
+    }
+
+    fn get_player_view(&self, _state: &u32, _phase: &Phase, _player_id: Option<&str>, _players: &[Player]) -> serde_json::Value {
+        serde_json::json!({})
+    }
+
+    fn get_scores(&self, _state: &u32) -> HashMap<String, f64> {
+        HashMap::new()
+    }
+
+    fn parse_ai_action(&self, response: &serde_json::Value, phase: &Phase, player_id: &str) -> Action {
+        Action { action_type: phase.name.clone(), player_id: player_id.into(), payload: response.clone() }
+    }
+
+    fn max_auto_resolves(&self) -> usize {
+        self.max_auto.unwrap_or(DEFAULT_MAX_AUTO_RESOLVES)
+    }
+}
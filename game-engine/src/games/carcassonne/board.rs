@@ -1,7 +1,7 @@
 //! Board logic: tile placement validation, open position calculation.
 //! Mirrors backend/src/games/carcassonne/board.py.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use super::tiles::ROTATED_EDGES;
 use super::types::*;
@@ -85,20 +85,57 @@ pub fn recalculate_open_positions(board_tiles: &HashMap<(i32, i32), PlacedTile>)
     result
 }
 
-/// Check if a tile type can be placed anywhere on the board.
-pub fn tile_has_valid_placement(
+/// Update sorted `open_positions` for a tile just placed at `placed_pos`: that
+/// cell is taken and its empty neighbors are open. Gives the same result as
+/// `recalculate_open_positions` without walking the whole board.
+pub fn update_open_positions(
+    board_tiles: &HashMap<(i32, i32), PlacedTile>,
+    open_positions: &mut Vec<(i32, i32)>,
+    placed_pos: (i32, i32),
+) {
+    if let Ok(i) = open_positions.binary_search(&placed_pos) {
+        open_positions.remove(i);
+    }
+    let (x, y) = placed_pos;
+    for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+        let neighbor = (x + dx, y + dy);
+        if board_tiles.contains_key(&neighbor) {
+            continue;
+        }
+        if let Err(i) = open_positions.binary_search(&neighbor) {
+            open_positions.insert(i, neighbor);
+        }
+    }
+}
+
+/// All (position, rotation) pairs where a tile type can be placed.
+/// Both the draw-time filter and the place_tile action list enumerate through
+/// this, so a drawn tile always has at least one listed placement.
+pub fn valid_tile_placements<'a>(
+    board_tiles: &'a HashMap<(i32, i32), PlacedTile>,
+    open_positions: &'a [(i32, i32)],
+    tile_type_idx: u8,
+) -> impl Iterator<Item = ((i32, i32), u32)> + 'a {
+    open_positions.iter().flat_map(move |&pos| {
+        [0u32, 90, 180, 270]
+            .into_iter()
+            .filter(move |&rotation| can_place_tile(board_tiles, tile_type_idx, pos, rotation))
+            .map(move |rotation| (pos, rotation))
+    })
+}
+
+/// `valid_tile_placements` grouped by position: each open position where the
+/// tile fits, with its legal rotations in ascending order.
+pub fn placements_by_position(
     board_tiles: &HashMap<(i32, i32), PlacedTile>,
     open_positions: &[(i32, i32)],
     tile_type_idx: u8,
-) -> bool {
-    for &pos in open_positions {
-        for rotation in [0, 90, 180, 270] {
-            if can_place_tile(board_tiles, tile_type_idx, pos, rotation) {
-                return true;
-            }
-        }
+) -> BTreeMap<(i32, i32), Vec<u32>> {
+    let mut grouped: BTreeMap<(i32, i32), Vec<u32>> = BTreeMap::new();
+    for (pos, rotation) in valid_tile_placements(board_tiles, open_positions, tile_type_idx) {
+        grouped.entry(pos).or_default().push(rotation);
     }
-    false
+    grouped
 }
 
 #[cfg(test)]
@@ -162,9 +199,29 @@ mod tests {
         let board = make_board_with_starting_tile();
         let open = recalculate_open_positions(&board);
         // E (city N) should be placeable somewhere
-        assert!(tile_has_valid_placement(&board, &open, tile_type_to_index("E")));
+        assert!(valid_tile_placements(&board, &open, tile_type_to_index("E")).next().is_some());
         // C (city on all sides) can only go next to all-city neighbors
-        assert!(tile_has_valid_placement(&board, &open, tile_type_to_index("C")));
+        assert!(valid_tile_placements(&board, &open, tile_type_to_index("C")).next().is_some());
+    }
+
+    #[test]
+    fn test_incremental_open_positions_match_recalculation() {
+        use rand::seq::SliceRandom;
+        use crate::engine::rng::GameRng;
+
+        // Placement legality doesn't matter here, only which cells are filled
+        let tile = PlacedTile { tile_type_id: tile_type_to_index("C"), rotation: 0 };
+        for seed in 0..20 {
+            let mut rng = GameRng::from_seed(seed);
+            let mut board = make_board_with_starting_tile();
+            let mut open = recalculate_open_positions(&board);
+            for _ in 0..80 {
+                let pos = *open.choose(&mut rng).unwrap();
+                board.insert(pos, tile);
+                update_open_positions(&board, &mut open, pos);
+                assert_eq!(open, recalculate_open_positions(&board), "seed {seed}, after {pos:?}");
+            }
+        }
     }
 
     /// Verify board edge consistency: every placed tile must have matching
@@ -224,9 +281,10 @@ mod tests {
             };
             let (state, phase, _) = plugin.create_initial_state(&players, &config);
             let mut sim = SimulationState {
-                state, phase, players: players.clone(),
+                state, phase, players: players.clone().into(),
                 scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
                 game_over: None,
+                history: None,
             };
 
             for _ in 0..300 {
@@ -237,7 +295,7 @@ mod tests {
                     let at = sim.phase.name.clone();
                     apply_action_and_resolve(&plugin, &mut sim, &Action {
                         action_type: at, player_id: "system".into(), payload: serde_json::json!({}),
-                    });
+                    }).unwrap();
                 }
                 if sim.game_over.is_some() { break; }
 
@@ -259,10 +317,97 @@ mod tests {
                     player_id: acting_pid,
                     payload: chosen,
                 };
-                apply_action_and_resolve(&plugin, &mut sim, &action);
+                apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
                 total_moves += 1;
             }
         }
         println!("Fuzz test passed: {} total moves across 50 games, all boards consistent", total_moves);
     }
+
+    #[test]
+    fn test_fuzz_drawn_tile_always_has_placement() {
+        // A tile admitted at draw time must yield at least one place_tile action,
+        // and every listed placement must pass validation.
+        use crate::engine::models::*;
+        use crate::engine::plugin::TypedGamePlugin;
+        use crate::engine::simulator::{apply_action_and_resolve, SimulationState};
+        use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+        let plugin = CarcassonnePlugin;
+        let players = vec![
+            Player { player_id: "p0".into(), display_name: "P0".into(), seat_index: 0, is_bot: true, bot_id: None },
+            Player { player_id: "p1".into(), display_name: "P1".into(), seat_index: 1, is_bot: true, bot_id: None },
+        ];
+
+        let mut rng = 4242u64;
+        let mut placements_checked = 0u64;
+
+        for seed in 0..10 {
+            let config = GameConfig {
+                random_seed: Some(seed),
+                options: serde_json::json!({}),
+            };
+            let (state, phase, _) = plugin.create_initial_state(&players, &config);
+            let mut sim = SimulationState {
+                state, phase, players: players.clone().into(),
+                scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
+                game_over: None,
+                history: None,
+            };
+
+            for _ in 0..300 {
+                while sim.phase.auto_resolve && sim.game_over.is_none() {
+                    let at = sim.phase.name.clone();
+                    apply_action_and_resolve(&plugin, &mut sim, &Action {
+                        action_type: at, player_id: "system".into(), payload: serde_json::json!({}),
+                    }).unwrap();
+                }
+                if sim.game_over.is_some() { break; }
+
+                let acting_pid = sim.phase.expected_actions[0].player_id.clone();
+                let action_type = sim.phase.expected_actions[0].action_type.clone();
+                let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &acting_pid);
+
+                if sim.phase.name == "place_tile" {
+                    let tile = sim.state.current_tile.expect("place_tile phase without a drawn tile");
+                    assert_eq!(
+                        sim.state.board.open_positions,
+                        recalculate_open_positions(&sim.state.board.tiles),
+                        "Seed {}: incrementally updated open positions drifted",
+                        seed,
+                    );
+                    assert!(
+                        valid_tile_placements(&sim.state.board.tiles, &sim.state.board.open_positions, tile)
+                            .next()
+                            .is_some(),
+                        "Seed {}: drawn tile {} has no valid placement",
+                        seed, tile_index_to_type(tile),
+                    );
+                    assert!(
+                        !valid.is_empty(),
+                        "Seed {}: tile {} passed the draw filter but has no place_tile actions",
+                        seed, tile_index_to_type(tile),
+                    );
+                    for payload in &valid {
+                        let action = Action {
+                            action_type: action_type.clone(),
+                            player_id: acting_pid.clone(),
+                            payload: payload.clone(),
+                        };
+                        assert_eq!(plugin.validate_action(&sim.state, &sim.phase, &action), None);
+                    }
+                    placements_checked += valid.len() as u64;
+                }
+                assert!(!valid.is_empty(), "Seed {}: no valid actions in {}", seed, sim.phase.name);
+
+                rng = rng.wrapping_mul(6364136223846793005).wrapping_add(seed);
+                let chosen = valid[(rng >> 33) as usize % valid.len()].clone();
+                apply_action_and_resolve(&plugin, &mut sim, &Action {
+                    action_type, player_id: acting_pid, payload: chosen,
+                }).unwrap();
+            }
+            assert!(sim.game_over.is_some(), "Seed {}: game did not finish", seed);
+        }
+        assert!(placements_checked > 0);
+    }
 }
@@ -4,10 +4,10 @@
 
 use crate::engine::models::*;
 use crate::games::carcassonne::scoring::get_adjacent_completed_cities;
-use crate::games::carcassonne::types::{CarcassonneState, FeatureType, PlacedMeeple, Position};
+use crate::games::carcassonne::types::{CarcassonneState, Feature, FeatureType, PlacedMeeple, Position};
 
 /// Tunable parameters for the Carcassonne heuristic evaluator.
-#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct EvalWeights {
     pub score_base: f64,
     pub score_delta: f64,
@@ -43,18 +43,93 @@ pub struct EvalWeights {
     /// Suggested: 0.0-0.3 (added to potential for uncontested features).
     #[serde(default)]
     pub dominance_bonus: f64,
+    /// Potential penalty per meeple stuck in a feature where an opponent holds the majority.
+    #[serde(default = "default_wasted_meeple_multiplier")]
+    pub wasted_meeple_multiplier: f64,
+    /// Field value per adjacent completed city (and per likely-to-complete nearby city,
+    /// scaled by completion probability).
+    #[serde(default = "default_field_city_bonus")]
+    pub field_city_bonus: f64,
+    /// Extra potential for incomplete cities of at least `city_bonus_min_tiles` tiles:
+    /// bonus × completion probability × sqrt(tiles beyond the threshold + 1), so finishing
+    /// big cities is worth more, with diminishing returns. Suggested: 1.0-4.0.
+    #[serde(default)]
+    pub city_completion_bonus: f64,
+    #[serde(default = "default_city_bonus_min_tiles")]
+    pub city_bonus_min_tiles: i64,
 }
 
+/// Points a field earns per adjacent completed city under the base rules.
+const FIELD_POINTS_PER_CITY: f64 = 3.0;
+
+/// Below this completion probability a meeple on the feature counts as stuck.
+pub(crate) const TRAPPED_COMPLETION_PROBABILITY: f64 = 0.15;
+
 fn one() -> f64 {
     1.0
 }
 
+fn default_wasted_meeple_multiplier() -> f64 {
+    1.5
+}
+
+fn default_field_city_bonus() -> f64 {
+    FIELD_POINTS_PER_CITY
+}
+
+fn default_city_bonus_min_tiles() -> i64 {
+    4
+}
+
 impl Default for EvalWeights {
     fn default() -> Self {
         DEFAULT_WEIGHTS
     }
 }
 
+impl EvalWeights {
+    /// Copy of these weights with the named fields replaced (field name -> value).
+    /// Unknown names and non-integral values for integer fields are rejected.
+    pub fn with_overrides(
+        &self,
+        overrides: &std::collections::HashMap<String, f64>,
+    ) -> Result<Self, String> {
+        let mut fields = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => return Err("EvalWeights did not serialize to an object".into()),
+        };
+        for (name, &value) in overrides {
+            let Some(slot) = fields.get_mut(name) else {
+                return Err(format!("unknown eval weight: '{}'", name));
+            };
+            *slot = if slot.is_i64() {
+                if value.fract() != 0.0 {
+                    return Err(format!("eval weight '{}' must be an integer, got {}", name, value));
+                }
+                serde_json::json!(value as i64)
+            } else {
+                serde_json::json!(value)
+            };
+        }
+        serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(|e| format!("invalid eval weights: {}", e))
+    }
+}
+
+/// Names of the preset weight sets, default first.
+pub const EVAL_PROFILES: [&str; 4] = ["default", "aggressive", "field_heavy", "conservative"];
+
+/// The preset weights named by an `EVAL_PROFILES` entry.
+pub fn preset_weights(eval_profile: &str) -> Option<&'static EvalWeights> {
+    match eval_profile {
+        "aggressive" => Some(&AGGRESSIVE_WEIGHTS),
+        "field_heavy" => Some(&FIELD_HEAVY_WEIGHTS),
+        "conservative" => Some(&CONSERVATIVE_WEIGHTS),
+        "default" => Some(&DEFAULT_WEIGHTS),
+        _ => None,
+    }
+}
+
 pub static AGGRESSIVE_WEIGHTS: EvalWeights = EvalWeights {
     score_base: 0.45,
     score_delta: 0.10,
@@ -74,6 +149,10 @@ pub static AGGRESSIVE_WEIGHTS: EvalWeights = EvalWeights {
     trapped_meeple_weight: 0.0,
     city_size_exponent: 1.0,
     dominance_bonus: 0.0,
+    wasted_meeple_multiplier: 1.5,
+    field_city_bonus: FIELD_POINTS_PER_CITY,
+    city_completion_bonus: 0.0,
+    city_bonus_min_tiles: 4,
 };
 
 pub static FIELD_HEAVY_WEIGHTS: EvalWeights = EvalWeights {
@@ -95,6 +174,10 @@ pub static FIELD_HEAVY_WEIGHTS: EvalWeights = EvalWeights {
     trapped_meeple_weight: 0.0,
     city_size_exponent: 1.0,
     dominance_bonus: 0.0,
+    wasted_meeple_multiplier: 1.5,
+    field_city_bonus: FIELD_POINTS_PER_CITY,
+    city_completion_bonus: 0.0,
+    city_bonus_min_tiles: 4,
 };
 
 pub static DEFAULT_WEIGHTS: EvalWeights = EvalWeights {
@@ -116,6 +199,10 @@ pub static DEFAULT_WEIGHTS: EvalWeights = EvalWeights {
     trapped_meeple_weight: 0.0,
     city_size_exponent: 1.0,
     dominance_bonus: 0.0,
+    wasted_meeple_multiplier: 1.5,
+    field_city_bonus: FIELD_POINTS_PER_CITY,
+    city_completion_bonus: 0.0,
+    city_bonus_min_tiles: 4,
 };
 
 pub static CONSERVATIVE_WEIGHTS: EvalWeights = EvalWeights {
@@ -137,12 +224,17 @@ pub static CONSERVATIVE_WEIGHTS: EvalWeights = EvalWeights {
     trapped_meeple_weight: 0.0,
     city_size_exponent: 1.0,
     dominance_bonus: 0.0,
+    wasted_meeple_multiplier: 1.5,
+    field_city_bonus: FIELD_POINTS_PER_CITY,
+    city_completion_bonus: 0.0,
+    city_bonus_min_tiles: 4,
 };
 
-/// Create an evaluation function parameterised by `weights` (static reference).
+/// Create an evaluation function that captures `weights` by value, so presets and
+/// runtime-loaded or tuned weights are handled alike.
 /// If score_scale is negative, uses the v2 evaluator (unified score space).
 pub fn make_carcassonne_eval(
-    weights: &'static EvalWeights,
+    weights: EvalWeights,
 ) -> Box<dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Send + Sync> {
     if weights.score_scale < 0.0 {
         let scale = weights.score_scale.abs();
@@ -151,26 +243,39 @@ pub fn make_carcassonne_eval(
         })
     } else {
         Box::new(move |state, phase, player_id, players| {
-            evaluate(state, phase, player_id, players, weights)
+            evaluate(state, phase, player_id, players, &weights)
         })
     }
 }
 
-/// Create an evaluation function that captures `weights` by value (for runtime-loaded weights).
-/// If score_scale is negative, uses the v2 evaluator (unified score space).
-pub fn make_carcassonne_eval_owned(
-    weights: EvalWeights,
-) -> Box<dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Send + Sync> {
-    if weights.score_scale < 0.0 {
-        let scale = weights.score_scale.abs();
-        Box::new(move |state, phase, player_id, players| {
-            evaluate_v2(state, phase, player_id, players, scale)
+/// Win probability per player from a neutral (spectator) perspective.
+/// Each player's one-sided eval is mapped to logit space and softmax-normalized,
+/// so the values form a distribution over `players` that sums to 1.
+#[allow(dead_code)]
+pub fn evaluate_neutral(state: &CarcassonneState, players: &[Player]) -> std::collections::HashMap<String, f64> {
+    // The evaluators ignore the phase; any placeholder will do
+    let phase = Phase {
+        name: String::new(),
+        concurrent_mode: None,
+        expected_actions: vec![],
+        auto_resolve: false,
+        metadata: serde_json::json!({}),
+    };
+    let logits: Vec<f64> = players
+        .iter()
+        .map(|p| {
+            let v = evaluate(state, &phase, &p.player_id, players, &DEFAULT_WEIGHTS).clamp(1e-6, 1.0 - 1e-6);
+            (v / (1.0 - v)).ln()
         })
-    } else {
-        Box::new(move |state, phase, player_id, players| {
-            evaluate(state, phase, player_id, players, &weights)
-        })
-    }
+        .collect();
+    let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = logits.iter().map(|l| (l - max).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    players
+        .iter()
+        .zip(exps)
+        .map(|(p, e)| (p.player_id.clone(), e / total))
+        .collect()
 }
 
 fn evaluate(
@@ -225,7 +330,7 @@ fn evaluate(
             tiles_remaining,
             state,
             &feat.tiles,
-            w.city_size_exponent,
+            w,
         );
 
         // Enhanced: near-completion bonus
@@ -238,7 +343,7 @@ fn evaluate(
         // Enhanced: trapped meeple detection
         if w.trapped_meeple_weight > 0.0 && my_count > 0 && game_progress > 0.4 {
             let cp = completion_probability(open_edges, tiles_remaining);
-            if cp < 0.15 {
+            if cp < TRAPPED_COMPLETION_PROBABILITY {
                 my_trapped_meeples += my_count as f64;
             }
         }
@@ -253,7 +358,7 @@ fn evaluate(
             }
         } else {
             opp_potential += potential;
-            wasted_meeple_penalty += my_count as f64 * 1.5;
+            wasted_meeple_penalty += my_count as f64 * w.wasted_meeple_multiplier;
         }
     }
 
@@ -273,7 +378,7 @@ fn evaluate(
     }
     let avg_opp_meeples = opp_meeple_sum as f64 / opp_count.max(1) as f64;
 
-    let mut meeple_value = (my_meeples as f64 / 7.0).min(1.0);
+    let mut meeple_value = (my_meeples as f64 / state.meeples_per_player.max(1) as f64).min(1.0);
 
     if my_meeples >= w.meeple_hoard_threshold && game_progress > w.meeple_hoard_progress_gate {
         meeple_value *= w.meeple_hoard_penalty;
@@ -288,11 +393,11 @@ fn evaluate(
     let meeple_component = 0.5 * relative + 0.5 * meeple_value;
 
     // 4. Field scoring potential
-    let my_field = estimate_field_value(state, player_id, tiles_remaining);
+    let my_field = estimate_field_value(state, player_id, tiles_remaining, w.field_city_bonus);
     let mut max_opp_field = 0.0_f64;
     for p in players {
         if p.player_id != player_id {
-            let f = estimate_field_value(state, &p.player_id, tiles_remaining);
+            let f = estimate_field_value(state, &p.player_id, tiles_remaining, w.field_city_bonus);
             if f > max_opp_field {
                 max_opp_field = f;
             }
@@ -312,7 +417,7 @@ fn evaluate(
         + meeple_weight * meeple_component
         + field_weight * field_component;
 
-    value.clamp(0.0, 1.0)
+    unit_value(value)
 }
 
 /// V2 evaluator: all signals combined in raw points, single final sigmoid.
@@ -394,7 +499,7 @@ fn evaluate_v2(
         }
 
         // Track stuck meeples on hopeless features
-        if my_count > 0 && cp < 0.15 && game_progress > 0.4 {
+        if my_count > 0 && cp < TRAPPED_COMPLETION_PROBABILITY && game_progress > 0.4 {
             my_stuck_meeples += my_count as i64;
         }
     }
@@ -425,11 +530,11 @@ fn evaluate_v2(
     let stuck_penalty = my_stuck_meeples as f64 * meeple_point_value * 0.5;
 
     // 4. Field scoring differential (raw points, already in points from estimate_field_value)
-    let my_field = estimate_field_value(state, player_id, tiles_remaining);
+    let my_field = estimate_field_value(state, player_id, tiles_remaining, FIELD_POINTS_PER_CITY);
     let mut max_opp_field = 0.0_f64;
     for p in players {
         if p.player_id != player_id {
-            let f = estimate_field_value(state, &p.player_id, tiles_remaining);
+            let f = estimate_field_value(state, &p.player_id, tiles_remaining, FIELD_POINTS_PER_CITY);
             if f > max_opp_field {
                 max_opp_field = f;
             }
@@ -450,13 +555,26 @@ fn evaluate_v2(
         - stuck_penalty * 0.1;
 
     // Single sigmoid over total point advantage
-    sigmoid(total_advantage, scale).clamp(0.0, 1.0)
+    unit_value(sigmoid(total_advantage, scale))
 }
 
+/// NaN inputs (e.g. inf - inf from degenerate weights) count as an even position.
 fn sigmoid(x: f64, scale: f64) -> f64 {
+    if x.is_nan() {
+        return 0.5;
+    }
     1.0 / (1.0 + (-x / scale.max(1e-9)).exp())
 }
 
+/// Clamp an eval to [0, 1]; NaN, which `clamp` would pass through, becomes 0.5.
+fn unit_value(value: f64) -> f64 {
+    if value.is_nan() {
+        0.5
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
 fn completion_probability(open_edges: usize, tiles_remaining: i64) -> f64 {
     if open_edges == 0 {
         return 1.0;
@@ -468,10 +586,137 @@ fn completion_probability(open_edges: usize, tiles_remaining: i64) -> f64 {
     (ratio * 0.5).min(1.0)
 }
 
+/// Completion estimate for one incomplete feature, as seen by the evaluator.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FeatureOutlook {
+    pub feature_id: String,
+    pub feature_type: FeatureType,
+    pub tiles: usize,
+    pub open_edges: usize,
+    /// The player's meeples on the feature.
+    pub meeples: i64,
+    /// True when the player has (or shares) the meeple majority and would score it.
+    pub leading: bool,
+    pub completion_probability: f64,
+    /// Points the feature is worth, weighting the completed and end-game values
+    /// by `completion_probability`.
+    pub expected_points: f64,
+}
+
+/// Outlook for every incomplete city, road and monastery `player_id` has a meeple
+/// on, using the same `completion_probability` estimate as the evaluator.
+/// Sorted by expected points, highest first.
+pub fn feature_outlook(state: &CarcassonneState, player_id: &str) -> Vec<FeatureOutlook> {
+    let tiles_remaining = state.tile_bag.len() as i64;
+    let mut outlook: Vec<FeatureOutlook> = state
+        .features
+        .values()
+        .filter(|f| !f.is_complete && f.feature_type != FeatureType::Field)
+        .filter_map(|feat| {
+            let (my_count, max_opp) = meeple_counts(&feat.meeples, player_id);
+            if my_count == 0 {
+                return None;
+            }
+            let (open_edges, cp, expected_points) = project_feature(state, feat, tiles_remaining)?;
+            Some(FeatureOutlook {
+                feature_id: feat.feature_id.clone(),
+                feature_type: feat.feature_type,
+                tiles: feat.tiles.len(),
+                open_edges,
+                meeples: my_count,
+                leading: my_count >= max_opp,
+                completion_probability: cp,
+                expected_points,
+            })
+        })
+        .collect();
+    outlook.sort_by(|a, b| {
+        b.expected_points
+            .total_cmp(&a.expected_points)
+            .then_with(|| a.feature_id.cmp(&b.feature_id))
+    });
+    outlook
+}
+
+/// Completion probability of the city, road or monastery under meeple spot
+/// `spot` of the tile at `position` ("x,y"); None for fields and unknown spots.
+pub(crate) fn spot_completion_probability(state: &CarcassonneState, position: &str, spot: &str) -> Option<f64> {
+    let feat = state.features.get(state.tile_feature_map.get(position)?.get(spot)?)?;
+    project_feature(state, feat, state.tile_bag.len() as i64).map(|(_, cp, _)| cp)
+}
+
+/// (open edges, completion probability, expected points) for a city, road or
+/// monastery. Completed features have probability 1 and score their final value.
+fn project_feature(state: &CarcassonneState, feat: &Feature, tiles_remaining: i64) -> Option<(usize, f64, f64)> {
+    let tiles = feat.tiles.len() as f64;
+    let pennants = feat.pennants as f64;
+    // (open edges, points if completed, points at game end if not)
+    let (open_edges, complete, incomplete) = match feat.feature_type {
+        FeatureType::City => (feat.open_edges.len(), tiles * 2.0 + pennants * 2.0, tiles + pennants),
+        FeatureType::Road => (feat.open_edges.len(), tiles, tiles),
+        FeatureType::Monastery => {
+            let pos = Position::from_key(feat.tiles.first()?);
+            let neighbors = pos
+                .all_surrounding()
+                .iter()
+                .filter(|p| state.board.tiles.contains_key(&(p.x, p.y)))
+                .count();
+            (8 - neighbors, 9.0, 1.0 + neighbors as f64)
+        }
+        FeatureType::Field => return None,
+    };
+    if feat.is_complete {
+        return Some((0, 1.0, complete));
+    }
+    let cp = completion_probability(open_edges, tiles_remaining);
+    Some((open_edges, cp, cp * complete + (1.0 - cp) * incomplete))
+}
+
+/// Describe the feature under meeple spot `spot` of the tile at `position`
+/// ("x,y"): owner of the meeple standing there (if any), meeple majority,
+/// completion status and projected points. Fields report their end-game value
+/// so far. `None` if the tile or spot does not exist.
+pub fn describe_meeple(state: &CarcassonneState, position: &str, spot: &str) -> Option<serde_json::Value> {
+    let feature_id = state.tile_feature_map.get(position)?.get(spot)?;
+    let feat = state.features.get(feature_id)?;
+
+    let mut counts: std::collections::BTreeMap<&str, i64> = std::collections::BTreeMap::new();
+    for m in &feat.meeples {
+        *counts.entry(m.player_id.as_str()).or_insert(0) += m.count as i64;
+    }
+    let max_count = counts.values().copied().max().unwrap_or(0);
+    let leaders: Vec<&str> = counts.iter().filter(|(_, &c)| c == max_count).map(|(pid, _)| *pid).collect();
+    let owner = feat
+        .meeples
+        .iter()
+        .find(|m| m.position == position && m.spot == spot)
+        .map(|m| m.player_id.as_str());
+
+    let (completion_probability, projected_points) =
+        match project_feature(state, feat, state.tile_bag.len() as i64) {
+            Some((_, cp, points)) => (Some(cp), points),
+            None => (None, get_adjacent_completed_cities(state, feat, feature_id).len() as f64 * 3.0),
+        };
+
+    Some(serde_json::json!({
+        "position": position,
+        "spot": spot,
+        "feature_id": feature_id,
+        "feature_type": feat.feature_type,
+        "tiles": feat.tiles.len(),
+        "is_complete": feat.is_complete,
+        "meeple": owner,
+        "meeples": counts,
+        "leaders": leaders,
+        "completion_probability": completion_probability,
+        "projected_points": projected_points,
+    }))
+}
+
 fn meeple_counts(meeples: &[PlacedMeeple], player_id: &str) -> (i64, i64) {
     let mut counts: std::collections::HashMap<&str, i64> = std::collections::HashMap::new();
     for m in meeples {
-        *counts.entry(m.player_id.as_str()).or_insert(0) += 1;
+        *counts.entry(m.player_id.as_str()).or_insert(0) += m.count as i64;
     }
     let my_count = counts.remove(player_id).unwrap_or(0);
     let max_opp = counts.values().copied().max().unwrap_or(0);
@@ -486,14 +731,20 @@ fn raw_feature_potential(
     tiles_remaining: i64,
     state: &CarcassonneState,
     tiles: &[String],
-    city_size_exponent: f64,
+    w: &EvalWeights,
 ) -> f64 {
     match feature_type {
         FeatureType::City => {
             let cp = completion_probability(open_edge_count, tiles_remaining);
-            let size = (tile_count as f64).powf(city_size_exponent);
-            cp * (size * 2.0 + pennants as f64 * 2.0)
-                + (1.0 - cp) * (size + pennants as f64)
+            // 0^negative is inf; an empty city has no size to reward
+            let size = if tile_count == 0 { 0.0 } else { (tile_count as f64).powf(w.city_size_exponent) };
+            let mut potential = cp * (size * 2.0 + pennants as f64 * 2.0)
+                + (1.0 - cp) * (size + pennants as f64);
+            let beyond = tile_count as i64 - w.city_bonus_min_tiles;
+            if w.city_completion_bonus > 0.0 && beyond >= 0 {
+                potential += w.city_completion_bonus * cp * ((beyond + 1) as f64).sqrt();
+            }
+            potential
         }
         FeatureType::Road => tile_count as f64,
         FeatureType::Monastery => {
@@ -513,7 +764,12 @@ fn raw_feature_potential(
     }
 }
 
-fn estimate_field_value(state: &CarcassonneState, player_id: &str, tiles_remaining: i64) -> f64 {
+fn estimate_field_value(
+    state: &CarcassonneState,
+    player_id: &str,
+    tiles_remaining: i64,
+    city_bonus: f64,
+) -> f64 {
     let mut total = 0.0_f64;
 
     for (fid, feat) in &state.features {
@@ -531,7 +787,7 @@ fn estimate_field_value(state: &CarcassonneState, player_id: &str, tiles_remaini
 
         // 1. Count properly adjacent completed cities (using tile definitions)
         let adj_cities = get_adjacent_completed_cities(state, feat, fid);
-        total += adj_cities.len() as f64 * 3.0;
+        total += adj_cities.len() as f64 * city_bonus;
 
         // 2. Estimate value from nearby incomplete cities
         let completed_set: std::collections::HashSet<&str> =
@@ -557,7 +813,7 @@ fn estimate_field_value(state: &CarcassonneState, player_id: &str, tiles_remaini
                         seen_cities.insert(city_fid.as_str());
                         let prob = completion_probability(city_feat.open_edges.len(), tiles_remaining);
                         if prob > 0.3 {
-                            total += prob * 3.0;
+                            total += prob * city_bonus;
                         }
                     }
                 }
@@ -572,6 +828,20 @@ fn estimate_field_value(state: &CarcassonneState, player_id: &str, tiles_remaini
 mod tests {
     use super::*;
     use crate::engine::models::Player;
+    use crate::engine::plugin::TypedGamePlugin;
+    use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+    fn make_players(n: u32) -> Vec<Player> {
+        (0..n)
+            .map(|i| Player {
+                player_id: format!("p{}", i + 1),
+                display_name: format!("P{}", i + 1),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
 
     #[test]
     fn test_eval_diagnostic() {
@@ -587,10 +857,7 @@ mod tests {
         let game_data: serde_json::Value = serde_json::from_str(&json_str).unwrap();
         let state: CarcassonneState = serde_json::from_value(game_data).unwrap();
         let player_id = "p1";
-        let players = vec![
-            Player { player_id: "p1".into(), display_name: "P1".into(), seat_index: 0, is_bot: false, bot_id: None },
-            Player { player_id: "p2".into(), display_name: "P2".into(), seat_index: 1, is_bot: false, bot_id: None },
-        ];
+        let players = make_players(2);
         let w = &DEFAULT_WEIGHTS;
         let phase = Phase {
             name: "place_tile".into(),
@@ -628,8 +895,7 @@ mod tests {
             if feat.meeples.is_empty() { continue; }
             let potential = raw_feature_potential(
                 feat.feature_type, feat.tiles.len(), feat.open_edges.len(),
-                feat.pennants as i64, tiles_remaining, &state, &feat.tiles,
-                w.city_size_exponent,
+                feat.pennants as i64, tiles_remaining, &state, &feat.tiles, w,
             );
             let (my_count, max_count) = meeple_counts(&feat.meeples, player_id);
             if my_count == 0 {
@@ -638,7 +904,7 @@ mod tests {
                 my_potential += potential;
             } else {
                 opp_potential += potential;
-                wasted += my_count as f64 * 1.5;
+                wasted += my_count as f64 * w.wasted_meeple_multiplier;
             }
         }
         let potential_diff = my_potential - opp_potential - wasted;
@@ -655,7 +921,7 @@ mod tests {
             }
         }
         let avg_opp_meeples = opp_meeple_sum as f64 / opp_count.max(1) as f64;
-        let mut meeple_value = (my_meeples as f64 / 7.0).min(1.0);
+        let mut meeple_value = (my_meeples as f64 / state.meeples_per_player.max(1) as f64).min(1.0);
         if my_meeples >= w.meeple_hoard_threshold && game_progress > w.meeple_hoard_progress_gate {
             meeple_value *= w.meeple_hoard_penalty;
         }
@@ -668,11 +934,11 @@ mod tests {
         let meeple_component = 0.5 * relative + 0.5 * meeple_value;
 
         // 4. Field
-        let my_field = estimate_field_value(&state, player_id, tiles_remaining);
+        let my_field = estimate_field_value(&state, player_id, tiles_remaining, w.field_city_bonus);
         let mut max_opp_field = 0.0_f64;
         for p in &players {
             if p.player_id != player_id {
-                let f = estimate_field_value(&state, &p.player_id, tiles_remaining);
+                let f = estimate_field_value(&state, &p.player_id, tiles_remaining, w.field_city_bonus);
                 if f > max_opp_field { max_opp_field = f; }
             }
         }
@@ -723,4 +989,250 @@ mod tests {
         eprintln!("  FINAL VALUE (manual): {:.6}", value);
         eprintln!("  FINAL VALUE (evaluate fn): {:.6}", eval_result);
     }
+
+    #[test]
+    fn test_wasted_meeple_multiplier_monotonic() {
+        use crate::games::carcassonne::types::Feature;
+
+        let players = make_players(2);
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(7) };
+        let (mut state, phase, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+
+        // p1 is outnumbered on an open road, so its meeple counts as wasted.
+        let meeple = |pid: &str| PlacedMeeple {
+            player_id: pid.into(),
+            position: "0,0".into(),
+            spot: "road".into(),
+            count: 1,
+        };
+        state.features.insert(
+            "f_test".into(),
+            Feature {
+                feature_id: "f_test".into(),
+                feature_type: FeatureType::Road,
+                tiles: vec!["0,0".into()],
+                meeples: vec![meeple("p1"), meeple("p2"), meeple("p2")],
+                is_complete: false,
+                pennants: 0,
+                open_edges: vec![["0,0".into(), "E".into()], ["0,0".into(), "W".into()]],
+                merged_from: vec![],
+                has_inn: false,
+                has_cathedral: false,
+            },
+        );
+
+        let values: Vec<f64> = [0.0, 1.5, 3.0, 6.0]
+            .iter()
+            .map(|&m| {
+                let w = EvalWeights { wasted_meeple_multiplier: m, ..DEFAULT_WEIGHTS };
+                evaluate(&state, &phase, "p1", &players, &w)
+            })
+            .collect();
+        for pair in values.windows(2) {
+            assert!(pair[1] < pair[0], "eval should decrease as penalty grows: {:?}", values);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_state_eval_is_finite() {
+        use crate::games::carcassonne::types::Feature;
+
+        // One player, no tiles in the bag or on the board, nobody's meeples in supply
+        let players = make_players(1);
+        let config = GameConfig { options: serde_json::json!({"tile_count": 0}), random_seed: Some(7) };
+        let (mut state, phase, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+        assert!(state.tile_bag.is_empty());
+        state.board.tiles.clear();
+        state.meeple_supply.clear();
+        // A claimed city with no tiles: 0^negative exponent used to give inf * 0 = NaN
+        state.features.insert(
+            "f_empty".into(),
+            Feature {
+                feature_id: "f_empty".into(),
+                feature_type: FeatureType::City,
+                tiles: vec![],
+                meeples: vec![PlacedMeeple { player_id: "p1".into(), position: "0,0".into(), spot: "city".into(), count: 1 }],
+                is_complete: false,
+                pennants: 0,
+                open_edges: vec![["0,0".into(), "N".into()]],
+                merged_from: vec![],
+                has_inn: false,
+                has_cathedral: false,
+            },
+        );
+
+        let weights = [
+            DEFAULT_WEIGHTS,
+            EvalWeights { city_size_exponent: -1.0, ..DEFAULT_WEIGHTS },
+            EvalWeights { potential_base: f64::INFINITY, ..DEFAULT_WEIGHTS },
+            EvalWeights { score_scale: -20.0, ..DEFAULT_WEIGHTS },
+        ];
+        for w in weights {
+            for pid in ["p1", "absent"] {
+                let value = make_carcassonne_eval(w)(&state, &phase, pid, &players);
+                assert!(value.is_finite() && (0.0..=1.0).contains(&value), "{:?} gave {}", w, value);
+            }
+        }
+        assert_eq!(unit_value(f64::NAN), 0.5);
+        assert_eq!(sigmoid(f64::INFINITY - f64::INFINITY, 10.0), 0.5);
+    }
+
+    #[test]
+    fn test_neutral_eval_favors_clear_leader() {
+
+        let players = make_players(3);
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(11) };
+        let (mut state, _, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+
+        let even = evaluate_neutral(&state, &players);
+        assert_eq!(even.len(), 3);
+        assert!((even.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        state.scores.insert("p2".into(), 60);
+        state.scores.insert("p1".into(), 5);
+        state.scores.insert("p3".into(), 8);
+        let probs = evaluate_neutral(&state, &players);
+        assert!((probs.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(probs["p2"] > probs["p1"] && probs["p2"] > probs["p3"], "{:?}", probs);
+        assert!(probs["p2"] > 0.5, "{:?}", probs);
+    }
+
+    #[test]
+    fn test_owned_eval_matches_static_weights() {
+
+        let players = make_players(2);
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(3) };
+        let (mut state, mut phase, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+
+        let presets = [&AGGRESSIVE_WEIGHTS, &FIELD_HEAVY_WEIGHTS, &DEFAULT_WEIGHTS, &CONSERVATIVE_WEIGHTS];
+        let v2 = EvalWeights { score_scale: -30.0, ..DEFAULT_WEIGHTS };
+        for _ in 0..40 {
+            for w in presets {
+                let eval_fn = make_carcassonne_eval(*w);
+                let expected = evaluate(&state, &phase, "p1", &players, w);
+                assert_eq!(eval_fn(&state, &phase, "p1", &players).to_bits(), expected.to_bits());
+            }
+            let expected = evaluate_v2(&state, &phase, "p1", &players, 30.0);
+            let got = make_carcassonne_eval(v2)(&state, &phase, "p1", &players);
+            assert_eq!(got.to_bits(), expected.to_bits());
+
+            let pid = players[phase.metadata["player_index"].as_u64().unwrap_or(0) as usize]
+                .player_id
+                .clone();
+            let payload = CarcassonnePlugin
+                .get_valid_actions(&state, &phase, &pid)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| serde_json::json!({}));
+            let action = Action { action_type: phase.name.clone(), player_id: pid, payload };
+            let r = CarcassonnePlugin.apply_action(&state, &phase, &action, &players).unwrap();
+            state = r.state;
+            phase = r.next_phase;
+        }
+    }
+
+    #[test]
+    fn test_city_completion_bonus_favors_big_near_complete_city() {
+
+        let players = make_players(2);
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(7) };
+        let (state, _, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+        let city = |tiles: usize, open_edges: usize, w: &EvalWeights| {
+            raw_feature_potential(FeatureType::City, tiles, open_edges, 0, 6, &state, &[], w)
+        };
+
+        // Defaults leave the potential unchanged
+        let off = EvalWeights { city_completion_bonus: 0.0, city_bonus_min_tiles: 1, ..DEFAULT_WEIGHTS };
+        assert_eq!(city(5, 1, &DEFAULT_WEIGHTS), city(5, 1, &off));
+        assert_eq!(city(5, 1, &DEFAULT_WEIGHTS), 10.0);
+
+        let w = EvalWeights { city_completion_bonus: 2.0, city_bonus_min_tiles: 4, ..DEFAULT_WEIGHTS };
+        let big = city(5, 1, &w);
+        let small = city(2, 2, &w);
+        assert!(big > small);
+        assert!(big - small > city(5, 1, &DEFAULT_WEIGHTS) - city(2, 2, &DEFAULT_WEIGHTS));
+        // Below the threshold nothing is added
+        assert_eq!(small, city(2, 2, &DEFAULT_WEIGHTS));
+        // Diminishing returns: each extra tile adds less bonus than the last
+        let bonus = |tiles| city(tiles, 1, &w) - city(tiles, 1, &DEFAULT_WEIGHTS);
+        assert!(bonus(5) - bonus(4) > bonus(6) - bonus(5));
+    }
+
+    #[test]
+    fn test_weight_overrides() {
+        let overrides = [
+            ("field_scale".to_string(), 9.0),
+            ("meeple_hoard_threshold".to_string(), 4.0),
+        ]
+        .into_iter()
+        .collect();
+        let w = AGGRESSIVE_WEIGHTS.with_overrides(&overrides).unwrap();
+        assert_eq!(w.field_scale, 9.0);
+        assert_eq!(w.meeple_hoard_threshold, 4);
+        assert_eq!(w.score_base, AGGRESSIVE_WEIGHTS.score_base);
+
+        let unknown = [("no_such_weight".to_string(), 1.0)].into_iter().collect();
+        assert!(DEFAULT_WEIGHTS.with_overrides(&unknown).unwrap_err().contains("no_such_weight"));
+        let fractional = [("meeple_hoard_threshold".to_string(), 2.5)].into_iter().collect();
+        assert!(DEFAULT_WEIGHTS.with_overrides(&fractional).is_err());
+    }
+
+    #[test]
+    fn test_feature_outlook_matches_completion_probability() {
+        use crate::games::carcassonne::types::Feature;
+
+        let players = make_players(2);
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(7) };
+        let (mut state, _, _) = CarcassonnePlugin.create_initial_state(&players, &config);
+        state.tile_bag.truncate(12);
+
+        let meeple = |pid: &str, pos: &str| PlacedMeeple {
+            player_id: pid.into(),
+            position: pos.into(),
+            spot: "x".into(),
+            count: 1,
+        };
+        let edges = |n: usize| -> Vec<[String; 2]> { (0..n).map(|i| [format!("{},9", i), "N".into()]).collect() };
+        let mut add = |id: &str, feature_type, tiles: &[&str], meeples, pennants, open_edges| {
+            state.features.insert(
+                id.into(),
+                Feature {
+                    feature_id: id.into(),
+                    feature_type,
+                    tiles: tiles.iter().map(|t| t.to_string()).collect(),
+                    meeples,
+                    is_complete: false,
+                    pennants,
+                    open_edges,
+                    merged_from: vec![],
+                    has_inn: false,
+                    has_cathedral: false,
+                },
+            );
+        };
+        add("f_city", FeatureType::City, &["9,0", "9,1", "9,2"], vec![meeple("p1", "9,0")], 1, edges(4));
+        add("f_road", FeatureType::Road, &["8,0", "8,1"], vec![meeple("p1", "8,0"), meeple("p2", "8,1"), meeple("p2", "8,1")], 0, edges(2));
+        add("f_monastery", FeatureType::Monastery, &["5,5"], vec![meeple("p1", "5,5")], 0, vec![]);
+        add("f_theirs", FeatureType::City, &["7,0"], vec![meeple("p2", "7,0")], 0, edges(1));
+
+        let outlook = feature_outlook(&state, "p1");
+        let ids: Vec<&str> = outlook.iter().map(|o| o.feature_id.as_str()).collect();
+        assert_eq!(ids, ["f_city", "f_monastery", "f_road"]);
+
+        let city = &outlook[0];
+        assert_eq!(city.completion_probability, completion_probability(4, 12));
+        assert_eq!(city.completion_probability, 0.5);
+        assert_eq!(city.expected_points, 0.5 * 8.0 + 0.5 * 4.0);
+        assert!(city.leading);
+
+        let monastery = &outlook[1];
+        assert_eq!(monastery.open_edges, 8);
+        assert_eq!(monastery.completion_probability, completion_probability(8, 12));
+        assert_eq!(monastery.expected_points, 0.25 * 9.0 + 0.75 * 1.0);
+
+        let road = &outlook[2];
+        assert_eq!(road.completion_probability, completion_probability(2, 12));
+        assert_eq!(road.expected_points, 2.0);
+        assert!(!road.leading);
+    }
 }
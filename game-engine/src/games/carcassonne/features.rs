@@ -65,6 +65,8 @@ pub fn initialize_features_from_tile(
             pennants,
             open_edges,
             merged_from: vec![],
+            has_inn: tile_feat.has_inn,
+            has_cathedral: tile_feat.has_cathedral,
         };
         features.insert(feature_id.clone(), feature);
 
@@ -116,6 +118,8 @@ pub fn create_and_merge_features(
             pennants,
             open_edges,
             merged_from: vec![],
+            has_inn: tile_feat.has_inn,
+            has_cathedral: tile_feat.has_cathedral,
         };
 
         state.features.insert(feature_id.clone(), feature);
@@ -170,7 +174,7 @@ pub fn create_and_merge_features(
             continue;
         }
 
-        // Merge: absorb adj into ours
+        // Merge: the earlier of the two ids survives
         let merged_id = merge_features(state, &our_feature_id, &adj_feature_id);
 
         remove_open_edge(state, &merged_id, position_key, &edge_key);
@@ -200,7 +204,7 @@ pub fn create_and_merge_features(
 
 /// Resolve a feature ID that may have been merged into another.
 /// Uses the redirect table for O(1) lookup instead of O(n*m) linear scan.
-fn resolve_feature_id(state: &CarcassonneState, feature_id: &str) -> String {
+pub(super) fn resolve_feature_id(state: &CarcassonneState, feature_id: &str) -> String {
     if state.features.contains_key(feature_id) {
         return feature_id.to_string();
     }
@@ -231,12 +235,27 @@ fn find_feature_at_edge(
     None
 }
 
-/// Merge feature_b into feature_a. Returns the surviving feature ID.
+/// Creation order of a feature ID: sequential "f<n>" IDs by counter, anything
+/// else (e.g. IDs from older saved states) after them, by name.
+fn feature_order(feature_id: &str) -> (u64, &str) {
+    let seq = feature_id.strip_prefix('f').and_then(|n| n.parse().ok());
+    (seq.unwrap_or(u64::MAX), feature_id)
+}
+
+/// Merge two features, union-find style: the earlier ID (see `feature_order`)
+/// is the representative and keeps its ID, the later one is absorbed and
+/// redirected. Returns the surviving feature ID.
 fn merge_features(
     state: &mut CarcassonneState,
-    feature_a_id: &str,
-    feature_b_id: &str,
+    first_id: &str,
+    second_id: &str,
 ) -> String {
+    let (feature_a_id, feature_b_id) = if feature_order(second_id) < feature_order(first_id) {
+        (second_id, first_id)
+    } else {
+        (first_id, second_id)
+    };
+
     // Read feature_b data before removing it
     let b = match state.features.remove(feature_b_id) {
         Some(f) => f,
@@ -261,8 +280,11 @@ fn merge_features(
         // Combine meeples
         a.meeples.extend(b.meeples);
 
-        // Combine pennants
+        // Combine pennants. A pennant tile has a single city, so none of b's
+        // pennant tiles can already be in a: summing never double-counts.
         a.pennants += b.pennants;
+        a.has_inn |= b.has_inn;
+        a.has_cathedral |= b.has_cathedral;
 
         // Combine open edges
         a.open_edges.extend(b.open_edges);
@@ -272,9 +294,12 @@ fn merge_features(
         a.merged_from.extend(b.merged_from);
     }
 
-    // Update tile_feature_map: all references to feature_b → feature_a
-    for (_pos, spots) in state.tile_feature_map.iter_mut() {
-        for (_spot, fid) in spots.iter_mut() {
+    // Update tile_feature_map: references to feature_b → feature_a, only on b's tiles
+    for pos in &b.tiles {
+        let Some(spots) = state.tile_feature_map.get_mut(pos) else {
+            continue;
+        };
+        for fid in spots.values_mut() {
             if fid == feature_b_id {
                 *fid = feature_a_id.to_string();
             }
@@ -372,7 +397,7 @@ pub fn check_monastery_completion(
 
             let point_awards = {
                 let feature = &state.features[&feature_id];
-                score_completed_feature(feature)
+                score_completed_feature(feature, state.inns_cathedrals)
             };
 
             let tiles_clone = state.features[&feature_id].tiles.clone();
@@ -0,0 +1,59 @@
+//! Inns & Cathedrals expansion: road tiles with inns and the cathedral city.
+//!
+//! An inn makes a completed road worth 2 per tile and an incomplete one worth
+//! nothing at game end; a cathedral does the same for cities at 3 per tile
+//! and pennant. The scoring lives in `scoring.rs`, gated on
+//! `CarcassonneState::inns_cathedrals`.
+
+use once_cell::sync::Lazy;
+
+use super::tiles::{edges, feat, feat_with_adj};
+use super::types::*;
+
+use EdgeType::{City as C, Field as F, Road as R};
+use FeatureType::{City, Field, Road};
+
+/// Value in the `expansions` option that enables these tiles and scoring rules.
+pub const INNS_CATHEDRALS_EXPANSION: &str = "inns_cathedrals";
+
+fn with_inn(feature: TileFeature) -> TileFeature {
+    TileFeature { has_inn: true, ..feature }
+}
+
+pub static INNS_CATHEDRALS_CATALOG: Lazy<Vec<TileDefinition>> = Lazy::new(|| {
+    let tile = |id: &str, edges: [EdgeType; 4], features: Vec<TileFeature>, count: u32| {
+        TileDefinition {
+            tile_type_id: id.into(),
+            edges,
+            features,
+            count,
+            image_id: format!("tile_{id}"),
+            internal_connections: vec![],
+        }
+    };
+    vec![
+        // IC1: Cathedral, city on all sides (x2)
+        tile("IC1", edges(C, C, C, C), vec![
+            TileFeature { has_cathedral: true, ..feat(City, &["N", "E", "S", "W"], &["city_NESW"]) },
+        ], 2),
+        // IC2: Road E-W with inn (x2)
+        tile("IC2", edges(F, R, F, R), vec![
+            with_inn(feat(Road, &["E", "W"], &["road_EW"])),
+            feat_with_adj(Field, &["N", "E:N", "W:N"], &["field_N"], &[]),
+            feat_with_adj(Field, &["S", "E:S", "W:S"], &["field_S"], &[]),
+        ], 2),
+        // IC3: Road curve S-W with inn (x2)
+        tile("IC3", edges(F, F, R, R), vec![
+            with_inn(feat(Road, &["S", "W"], &["road_SW"])),
+            feat_with_adj(Field, &["S:W", "W:S"], &["field_SW"], &[]),
+            feat_with_adj(Field, &["N", "E", "S:E", "W:N"], &["field_NE"], &[]),
+        ], 2),
+        // IC4: City N, road E-W with inn (x2)
+        tile("IC4", edges(C, R, F, R), vec![
+            feat(City, &["N"], &["city_N"]),
+            with_inn(feat(Road, &["E", "W"], &["road_EW"])),
+            feat_with_adj(Field, &["E:N", "W:N"], &["field_N"], &["city_N"]),
+            feat_with_adj(Field, &["S", "E:S", "W:S"], &["field_S"], &["city_N"]),
+        ], 2),
+    ]
+});
@@ -4,6 +4,11 @@
 use crate::engine::models::Event;
 use super::types::{CarcassonneState, PlacedMeeple};
 
+/// Meeples each player starts with, unless the `meeples_per_player` option says otherwise.
+pub const MEEPLES_PER_PLAYER: i32 = 7;
+/// Accepted range of the `meeples_per_player` option.
+pub const MEEPLES_PER_PLAYER_RANGE: std::ops::RangeInclusive<i32> = 1..=20;
+
 /// Check if a meeple can be placed on this spot.
 ///
 /// Rules:
@@ -16,10 +21,21 @@ pub fn can_place_meeple(
     meeple_spot: &str,
 ) -> bool {
     let supply = state.meeple_supply.get(player_id).copied().unwrap_or(0);
-    if supply <= 0 {
-        return false;
-    }
+    supply > 0 && spot_is_unclaimed(state, position_key, meeple_spot)
+}
+
+/// Same as `can_place_meeple`, but for the player's big meeple.
+pub fn can_place_big_meeple(
+    state: &CarcassonneState,
+    player_id: &str,
+    position_key: &str,
+    meeple_spot: &str,
+) -> bool {
+    let supply = state.big_meeple_supply.get(player_id).copied().unwrap_or(0);
+    supply > 0 && spot_is_unclaimed(state, position_key, meeple_spot)
+}
 
+fn spot_is_unclaimed(state: &CarcassonneState, position_key: &str, meeple_spot: &str) -> bool {
     let feature_id = match state
         .tile_feature_map
         .get(position_key)
@@ -51,18 +67,28 @@ pub fn return_meeples(
     };
 
     for meeple in &meeples {
-        // Increment meeple supply
-        if let Some(supply) = state.meeple_supply.get_mut(&meeple.player_id) {
+        state.zobrist ^= super::zobrist::meeple_key(meeple);
+        // Increment meeple supply; the big meeple goes back to its own slot
+        let supply = if meeple.is_big() {
+            &mut state.big_meeple_supply
+        } else {
+            &mut state.meeple_supply
+        };
+        if let Some(supply) = supply.get_mut(&meeple.player_id) {
             *supply += 1;
         }
 
+        let mut payload = serde_json::json!({
+            "position": meeple.position,
+            "spot": meeple.spot,
+        });
+        if meeple.is_big() {
+            payload["big"] = serde_json::json!(true);
+        }
         events.push(Event {
             event_type: "meeple_returned".to_string(),
             player_id: Some(meeple.player_id.clone()),
-            payload: serde_json::json!({
-                "position": meeple.position,
-                "spot": meeple.spot,
-            }),
+            payload,
         });
     }
 
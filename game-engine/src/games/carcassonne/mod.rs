@@ -1,8 +1,12 @@
 pub mod types;
 pub mod tiles;
+pub mod river;
+pub mod inns_cathedrals;
 pub mod board;
 pub mod features;
 pub mod scoring;
 pub mod meeples;
 pub mod plugin;
 pub mod evaluator;
+pub mod zobrist;
+pub mod notation;
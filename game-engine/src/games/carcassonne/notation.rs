@@ -0,0 +1,492 @@
+//! One-line position notation for Carcassonne, for logs and sharing positions.
+//!
+//! Six space-separated fields, then optional flags:
+//!
+//! ```text
+//! D:0,0:0;V:5,6:270 p1:5,6:road_E p1:0:6;p2:0:7 1 E A,B,B last=5,6 unscored
+//! ```
+//!
+//! 1. tiles `type:x,y:rotation`, `;`-separated, in placement order (see below)
+//! 2. meeples `player:x,y:spot`, with `:big` for the big meeple (`-` for none)
+//! 3. players `id:score:supply`, plus `:big_supply` with Inns & Cathedrals
+//! 4. index of the current player
+//! 5. drawn tile (`-` for none)
+//! 6. tile bag, top first, `,`-separated (`-` when empty)
+//!
+//! Flags: `last=x,y`, `river=x,y:heading[:left|right]`, `meeples=n` (per player,
+//! when not 7), `ic` (Inns & Cathedrals), `unplaceable=bottom|end_game`, `groups`
+//! (AI view placement groups), `history` (score history tracked, with
+//! `=p1:3,p2:0;...` once it has entries), `breakdown=p1:fields:roads:cities:monasteries;...`
+//! (end-game breakdown), `forfeit=a,b`, and `unscored` when the last tile
+//! completed features that score_check has not scored yet.
+//!
+//! Features are rebuilt by placing the tiles in the listed order, each followed
+//! by the meeples on it. The state doesn't record the order the tiles went down
+//! in, so `to_notation` replays the board to find one that rebuilds the features
+//! exactly: their ids, merges and the order of tiles, edges and meeples in each.
+//! When none does (features edited by hand, or ids from before they were
+//! sequential) tiles are listed by position and only the features' ids and
+//! ordering differ from the original. Player ids must not contain spaces, `:`,
+//! `;`, `,` or `=`.
+
+use std::collections::HashMap;
+
+use super::features::{create_and_merge_features, is_feature_complete, resolve_feature_id};
+use super::meeples::MEEPLES_PER_PLAYER;
+use super::plugin::river_open_positions;
+use super::river::{RiverState, RiverTurn};
+use super::tiles::get_rotated_features;
+use super::types::*;
+use super::zobrist::board_hash;
+
+impl CarcassonneState {
+    pub fn to_notation(&self) -> String {
+        let order = self.placement_order().unwrap_or_else(|| {
+            let mut positions: Vec<_> = self.board.tiles.keys().copied().collect();
+            positions.sort();
+            positions
+        });
+        let tiles: Vec<String> = order
+            .iter()
+            .map(|&(x, y)| {
+                let t = self.board.tiles[&(x, y)];
+                format!("{}:{x},{y}:{}", tile_index_to_type(t.tile_type_id), t.rotation)
+            })
+            .collect();
+
+        let meeples: Vec<String> = self.sorted_meeples().iter().map(meeple_notation).collect();
+
+        let mut player_ids: Vec<&String> = self.meeple_supply.keys().chain(self.scores.keys()).collect();
+        player_ids.sort();
+        player_ids.dedup();
+        let players: Vec<String> = player_ids
+            .iter()
+            .map(|&pid| {
+                let score = self.scores.get(pid).copied().unwrap_or(0);
+                let supply = self.meeple_supply.get(pid).copied().unwrap_or(0);
+                match self.big_meeple_supply.get(pid) {
+                    Some(big) => format!("{pid}:{score}:{supply}:{big}"),
+                    None => format!("{pid}:{score}:{supply}"),
+                }
+            })
+            .collect();
+
+        let bag: Vec<&str> = self.tile_bag.iter().map(|&t| tile_index_to_type(t)).collect();
+        let mut fields = vec![
+            tiles.join(";"),
+            or_dash(meeples.join(";")),
+            players.join(";"),
+            self.current_player_index.to_string(),
+            self.current_tile.map_or("-".into(), |t| tile_index_to_type(t).to_string()),
+            or_dash(bag.join(",")),
+        ];
+
+        if let Some(last) = &self.last_placed_position {
+            fields.push(format!("last={last}"));
+        }
+        if let Some(river) = &self.river {
+            let turn = match river.last_turn {
+                Some(RiverTurn::Left) => ":left",
+                Some(RiverTurn::Right) => ":right",
+                None => "",
+            };
+            fields.push(format!("river={}:{}{turn}", river.open_end, river.heading));
+        }
+        if self.meeples_per_player != MEEPLES_PER_PLAYER {
+            fields.push(format!("meeples={}", self.meeples_per_player));
+        }
+        if self.inns_cathedrals {
+            fields.push("ic".into());
+        }
+        match self.unplaceable_policy {
+            UnplaceablePolicy::Discard => {}
+            UnplaceablePolicy::Bottom => fields.push("unplaceable=bottom".into()),
+            UnplaceablePolicy::EndGame => fields.push("unplaceable=end_game".into()),
+        }
+        if self.ai_view_placement_groups {
+            fields.push("groups".into());
+        }
+        match self.score_history.as_deref() {
+            None => {}
+            Some([]) => fields.push("history".into()),
+            Some(history) => {
+                let entries: Vec<String> = history.iter().map(|scores| score_list(scores, ",")).collect();
+                fields.push(format!("history={}", entries.join(";")));
+            }
+        }
+        if let Some(breakdown) = self.end_game_breakdown.as_ref().and_then(breakdown_notation) {
+            fields.push(format!("breakdown={breakdown}"));
+        }
+        if !self.forfeited_players.is_empty() {
+            fields.push(format!("forfeit={}", self.forfeited_players.join(",")));
+        }
+        if self.features.values().any(|f| !f.is_complete && is_feature_complete(self, f)) {
+            fields.push("unscored".into());
+        }
+        fields.join(" ")
+    }
+
+    pub fn from_notation(notation: &str) -> Result<CarcassonneState, String> {
+        let mut fields = notation.split_whitespace();
+        let mut field = |name: &str| fields.next().ok_or_else(|| format!("missing {name} field"));
+        let (tiles, meeples, players, turn, current, bag) = (
+            field("tiles")?,
+            field("meeples")?,
+            field("players")?,
+            field("turn")?,
+            field("tile")?,
+            field("bag")?,
+        );
+        let flags: Vec<&str> = fields.collect();
+
+        let mut state = CarcassonneState {
+            board: Board { tiles: HashMap::new(), open_positions: vec![] },
+            tile_bag: list(bag, ',').map(parse_tile_type).collect::<Result<_, _>>()?,
+            current_tile: match current {
+                "-" => None,
+                t => Some(parse_tile_type(t)?),
+            },
+            last_placed_position: None,
+            features: HashMap::new(),
+            tile_feature_map: HashMap::new(),
+            meeple_supply: HashMap::new(),
+            meeples_per_player: MEEPLES_PER_PLAYER,
+            scores: HashMap::new(),
+            current_player_index: turn.parse().map_err(|_| format!("bad turn '{turn}'"))?,
+            rng_state: serde_json::Value::Null,
+            forfeited_players: vec![],
+            end_game_breakdown: None,
+            next_feature_id: 0,
+            feature_redirects: HashMap::new(),
+            score_history: None,
+            unplaceable_policy: UnplaceablePolicy::Discard,
+            ai_view_placement_groups: false,
+            big_meeple_supply: HashMap::new(),
+            inns_cathedrals: false,
+            river: None,
+            zobrist: 0,
+        };
+
+        for player in players.split(';') {
+            let parts: Vec<&str> = player.split(':').collect();
+            let number = |s: &str| s.parse::<i64>().map_err(|_| format!("bad player '{player}'"));
+            let (pid, score, supply, big) = match parts[..] {
+                [pid, score, supply] => (pid, number(score)?, number(supply)?, None),
+                [pid, score, supply, big] => (pid, number(score)?, number(supply)?, Some(number(big)?)),
+                _ => return Err(format!("bad player '{player}'")),
+            };
+            state.scores.insert(pid.into(), score);
+            state.meeple_supply.insert(pid.into(), supply as i32);
+            if let Some(big) = big {
+                state.big_meeple_supply.insert(pid.into(), big as i32);
+            }
+        }
+
+        let mut unscored = false;
+        for flag in flags {
+            let (key, value) = flag.split_once('=').unwrap_or((flag, ""));
+            match key {
+                "last" => state.last_placed_position = Some(parse_position(value)?.to_key()),
+                "river" => state.river = Some(parse_river(value)?),
+                "meeples" => {
+                    state.meeples_per_player = value.parse().map_err(|_| format!("bad flag '{flag}'"))?;
+                }
+                "ic" => state.inns_cathedrals = true,
+                "unplaceable" => {
+                    state.unplaceable_policy = match value {
+                        "discard" => UnplaceablePolicy::Discard,
+                        "bottom" => UnplaceablePolicy::Bottom,
+                        "end_game" => UnplaceablePolicy::EndGame,
+                        _ => return Err(format!("bad flag '{flag}'")),
+                    };
+                }
+                "groups" => state.ai_view_placement_groups = true,
+                "history" => {
+                    let entries = list(value, ';').map(|entry| parse_scores(entry, flag)).collect::<Result<_, _>>()?;
+                    state.score_history = Some(entries);
+                }
+                "breakdown" => state.end_game_breakdown = Some(parse_breakdown(value, flag)?),
+                "forfeit" => state.forfeited_players = list(value, ',').map(String::from).collect(),
+                "unscored" => unscored = true,
+                _ => return Err(format!("unknown flag '{flag}'")),
+            }
+        }
+
+        let mut placed_meeples = Vec::new();
+        for meeple in list(meeples, ';') {
+            let parts: Vec<&str> = meeple.split(':').collect();
+            let (pid, pos, spot, count) = match parts[..] {
+                [pid, pos, spot] => (pid, pos, spot, 1),
+                [pid, pos, spot, "big"] => (pid, pos, spot, PlacedMeeple::BIG_COUNT),
+                _ => return Err(format!("bad meeple '{meeple}'")),
+            };
+            let position = parse_position(pos)?.to_key();
+            placed_meeples.push(PlacedMeeple { player_id: pid.into(), position, spot: spot.into(), count });
+        }
+
+        for tile in tiles.split(';') {
+            let (tile_type, rest) = tile.split_once(':').ok_or_else(|| format!("bad tile '{tile}'"))?;
+            let (pos, rotation) = rest.rsplit_once(':').ok_or_else(|| format!("bad tile '{tile}'"))?;
+            let pos = parse_position(pos)?;
+            let rotation: u32 = rotation.parse().map_err(|_| format!("bad tile '{tile}'"))?;
+            if !rotation.is_multiple_of(90) || rotation >= 360 {
+                return Err(format!("bad rotation in '{tile}'"));
+            }
+            let tile_type_id = parse_tile_type(tile_type)?;
+            place(&mut state, (pos.x, pos.y), PlacedTile { tile_type_id, rotation }, &placed_meeples)?;
+        }
+        if let Some(m) = placed_meeples.iter().find(|m| !state.tile_feature_map.contains_key(&m.position)) {
+            return Err(format!("no spot {} at {}", m.spot, m.position));
+        }
+        state.board.open_positions = river_open_positions(state.river.as_ref(), &state.board.tiles);
+
+        // Closed features were scored, except those the last tile just closed
+        // when score_check is still pending
+        let last = state.last_placed_position.as_deref().and_then(Position::parse_key);
+        let complete: Vec<String> = state
+            .features
+            .values()
+            .filter(|f| is_feature_complete(&state, f))
+            .filter(|f| !(unscored && last.is_some_and(|last| touches(f, last))))
+            .map(|f| f.feature_id.clone())
+            .collect();
+        for feature_id in complete {
+            if let Some(feature) = state.features.get_mut(&feature_id) {
+                feature.is_complete = true;
+            }
+        }
+
+        state.zobrist = board_hash(&state);
+        Ok(state)
+    }
+
+    /// Meeples on the board, ordered by their notation.
+    fn sorted_meeples(&self) -> Vec<PlacedMeeple> {
+        let mut meeples: Vec<PlacedMeeple> = self.features.values().flat_map(|f| f.meeples.iter().cloned()).collect();
+        meeples.sort_by_cached_key(meeple_notation);
+        meeples
+    }
+
+    /// An order of the board's tiles that `place` turns back into these exact
+    /// features, or None if there is none. Each tile's features took the next
+    /// ids when it went down, which leaves few candidates for each step; ties
+    /// are broken by features' tile lists, which only ever grow at the end.
+    fn placement_order(&self) -> Option<Vec<(i32, i32)>> {
+        let mut replay = self.clone();
+        replay.board.tiles.clear();
+        replay.features.clear();
+        replay.tile_feature_map.clear();
+        replay.feature_redirects.clear();
+        replay.next_feature_id = 0;
+        let meeples = self.sorted_meeples();
+        let mut order = Vec::new();
+        self.extend_placement_order(&replay, &meeples, &mut order).then_some(order)
+    }
+
+    fn extend_placement_order(
+        &self,
+        replay: &CarcassonneState,
+        meeples: &[PlacedMeeple],
+        order: &mut Vec<(i32, i32)>,
+    ) -> bool {
+        if order.len() == self.board.tiles.len() {
+            return self.same_features(replay);
+        }
+        let mut candidates: Vec<(i32, i32)> = self
+            .board
+            .tiles
+            .keys()
+            .copied()
+            .filter(|pos| {
+                !replay.board.tiles.contains_key(pos) && self.takes_next_ids(*pos, replay.next_feature_id)
+            })
+            .collect();
+        candidates.sort();
+        for pos in candidates {
+            let mut next = replay.clone();
+            if place(&mut next, pos, self.board.tiles[&pos], meeples).is_err() || !self.grows_into(&next, pos) {
+                continue;
+            }
+            order.push(pos);
+            if self.extend_placement_order(&next, meeples, order) {
+                return true;
+            }
+            order.pop();
+        }
+        false
+    }
+
+    /// Whether the tile at `pos` created features `f<first_id>` onwards, going
+    /// by the features they were merged into.
+    fn takes_next_ids(&self, (x, y): (i32, i32), first_id: u64) -> bool {
+        let tile = self.board.tiles[&(x, y)];
+        let Some(spots) = self.tile_feature_map.get(&Position::new(x, y).to_key()) else {
+            return false;
+        };
+        get_rotated_features(tile.tile_type_id, tile.rotation).iter().zip(first_id..).all(|(feature, id)| {
+            let merged_into = resolve_feature_id(self, &format!("f{id}"));
+            feature.meeple_spots.iter().all(|spot| spots.get(spot) == Some(&merged_into))
+        })
+    }
+
+    /// Whether the features the tile at `pos` belongs to in `replay` can still
+    /// grow into this state's: their tile and merge lists are prefixes of ours.
+    fn grows_into(&self, replay: &CarcassonneState, (x, y): (i32, i32)) -> bool {
+        replay.tile_feature_map[&Position::new(x, y).to_key()].values().all(|id| {
+            match (replay.features.get(id), self.features.get(id)) {
+                (Some(partial), Some(full)) => {
+                    full.tiles.starts_with(&partial.tiles) && full.merged_from.starts_with(&partial.merged_from)
+                }
+                _ => true,
+            }
+        })
+    }
+
+    /// Whether `other` has the same features, ids and redirects. Completion
+    /// is left out: it is set after the tiles are placed.
+    fn same_features(&self, other: &CarcassonneState) -> bool {
+        let features = |s: &CarcassonneState| {
+            let mut features = s.features.clone();
+            features.values_mut().for_each(|f| f.is_complete = false);
+            serde_json::to_value(features).ok()
+        };
+        self.next_feature_id == other.next_feature_id
+            && self.tile_feature_map == other.tile_feature_map
+            && self.feature_redirects == other.feature_redirects
+            && features(self) == features(other)
+    }
+}
+
+/// Put `tile` at `pos`, merge its features into the board's, then put down
+/// those of `meeples` standing on it.
+fn place(
+    state: &mut CarcassonneState,
+    (x, y): (i32, i32),
+    tile: PlacedTile,
+    meeples: &[PlacedMeeple],
+) -> Result<(), String> {
+    if state.board.tiles.insert((x, y), tile).is_some() {
+        return Err(format!("two tiles at {x},{y}"));
+    }
+    let key = Position::new(x, y).to_key();
+    create_and_merge_features(state, tile_index_to_type(tile.tile_type_id), &key, tile.rotation);
+    for meeple in meeples.iter().filter(|m| m.position == key) {
+        let feature_id = state
+            .tile_feature_map
+            .get(&key)
+            .and_then(|spots| spots.get(&meeple.spot))
+            .ok_or_else(|| format!("no spot {} at {key}", meeple.spot))?;
+        let feature = state.features.get_mut(feature_id).ok_or("feature map out of sync")?;
+        feature.meeples.push(meeple.clone());
+    }
+    Ok(())
+}
+
+fn meeple_notation(m: &PlacedMeeple) -> String {
+    let big = if m.is_big() { ":big" } else { "" };
+    format!("{}:{}:{}{big}", m.player_id, m.position, m.spot)
+}
+
+/// `scores` as `id:score` items joined by `sep`, by player id.
+fn score_list(scores: &HashMap<String, i64>, sep: &str) -> String {
+    let mut items: Vec<String> = scores.iter().map(|(pid, score)| format!("{pid}:{score}")).collect();
+    items.sort();
+    items.join(sep)
+}
+
+fn parse_scores(entry: &str, flag: &str) -> Result<HashMap<String, i64>, String> {
+    list(entry, ',')
+        .map(|item| {
+            let (pid, score) = item.split_once(':').ok_or_else(|| format!("bad flag '{flag}'"))?;
+            Ok((pid.to_string(), score.parse().map_err(|_| format!("bad flag '{flag}'"))?))
+        })
+        .collect()
+}
+
+/// Categories of the end-game breakdown, in notation order.
+const BREAKDOWN_CATEGORIES: [&str; 4] = ["fields", "roads", "cities", "monasteries"];
+
+/// The `breakdown` flag's value, or None if `breakdown` isn't per-player points
+/// in exactly `BREAKDOWN_CATEGORIES`.
+fn breakdown_notation(breakdown: &serde_json::Value) -> Option<String> {
+    let breakdown: HashMap<String, HashMap<String, i64>> = serde_json::from_value(breakdown.clone()).ok()?;
+    let mut players = Vec::new();
+    for (pid, points) in &breakdown {
+        if points.len() != BREAKDOWN_CATEGORIES.len() {
+            return None;
+        }
+        let points = BREAKDOWN_CATEGORIES.iter().map(|c| points.get(*c).map(i64::to_string)).collect::<Option<Vec<_>>>()?;
+        players.push(format!("{pid}:{}", points.join(":")));
+    }
+    players.sort();
+    Some(or_dash(players.join(";")))
+}
+
+fn parse_breakdown(value: &str, flag: &str) -> Result<serde_json::Value, String> {
+    let mut breakdown = serde_json::Map::new();
+    for player in list(value, ';') {
+        let (pid, points) = player.split_once(':').ok_or_else(|| format!("bad flag '{flag}'"))?;
+        let points: Vec<&str> = points.split(':').collect();
+        if points.len() != BREAKDOWN_CATEGORIES.len() {
+            return Err(format!("bad flag '{flag}'"));
+        }
+        let mut categories = serde_json::Map::new();
+        for (category, points) in BREAKDOWN_CATEGORIES.iter().zip(points) {
+            let points: i64 = points.parse().map_err(|_| format!("bad flag '{flag}'"))?;
+            categories.insert(category.to_string(), points.into());
+        }
+        breakdown.insert(pid.to_string(), categories.into());
+    }
+    Ok(breakdown.into())
+}
+
+/// Whether placing `last` can have completed `feature`: it covers that tile,
+/// or for a monastery, one of its neighbors.
+fn touches(feature: &Feature, last: Position) -> bool {
+    feature.tiles.iter().filter_map(|t| Position::parse_key(t)).any(|p| match feature.feature_type {
+        FeatureType::Monastery => (p.x - last.x).abs() <= 1 && (p.y - last.y).abs() <= 1,
+        _ => p == last,
+    })
+}
+
+fn or_dash(s: String) -> String {
+    if s.is_empty() {
+        "-".into()
+    } else {
+        s
+    }
+}
+
+/// Items of a list field; `-` is the empty list.
+fn list(field: &str, sep: char) -> impl Iterator<Item = &str> {
+    field.split(sep).filter(move |item| field != "-" && !item.is_empty())
+}
+
+fn parse_tile_type(id: &str) -> Result<u8, String> {
+    let known = |idx: u8| (idx as usize) < TILE_TYPE_COUNT && tile_index_to_type(idx) == id;
+    // tile_type_to_index assumes single-letter ids are uppercase
+    let idx = if id.len() == 1 && !id.as_bytes()[0].is_ascii_uppercase() { u8::MAX } else { tile_type_to_index(id) };
+    if known(idx) {
+        Ok(idx)
+    } else {
+        Err(format!("unknown tile type '{id}'"))
+    }
+}
+
+fn parse_position(key: &str) -> Result<Position, String> {
+    Position::parse_key(key).ok_or_else(|| format!("bad position '{key}'"))
+}
+
+fn parse_river(value: &str) -> Result<RiverState, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let (open_end, heading, last_turn) = match parts[..] {
+        [open_end, heading] => (open_end, heading, None),
+        [open_end, heading, "left"] => (open_end, heading, Some(RiverTurn::Left)),
+        [open_end, heading, "right"] => (open_end, heading, Some(RiverTurn::Right)),
+        _ => return Err(format!("bad river '{value}'")),
+    };
+    if !DIRECTIONS.contains(&heading) {
+        return Err(format!("bad river '{value}'"));
+    }
+    Ok(RiverState { open_end: parse_position(open_end)?.to_key(), heading: heading.into(), last_turn })
+}
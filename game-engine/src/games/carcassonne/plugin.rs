@@ -1,22 +1,59 @@
 //! CarcassonnePlugin — implements TypedGamePlugin trait.
 //! Mirrors backend/src/games/carcassonne/plugin.py.
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 use crate::engine::models::*;
-use crate::engine::plugin::{TypedGamePlugin, TypedTransitionResult};
-use super::board::{can_place_tile, recalculate_open_positions, tile_has_valid_placement};
+use crate::engine::payload::{FieldKind, PayloadField};
+use crate::engine::plugin::{metadata_player, EvalFn, TypedGamePlugin, TypedTransitionResult};
+use crate::engine::rng::GameRng;
+use super::board::{
+    can_place_tile, placements_by_position, recalculate_open_positions,
+    update_open_positions, valid_tile_placements,
+};
+use super::evaluator::{spot_completion_probability, TRAPPED_COMPLETION_PROBABILITY};
 use super::features::{
     check_monastery_completion, create_and_merge_features,
     initialize_features_from_tile, is_feature_complete,
 };
-use super::meeples::{can_place_meeple, return_meeples};
+use super::inns_cathedrals::INNS_CATHEDRALS_EXPANSION;
+use super::meeples::{
+    can_place_big_meeple, can_place_meeple, return_meeples, MEEPLES_PER_PLAYER, MEEPLES_PER_PLAYER_RANGE,
+};
+use super::river::{
+    build_river_bag, is_river_tile, shuffle_bag, RiverState, RIVER_EXPANSION, RIVER_SPRING_ID,
+    RIVER_SPRING_IDX,
+};
 use super::scoring::{score_completed_feature, score_end_game};
-use super::tiles::{STARTING_TILE_ID, STARTING_TILE_IDX, build_tile_bag, get_rotated_features};
+use super::tiles::{
+    STARTING_TILE_ID, STARTING_TILE_IDX, TILE_DEFS, TILE_LOOKUP, build_tile_bag,
+    get_rotated_features, tile_has_pennant,
+};
 use super::types::*;
+use super::zobrist::{board_hash, meeple_key, tile_key};
 
 pub struct CarcassonnePlugin;
 
+const PLACE_TILE_SCHEMA: &[PayloadField] = &[
+    PayloadField::required("x", FieldKind::Int),
+    PayloadField::required("y", FieldKind::Int),
+    PayloadField::required("rotation", FieldKind::UInt),
+];
+
+/// Either `{"skip": true}` or `{"meeple_spot": ...}`; which one is checked by validation.
+const PLACE_MEEPLE_SCHEMA: &[PayloadField] = &[
+    PayloadField::optional("skip", FieldKind::Bool),
+    PayloadField::optional("meeple_spot", FieldKind::String),
+];
+
+/// `big_meeple` marks the payload in valid action lists; see `action_type_for`.
+const PLACE_BIG_MEEPLE_SCHEMA: &[PayloadField] = &[
+    PayloadField::required("meeple_spot", FieldKind::String),
+    PayloadField::optional("big_meeple", FieldKind::Bool),
+];
+
 // ================================================================== //
 //  TypedGamePlugin implementation (fast path for MCTS / Arena)
 // ================================================================== //
@@ -33,27 +70,155 @@ impl TypedGamePlugin for CarcassonnePlugin {
     }
     fn disconnect_policy(&self) -> &str { "forfeit_player" }
 
-    fn decode_state(&self, game_data: &serde_json::Value) -> CarcassonneState {
-        serde_json::from_value(game_data.clone())
-            .unwrap_or_else(|e| panic!("Failed to decode CarcassonneState: {e}"))
+    fn supported_options(&self) -> Vec<OptionSpec> {
+        vec![
+            OptionSpec {
+                name: "tile_count".into(),
+                option_type: OptionType::Integer,
+                default: serde_json::Value::Null,
+                description: "Number of tiles in the bag (default: the full bag)".into(),
+            },
+            OptionSpec {
+                name: "meeples_per_player".into(),
+                option_type: OptionType::Integer,
+                default: serde_json::json!(MEEPLES_PER_PLAYER),
+                description: format!(
+                    "Meeples each player starts with ({}-{})",
+                    MEEPLES_PER_PLAYER_RANGE.start(),
+                    MEEPLES_PER_PLAYER_RANGE.end()
+                ),
+            },
+            OptionSpec {
+                name: "track_score_history".into(),
+                option_type: OptionType::Boolean,
+                default: serde_json::json!(false),
+                description: "Record every player's score after each scoring check".into(),
+            },
+            OptionSpec {
+                name: "unplaceable_policy".into(),
+                option_type: OptionType::String,
+                default: serde_json::json!("discard"),
+                description: "Drawn tile with no legal placement: \"discard\" it, return it to \
+                    the \"bottom\" of the bag, or \"end_game\"".into(),
+            },
+            OptionSpec {
+                name: "ai_view_placement_groups".into(),
+                option_type: OptionType::Boolean,
+                default: serde_json::json!(false),
+                description: "Add the drawn tile's legal rotations per open position to the AI view".into(),
+            },
+            OptionSpec {
+                name: "expansions".into(),
+                option_type: OptionType::Array,
+                default: serde_json::json!([]),
+                description: "Expansions to play with: \"river\" starts from the river spring \
+                    and places the river tiles first; \"inns_cathedrals\" adds inn and cathedral \
+                    tiles and their scoring".into(),
+            },
+            OptionSpec {
+                name: "starting_tile".into(),
+                option_type: OptionType::String,
+                default: serde_json::Value::Null,
+                description: "Tile type placed at (0,0) instead of D".into(),
+            },
+            OptionSpec {
+                name: "tile_sequence".into(),
+                option_type: OptionType::Array,
+                default: serde_json::Value::Null,
+                description: "Tile type ids drawn in this exact order instead of a shuffled bag".into(),
+            },
+        ]
+    }
+
+    /// The Zobrist hash isn't part of `game_data`, so it is rebuilt here.
+    fn try_decode_state(&self, game_data: &serde_json::Value) -> Result<CarcassonneState, String> {
+        let mut state: CarcassonneState = serde_json::from_value(game_data.clone())
+            .map_err(|e| format!("invalid {} state: {}", self.game_id(), e))?;
+        state.zobrist = board_hash(&state);
+        Ok(state)
     }
 
     fn encode_state(&self, state: &CarcassonneState) -> serde_json::Value {
         state.to_json()
     }
 
+    /// MessagePack of the typed state, with tiles and positions as numbers
+    /// rather than the JSON document's strings.
+    fn encode_state_binary(&self, state: &CarcassonneState) -> Result<Vec<u8>, String> {
+        to_msgpack(state)
+    }
+
+    fn decode_state_binary(&self, bytes: &[u8]) -> Result<CarcassonneState, String> {
+        let mut state: CarcassonneState = from_msgpack(bytes)
+            .map_err(|e| format!("invalid {} state: {}", self.game_id(), e))?;
+        state.zobrist = board_hash(&state);
+        Ok(state)
+    }
+
+    fn load_state(&self, game_data: &serde_json::Value, phase: &Phase) -> Result<(), String> {
+        let state: CarcassonneState = serde_json::from_value(game_data.clone())
+            .map_err(|e| format!("Invalid CarcassonneState: {e}"))?;
+        match phase.name.as_str() {
+            "draw_tile" | "score_check" | "end_game_scoring" | "game_over" => {}
+            "place_tile" if state.current_tile.is_none() => {
+                return Err("place_tile phase requires a current_tile".into());
+            }
+            "place_tile" => {}
+            "place_meeple" => {
+                let placed = state.last_placed_position.as_deref().and_then(Position::parse_key);
+                if !placed.is_some_and(|p| state.board.tiles.contains_key(&(p.x, p.y))) {
+                    return Err("place_meeple phase requires last_placed_position on the board".into());
+                }
+            }
+            other => return Err(format!("Unknown phase: {other}")),
+        }
+        let violations = check_invariants(&state, "load_state");
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations.join("\n"))
+        }
+    }
+
+    fn validate_config(&self, config: &GameConfig) -> Result<(), String> {
+        meeples_per_player(config)?;
+        unplaceable_policy(config)?;
+        let river = expansions(config)?.iter().any(|e| e == RIVER_EXPANSION);
+        custom_setup(config, river).map(|_| ())
+    }
+
+    /// Panics on options `validate_config` rejects; callers must validate first.
     fn create_initial_state(
         &self,
         players: &[Player],
         config: &GameConfig,
     ) -> (CarcassonneState, Phase, Vec<Event>) {
-        let mut tile_bag = build_tile_bag(None);
+        let meeples_per_player = meeples_per_player(config).unwrap_or_else(|e| panic!("{e}"));
+        let expansions = expansions(config).unwrap_or_else(|e| panic!("{e}"));
+        let river = expansions.iter().any(|e| e == RIVER_EXPANSION).then(RiverState::from_spring);
+        let inns_cathedrals = expansions.iter().any(|e| e == INNS_CATHEDRALS_EXPANSION);
+        let (custom_start, tile_sequence) =
+            custom_setup(config, river.is_some()).unwrap_or_else(|e| panic!("{e}"));
 
         use rand::seq::SliceRandom;
-        use rand::SeedableRng;
-        let seed = config.random_seed.unwrap_or(0);
-        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
-        tile_bag.shuffle(&mut rng);
+        let mut rng = GameRng::from_seed(config.random_seed.unwrap_or(0));
+        let mut tile_bag = match tile_sequence {
+            Some(sequence) => sequence,
+            None => {
+                let mut bag = build_tile_bag(Some(&expansions));
+                // A custom starting tile comes out of the bag instead of the usual D
+                if let Some(start_idx) = custom_start.map(tile_type_to_index) {
+                    if start_idx != STARTING_TILE_IDX {
+                        if let Some(i) = bag.iter().position(|&t| t == start_idx) {
+                            bag.remove(i);
+                        }
+                        bag.push(STARTING_TILE_IDX);
+                    }
+                }
+                bag.shuffle(&mut rng);
+                bag
+            }
+        };
 
         if let Some(tile_count) = config.options.get("tile_count").and_then(|v| v.as_u64()) {
             let tc = tile_count as usize;
@@ -62,25 +227,47 @@ impl TypedGamePlugin for CarcassonnePlugin {
             }
         }
 
+        // River tiles come off the top of the bag before any base tile
+        let (starting_tile_id, starting_tile_idx) = if river.is_some() {
+            tile_bag.splice(0..0, build_river_bag(&mut rng));
+            (RIVER_SPRING_ID, RIVER_SPRING_IDX)
+        } else if let Some(id) = custom_start {
+            (id, tile_type_to_index(id))
+        } else {
+            (STARTING_TILE_ID, STARTING_TILE_IDX)
+        };
+
         let mut board_tiles: HashMap<(i32, i32), PlacedTile> = HashMap::new();
         board_tiles.insert((0, 0), PlacedTile {
-            tile_type_id: STARTING_TILE_IDX,
+            tile_type_id: starting_tile_idx,
             rotation: 0,
         });
-        let open_positions = recalculate_open_positions(&board_tiles);
+        let open_positions = river_open_positions(river.as_ref(), &board_tiles);
 
         let mut feature_id_counter: u64 = 0;
         let (features, tile_feature_map) =
-            initialize_features_from_tile(STARTING_TILE_ID, "0,0", 0, &mut feature_id_counter);
+            initialize_features_from_tile(starting_tile_id, "0,0", 0, &mut feature_id_counter);
 
         let meeple_supply: HashMap<String, i32> = players
             .iter()
-            .map(|p| (p.player_id.clone(), 7))
+            .map(|p| (p.player_id.clone(), meeples_per_player))
             .collect();
         let scores: HashMap<String, i64> = players
             .iter()
             .map(|p| (p.player_id.clone(), 0))
             .collect();
+        let big_meeple_supply: HashMap<String, i32> = if inns_cathedrals {
+            players.iter().map(|p| (p.player_id.clone(), 1)).collect()
+        } else {
+            HashMap::new()
+        };
+        let track_history = config.options.get("track_score_history")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let unplaceable_policy = unplaceable_policy(config).unwrap_or_else(|e| panic!("{e}"));
+        let ai_view_placement_groups = config.options.get("ai_view_placement_groups")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
         let state = CarcassonneState {
             board: Board { tiles: board_tiles, open_positions },
@@ -90,6 +277,7 @@ impl TypedGamePlugin for CarcassonnePlugin {
             features,
             tile_feature_map,
             meeple_supply,
+            meeples_per_player,
             scores,
             current_player_index: 0,
             rng_state: serde_json::Value::Null,
@@ -97,6 +285,13 @@ impl TypedGamePlugin for CarcassonnePlugin {
             end_game_breakdown: None,
             next_feature_id: feature_id_counter,
             feature_redirects: HashMap::new(),
+            score_history: track_history.then(Vec::new),
+            unplaceable_policy,
+            ai_view_placement_groups,
+            big_meeple_supply,
+            inns_cathedrals,
+            river,
+            zobrist: tile_key(0, 0, starting_tile_idx, 0),
         };
 
         let first_phase = Phase {
@@ -119,7 +314,7 @@ impl TypedGamePlugin for CarcassonnePlugin {
                 event_type: "starting_tile_placed".into(),
                 player_id: None,
                 payload: serde_json::json!({
-                    "tile": STARTING_TILE_ID,
+                    "tile": starting_tile_id,
                     "position": "0,0",
                 }),
             },
@@ -160,21 +355,25 @@ impl TypedGamePlugin for CarcassonnePlugin {
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TypedTransitionResult<CarcassonneState> {
+    ) -> Result<TypedTransitionResult<CarcassonneState>, String> {
         let s = state.clone();
+        let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
+        if player_index >= players.len() {
+            return Err(format!("player_index {} out of range for {} players", player_index, players.len()));
+        }
         match phase.name.as_str() {
-            "draw_tile" => apply_draw_tile(s, phase, players),
+            "draw_tile" => Ok(apply_draw_tile(s, phase, players)),
             "place_tile" => apply_place_tile(s, phase, action, players),
             "place_meeple" => apply_place_meeple(s, phase, action, players),
-            "score_check" => apply_score_check(s, phase, players),
-            "end_game_scoring" => apply_end_game_scoring(s, phase, players),
-            _ => TypedTransitionResult {
+            "score_check" => Ok(apply_score_check(s, phase, players)),
+            "end_game_scoring" => Ok(apply_end_game_scoring(s, phase, players)),
+            _ => Ok(TypedTransitionResult {
                 state: s,
                 events: vec![],
                 next_phase: phase.clone(),
                 scores: HashMap::new(),
                 game_over: None,
-            },
+            }),
         }
     }
 
@@ -199,6 +398,12 @@ impl TypedGamePlugin for CarcassonnePlugin {
         if let Some(ref breakdown) = state.end_game_breakdown {
             view["end_game_breakdown"] = breakdown.clone();
         }
+        if let Some(ref history) = state.score_history {
+            view["score_history"] = serde_json::json!(history);
+        }
+        if !state.big_meeple_supply.is_empty() {
+            view["big_meeple_supply"] = serde_json::json!(state.big_meeple_supply);
+        }
         view
     }
 
@@ -224,6 +429,19 @@ impl TypedGamePlugin for CarcassonnePlugin {
         view["my_meeples"] = serde_json::json!(
             state.meeple_supply.get(player_id).copied().unwrap_or(0)
         );
+        if let Some(big) = state.big_meeple_supply.get(player_id) {
+            view["my_big_meeples"] = serde_json::json!(big);
+        }
+        if let (true, "place_tile", Some(tile_idx)) =
+            (state.ai_view_placement_groups, phase.name.as_str(), state.current_tile)
+        {
+            let grouped: serde_json::Map<String, serde_json::Value> =
+                placements_by_position(&state.board.tiles, &state.board.open_positions, tile_idx)
+                    .into_iter()
+                    .map(|((x, y), rotations)| (format!("{},{}", x, y), serde_json::json!(rotations)))
+                    .collect();
+            view["placements_by_position"] = serde_json::Value::Object(grouped);
+        }
         view
     }
 
@@ -233,23 +451,47 @@ impl TypedGamePlugin for CarcassonnePlugin {
         phase: &Phase,
         player_id: &str,
     ) -> Action {
-        let action_type = if !phase.expected_actions.is_empty() {
-            phase.expected_actions[0].action_type.clone()
-        } else {
-            phase.name.clone()
-        };
         let payload = response
             .get("action")
             .and_then(|a| a.get("payload"))
             .unwrap_or(response)
             .clone();
         Action {
-            action_type,
+            action_type: self.action_type_for(phase, &payload),
             player_id: player_id.into(),
             payload,
         }
     }
 
+    fn payload_schema(&self, action_type: &str) -> Option<&'static [PayloadField]> {
+        match action_type {
+            "place_tile" => Some(PLACE_TILE_SCHEMA),
+            "place_meeple" => Some(PLACE_MEEPLE_SCHEMA),
+            "place_big_meeple" => Some(PLACE_BIG_MEEPLE_SCHEMA),
+            _ => None,
+        }
+    }
+
+    fn phase_action_types(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        &[("place_tile", &["place_tile"]), ("place_meeple", &["place_meeple", "place_big_meeple"])]
+    }
+
+    fn feature_outlook(&self, state: &CarcassonneState, player_id: &str) -> Option<serde_json::Value> {
+        Some(serde_json::json!(super::evaluator::feature_outlook(state, player_id)))
+    }
+
+    fn describe_meeple(&self, state: &CarcassonneState, position: &str, spot: &str) -> Option<serde_json::Value> {
+        super::evaluator::describe_meeple(state, position, spot)
+    }
+
+    fn export_notation(&self, state: &CarcassonneState) -> Option<String> {
+        Some(state.to_notation())
+    }
+
+    fn import_notation(&self, notation: &str) -> Option<Result<CarcassonneState, String>> {
+        Some(CarcassonneState::from_notation(notation))
+    }
+
     fn on_player_forfeit(
         &self,
         state: &CarcassonneState,
@@ -299,10 +541,59 @@ impl TypedGamePlugin for CarcassonnePlugin {
         state.float_scores()
     }
 
-    fn determinize(&self, state: &mut CarcassonneState) {
-        use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        state.tile_bag.shuffle(&mut rng);
+    fn determinize(&self, state: &mut CarcassonneState, rng: &mut GameRng) {
+        shuffle_bag(&mut state.tile_bag, rng);
+        // History is for post-game analysis only; don't clone or grow it in search.
+        state.score_history = None;
+    }
+
+    /// Every phase but final scoring belongs to the turn in `player_index`.
+    fn acting_player(
+        &self,
+        _state: &CarcassonneState,
+        phase: &Phase,
+        players: &[Player],
+    ) -> Option<String> {
+        match phase.name.as_str() {
+            "draw_tile" | "place_tile" | "place_meeple" | "score_check" => {
+                metadata_player(phase, players)
+            }
+            _ => None,
+        }
+    }
+
+    /// Big meeple placements are listed as `{"meeple_spot", "big_meeple": true}`.
+    fn action_type_for(&self, phase: &Phase, payload: &serde_json::Value) -> String {
+        if payload.get("big_meeple").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return "place_big_meeple".into();
+        }
+        match phase.expected_actions.first() {
+            Some(expected) => expected.action_type.clone(),
+            None => phase.name.clone(),
+        }
+    }
+
+    fn eval_profiles(&self) -> Vec<&'static str> {
+        super::evaluator::EVAL_PROFILES.to_vec()
+    }
+
+    fn make_eval(&self, profile: &str) -> Option<EvalFn<CarcassonneState>> {
+        super::evaluator::preset_weights(profile).map(|w| super::evaluator::make_carcassonne_eval(*w))
+    }
+
+    /// The board's Zobrist hash plus the drawn tile, the turn and the scores,
+    /// which the same board can be reached with different values of.
+    fn state_hash(&self, state: &CarcassonneState) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (state.zobrist, state.current_tile, state.current_player_index).hash(&mut hasher);
+        // XOR so the scores map's iteration order doesn't matter
+        let scores = state.scores.iter().fold(0, |acc, (player, score)| {
+            let mut h = DefaultHasher::new();
+            (player, score).hash(&mut h);
+            acc ^ h.finish()
+        });
+        scores.hash(&mut hasher);
+        hasher.finish()
     }
 
     fn amaf_context(&self, state: &CarcassonneState) -> String {
@@ -310,6 +601,113 @@ impl TypedGamePlugin for CarcassonnePlugin {
             .map(|idx| tile_index_to_type(idx).to_string())
             .unwrap_or_default()
     }
+
+    fn prunable_actions(
+        &self,
+        state: &CarcassonneState,
+        phase: &Phase,
+        player_id: &str,
+    ) -> Vec<serde_json::Value> {
+        if phase.name != "place_meeple" {
+            return vec![];
+        }
+        // A free claim on a city, road or monastery with positive potential (likely
+        // enough to complete that the meeple comes back with points) dominates skipping.
+        let Some(position) = state.last_placed_position.as_deref() else {
+            return vec![];
+        };
+        let has_free_claim = get_valid_meeple_placements(state, player_id)
+            .iter()
+            .filter_map(|a| a.get("meeple_spot").and_then(|v| v.as_str()))
+            .any(|spot| {
+                spot_completion_probability(state, position, spot)
+                    .is_some_and(|cp| cp >= TRAPPED_COMPLETION_PROBABILITY)
+            });
+        if has_free_claim {
+            vec![serde_json::json!({"skip": true})]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// The `meeples_per_player` option, or the default when it is absent.
+fn meeples_per_player(config: &GameConfig) -> Result<i32, String> {
+    let Some(value) = config.options.get("meeples_per_player") else {
+        return Ok(MEEPLES_PER_PLAYER);
+    };
+    value
+        .as_i64()
+        .and_then(|n| i32::try_from(n).ok())
+        .filter(|n| MEEPLES_PER_PLAYER_RANGE.contains(n))
+        .ok_or_else(|| {
+            format!(
+                "meeples_per_player must be an integer from {} to {}, got {}",
+                MEEPLES_PER_PLAYER_RANGE.start(),
+                MEEPLES_PER_PLAYER_RANGE.end(),
+                value
+            )
+        })
+}
+
+/// The `unplaceable_policy` option, or the default when it is absent.
+fn unplaceable_policy(config: &GameConfig) -> Result<UnplaceablePolicy, String> {
+    let Some(value) = config.options.get("unplaceable_policy") else {
+        return Ok(UnplaceablePolicy::default());
+    };
+    serde_json::from_value(value.clone()).map_err(|_| {
+        format!("unplaceable_policy must be one of discard, bottom, end_game, got {}", value)
+    })
+}
+
+/// The `expansions` option, empty when it is absent.
+fn expansions(config: &GameConfig) -> Result<Vec<String>, String> {
+    const KNOWN: [&str; 2] = [RIVER_EXPANSION, INNS_CATHEDRALS_EXPANSION];
+    let Some(value) = config.options.get("expansions") else {
+        return Ok(Vec::new());
+    };
+    let names: Vec<String> = serde_json::from_value(value.clone())
+        .map_err(|_| format!("expansions must be an array of expansion names, got {}", value))?;
+    match names.iter().find(|name| !KNOWN.contains(&name.as_str())) {
+        Some(name) => Err(format!("unknown expansion {:?}, expected one of {}", name, KNOWN.join(", "))),
+        None => Ok(names),
+    }
+}
+
+/// The `starting_tile` and `tile_sequence` options, each None when absent.
+/// Neither combines with the river, which fixes its own start and draw order.
+fn custom_setup(
+    config: &GameConfig,
+    river: bool,
+) -> Result<(Option<&'static str>, Option<Vec<u8>>), String> {
+    let option = |name: &str| config.options.get(name).filter(|v| !v.is_null());
+    let tile_id = |name: &str, value: &serde_json::Value| -> Result<&'static str, String> {
+        let id = value
+            .as_str()
+            .ok_or_else(|| format!("{name} must hold tile type ids, got {value}"))?;
+        match TILE_LOOKUP.get(id) {
+            Some(def) if !is_river_tile(tile_type_to_index(id)) => Ok(def.tile_type_id.as_str()),
+            Some(_) => Err(format!("{name}: river tile '{id}' can only be placed by the river expansion")),
+            None => Err(format!("{name}: unknown tile type '{id}'")),
+        }
+    };
+
+    let starting_tile = option("starting_tile")
+        .map(|v| tile_id("starting_tile", v))
+        .transpose()?;
+    let tile_sequence = option("tile_sequence")
+        .map(|v| {
+            v.as_array()
+                .ok_or_else(|| format!("tile_sequence must be an array, got {v}"))?
+                .iter()
+                .map(|id| tile_id("tile_sequence", id).map(tile_type_to_index))
+                .collect::<Result<Vec<u8>, String>>()
+        })
+        .transpose()?;
+    if river && (starting_tile.is_some() || tile_sequence.is_some()) {
+        return Err("starting_tile and tile_sequence can't be combined with the river expansion".into());
+    }
+    Ok((starting_tile, tile_sequence))
 }
 
 // ================================================================== //
@@ -322,62 +720,42 @@ fn apply_draw_tile(
     players: &[Player],
 ) -> TypedTransitionResult<CarcassonneState> {
     if state.tile_bag.is_empty() {
-        let scores = state.float_scores();
-        return TypedTransitionResult {
-            state,
-            events: vec![Event {
-                event_type: "tile_bag_empty".into(),
-                player_id: None,
-                payload: serde_json::json!({}),
-            }],
-            next_phase: Phase {
-                name: "end_game_scoring".into(),
-                auto_resolve: true,
-                concurrent_mode: None,
-                expected_actions: vec![],
-                metadata: serde_json::json!({}),
-            },
-            scores,
-            game_over: None,
-        };
+        return end_game_on_draw(state, vec![tile_bag_empty_event()]);
     }
 
     let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
     let player = &players[player_index];
 
     let mut drawn_tile = state.tile_bag.remove(0);
-
-    // Skip unplaceable tiles
-    while !tile_has_valid_placement(&state.board.tiles, &state.board.open_positions, drawn_tile) {
-        if state.tile_bag.is_empty() {
-            let scores = state.float_scores();
-            return TypedTransitionResult {
-                state,
-                events: vec![
-                    Event {
-                        event_type: "tile_discarded".into(),
-                        player_id: Some(player.player_id.clone()),
-                        payload: serde_json::json!({
-                            "tile": tile_index_to_type(drawn_tile),
-                            "reason": "no_valid_placement",
-                        }),
-                    },
-                    Event {
-                        event_type: "tile_bag_empty".into(),
+    // Tiles sent to the bottom since the last placeable draw. Once every
+    // tile in the bag has been tried, none of them fits.
+    let mut returned = 0;
+
+    // Skip unplaceable tiles according to the game's policy
+    while !has_legal_placement(&state, drawn_tile) {
+        match state.unplaceable_policy {
+            UnplaceablePolicy::Discard => {
+                if state.tile_bag.is_empty() {
+                    let discarded = tile_discarded_event(&player.player_id, drawn_tile);
+                    return end_game_on_draw(state, vec![discarded, tile_bag_empty_event()]);
+                }
+            }
+            UnplaceablePolicy::Bottom => {
+                state.tile_bag.push(drawn_tile);
+                returned += 1;
+                if returned >= state.tile_bag.len() {
+                    let event = Event {
+                        event_type: "no_placeable_tiles".into(),
                         player_id: None,
-                        payload: serde_json::json!({}),
-                    },
-                ],
-                next_phase: Phase {
-                    name: "end_game_scoring".into(),
-                    auto_resolve: true,
-                    concurrent_mode: None,
-                    expected_actions: vec![],
-                    metadata: serde_json::json!({}),
-                },
-                scores,
-                game_over: None,
-            };
+                        payload: serde_json::json!({"tiles_remaining": state.tile_bag.len()}),
+                    };
+                    return end_game_on_draw(state, vec![event]);
+                }
+            }
+            UnplaceablePolicy::EndGame => {
+                let discarded = tile_discarded_event(&player.player_id, drawn_tile);
+                return end_game_on_draw(state, vec![discarded]);
+            }
         }
         drawn_tile = state.tile_bag.remove(0);
     }
@@ -416,17 +794,62 @@ fn apply_draw_tile(
     }
 }
 
+fn tile_discarded_event(player_id: &str, tile: u8) -> Event {
+    Event {
+        event_type: "tile_discarded".into(),
+        player_id: Some(player_id.to_string()),
+        payload: serde_json::json!({
+            "tile": tile_index_to_type(tile),
+            "reason": "no_valid_placement",
+        }),
+    }
+}
+
+fn tile_bag_empty_event() -> Event {
+    Event {
+        event_type: "tile_bag_empty".into(),
+        player_id: None,
+        payload: serde_json::json!({}),
+    }
+}
+
+/// Stop drawing and move to final scoring.
+fn end_game_on_draw(
+    state: CarcassonneState,
+    events: Vec<Event>,
+) -> TypedTransitionResult<CarcassonneState> {
+    let scores = state.float_scores();
+    TypedTransitionResult {
+        state,
+        events,
+        next_phase: Phase {
+            name: "end_game_scoring".into(),
+            auto_resolve: true,
+            concurrent_mode: None,
+            expected_actions: vec![],
+            metadata: serde_json::json!({}),
+        },
+        scores,
+        game_over: None,
+    }
+}
+
 fn apply_place_tile(
     mut state: CarcassonneState,
     phase: &Phase,
     action: &Action,
     players: &[Player],
-) -> TypedTransitionResult<CarcassonneState> {
-    let x = action.payload["x"].as_i64().unwrap_or(0) as i32;
-    let y = action.payload["y"].as_i64().unwrap_or(0) as i32;
+) -> Result<TypedTransitionResult<CarcassonneState>, String> {
+    let (Some(x), Some(y)) = (action.payload["x"].as_i64(), action.payload["y"].as_i64()) else {
+        return Err("place_tile needs integer x and y".into());
+    };
+    let (x, y) = (x as i32, y as i32);
     let rotation = action.payload["rotation"].as_u64().unwrap_or(0) as u32;
     let pos_key = format!("{},{}", x, y);
-    let tile_type_idx = state.current_tile.unwrap_or(0);
+    let tile_type_idx = state.current_tile.ok_or("no tile has been drawn")?;
+    if state.board.tiles.contains_key(&(x, y)) {
+        return Err(format!("position {} is already occupied", pos_key));
+    }
     let tile_type_str = tile_index_to_type(tile_type_idx);
     let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
     let player = &players[player_index];
@@ -436,9 +859,16 @@ fn apply_place_tile(
         tile_type_id: tile_type_idx,
         rotation,
     });
+    state.zobrist ^= tile_key(x, y, tile_type_idx, rotation);
 
-    // Recalculate open positions
-    state.board.open_positions = recalculate_open_positions(&state.board.tiles);
+    // Update open positions, following the river while it is still open
+    let river_was_open = state.river.is_some();
+    state.river = state.river.as_ref().and_then(|r| r.advance(tile_type_idx, rotation));
+    if river_was_open {
+        state.board.open_positions = river_open_positions(state.river.as_ref(), &state.board.tiles);
+    } else {
+        update_open_positions(&state.board.tiles, &mut state.board.open_positions, (x, y));
+    }
 
     state.last_placed_position = Some(pos_key.clone());
     state.current_tile = None;
@@ -457,6 +887,13 @@ fn apply_place_tile(
         }),
     }];
     events.extend(merge_events);
+    if river_was_open && state.river.is_none() {
+        events.push(Event {
+            event_type: "river_completed".into(),
+            player_id: Some(player.player_id.clone()),
+            payload: serde_json::json!({"position": pos_key}),
+        });
+    }
 
     let next_phase = Phase {
         name: "place_meeple".into(),
@@ -472,13 +909,13 @@ fn apply_place_tile(
     };
 
     let scores = state.float_scores();
-    TypedTransitionResult {
+    Ok(TypedTransitionResult {
         state,
         events,
         next_phase,
         scores,
         game_over: None,
-    }
+    })
 }
 
 fn apply_place_meeple(
@@ -486,45 +923,57 @@ fn apply_place_meeple(
     phase: &Phase,
     action: &Action,
     players: &[Player],
-) -> TypedTransitionResult<CarcassonneState> {
+) -> Result<TypedTransitionResult<CarcassonneState>, String> {
     let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
     let player = &players[player_index];
     let mut events: Vec<Event> = Vec::new();
 
     let skip = action.payload.get("skip").and_then(|v| v.as_bool()).unwrap_or(false);
+    let big = action.action_type == "place_big_meeple";
 
     if !skip {
-        let spot = action.payload["meeple_spot"].as_str().unwrap_or("").to_string();
-        let pos = state.last_placed_position.clone().unwrap_or_default();
+        let spot = action.payload["meeple_spot"]
+            .as_str()
+            .ok_or("place_meeple needs a meeple_spot or skip")?
+            .to_string();
+        let pos = state.last_placed_position.clone().ok_or("no tile has been placed")?;
 
         let feature_id = state.tile_feature_map
             .get(&pos)
             .and_then(|spots| spots.get(&spot))
             .cloned()
-            .unwrap_or_default();
+            .ok_or_else(|| format!("no meeple spot '{}' at {}", spot, pos))?;
 
         // Decrement meeple supply
-        if let Some(supply) = state.meeple_supply.get_mut(&player.player_id) {
+        let supply = if big { &mut state.big_meeple_supply } else { &mut state.meeple_supply };
+        if let Some(supply) = supply.get_mut(&player.player_id) {
             *supply -= 1;
         }
 
         // Add meeple to feature
         if let Some(feature) = state.features.get_mut(&feature_id) {
-            feature.meeples.push(PlacedMeeple {
+            let meeple = PlacedMeeple {
                 player_id: player.player_id.clone(),
                 position: pos.clone(),
                 spot: spot.clone(),
-            });
+                count: if big { PlacedMeeple::BIG_COUNT } else { 1 },
+            };
+            state.zobrist ^= meeple_key(&meeple);
+            feature.meeples.push(meeple);
         }
 
+        let mut payload = serde_json::json!({
+            "position": pos,
+            "spot": spot,
+            "feature_id": feature_id,
+        });
+        if big {
+            payload["big"] = serde_json::json!(true);
+        }
         events.push(Event {
             event_type: "meeple_placed".into(),
             player_id: Some(player.player_id.clone()),
-            payload: serde_json::json!({
-                "position": pos,
-                "spot": spot,
-                "feature_id": feature_id,
-            }),
+            payload,
         });
     } else {
         events.push(Event {
@@ -543,13 +992,13 @@ fn apply_place_meeple(
     };
 
     let scores = state.float_scores();
-    TypedTransitionResult {
+    Ok(TypedTransitionResult {
         state,
         events,
         next_phase,
         scores,
         game_over: None,
-    }
+    })
 }
 
 fn apply_score_check(
@@ -595,7 +1044,7 @@ fn apply_score_check(
             feat.is_complete = true;
         }
 
-        let point_awards = score_completed_feature(&state.features[&feature_id]);
+        let point_awards = score_completed_feature(&state.features[&feature_id], state.inns_cathedrals);
 
         let ft = state.features[&feature_id].feature_type;
         let tiles = state.features[&feature_id].tiles.clone();
@@ -624,6 +1073,7 @@ fn apply_score_check(
     for (pid, points) in &monastery_scores {
         *state.scores.entry(pid.clone()).or_insert(0) += points;
     }
+    state.record_score_history();
 
     let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
     let next_index = find_next_player(&state, players, player_index);
@@ -710,34 +1160,31 @@ fn get_valid_tile_placements(
         None => return vec![],
     };
 
-    let has_meeples = state.meeple_supply.get(player_id).copied().unwrap_or(0) > 0;
+    let has_meeples = state.meeple_supply.get(player_id).copied().unwrap_or(0) > 0
+        || state.big_meeple_supply.get(player_id).copied().unwrap_or(0) > 0;
 
     let mut placements = Vec::new();
 
-    for &(x, y) in &state.board.open_positions {
-        for rotation in [0u32, 90, 180, 270] {
-            if can_place_tile(&state.board.tiles, current_tile_idx, (x, y), rotation) {
-                let mut meeple_spots: Vec<String> = Vec::new();
-                if has_meeples {
-                    let rotated_features = get_rotated_features(current_tile_idx, rotation);
-                    let mut seen = std::collections::HashSet::new();
-                    for feat in rotated_features {
-                        for spot in &feat.meeple_spots {
-                            if seen.insert(spot.to_string()) {
-                                meeple_spots.push(spot.to_string());
-                            }
-                        }
+    for ((x, y), rotation) in legal_tile_placements(state, current_tile_idx) {
+        let mut meeple_spots: Vec<String> = Vec::new();
+        if has_meeples {
+            let rotated_features = get_rotated_features(current_tile_idx, rotation);
+            let mut seen = std::collections::HashSet::new();
+            for feat in rotated_features {
+                for spot in &feat.meeple_spots {
+                    if seen.insert(spot.to_string()) {
+                        meeple_spots.push(spot.to_string());
                     }
                 }
-
-                placements.push(serde_json::json!({
-                    "x": x,
-                    "y": y,
-                    "rotation": rotation,
-                    "meeple_spots": meeple_spots,
-                }));
             }
         }
+
+        placements.push(serde_json::json!({
+            "x": x,
+            "y": y,
+            "rotation": rotation,
+            "meeple_spots": meeple_spots,
+        }));
     }
 
     placements
@@ -770,6 +1217,9 @@ fn get_valid_meeple_placements(
             if can_place_meeple(state, player_id, last_pos, spot) {
                 spots.push(serde_json::json!({"meeple_spot": spot}));
             }
+            if can_place_big_meeple(state, player_id, last_pos, spot) {
+                spots.push(serde_json::json!({"meeple_spot": spot, "big_meeple": true}));
+            }
         }
     }
 
@@ -777,6 +1227,33 @@ fn get_valid_meeple_placements(
     spots
 }
 
+/// Placements of a tile that fit its neighbours and, while the river is open,
+/// continue it.
+fn legal_tile_placements(
+    state: &CarcassonneState,
+    tile_type_idx: u8,
+) -> impl Iterator<Item = ((i32, i32), u32)> + '_ {
+    valid_tile_placements(&state.board.tiles, &state.board.open_positions, tile_type_idx)
+        .filter(move |&(pos, rotation)| {
+            state.river.as_ref().is_none_or(|r| r.allows(tile_type_idx, pos, rotation))
+        })
+}
+
+fn has_legal_placement(state: &CarcassonneState, tile_type_idx: u8) -> bool {
+    legal_tile_placements(state, tile_type_idx).next().is_some()
+}
+
+/// Open positions on the board; only the river's open end while it is being built.
+pub(super) fn river_open_positions(
+    river: Option<&RiverState>,
+    board_tiles: &HashMap<(i32, i32), PlacedTile>,
+) -> Vec<(i32, i32)> {
+    match river.and_then(|r| r.open_end_pos()) {
+        Some(pos) => vec![pos],
+        None => recalculate_open_positions(board_tiles),
+    }
+}
+
 // ================================================================== //
 //  Typed validation helpers
 // ================================================================== //
@@ -803,6 +1280,14 @@ fn validate_place_tile(
     };
 
     let pos = (x.unwrap() as i32, y.unwrap() as i32);
+    if let Some(river) = &state.river {
+        if !river.allows(current_tile_idx, pos, rotation) {
+            return Some(format!(
+                "Tile {} at {},{} with rotation {} does not continue the river at {}",
+                tile_index_to_type(current_tile_idx), pos.0, pos.1, rotation, river.open_end
+            ));
+        }
+    }
     if !can_place_tile(&state.board.tiles, current_tile_idx, pos, rotation) {
         return Some(format!(
             "Cannot place tile {} at {},{} with rotation {}",
@@ -817,7 +1302,8 @@ fn validate_place_meeple(
     state: &CarcassonneState,
     action: &Action,
 ) -> Option<String> {
-    if action.payload.get("skip").and_then(|v| v.as_bool()).unwrap_or(false) {
+    let big = action.action_type == "place_big_meeple";
+    if !big && action.payload.get("skip").and_then(|v| v.as_bool()).unwrap_or(false) {
         return None;
     }
 
@@ -831,7 +1317,13 @@ fn validate_place_meeple(
         None => return Some("No tile was placed this turn".into()),
     };
 
-    if !can_place_meeple(state, &action.player_id, last_pos, spot) {
+    if big && !can_place_big_meeple(state, &action.player_id, last_pos, spot) {
+        return Some(format!(
+            "Cannot place big meeple on spot {} at {}",
+            spot, last_pos
+        ));
+    }
+    if !big && !can_place_meeple(state, &action.player_id, last_pos, spot) {
         return Some(format!(
             "Cannot place meeple on spot {} at {}",
             spot, last_pos
@@ -845,50 +1337,226 @@ fn validate_place_meeple(
 //  Utility helpers
 // ================================================================== //
 
-fn find_next_player(
-    state: &CarcassonneState,
-    players: &[Player],
-    current_index: usize,
-) -> usize {
-    let forfeited: std::collections::HashSet<&str> = state.forfeited_players
-        .iter()
-        .map(|s| s.as_str())
-        .collect();
+/// Validate structural state invariants. Returns a list of violation messages
+/// (empty = pass), each tagged with `context`.
+pub fn check_invariants(state: &CarcassonneState, context: &str) -> Vec<String> {
+    let mut violations = Vec::new();
 
-    let num_players = players.len();
-    let mut next = (current_index + 1) % num_players;
-    for _ in 0..num_players {
-        if !forfeited.contains(players[next].player_id.as_str()) {
-            break;
+    // INV1: All feature IDs in tile_feature_map exist in state.features
+    for (pos, spots) in &state.tile_feature_map {
+        for (spot, fid) in spots {
+            if !state.features.contains_key(fid) {
+                violations.push(format!(
+                    "INV1 [{}]: tile_feature_map[{}][{}] -> feature '{}' not in features",
+                    context, pos, spot, fid
+                ));
+            }
         }
-        next = (next + 1) % num_players;
     }
-    next
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::engine::plugin::{GamePlugin, JsonAdapter};
+    // INV2: All features' tiles are on the board
+    for (fid, feat) in &state.features {
+        for tile_pos in &feat.tiles {
+            let on_board = Position::parse_key(tile_pos)
+                .is_some_and(|pos| state.board.tiles.contains_key(&(pos.x, pos.y)));
+            if !on_board {
+                violations.push(format!(
+                    "INV2 [{}]: feature '{}' ({:?}) references tile '{}' not on board",
+                    context, fid, feat.feature_type, tile_pos
+                ));
+            }
+        }
+    }
 
-    fn make_players(n: u32) -> Vec<Player> {
-        (0..n)
-            .map(|i| Player {
-                player_id: format!("p{}", i + 1),
-                display_name: format!("Player {}", i + 1),
-                seat_index: i as i32,
-                is_bot: false,
-                bot_id: None,
-            })
-            .collect()
+    // INV3: Meeple count consistency
+    // Total meeples = placed on features + in supply for each player
+    let mut placed_meeples: HashMap<String, i32> = HashMap::new();
+    let mut placed_big: HashMap<String, i32> = HashMap::new();
+    for feat in state.features.values() {
+        for m in &feat.meeples {
+            let placed = if m.is_big() { &mut placed_big } else { &mut placed_meeples };
+            *placed.entry(m.player_id.clone()).or_insert(0) += 1;
+        }
+    }
+    for pid in placed_big.keys().filter(|pid| !state.big_meeple_supply.contains_key(*pid)) {
+        violations.push(format!("INV3 [{}]: player '{}' has a big meeple without a supply slot", context, pid));
+    }
+    for (pid, &supply) in &state.big_meeple_supply {
+        let placed = placed_big.get(pid).copied().unwrap_or(0);
+        if supply + placed != 1 {
+            violations.push(format!(
+                "INV3 [{}]: player '{}' big meeple count: supply={} + placed={} (expected 1)",
+                context, pid, supply, placed
+            ));
+        }
+    }
+    let mut player_ids: Vec<&String> = state.meeple_supply.keys().chain(placed_meeples.keys()).collect();
+    player_ids.sort();
+    player_ids.dedup();
+    for pid in player_ids {
+        let supply = state.meeple_supply.get(pid).copied().unwrap_or(0);
+        let placed = placed_meeples.get(pid).copied().unwrap_or(0);
+        if supply + placed != state.meeples_per_player {
+            violations.push(format!(
+                "INV3 [{}]: player '{}' meeple count: supply={} + placed={} = {} (expected {})",
+                context, pid, supply, placed, supply + placed, state.meeples_per_player
+            ));
+        }
     }
 
-    #[test]
-    fn test_create_initial_state() {
-        let plugin = CarcassonnePlugin;
-        let players = make_players(2);
-        let config = GameConfig {
-            random_seed: Some(42),
+    // INV4: Completed features should have no meeples (they get returned)
+    for (fid, feat) in &state.features {
+        if feat.is_complete && !feat.meeples.is_empty() {
+            violations.push(format!(
+                "INV4 [{}]: completed feature '{}' ({:?}) still has {} meeples",
+                context, fid, feat.feature_type, feat.meeples.len()
+            ));
+        }
+    }
+
+    // INV5: Open edges should reference positions in the feature's tiles
+    for (fid, feat) in &state.features {
+        for oe in &feat.open_edges {
+            if !feat.tiles.contains(&oe[0]) {
+                violations.push(format!(
+                    "INV5 [{}]: feature '{}' open_edge [{}, {}] references tile not in feature.tiles",
+                    context, fid, oe[0], oe[1]
+                ));
+            }
+        }
+    }
+
+    // INV6: Non-complete city/road features with open edges - the neighbor
+    // position in that direction should NOT be on the board
+    for (fid, feat) in &state.features {
+        if feat.is_complete {
+            continue;
+        }
+        if !matches!(feat.feature_type, FeatureType::City | FeatureType::Road) {
+            continue;
+        }
+        for oe in &feat.open_edges {
+            let dir = oe[1].split(':').next().unwrap_or(&oe[1]);
+            let Some(pos) = Position::parse_key(&oe[0]) else { continue };  // reported by INV2/INV5
+            if !matches!(dir, "N" | "E" | "S" | "W") {
+                violations.push(format!(
+                    "INV6 [{}]: feature '{}' has open_edge [{}, {}] with an invalid direction",
+                    context, fid, oe[0], oe[1]
+                ));
+                continue;
+            }
+            let neighbor = pos.neighbor(dir);
+            if state.board.tiles.contains_key(&(neighbor.x, neighbor.y)) {
+                violations.push(format!(
+                    "INV6 [{}]: feature '{}' ({:?}) has open_edge [{}, {}] but neighbor '{},{}' IS on board",
+                    context, fid, feat.feature_type, oe[0], oe[1], neighbor.x, neighbor.y
+                ));
+            }
+        }
+    }
+
+    // INV7: All tile positions on board should have entries in tile_feature_map
+    for &(x, y) in state.board.tiles.keys() {
+        let pos_key = format!("{},{}", x, y);
+        if !state.tile_feature_map.contains_key(&pos_key) {
+            violations.push(format!(
+                "INV7 [{}]: board tile at '{}' has no tile_feature_map entry",
+                context, pos_key
+            ));
+        }
+    }
+
+    // INV8: Scores should never be negative
+    for (pid, &score) in &state.scores {
+        if score < 0 {
+            violations.push(format!(
+                "INV8 [{}]: player '{}' has negative score: {}",
+                context, pid, score
+            ));
+        }
+    }
+
+    // INV9: All tile types (board, bag, drawn tile) are known
+    let tile_types = state.board.tiles.values().map(|t| t.tile_type_id)
+        .chain(state.tile_bag.iter().copied())
+        .chain(state.current_tile);
+    for idx in tile_types {
+        if idx as usize >= TILE_DEFS.len() {
+            violations.push(format!("INV9 [{}]: unknown tile type index {}", context, idx));
+        }
+    }
+
+    // INV10: A city's pennants match its pennant-bearing tiles (each pennant
+    // tile has a single city); other features have none
+    for (fid, feat) in &state.features {
+        let expected = match feat.feature_type {
+            FeatureType::City => feat
+                .tiles
+                .iter()
+                .filter_map(|key| Position::parse_key(key))
+                .filter_map(|pos| state.board.tiles.get(&(pos.x, pos.y)))
+                .filter(|tile| {
+                    (tile.tile_type_id as usize) < TILE_DEFS.len() && tile_has_pennant(tile.tile_type_id)
+                })
+                .count() as u32,
+            _ => 0,
+        };
+        if feat.pennants != expected {
+            violations.push(format!(
+                "INV10 [{}]: feature '{}' ({:?}) has {} pennants, expected {}",
+                context, fid, feat.feature_type, feat.pennants, expected
+            ));
+        }
+    }
+
+    violations
+}
+
+
+fn find_next_player(
+    state: &CarcassonneState,
+    players: &[Player],
+    current_index: usize,
+) -> usize {
+    let forfeited: std::collections::HashSet<&str> = state.forfeited_players
+        .iter()
+        .map(|s| s.as_str())
+        .collect();
+
+    let num_players = players.len();
+    let mut next = (current_index + 1) % num_players;
+    for _ in 0..num_players {
+        if !forfeited.contains(players[next].player_id.as_str()) {
+            break;
+        }
+        next = (next + 1) % num_players;
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::{GamePlugin, JsonAdapter};
+
+    fn make_players(n: u32) -> Vec<Player> {
+        (0..n)
+            .map(|i| Player {
+                player_id: format!("p{}", i + 1),
+                display_name: format!("Player {}", i + 1),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_create_initial_state() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
             options: serde_json::json!({}),
         };
 
@@ -914,6 +1582,884 @@ mod tests {
         assert_eq!(events[1].event_type, "starting_tile_placed");
     }
 
+    #[test]
+    fn test_prunable_actions_hint_skip_on_free_claim() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        // Place the V road curve away from everything: a free road claim with both
+        // ends open. Placement legality is not checked.
+        let place_v = |state: &CarcassonneState| {
+            let mut state = state.clone();
+            state.current_tile = Some(tile_type_to_index("V"));
+            let phase = Phase {
+                name: "place_tile".into(),
+                concurrent_mode: Some(ConcurrentMode::Sequential),
+                expected_actions: vec![],
+                auto_resolve: false,
+                metadata: serde_json::json!({"player_index": 0}),
+            };
+            let place = Action {
+                action_type: "place_tile".into(),
+                player_id: "p1".into(),
+                payload: serde_json::json!({"x": 5, "y": 6, "rotation": 0}),
+            };
+            assert!(plugin.prunable_actions(&state, &phase, "p1").is_empty());
+            plugin.apply_action(&state, &phase, &place, &players).unwrap()
+        };
+
+        // With a full bag the road is likely to complete: skipping is dominated
+        let r = place_v(&state);
+        assert_eq!(r.next_phase.name, "place_meeple");
+        let valid = plugin.get_valid_actions(&r.state, &r.next_phase, "p1");
+        assert!(valid.iter().any(|a| a["meeple_spot"].as_str().is_some_and(|s| s.starts_with("road"))));
+        let hints = plugin.prunable_actions(&r.state, &r.next_phase, "p1");
+        assert_eq!(hints, vec![serde_json::json!({"skip": true})]);
+        assert!(hints.iter().all(|h| valid.contains(h)));
+
+        // One tile left: the road has no real chance to complete, so no hint
+        let mut last_tile = state.clone();
+        last_tile.tile_bag.truncate(1);
+        let r = place_v(&last_tile);
+        assert!(plugin.get_valid_actions(&r.state, &r.next_phase, "p1").len() > 1);
+        assert!(plugin.prunable_actions(&r.state, &r.next_phase, "p1").is_empty());
+
+        // Nor without a meeple to place
+        let mut no_meeples = state.clone();
+        no_meeples.meeple_supply.insert("p1".into(), 0);
+        let r = place_v(&no_meeples);
+        assert!(plugin.prunable_actions(&r.state, &r.next_phase, "p1").is_empty());
+    }
+
+    /// Place `tile` at (x, y) for p1, optionally claiming the first spot starting with
+    /// `claim`, then run score_check. Placement legality is not checked, so tests can
+    /// build shapes away from the starting tile. Returns the score_check events.
+    fn play_tile(
+        state: CarcassonneState,
+        players: &[Player],
+        tile: &str,
+        (x, y, rotation): (i32, i32, u32),
+        claim: Option<&str>,
+    ) -> (CarcassonneState, Vec<Event>) {
+        let plugin = CarcassonnePlugin;
+        let mut state = state;
+        state.current_tile = Some(tile_type_to_index(tile));
+        let phase = Phase {
+            name: "place_tile".into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        let place = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"x": x, "y": y, "rotation": rotation}),
+        };
+        let r = plugin.apply_action(&state, &phase, &place, players).unwrap();
+
+        let payload = claim
+            .and_then(|prefix| {
+                plugin.get_valid_actions(&r.state, &r.next_phase, "p1").into_iter().find(|a| {
+                    a.get("meeple_spot")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(|s| s.starts_with(prefix))
+                })
+            })
+            .unwrap_or_else(|| serde_json::json!({"skip": true}));
+        let meeple = Action {
+            action_type: "place_meeple".into(),
+            player_id: "p1".into(),
+            payload,
+        };
+        let r = plugin.apply_action(&r.state, &r.next_phase, &meeple, players).unwrap();
+
+        let score = Action {
+            action_type: "score_check".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({}),
+        };
+        let r = plugin.apply_action(&r.state, &r.next_phase, &score, players).unwrap();
+        (r.state, r.events)
+    }
+
+    fn scored_events(events: &[Event]) -> Vec<&Event> {
+        events.iter().filter(|e| e.event_type == "feature_scored").collect()
+    }
+
+    /// Feature id behind the first meeple spot at `pos` starting with `prefix`.
+    fn feature_at(state: &CarcassonneState, pos: &str, prefix: &str) -> String {
+        state.tile_feature_map[pos]
+            .iter()
+            .find(|(spot, _)| spot.starts_with(prefix))
+            .map(|(_, fid)| fid.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_looped_road_completes_once() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+
+        // Four V curves closing a 2x2 ring: NW(E,S) NE(W,S) SE(N,W) SW(N,E)
+        let (state, events) = play_tile(state, &players, "V", (5, 6, 270), Some("road"));
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "V", (6, 6, 0), None);
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "V", (6, 5, 90), None);
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "V", (5, 5, 180), None);
+
+        let scored = scored_events(&events);
+        assert_eq!(scored.len(), 1, "loop should be scored exactly once");
+        assert_eq!(scored[0].payload["feature_type"], "road");
+        assert_eq!(scored[0].payload["points"], 4);
+        assert_eq!(state.scores["p1"], 4);
+        assert_eq!(state.meeple_supply["p1"], 7, "meeple returned after scoring");
+
+        let road = &state.features[&feature_at(&state, "5,6", "road")];
+        assert!(road.is_complete);
+        assert!(road.open_edges.is_empty());
+        assert_eq!(road.tiles.len(), 4);
+
+        // A later tile touching the loop must not score it again
+        let (state, events) = play_tile(state, &players, "E", (5, 7, 180), None);
+        assert!(scored_events(&events).is_empty());
+        assert_eq!(state.scores["p1"], 4);
+    }
+
+    #[test]
+    fn test_merged_feature_keeps_earliest_id() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (start, _, _) = plugin.create_initial_state(&players, &config);
+
+        // A city capped at both ends: E (city E), G (city E-W), E (city W)
+        let segments = [("E", (-1, 5, 90)), ("G", (0, 5, 90)), ("E", (1, 5, 270))];
+        let orders = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        for order in orders {
+            let first_pos = {
+                let (x, y, _) = segments[order[0]].1;
+                format!("{x},{y}")
+            };
+            let mut state = start.clone();
+            let mut first_id = None;
+            let mut events = vec![];
+            for &i in &order {
+                let (tile, placement) = segments[i];
+                (state, events) = play_tile(state, &players, tile, placement, None);
+                // Once assigned, the first segment's id never changes
+                let id = feature_at(&state, &first_pos, "city");
+                assert_eq!(*first_id.get_or_insert_with(|| id.clone()), id, "order {order:?}");
+            }
+
+            let first_id = first_id.unwrap();
+            for pos in ["-1,5", "0,5", "1,5"] {
+                assert_eq!(feature_at(&state, pos, "city"), first_id, "order {order:?}");
+            }
+            let city = &state.features[&first_id];
+            assert_eq!(city.tiles.len(), 3);
+            assert!(city.is_complete, "order {order:?}");
+            assert_eq!(scored_events(&events).len(), 0, "unclaimed city scores nobody");
+            assert!(check_invariants(&state, "merge order").is_empty());
+
+            // Replaying the same order gives the same ids
+            let mut replay = start.clone();
+            for &i in &order {
+                let (tile, placement) = segments[i];
+                (replay, _) = play_tile(replay, &players, tile, placement, None);
+            }
+            assert_eq!(feature_at(&replay, "0,5", "city"), first_id);
+        }
+    }
+
+    #[test]
+    fn test_zobrist_hash_ignores_placement_order() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (start, _, _) = plugin.create_initial_state(&players, &config);
+        assert_eq!(start.zobrist, board_hash(&start));
+
+        let (a, _) = play_tile(start.clone(), &players, "V", (5, 6, 270), Some("road"));
+        let (a, _) = play_tile(a, &players, "E", (-3, 2, 90), None);
+        let (b, _) = play_tile(start.clone(), &players, "E", (-3, 2, 90), None);
+        let (b, _) = play_tile(b, &players, "V", (5, 6, 270), Some("road"));
+        assert_eq!(a.zobrist, b.zobrist);
+        assert_eq!(plugin.state_hash(&a), plugin.state_hash(&b));
+        assert_eq!(a.zobrist, board_hash(&a));
+
+        // Without the meeple it's a different position
+        let (c, _) = play_tile(start.clone(), &players, "E", (-3, 2, 90), None);
+        let (c, _) = play_tile(c, &players, "V", (5, 6, 270), None);
+        assert_ne!(a.zobrist, c.zobrist);
+
+        // Merges grow the meeple's feature and scoring returns it; the
+        // incremental hash keeps matching a full recount and survives decoding
+        let (d, _) = play_tile(a, &players, "V", (6, 6, 0), None);
+        let (d, _) = play_tile(d, &players, "V", (6, 5, 90), None);
+        let (d, events) = play_tile(d, &players, "V", (5, 5, 180), None);
+        assert_eq!(scored_events(&events).len(), 1);
+        assert_eq!(d.zobrist, board_hash(&d));
+        let decoded = plugin.try_decode_state(&plugin.encode_state(&d)).unwrap();
+        assert_eq!(plugin.state_hash(&decoded), plugin.state_hash(&d));
+    }
+
+    #[test]
+    fn test_score_history_records_each_score_check() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"track_score_history": true}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        assert_eq!(state.score_history.as_deref(), Some(&[][..]));
+
+        let (state, _) = play_tile(state, &players, "V", (5, 6, 270), Some("road"));
+        let (state, _) = play_tile(state, &players, "V", (6, 6, 0), None);
+        let (state, _) = play_tile(state, &players, "V", (6, 5, 90), None);
+        let (mut state, _) = play_tile(state, &players, "V", (5, 5, 180), None);
+
+        let history = state.score_history.as_ref().unwrap();
+        let p1: Vec<i64> = history.iter().map(|h| h["p1"]).collect();
+        assert_eq!(p1, vec![0, 0, 0, 4]);
+        assert!(history.iter().all(|h| h["p2"] == 0));
+        assert_eq!(plugin.encode_state(&state)["score_history"][3]["p1"], 4);
+
+        plugin.determinize(&mut state, &mut GameRng::from_seed(0));
+        assert!(state.score_history.is_none());
+    }
+
+    #[test]
+    fn test_score_history_off_by_default() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        let (state, _) = play_tile(state, &players, "V", (5, 6, 270), None);
+        assert!(state.score_history.is_none());
+        assert!(plugin.encode_state(&state).get("score_history").is_none());
+    }
+
+    #[test]
+    fn test_ai_view_placement_groups_match_flat_placements() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        for seed in [1u64, 7, 42] {
+            let config = GameConfig {
+                random_seed: Some(seed),
+                options: serde_json::json!({"ai_view_placement_groups": true}),
+            };
+            let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+            for _ in 0..12 {
+                let pid = plugin.acting_player(&state, &phase, &players).unwrap();
+                let valid = plugin.get_valid_actions(&state, &phase, &pid);
+                let view = plugin.state_to_ai_view(&state, &phase, &pid, &players);
+                if phase.name == "place_tile" {
+                    let mut flat: Vec<(String, u64)> = valid
+                        .iter()
+                        .map(|a| (format!("{},{}", a["x"], a["y"]), a["rotation"].as_u64().unwrap()))
+                        .collect();
+                    let mut grouped: Vec<(String, u64)> = view["placements_by_position"]
+                        .as_object()
+                        .unwrap()
+                        .iter()
+                        .flat_map(|(pos, rots)| {
+                            rots.as_array().unwrap().iter().map(|r| (pos.clone(), r.as_u64().unwrap()))
+                        })
+                        .collect();
+                    flat.sort();
+                    grouped.sort();
+                    assert_eq!(grouped, flat);
+                } else {
+                    assert!(view.get("placements_by_position").is_none());
+                }
+                let payload = valid.into_iter().next().unwrap_or_else(|| serde_json::json!({}));
+                let action = Action { action_type: phase.name.clone(), player_id: pid, payload };
+                let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+                state = r.state;
+                phase = r.next_phase;
+            }
+        }
+
+        // Off by default
+        let config = GameConfig { random_seed: Some(1), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let draw = Action { action_type: "draw_tile".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+        assert_eq!(r.next_phase.name, "place_tile");
+        let view = plugin.state_to_ai_view(&r.state, &r.next_phase, "p1", &players);
+        assert!(view.get("placements_by_position").is_none());
+        assert!(plugin.encode_state(&r.state).get("ai_view_placement_groups").is_none());
+    }
+
+    /// Draw from `bag` with only (0,1) open, above the starting tile's city
+    /// edge, so tiles without a city edge (like V) cannot be placed.
+    fn draw_with_policy(policy: &str, bag: &[&str]) -> TypedTransitionResult<CarcassonneState> {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"unplaceable_policy": policy}),
+        };
+        let (mut state, phase, _) = plugin.create_initial_state(&players, &config);
+        state.tile_bag = bag.iter().map(|t| tile_type_to_index(t)).collect();
+        state.board.open_positions = vec![(0, 1)];
+        let draw = Action {
+            action_type: "draw_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({}),
+        };
+        plugin.apply_action(&state, &phase, &draw, &players).unwrap()
+    }
+
+    fn bag_types(state: &CarcassonneState) -> Vec<&'static str> {
+        state.tile_bag.iter().map(|&t| tile_index_to_type(t)).collect()
+    }
+
+    #[test]
+    fn test_unplaceable_policy_discard() {
+        let result = draw_with_policy("discard", &["V", "E", "V"]);
+        assert_eq!(result.next_phase.name, "place_tile");
+        assert_eq!(result.state.current_tile, Some(tile_type_to_index("E")));
+        assert_eq!(bag_types(&result.state), vec!["V"]);
+
+        // Also the default, and not written out when default
+        assert_eq!(result.state.unplaceable_policy, UnplaceablePolicy::Discard);
+        assert!(result.state.to_json().get("unplaceable_policy").is_none());
+
+        let result = draw_with_policy("discard", &["V"]);
+        assert_eq!(result.next_phase.name, "end_game_scoring");
+        let kinds: Vec<&str> = result.events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(kinds, vec!["tile_discarded", "tile_bag_empty"]);
+    }
+
+    #[test]
+    fn test_unplaceable_policy_bottom() {
+        let result = draw_with_policy("bottom", &["V", "E", "V"]);
+        assert_eq!(result.next_phase.name, "place_tile");
+        assert_eq!(result.state.current_tile, Some(tile_type_to_index("E")));
+        assert_eq!(bag_types(&result.state), vec!["V", "V"]);
+        assert_eq!(result.state.to_json()["unplaceable_policy"], "bottom");
+
+        // A full lap with nothing placeable ends the game, keeping the bag intact
+        let result = draw_with_policy("bottom", &["V", "U", "V"]);
+        assert_eq!(result.next_phase.name, "end_game_scoring");
+        assert_eq!(bag_types(&result.state), vec!["V", "U", "V"]);
+        assert_eq!(result.events[0].event_type, "no_placeable_tiles");
+    }
+
+    #[test]
+    fn test_unplaceable_policy_end_game() {
+        let result = draw_with_policy("end_game", &["V", "E"]);
+        assert_eq!(result.next_phase.name, "end_game_scoring");
+        assert_eq!(result.state.current_tile, None);
+        assert_eq!(bag_types(&result.state), vec!["E"]);
+        assert_eq!(result.events[0].event_type, "tile_discarded");
+
+        // Placeable tiles are unaffected
+        let result = draw_with_policy("end_game", &["E", "V"]);
+        assert_eq!(result.next_phase.name, "place_tile");
+    }
+
+    #[test]
+    fn test_unplaceable_policy_validation() {
+        let config = |policy: serde_json::Value| GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"unplaceable_policy": policy}),
+        };
+        for policy in ["discard", "bottom", "end_game"] {
+            assert!(CarcassonnePlugin.validate_config(&config(serde_json::json!(policy))).is_ok());
+        }
+        for bad in [serde_json::json!("skip"), serde_json::json!("Bottom"), serde_json::json!(1)] {
+            assert!(CarcassonnePlugin.validate_config(&config(bad.clone())).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_expansions_validation() {
+        let config = |expansions: serde_json::Value| GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"expansions": expansions}),
+        };
+        for ok in [serde_json::json!([]), serde_json::json!(["river", "inns_cathedrals"])] {
+            assert!(CarcassonnePlugin.validate_config(&config(ok.clone())).is_ok(), "{ok}");
+        }
+        for bad in [serde_json::json!(["rivr"]), serde_json::json!(["river", "traders"]), serde_json::json!("river")] {
+            assert!(CarcassonnePlugin.validate_config(&config(bad.clone())).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_acting_player_per_phase() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let mut seen = Vec::new();
+        let mut current = (state, phase);
+
+        // One full turn: draw, place, skip meeple, score, then the next draw
+        for _ in 0..5 {
+            let (state, phase) = &current;
+            let acting = plugin.acting_player(state, phase, &players);
+            seen.push((phase.name.clone(), acting.clone()));
+            let payload = match phase.name.as_str() {
+                "place_tile" => get_valid_tile_placements(state, "p1")[0].clone(),
+                "place_meeple" => serde_json::json!({"skip": true}),
+                _ => serde_json::json!({}),
+            };
+            let action = Action {
+                action_type: phase.name.clone(),
+                player_id: acting.unwrap_or_else(|| "system".into()),
+                payload,
+            };
+            let result = plugin.apply_action(state, phase, &action, &players).unwrap();
+            current = (result.state, result.next_phase);
+        }
+
+        let expected = [
+            ("draw_tile", "p1"),
+            ("place_tile", "p1"),
+            ("place_meeple", "p1"),
+            ("score_check", "p1"),
+            ("draw_tile", "p2"),
+        ];
+        let expected: Vec<(String, Option<String>)> = expected
+            .iter()
+            .map(|&(name, pid)| (name.to_string(), Some(pid.to_string())))
+            .collect();
+        assert_eq!(seen, expected);
+
+        let end = Phase {
+            name: "end_game_scoring".into(),
+            auto_resolve: true,
+            concurrent_mode: None,
+            expected_actions: vec![],
+            metadata: serde_json::json!({}),
+        };
+        assert_eq!(plugin.acting_player(&current.0, &end, &players), None);
+    }
+
+    #[test]
+    fn test_enclosed_city_completes_once() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+
+        // Full-city C (with pennant) capped on every side by E tiles facing it
+        let (state, _) = play_tile(state, &players, "C", (5, 5, 0), Some("city"));
+        let (state, events) = play_tile(state, &players, "E", (5, 6, 180), None);
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "E", (6, 5, 270), None);
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "E", (5, 4, 0), None);
+        assert!(scored_events(&events).is_empty());
+        let (state, events) = play_tile(state, &players, "E", (4, 5, 90), None);
+
+        let scored = scored_events(&events);
+        assert_eq!(scored.len(), 1, "city should be scored exactly once");
+        assert_eq!(scored[0].payload["feature_type"], "city");
+        // 5 tiles + 1 pennant, 2 points each
+        assert_eq!(scored[0].payload["points"], 12);
+        assert_eq!(state.scores["p1"], 12);
+
+        // Two city caps facing each other close a 2-tile city immediately
+        let (state, _) = play_tile(state, &players, "E", (10, 10, 0), Some("city"));
+        let (state, events) = play_tile(state, &players, "E", (10, 11, 180), None);
+        let scored = scored_events(&events);
+        assert_eq!(scored.len(), 1);
+        assert_eq!(scored[0].payload["points"], 4);
+        assert_eq!(state.scores["p1"], 16);
+    }
+
+    #[test]
+    fn test_describe_meeple() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        let city_spot = |state: &CarcassonneState, pos: &str| {
+            state.tile_feature_map[pos].keys().find(|s| s.starts_with("city")).unwrap().clone()
+        };
+
+        // Open city cap claimed by p1
+        let (state, _) = play_tile(state, &players, "E", (10, 10, 0), Some("city"));
+        let spot = city_spot(&state, "10,10");
+        let open = plugin.describe_meeple(&state, "10,10", &spot).unwrap();
+        assert_eq!(open["is_complete"], false);
+        assert_eq!(open["meeple"], "p1");
+        assert_eq!(open["leaders"], serde_json::json!(["p1"]));
+        assert_eq!(open["tiles"], 1);
+        assert!(open["completion_probability"].as_f64().unwrap() > 0.0);
+
+        // Closing it scores 4 and returns the meeple
+        let (state, events) = play_tile(state, &players, "E", (10, 11, 180), None);
+        assert_eq!(scored_events(&events)[0].payload["points"], 4);
+        let done = plugin.describe_meeple(&state, "10,10", &spot).unwrap();
+        assert_eq!(done["is_complete"], true);
+        assert_eq!(done["projected_points"], 4.0);
+        assert_eq!(done["completion_probability"], 1.0);
+        assert_eq!(done["tiles"], 2);
+        assert!(done["meeple"].is_null());
+
+        assert!(plugin.describe_meeple(&state, "10,10", "no_such_spot").is_none());
+        assert!(plugin.describe_meeple(&state, "99,99", &spot).is_none());
+    }
+
+    #[test]
+    fn test_end_game_scoring_majority() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+
+        // Open one-tile city claimed by p1 (p2 is added to it further down)
+        let (state, _) = play_tile(state, &players, "E", (5, 5, 0), Some("city"));
+        // Open road with a lone farmer beside it and nobody on the road
+        let (mut state, _) = play_tile(state, &players, "V", (10, 10, 0), Some("field"));
+        let city_id = feature_at(&state, "5,5", "city");
+        let road_id = feature_at(&state, "10,10", "road");
+        assert!(state.features[&road_id].meeples.is_empty());
+        assert!(!state.features[&city_id].is_complete);
+
+        let p2_meeple = PlacedMeeple {
+            player_id: "p2".into(),
+            position: "5,5".into(),
+            spot: state.features[&city_id].meeples[0].spot.clone(),
+            count: 1,
+        };
+        state.features.get_mut(&city_id).unwrap().meeples.push(p2_meeple);
+
+        // Tied city: both holders score the incomplete value (1 tile, no pennant)
+        let (scores, breakdown) = score_end_game(&state);
+        assert_eq!(scores["p1"], 1);
+        assert_eq!(scores["p2"], 1);
+        assert_eq!(breakdown["p1"]["cities"], 1);
+        assert_eq!(breakdown["p2"]["cities"], 1);
+        // The unclaimed road scores for nobody; the farmer has no completed city
+        assert_eq!(breakdown["p1"]["roads"], 0);
+        assert_eq!(breakdown["p1"]["fields"], 0);
+
+        // A second p1 meeple makes p2 a minority holder, who then scores nothing
+        let extra = state.features[&city_id].meeples[0].clone();
+        state.features.get_mut(&city_id).unwrap().meeples.push(extra);
+        let (scores, breakdown) = score_end_game(&state);
+        assert_eq!(scores["p1"], 1);
+        assert_eq!(scores.get("p2").copied().unwrap_or(0), 0);
+        assert!(!breakdown.contains_key("p2"));
+    }
+
+    #[test]
+    fn test_inns_cathedrals_scoring() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = |options| GameConfig { random_seed: Some(42), options };
+        let (plain, _, _) = plugin.create_initial_state(&players, &config(serde_json::json!({})));
+        let (expansion, _, _) = plugin.create_initial_state(
+            &players,
+            &config(serde_json::json!({"expansions": ["inns_cathedrals"]})),
+        );
+        assert!(expansion.inns_cathedrals);
+        assert_eq!(expansion.tile_bag.len(), plain.tile_bag.len() + 8);
+
+        // An inn road extends the starting tile's road: the merged road keeps the inn
+        let build = |state: CarcassonneState| {
+            let (state, _) = play_tile(state, &players, "IC2", (1, 0, 0), Some("road"));
+            let road_id = feature_at(&state, "1,0", "road");
+            assert!(state.features[&road_id].has_inn);
+            assert_eq!(state.features[&road_id].tiles.len(), 2);
+            (state, road_id)
+        };
+
+        // Incomplete at game end: 1 per tile normally, nothing with the expansion
+        let (plain, road_id) = build(plain);
+        let (expansion, _) = build(expansion);
+        assert_eq!(score_end_game(&plain).0["p1"], 2);
+        assert_eq!(score_end_game(&expansion).0.get("p1").copied().unwrap_or(0), 0);
+
+        // Completed: 1 per tile normally, 2 with the expansion
+        let road = &plain.features[&road_id];
+        assert_eq!(score_completed_feature(road, false)["p1"], 2);
+        assert_eq!(score_completed_feature(road, true)["p1"], 4);
+
+        // Cathedral cities: 3 per tile and pennant when complete, nothing when not
+        let (state, _) = play_tile(expansion, &players, "IC1", (0, 1, 0), Some("city"));
+        let city_id = feature_at(&state, "0,1", "city");
+        let city = &state.features[&city_id];
+        assert!(city.has_cathedral && !city.is_complete);
+        assert_eq!(score_completed_feature(city, true)["p1"], 6);
+        assert_eq!(score_completed_feature(city, false)["p1"], 4);
+        assert_eq!(score_end_game(&state).1["p1"]["cities"], 0);
+    }
+
+    #[test]
+    fn test_parse_ai_action_big_meeple() {
+        let plugin = CarcassonnePlugin;
+        let phase = Phase {
+            name: "place_meeple".into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![ExpectedAction {
+                player_id: "p1".into(),
+                action_type: "place_meeple".into(),
+                constraints: Default::default(),
+                timeout_ms: None,
+            }],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        let big = serde_json::json!({"action": {"payload": {"meeple_spot": "city_N", "big_meeple": true}}});
+        let action = plugin.parse_ai_action(&big, &phase, "p1");
+        assert_eq!(action.action_type, "place_big_meeple");
+        assert_eq!(action.payload["meeple_spot"], "city_N");
+
+        let normal = serde_json::json!({"meeple_spot": "city_N"});
+        assert_eq!(plugin.parse_ai_action(&normal, &phase, "p1").action_type, "place_meeple");
+    }
+
+    #[test]
+    fn test_big_meeple_wins_city_tie() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"expansions": ["inns_cathedrals"]}),
+        };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        assert_eq!(state.big_meeple_supply["p1"], 1);
+
+        // p2 holds an open city with one normal meeple (claimed as p1, then handed over)
+        let (mut state, _) = play_tile(state, &players, "E", (5, 5, 0), Some("city"));
+        let city_id = feature_at(&state, "5,5", "city");
+        state.features.get_mut(&city_id).unwrap().meeples[0].player_id = "p2".into();
+        *state.meeple_supply.get_mut("p1").unwrap() += 1;
+        *state.meeple_supply.get_mut("p2").unwrap() -= 1;
+
+        // p1 puts the big meeple on a second city segment facing it
+        let phase = |name: &str| Phase {
+            name: name.into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![ExpectedAction {
+                player_id: "p1".into(),
+                action_type: name.into(),
+                constraints: Default::default(),
+                timeout_ms: None,
+            }],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        state.current_tile = Some(tile_type_to_index("E"));
+        let place = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"x": 5, "y": 7, "rotation": 180}),
+        };
+        let r = plugin.apply_action(&state, &phase("place_tile"), &place, &players).unwrap();
+        let valid = plugin.get_valid_actions(&r.state, &r.next_phase, "p1");
+        let payload = valid
+            .iter()
+            .find(|a| a["big_meeple"] == true && a["meeple_spot"] == "city_S")
+            .expect("big meeple placement should be offered")
+            .clone();
+        let claim = Action {
+            action_type: plugin.action_type_for(&r.next_phase, &payload),
+            player_id: "p1".into(),
+            payload,
+        };
+        assert_eq!(claim.action_type, "place_big_meeple");
+        assert_eq!(plugin.validate_action(&r.state, &r.next_phase, &claim), None);
+        let r = plugin.apply_action(&r.state, &r.next_phase, &claim, &players).unwrap();
+        assert_eq!(r.state.big_meeple_supply["p1"], 0);
+        assert_eq!(r.state.meeple_supply["p1"], MEEPLES_PER_PLAYER);
+        let score = Action {
+            action_type: "score_check".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({}),
+        };
+        let mut state = plugin.apply_action(&r.state, &phase("score_check"), &score, &players).unwrap().state;
+
+        // Without a big meeple left, only normal placements are listed
+        state.last_placed_position = Some("5,7".into());
+        let valid = plugin.get_valid_actions(&state, &phase("place_meeple"), "p1");
+        assert!(valid.iter().all(|a| a.get("big_meeple").is_none()));
+
+        // Joining the two segments: 2 vs 1, so only p1 scores the city
+        let (state, events) = play_tile(state, &players, "G", (5, 6, 0), None);
+        let scored = scored_events(&events);
+        assert_eq!(scored.len(), 1);
+        assert_eq!(scored[0].player_id.as_deref(), Some("p1"));
+        assert_eq!(scored[0].payload["points"], 6);
+
+        // Each meeple goes back to its own supply slot
+        assert_eq!(state.big_meeple_supply["p1"], 1);
+        assert_eq!(state.meeple_supply["p2"], MEEPLES_PER_PLAYER);
+        assert!(check_invariants(&state, "big meeple").is_empty());
+    }
+
+    #[test]
+    fn test_meeples_per_player_option() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = |n: serde_json::Value| GameConfig {
+            random_seed: Some(42),
+            options: serde_json::json!({"meeples_per_player": n}),
+        };
+        assert!(plugin.validate_config(&config(serde_json::json!(0))).is_err());
+        assert!(plugin.validate_config(&config(serde_json::json!(21))).is_err());
+        assert!(plugin.validate_config(&config(serde_json::json!("3"))).is_err());
+        assert!(plugin.validate_config(&config(serde_json::json!(3))).is_ok());
+
+        let (mut state, _, _) = plugin.create_initial_state(&players, &config(serde_json::json!(3)));
+        assert_eq!(state.meeple_supply["p1"], 3);
+        assert_eq!(state.meeples_per_player, 3);
+
+        for pos in [(5, 5), (10, 10), (15, 15)] {
+            state = play_tile(state, &players, "E", (pos.0, pos.1, 0), Some("city")).0;
+        }
+        assert_eq!(state.meeple_supply["p1"], 0);
+        assert!(check_invariants(&state, "meeples_per_player").is_empty());
+
+        // With every meeple on the board the only meeple action is to skip
+        state.current_tile = Some(tile_type_to_index("E"));
+        let phase = Phase {
+            name: "place_tile".into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        let place = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"x": 20, "y": 20, "rotation": 0}),
+        };
+        let r = plugin.apply_action(&state, &phase, &place, &players).unwrap();
+        let actions = plugin.get_valid_actions(&r.state, &r.next_phase, "p1");
+        assert_eq!(actions, vec![serde_json::json!({"skip": true})]);
+    }
+
+    #[test]
+    fn test_malformed_actions_fail_to_apply() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (mut state, _, _) = plugin.create_initial_state(&players, &config);
+        let phase = |name: &str, player_index: usize| Phase {
+            name: name.into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": player_index}),
+        };
+        let action = |action_type: &str, payload| Action {
+            action_type: action_type.into(),
+            player_id: "p1".into(),
+            payload,
+        };
+
+        // No tile drawn yet, then a missing coordinate, then an occupied spot
+        let place = action("place_tile", serde_json::json!({"x": 0, "y": 1, "rotation": 0}));
+        assert!(plugin.apply_action(&state, &phase("place_tile", 0), &place, &players).is_err());
+        state.current_tile = Some(tile_type_to_index("E"));
+        let no_y = action("place_tile", serde_json::json!({"x": 0, "rotation": 0}));
+        assert!(plugin.apply_action(&state, &phase("place_tile", 0), &no_y, &players).is_err());
+        let occupied = action("place_tile", serde_json::json!({"x": 0, "y": 0, "rotation": 0}));
+        assert!(plugin.apply_action(&state, &phase("place_tile", 0), &occupied, &players).is_err());
+        assert!(plugin.apply_action(&state, &phase("place_tile", 5), &place, &players).is_err());
+
+        let placed = plugin.apply_action(&state, &phase("place_tile", 0), &place, &players).unwrap();
+        for payload in [serde_json::json!({}), serde_json::json!({"meeple_spot": "nowhere"})] {
+            let meeple = action("place_meeple", payload);
+            assert!(plugin.apply_action(&placed.state, &placed.next_phase, &meeple, &players).is_err());
+        }
+    }
+
+    #[test]
+    fn test_tile_sequence_is_drawn_in_order() {
+        use crate::engine::simulator::{apply_action_and_resolve_with_events, SimulationState};
+
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = |options: serde_json::Value| GameConfig { random_seed: Some(7), options };
+        let sequence = ["E", "V", "K", "U", "B"];
+        let options = serde_json::json!({"starting_tile": "U", "tile_sequence": sequence});
+        assert!(plugin.validate_config(&config(options.clone())).is_ok());
+        for bad in [
+            serde_json::json!({"starting_tile": "Z"}),
+            serde_json::json!({"tile_sequence": ["E", "R2"]}),
+            serde_json::json!({"tile_sequence": "EVK"}),
+            serde_json::json!({"starting_tile": "U", "expansions": ["river"]}),
+        ] {
+            assert!(plugin.validate_config(&config(bad.clone())).is_err(), "{bad}");
+        }
+
+        let (state, phase, events) = plugin.create_initial_state(&players, &config(options));
+        assert_eq!(events[1].payload["tile"], "U");
+        assert_eq!(state.board.tiles[&(0, 0)].tile_type_id, tile_type_to_index("U"));
+        let mut sim = SimulationState {
+            state,
+            phase,
+            players: players.clone().into(),
+            scores: HashMap::new(),
+            game_over: None,
+            history: None,
+        };
+        let draw = Action {
+            action_type: "draw_tile".into(),
+            player_id: "system".into(),
+            payload: serde_json::json!({}),
+        };
+        apply_action_and_resolve_with_events(&plugin, &mut sim, &draw).unwrap();
+
+        let mut drawn = vec![];
+        while sim.game_over.is_none() {
+            if sim.phase.name == "place_tile" {
+                drawn.push(tile_index_to_type(sim.state.current_tile.unwrap()));
+            }
+            let player_id = sim.phase.expected_actions[0].player_id.clone();
+            let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &player_id);
+            let action = Action {
+                action_type: sim.phase.name.clone(),
+                player_id,
+                payload: valid[0].clone(),
+            };
+            apply_action_and_resolve_with_events(&plugin, &mut sim, &action).unwrap();
+        }
+        assert_eq!(drawn, sequence);
+
+        // tile_count still cuts the sequence short
+        let options = serde_json::json!({"tile_sequence": sequence, "tile_count": 2});
+        let (state, _, _) = plugin.create_initial_state(&players, &config(options));
+        assert_eq!(state.tile_bag, vec![tile_type_to_index("E"), tile_type_to_index("V")]);
+    }
+
     #[test]
     fn test_draw_and_place_tile() {
         let plugin = CarcassonnePlugin;
@@ -932,7 +2478,7 @@ mod tests {
             player_id: "p1".into(),
             payload: serde_json::json!({}),
         };
-        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players);
+        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players).unwrap();
 
         assert_eq!(result.next_phase.name, "place_tile");
         assert!(result.game_data["current_tile"].as_str().is_some());
@@ -952,7 +2498,7 @@ mod tests {
             &result.next_phase,
             &place_action,
             &players,
-        );
+        ).unwrap();
         assert_eq!(place_result.next_phase.name, "place_meeple");
 
         let skip_action = Action {
@@ -965,7 +2511,7 @@ mod tests {
             &place_result.next_phase,
             &skip_action,
             &players,
-        );
+        ).unwrap();
         assert_eq!(meeple_result.next_phase.name, "score_check");
 
         let score_action = Action {
@@ -978,7 +2524,7 @@ mod tests {
             &meeple_result.next_phase,
             &score_action,
             &players,
-        );
+        ).unwrap();
         assert_eq!(score_result.next_phase.name, "draw_tile");
         assert_eq!(
             score_result.next_phase.metadata["player_index"].as_u64().unwrap(),
@@ -1011,7 +2557,7 @@ mod tests {
                     player_id: "system".into(),
                     payload: serde_json::json!({}),
                 };
-                let result = json_plugin.apply_action(&game_data, &phase, &action, &players);
+                let result = json_plugin.apply_action(&game_data, &phase, &action, &players).unwrap();
                 game_data = result.game_data;
                 phase = result.next_phase;
                 continue;
@@ -1034,7 +2580,7 @@ mod tests {
                 payload: valid[0].clone(),
             };
 
-            let result = json_plugin.apply_action(&game_data, &phase, &action, &players);
+            let result = json_plugin.apply_action(&game_data, &phase, &action, &players).unwrap();
             game_data = result.game_data;
             phase = result.next_phase;
 
@@ -1051,124 +2597,221 @@ mod tests {
         );
     }
 
-    /// Validate state invariants. Returns a list of violation messages (empty = pass).
-    fn check_state_invariants(
-        state: &CarcassonneState,
-        players: &[Player],
-        context: &str,
-    ) -> Vec<String> {
-        let mut violations = Vec::new();
-
-        // INV1: All feature IDs in tile_feature_map exist in state.features
-        for (pos, spots) in &state.tile_feature_map {
-            for (spot, fid) in spots {
-                if !state.features.contains_key(fid) {
-                    violations.push(format!(
-                        "INV1 [{}]: tile_feature_map[{}][{}] -> feature '{}' not in features",
-                        context, pos, spot, fid
-                    ));
-                }
-            }
-        }
+    fn river_config(seed: u64) -> GameConfig {
+        GameConfig { random_seed: Some(seed), options: serde_json::json!({"expansions": ["river"]}) }
+    }
+
+    #[test]
+    fn test_river_tiles_are_placed_first() {
+        use crate::engine::simulator::{apply_action_and_resolve_with_events, SimulationState};
+        use rand::seq::SliceRandom;
 
-        // INV2: All features' tiles are on the board
-        for (fid, feat) in &state.features {
-            for tile_pos in &feat.tiles {
-                let pos = Position::from_key(tile_pos);
-                if !state.board.tiles.contains_key(&(pos.x, pos.y)) {
-                    violations.push(format!(
-                        "INV2 [{}]: feature '{}' ({:?}) references tile '{}' not on board",
-                        context, fid, feat.feature_type, tile_pos
-                    ));
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        for seed in 0..4 {
+            let (state, phase, events) = plugin.create_initial_state(&players, &river_config(seed));
+            assert_eq!(events[1].payload["tile"], "R1");
+            assert_eq!(state.board.open_positions, vec![(0, -1)]);
+            assert_eq!(state.tile_bag.len(), 11 + 72);
+            let mut sim = SimulationState {
+                state,
+                phase,
+                players: players.clone().into(),
+                scores: HashMap::new(),
+                game_over: None,
+                history: None,
+            };
+            let draw = Action {
+                action_type: "draw_tile".into(),
+                player_id: "system".into(),
+                payload: serde_json::json!({}),
+            };
+            apply_action_and_resolve_with_events(&plugin, &mut sim, &draw).unwrap();
+
+            let mut rng = GameRng::from_seed(seed);
+            let mut placed = vec![];
+            while placed.len() < 12 {
+                let player_id = sim.phase.expected_actions[0].player_id.clone();
+                let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &player_id);
+                let action = Action {
+                    action_type: sim.phase.name.clone(),
+                    player_id,
+                    payload: valid.choose(&mut rng).unwrap().clone(),
+                };
+                assert_eq!(plugin.validate_action(&sim.state, &sim.phase, &action), None);
+                let tile = sim.state.current_tile.map(tile_index_to_type);
+                let events = apply_action_and_resolve_with_events(&plugin, &mut sim, &action).unwrap();
+                if action.action_type != "place_tile" {
+                    continue;
+                }
+                placed.push(tile.unwrap());
+                let completed = events.iter().any(|e| e.event_type == "river_completed");
+                assert_eq!(completed, placed.len() == 11, "seed {seed}: {placed:?}");
+                if placed.len() <= 10 {
+                    assert!(sim.state.river.is_some());
+                    assert_eq!(sim.state.board.open_positions.len(), 1);
                 }
             }
-        }
 
-        // INV3: Meeple count consistency
-        // Total meeples = placed on features + in supply for each player
-        let mut placed_meeples: HashMap<String, i32> = HashMap::new();
-        for feat in state.features.values() {
-            for m in &feat.meeples {
-                *placed_meeples.entry(m.player_id.clone()).or_insert(0) += 1;
-            }
-        }
-        for p in players {
-            let supply = state.meeple_supply.get(&p.player_id).copied().unwrap_or(0);
-            let placed = placed_meeples.get(&p.player_id).copied().unwrap_or(0);
-            if supply + placed != 7 {
-                violations.push(format!(
-                    "INV3 [{}]: player '{}' meeple count: supply={} + placed={} = {} (expected 7)",
-                    context, p.player_id, supply, placed, supply + placed
-                ));
-            }
+            // The lake closes the river and the base tiles take over
+            assert!(placed[..10].iter().all(|t| t.starts_with('R') && t.len() > 1), "{placed:?}");
+            assert_eq!(placed[10], "R10");
+            assert_eq!(placed[11].len(), 1, "{placed:?}");
+            assert!(sim.state.river.is_none());
+            assert!(check_invariants(&sim.state, "river").is_empty());
         }
+    }
 
-        // INV4: Completed features should have no meeples (they get returned)
-        for (fid, feat) in &state.features {
-            if feat.is_complete && !feat.meeples.is_empty() {
-                violations.push(format!(
-                    "INV4 [{}]: completed feature '{}' ({:?}) still has {} meeples",
-                    context, fid, feat.feature_type, feat.meeples.len()
-                ));
-            }
-        }
+    #[test]
+    fn test_river_placement_must_extend_the_river() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let (state, phase, _) = plugin.create_initial_state(&players, &river_config(1));
+        let draw = Action {
+            action_type: "draw_tile".into(),
+            player_id: "system".into(),
+            payload: serde_json::json!({}),
+        };
+        let r = plugin.apply_action(&state, &phase, &draw, &players).unwrap();
+        let mut state = r.state;
+        state.current_tile = Some(tile_type_to_index("R2"));
+        let place = |x: i32, y: i32, rotation: u32| Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"x": x, "y": y, "rotation": rotation}),
+        };
 
-        // INV5: Open edges should reference positions in the feature's tiles
-        for (fid, feat) in &state.features {
-            for oe in &feat.open_edges {
-                if !feat.tiles.contains(&oe[0]) {
-                    violations.push(format!(
-                        "INV5 [{}]: feature '{}' open_edge [{}, {}] references tile not in feature.tiles",
-                        context, fid, oe[0], oe[1]
-                    ));
-                }
-            }
-        }
+        // Fields match next to the spring, but the river must flow on from its mouth
+        let err = plugin.validate_action(&state, &r.next_phase, &place(1, 0, 0)).unwrap();
+        assert!(err.contains("does not continue the river"), "{err}");
+        assert!(plugin.validate_action(&state, &r.next_phase, &place(0, -1, 90)).is_some());
+        assert_eq!(plugin.validate_action(&state, &r.next_phase, &place(0, -1, 0)), None);
 
-        // INV6: Non-complete city/road features with open edges - the neighbor
-        // position in that direction should NOT be on the board
-        for (fid, feat) in &state.features {
-            if feat.is_complete {
-                continue;
-            }
-            if !matches!(feat.feature_type, FeatureType::City | FeatureType::Road) {
-                continue;
-            }
-            for oe in &feat.open_edges {
-                let pos = Position::from_key(&oe[0]);
-                let dir = oe[1].split(':').next().unwrap_or(&oe[1]);
-                let neighbor = pos.neighbor(dir);
-                if state.board.tiles.contains_key(&(neighbor.x, neighbor.y)) {
-                    violations.push(format!(
-                        "INV6 [{}]: feature '{}' ({:?}) has open_edge [{}, {}] but neighbor '{},{}' IS on board",
-                        context, fid, feat.feature_type, oe[0], oe[1], neighbor.x, neighbor.y
-                    ));
-                }
-            }
-        }
+        // A base tile has nowhere to go while the river is open
+        state.current_tile = Some(tile_type_to_index("E"));
+        assert!(plugin.get_valid_actions(&state, &r.next_phase, "p1").is_empty());
+    }
 
-        // INV7: All tile positions on board should have entries in tile_feature_map
-        for &(x, y) in state.board.tiles.keys() {
-            let pos_key = format!("{},{}", x, y);
-            if !state.tile_feature_map.contains_key(&pos_key) {
-                violations.push(format!(
-                    "INV7 [{}]: board tile at '{}' has no tile_feature_map entry",
-                    context, pos_key
-                ));
-            }
+    #[test]
+    fn test_merged_city_pennants() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        let pennants_at = |state: &CarcassonneState, pos: &str| {
+            let mut ids = state.tile_feature_map[pos].values();
+            let fid = ids.find(|f| state.features[*f].feature_type == FeatureType::City).unwrap();
+            state.features[fid].pennants
+        };
+
+        // Two E-W pennant cities, joined by a plain E-W city tile between them
+        let (state, _) = play_tile(state, &players, "F", (-1, 5, 0), None);
+        let (state, _) = play_tile(state, &players, "F", (1, 5, 0), None);
+        assert_eq!(pennants_at(&state, "-1,5"), 1);
+        assert_eq!(pennants_at(&state, "1,5"), 1);
+        let (joined, _) = play_tile(state.clone(), &players, "G", (0, 5, 90), None);
+        assert_eq!(pennants_at(&joined, "0,5"), 2);
+        assert!(check_invariants(&joined, "two-way").is_empty());
+
+        // Three-way merge through a pennant tile, with a plain city to the north
+        let (state, _) = play_tile(state, &players, "E", (0, 6, 180), None);
+        let (merged, _) = play_tile(state, &players, "Q", (0, 5, 0), None);
+        assert_eq!(pennants_at(&merged, "0,5"), 3);
+        assert_eq!(pennants_at(&merged, "0,6"), 3);
+        assert!(check_invariants(&merged, "three-way").is_empty());
+    }
+
+    #[test]
+    fn test_load_near_endgame_state() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+
+        // Built from scratch, not from a new game: a road running east from the
+        // starting tile with p1's meeple on its end, one tile left to draw and
+        // scores carried over from an imagined game
+        let mut state = plugin.decode_state(&serde_json::json!({
+            "board": {"tiles": {}, "open_positions": []},
+            "tile_bag": ["U"],
+            "current_tile": null,
+            "last_placed_position": null,
+            "features": {},
+            "tile_feature_map": {},
+            "meeple_supply": {"p1": 6, "p2": 7},
+            "scores": {"p1": 41, "p2": 38},
+            "current_player_index": 0,
+        }));
+        for (x, tile, rotation) in [(0, "D", 0), (1, "U", 90), (2, "U", 90)] {
+            state.board.tiles.insert((x, 0), PlacedTile { tile_type_id: tile_type_to_index(tile), rotation });
+            create_and_merge_features(&mut state, tile, &format!("{x},0"), rotation);
         }
+        state.board.open_positions = recalculate_open_positions(&state.board.tiles);
+        let (spot, road) = state.tile_feature_map["2,0"]
+            .iter()
+            .find(|(_, fid)| state.features[*fid].feature_type == FeatureType::Road)
+            .map(|(spot, fid)| (spot.clone(), fid.clone()))
+            .unwrap();
+        let meeple = PlacedMeeple { player_id: "p1".into(), position: "2,0".into(), spot, count: 1 };
+        state.features.get_mut(&road).unwrap().meeples.push(meeple);
 
-        // INV8: Scores should never be negative
-        for (pid, &score) in &state.scores {
-            if score < 0 {
-                violations.push(format!(
-                    "INV8 [{}]: player '{}' has negative score: {}",
-                    context, pid, score
-                ));
+        let game_data = plugin.encode_state(&state);
+        let phase = Phase {
+            name: "draw_tile".into(),
+            concurrent_mode: None,
+            expected_actions: vec![],
+            auto_resolve: true,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        plugin.load_state(&game_data, &phase).unwrap();
+
+        let mut state = plugin.decode_state(&game_data);
+        let mut phase = phase;
+        let mut game_over = None;
+        for _ in 0..10 {
+            let pid = plugin.acting_player(&state, &phase, &players).unwrap_or_else(|| "p1".into());
+            let payload = match phase.name.as_str() {
+                "place_tile" => plugin.get_valid_actions(&state, &phase, &pid)[0].clone(),
+                "place_meeple" => serde_json::json!({"skip": true}),
+                _ => serde_json::json!({}),
+            };
+            let action = Action { action_type: phase.name.clone(), player_id: pid, payload };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            state = r.state;
+            phase = r.next_phase;
+            if r.game_over.is_some() {
+                game_over = r.game_over;
+                break;
             }
         }
 
-        violations
+        let result = game_over.expect("placing the last tile should end the game");
+        assert_eq!(state.board.tiles.len(), 4);
+        assert!(state.tile_bag.is_empty());
+        assert_eq!(result.winners, vec!["p1".to_string()]);
+        assert!(result.final_scores["p1"] > 41.0, "p1's road should score");
+        assert_eq!(result.final_scores["p2"], 38.0);
+    }
+
+    #[test]
+    fn test_load_state_rejects_broken_states() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let game_data = plugin.encode_state(&state);
+        let with_phase = |name: &str| Phase { name: name.into(), ..phase.clone() };
+
+        let mut lost_meeple = game_data.clone();
+        lost_meeple["meeple_supply"]["p2"] = serde_json::json!(6);
+        assert!(plugin.load_state(&lost_meeple, &phase).unwrap_err().contains("INV3"));
+
+        let mut bad_key = game_data.clone();
+        bad_key["board"]["open_positions"] = serde_json::json!(["1;0"]);
+        assert!(plugin.load_state(&bad_key, &phase).unwrap_err().contains("invalid position key"));
+
+        assert!(plugin.load_state(&serde_json::json!({"board": {}}), &phase).is_err());
+        assert!(plugin.load_state(&game_data, &with_phase("place_tile")).unwrap_err().contains("current_tile"));
+        assert!(plugin.load_state(&game_data, &with_phase("place_meeple")).is_err());
+        assert!(plugin.load_state(&game_data, &with_phase("bogus")).unwrap_err().contains("Unknown phase"));
     }
 
     #[test]
@@ -1187,7 +2830,7 @@ mod tests {
             let context_prefix = format!("seed={}", seed);
 
             // Check initial state
-            let vs = check_state_invariants(&state, &players, &format!("{} init", context_prefix));
+            let vs = check_invariants(&state, &format!("{} init", context_prefix));
             assert!(vs.is_empty(), "Initial state violations:\n{}", vs.join("\n"));
 
             let mut turns = 0;
@@ -1210,15 +2853,14 @@ mod tests {
                         player_id: pid,
                         payload: serde_json::json!({}),
                     };
-                    let result = plugin.apply_action(&state, &phase, &action, &players);
+                    let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
                     state = result.state;
                     phase = result.next_phase;
 
                     // Check invariants after auto-resolve steps
                     if phase.name == "place_tile" || phase.name == "game_over" || phase.name == "place_meeple" {
-                        let vs = check_state_invariants(
+                        let vs = check_invariants(
                             &state,
-                            &players,
                             &format!("{} turn={} after-{}", context_prefix, turns, action.action_type),
                         );
                         assert!(vs.is_empty(), "Invariant violations:\n{}", vs.join("\n"));
@@ -1240,14 +2882,13 @@ mod tests {
                     player_id: player_id.clone(),
                     payload: valid[0].clone(),
                 };
-                let result = plugin.apply_action(&state, &phase, &action, &players);
+                let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
                 state = result.state;
                 phase = result.next_phase;
 
                 // Check invariants after player actions
-                let vs = check_state_invariants(
+                let vs = check_invariants(
                     &state,
-                    &players,
                     &format!("{} turn={} after-{}", context_prefix, turns, action.action_type),
                 );
                 assert!(vs.is_empty(), "Invariant violations:\n{}", vs.join("\n"));
@@ -1306,7 +2947,7 @@ mod tests {
                     player_id: pid,
                     payload: serde_json::json!({}),
                 };
-                let result = plugin.apply_action(&state, &phase, &action, &players);
+                let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
                 state = result.state;
                 phase = result.next_phase;
                 continue;
@@ -1320,7 +2961,7 @@ mod tests {
                 player_id: pid,
                 payload: valid[0].clone(),
             };
-            let result = plugin.apply_action(&state, &phase, &action, &players);
+            let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
             state = result.state;
             phase = result.next_phase;
         }
@@ -1332,15 +2973,16 @@ mod tests {
         // Now simulate what MCTS does: clone, determinize, and play forward
         for det_idx in 0..10 {
             let mut det_state = state.clone();
-            plugin.determinize(&mut det_state);
+            plugin.determinize(&mut det_state, &mut GameRng::stream(42, det_idx));
 
             let base_scores = plugin.get_scores(&det_state);
             let mut sim = SimulationState {
                 state: det_state,
                 phase: phase.clone(),
-                players: players.to_vec(),
+                players: players.clone().into(),
                 scores: base_scores,
                 game_over: None,
+                history: None,
             };
 
             // Play forward 30 half-turns
@@ -1360,7 +3002,7 @@ mod tests {
                         player_id: pid,
                         payload: serde_json::json!({}),
                     };
-                    apply_action_and_resolve(&plugin, &mut sim, &action);
+                    apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
                     continue;
                 }
 
@@ -1374,12 +3016,11 @@ mod tests {
                     player_id: pid.clone(),
                     payload: valid[0].clone(),
                 };
-                apply_action_and_resolve(&plugin, &mut sim, &action);
+                apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
 
                 // Check invariants after every player action in simulation
-                let vs = check_state_invariants(
+                let vs = check_invariants(
                     &sim.state,
-                    &players,
                     &format!("det={} sim_step={}", det_idx, sim_step),
                 );
                 assert!(
@@ -1422,11 +3063,130 @@ mod tests {
             player_id: "p1".into(),
             payload: serde_json::json!({}),
         };
-        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players);
+        let result = json_plugin.apply_action(&game_data, &phase, &draw_action, &players).unwrap();
 
         let valid_json = json_plugin.get_valid_actions(&result.game_data, &result.next_phase, "p1");
         let state2 = plugin.decode_state(&result.game_data);
         let valid_direct = plugin.get_valid_actions(&state2, &result.next_phase, "p1");
         assert_eq!(valid_json.len(), valid_direct.len());
     }
+
+    #[test]
+    fn test_message_pack_roundtrip() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (state, _, _) = plugin.create_initial_state(&players, &config);
+        let (state, _) = play_tile(state, &players, "V", (5, 6, 270), Some("road"));
+        let (state, _) = play_tile(state, &players, "F", (-1, 5, 0), None);
+        let (state, _) = play_tile(state, &players, "G", (0, 5, 90), Some("city"));
+
+        let game_data = plugin.encode_state(&state);
+        let json = GameDataFormat::Json.encode(&game_data).unwrap();
+        let document = GameDataFormat::MessagePack.encode(&game_data).unwrap();
+        let packed = plugin.encode_state_binary(&state).unwrap();
+        assert!(packed.len() < document.len(), "{} bytes typed vs {} as a document", packed.len(), document.len());
+        assert!(document.len() < json.len(), "{} bytes packed vs {} as JSON", document.len(), json.len());
+
+        let decoded = plugin.decode_state_binary(&packed).unwrap();
+        assert_eq!(plugin.encode_state(&decoded), game_data);
+        assert_eq!(plugin.state_hash(&decoded), plugin.state_hash(&state));
+        assert_eq!(decoded.zobrist, state.zobrist);
+
+        assert!(plugin.decode_state_binary(&json).is_err());
+        assert!(plugin.decode_state_binary(&document).is_err());
+        let mut bad = state.clone();
+        bad.tile_bag.push(u8::MAX);
+        assert!(plugin.decode_state_binary(&plugin.encode_state_binary(&bad).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_notation_roundtrip() {
+        let plugin = CarcassonnePlugin;
+        let players = make_players(2);
+        let config = GameConfig { random_seed: Some(42), options: serde_json::json!({}) };
+        let (start, _, _) = plugin.create_initial_state(&players, &config);
+
+        let assert_roundtrip = |state: &CarcassonneState| {
+            let notation = state.to_notation();
+            let parsed = CarcassonneState::from_notation(&notation).unwrap();
+            assert_eq!(parsed.to_notation(), notation);
+            assert_eq!(plugin.state_hash(&parsed), plugin.state_hash(state));
+            assert_eq!(plugin.encode_state(&parsed), plugin.encode_state(state), "{notation}");
+            parsed
+        };
+
+        assert_roundtrip(&start);
+        let start_notation = start.to_notation();
+
+        let (state, _) = play_tile(start, &players, "V", (5, 6, 270), Some("road"));
+        let (mut state, _) = play_tile(state, &players, "F", (-1, 5, 0), None);
+        assert_roundtrip(&state);
+
+        // Close the starting city but stop before score_check
+        state.current_tile = Some(tile_type_to_index("E"));
+        let phase = Phase {
+            name: "place_tile".into(),
+            concurrent_mode: Some(ConcurrentMode::Sequential),
+            expected_actions: vec![],
+            auto_resolve: false,
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        let place = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"x": 0, "y": 1, "rotation": 180}),
+        };
+        let r = plugin.apply_action(&state, &phase, &place, &players).unwrap();
+        let claim = plugin
+            .get_valid_actions(&r.state, &r.next_phase, "p1")
+            .into_iter()
+            .find(|a| a.get("meeple_spot").and_then(|v| v.as_str()).is_some_and(|s| s.starts_with("city")))
+            .unwrap();
+        let claim = Action { action_type: "place_meeple".into(), player_id: "p1".into(), payload: claim };
+        let r = plugin.apply_action(&r.state, &r.next_phase, &claim, &players).unwrap();
+        let pending = r.state;
+        assert!(pending.to_notation().contains(" unscored"));
+        let parsed = assert_roundtrip(&pending);
+
+        // score_check sees the same completed city in both
+        let score = Action { action_type: "score_check".into(), player_id: "p1".into(), payload: serde_json::json!({}) };
+        let scored = plugin.apply_action(&pending, &r.next_phase, &score, &players).unwrap();
+        let scored_parsed = plugin.apply_action(&parsed, &r.next_phase, &score, &players).unwrap();
+        assert_eq!(scored_events(&scored_parsed.events).len(), scored_events(&scored.events).len());
+        assert_eq!(scored_events(&scored.events).len(), 1);
+        assert_eq!(scored_parsed.state.scores, scored.state.scores);
+        assert_roundtrip(&scored.state);
+
+        // Whole games, with merges in every order, score history and the end-game breakdown
+        let config = GameConfig {
+            random_seed: Some(7),
+            options: serde_json::json!({"track_score_history": true, "tile_count": 30}),
+        };
+        let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+        use rand::seq::SliceRandom;
+        let mut rng = GameRng::from_seed(7);
+        while phase.name != "game_over" {
+            let pid = plugin.acting_player(&state, &phase, &players).unwrap_or_else(|| "p1".into());
+            let payload = if phase.auto_resolve {
+                serde_json::json!({})
+            } else {
+                let valid = plugin.get_valid_actions(&state, &phase, &pid);
+                valid.choose(&mut rng).unwrap().clone()
+            };
+            let action = Action { action_type: phase.name.clone(), player_id: pid, payload };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            state = r.state;
+            phase = r.next_phase;
+            assert_roundtrip(&state);
+        }
+        assert!(state.to_notation().contains(" history="));
+        assert!(state.to_notation().contains(" breakdown="));
+
+        assert!(CarcassonneState::from_notation("D:0,0:0 - p1:0:7").is_err());
+        assert!(CarcassonneState::from_notation("Z:0,0:0 - p1:0:7 0 - -").is_err());
+        let json_plugin = JsonAdapter(CarcassonnePlugin);
+        let imported = json_plugin.import_notation(&start_notation).unwrap().unwrap();
+        assert_eq!(json_plugin.export_notation(&imported), Some(start_notation));
+    }
 }
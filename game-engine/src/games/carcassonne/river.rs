@@ -0,0 +1,255 @@
+//! River expansion: twelve river tiles that open the game.
+//!
+//! The spring replaces the starting tile at (0,0) and the other river tiles are
+//! drawn before the base bag, the lake always last. Each river tile must extend
+//! the open end of the river, and two curves in a row may not turn the same way
+//! (that U-turn could run the river back into itself).
+
+use once_cell::sync::Lazy;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::rng::GameRng;
+use super::tiles::{edges, feat, feat_monastery, feat_with_adj, ROTATED_EDGES};
+use super::types::*;
+
+use EdgeType::{City as C, Field as F, River as V, Road as R};
+use FeatureType::{City, Field, Road};
+
+/// Value in the `expansions` option that enables the river.
+pub const RIVER_EXPANSION: &str = "river";
+pub const RIVER_SPRING_ID: &str = "R1";
+pub const RIVER_SPRING_IDX: u8 = 24; // tile_type_to_index("R1")
+pub const RIVER_LAKE_IDX: u8 = 33; // tile_type_to_index("R10")
+
+pub static RIVER_CATALOG: Lazy<Vec<TileDefinition>> = Lazy::new(|| {
+    let river = |id: &str, edges: [EdgeType; 4], features: Vec<TileFeature>, count: u32| {
+        TileDefinition {
+            tile_type_id: id.into(),
+            edges,
+            features,
+            count,
+            image_id: format!("tile_{id}"),
+            internal_connections: vec![],
+        }
+    };
+    vec![
+        // R1: Spring, river flows out S (x1) — the starting tile
+        river("R1", edges(F, F, V, F), vec![
+            feat_with_adj(Field, &["N", "E", "W", "S:E", "S:W"], &["field_NESW"], &[]),
+        ], 1),
+        // R2: River N-S (x2)
+        river("R2", edges(V, F, V, F), vec![
+            feat_with_adj(Field, &["E", "N:E", "S:E"], &["field_E"], &[]),
+            feat_with_adj(Field, &["W", "N:W", "S:W"], &["field_W"], &[]),
+        ], 2),
+        // R3: River curve S-W (x2)
+        river("R3", edges(F, F, V, V), vec![
+            feat_with_adj(Field, &["S:W", "W:S"], &["field_SW"], &[]),
+            feat_with_adj(Field, &["N", "E", "S:E", "W:N"], &["field_NE"], &[]),
+        ], 2),
+        // R4: River N-S under a road E-W (x1)
+        river("R4", edges(V, R, V, R), vec![
+            feat(Road, &["E", "W"], &["road_EW"]),
+            feat_with_adj(Field, &["N:E", "E:N"], &["field_NE"], &[]),
+            feat_with_adj(Field, &["E:S", "S:E"], &["field_SE"], &[]),
+            feat_with_adj(Field, &["S:W", "W:S"], &["field_SW"], &[]),
+            feat_with_adj(Field, &["W:N", "N:W"], &["field_NW"], &[]),
+        ], 1),
+        // R5: River curve S-W, road curve N-E (x1)
+        river("R5", edges(R, R, V, V), vec![
+            feat(Road, &["N", "E"], &["road_NE"]),
+            feat_with_adj(Field, &["N:E", "E:N"], &["field_NE"], &[]),
+            feat_with_adj(Field, &["S:W", "W:S"], &["field_SW"], &[]),
+            feat_with_adj(Field, &["N:W", "W:N", "E:S", "S:E"], &["field_NESW"], &[]),
+        ], 1),
+        // R6: River N-S between cities E and W (x1)
+        river("R6", edges(V, C, V, C), vec![
+            feat(City, &["E"], &["city_E"]),
+            feat(City, &["W"], &["city_W"]),
+            feat_with_adj(Field, &["N:E", "S:E"], &["field_E"], &["city_E"]),
+            feat_with_adj(Field, &["N:W", "S:W"], &["field_W"], &["city_W"]),
+        ], 1),
+        // R7: River curve S-W, city N-E (x1)
+        river("R7", edges(C, C, V, V), vec![
+            feat(City, &["N", "E"], &["city_NE"]),
+            feat_with_adj(Field, &["S:W", "W:S"], &["field_SW"], &[]),
+            feat_with_adj(Field, &["S:E", "W:N"], &["field_NESW"], &["city_NE"]),
+        ], 1),
+        // R8: River N-S, monastery with road W (x1)
+        river("R8", edges(V, F, V, R), vec![
+            feat_monastery(&["monastery"]),
+            feat(Road, &["W"], &["road_W"]),
+            feat_with_adj(Field, &["E", "N:E", "S:E"], &["field_E"], &[]),
+            feat_with_adj(Field, &["N:W", "S:W", "W:N", "W:S"], &["field_W"], &[]),
+        ], 1),
+        // R9: River N-S, city E, road W (x1)
+        river("R9", edges(V, C, V, R), vec![
+            feat(City, &["E"], &["city_E"]),
+            feat(Road, &["W"], &["road_W"]),
+            feat_with_adj(Field, &["N:E", "S:E"], &["field_E"], &["city_E"]),
+            feat_with_adj(Field, &["N:W", "S:W", "W:N", "W:S"], &["field_W"], &[]),
+        ], 1),
+        // R10: Lake, river flows in from N (x1) — ends the river
+        river("R10", edges(V, F, F, F), vec![
+            feat_with_adj(Field, &["E", "S", "W", "N:E", "N:W"], &["field_NESW"], &[]),
+        ], 1),
+    ]
+});
+
+#[inline]
+pub fn is_river_tile(tile_type_idx: u8) -> bool {
+    (RIVER_SPRING_IDX..=RIVER_LAKE_IDX).contains(&tile_type_idx)
+}
+
+/// River tiles drawn before the base bag: shuffled, without the spring, lake last.
+pub fn build_river_bag(rng: &mut GameRng) -> Vec<u8> {
+    let mut bag = Vec::with_capacity(11);
+    for tile_def in RIVER_CATALOG.iter() {
+        let idx = tile_type_to_index(&tile_def.tile_type_id);
+        if idx == RIVER_SPRING_IDX || idx == RIVER_LAKE_IDX {
+            continue;
+        }
+        for _ in 0..tile_def.count {
+            bag.push(idx);
+        }
+    }
+    bag.shuffle(rng);
+    bag.push(RIVER_LAKE_IDX);
+    bag
+}
+
+/// Shuffle a draw bag without mixing river tiles into the base tiles: the
+/// leading river tiles are shuffled among themselves (the lake stays last),
+/// then the rest of the bag.
+pub fn shuffle_bag(bag: &mut [u8], rng: &mut GameRng) {
+    let river_len = bag.iter().take_while(|&&t| is_river_tile(t)).count();
+    let (river, rest) = bag.split_at_mut(river_len);
+    if let Some((_lake, middle)) = river.split_last_mut() {
+        middle.shuffle(rng);
+    }
+    rest.shuffle(rng);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiverTurn {
+    Left,
+    Right,
+}
+
+/// The open end of a river still being built.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiverState {
+    /// Empty "x,y" position the river flows into; the next tile goes here.
+    pub open_end: String,
+    /// Direction the river flows into `open_end`.
+    pub heading: String,
+    /// Direction of the latest curve; the next curve must turn the other way.
+    #[serde(default)]
+    pub last_turn: Option<RiverTurn>,
+}
+
+impl RiverState {
+    /// The river leaving a spring placed at (0,0) with rotation 0.
+    pub fn from_spring() -> Self {
+        Self { open_end: "0,-1".into(), heading: "S".into(), last_turn: None }
+    }
+
+    pub fn open_end_pos(&self) -> Option<(i32, i32)> {
+        Position::parse_key(&self.open_end).map(|p| (p.x, p.y))
+    }
+
+    /// Where a river tile at the open end sends the river: its outgoing edge and
+    /// the turn that makes, or None for the lake.
+    fn outflow(&self, tile_type_idx: u8, rotation: u32) -> Option<(&'static str, Option<RiverTurn>)> {
+        let rot_idx = ((rotation / 90) % 4) as usize;
+        let heading = direction_index(&self.heading);
+        let inflow = (heading + 2) % 4;
+        let tile_edges = ROTATED_EDGES[tile_type_idx as usize][rot_idx];
+        let out = (0..4).find(|&d| d != inflow && tile_edges[d] == EdgeType::River)?;
+        let turn = match (out + 4 - heading) % 4 {
+            1 => Some(RiverTurn::Right),
+            3 => Some(RiverTurn::Left),
+            _ => None,
+        };
+        Some((DIRECTIONS[out], turn))
+    }
+
+    /// Whether a placement continues the river. Edge matching separately makes
+    /// sure a river edge faces the previous river tile.
+    pub fn allows(&self, tile_type_idx: u8, pos: (i32, i32), rotation: u32) -> bool {
+        if !is_river_tile(tile_type_idx) || self.open_end_pos() != Some(pos) {
+            return false;
+        }
+        match self.outflow(tile_type_idx, rotation) {
+            Some((_, Some(turn))) => self.last_turn != Some(turn),
+            _ => true,
+        }
+    }
+
+    /// The river after a tile is placed at the open end; None once the lake closes it.
+    pub fn advance(&self, tile_type_idx: u8, rotation: u32) -> Option<RiverState> {
+        let (out, turn) = self.outflow(tile_type_idx, rotation)?;
+        let next = Position::from_key(&self.open_end).neighbor(out);
+        Some(RiverState {
+            open_end: next.to_key(),
+            heading: out.into(),
+            last_turn: turn.or(self.last_turn),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_river_catalog() {
+        let total: u32 = RIVER_CATALOG.iter().map(|t| t.count).sum();
+        assert_eq!(total, 12);
+        assert_eq!(tile_type_to_index(RIVER_SPRING_ID), RIVER_SPRING_IDX);
+        assert_eq!(tile_type_to_index("R10"), RIVER_LAKE_IDX);
+        for tile in RIVER_CATALOG.iter() {
+            let rivers = tile.edges.iter().filter(|&&e| e == EdgeType::River).count();
+            let expected = if matches!(tile.tile_type_id.as_str(), "R1" | "R10") { 1 } else { 2 };
+            assert_eq!(rivers, expected, "{}", tile.tile_type_id);
+        }
+    }
+
+    #[test]
+    fn test_river_bag_ends_with_lake() {
+        let bag = build_river_bag(&mut GameRng::from_seed(3));
+        assert_eq!(bag.len(), 11);
+        assert_eq!(bag.last(), Some(&RIVER_LAKE_IDX));
+        assert!(!bag.contains(&RIVER_SPRING_IDX));
+
+        let mut bag = [bag.clone(), vec![0, 1, 2, 3]].concat();
+        shuffle_bag(&mut bag, &mut GameRng::from_seed(4));
+        assert!(bag[..11].iter().all(|&t| is_river_tile(t)));
+        assert_eq!(bag[10], RIVER_LAKE_IDX);
+    }
+
+    #[test]
+    fn test_curves_alternate() {
+        let curve = tile_type_to_index("R3");
+        // Flowing S into (0,-1): river edges N+E turn left (east), N+W turn right (west).
+        let river = RiverState::from_spring();
+        assert!(river.allows(curve, (0, -1), 180)); // N, E
+        assert!(river.allows(curve, (0, -1), 90)); // W, N
+        assert!(!river.allows(curve, (0, -2), 90));
+
+        let after_left = river.advance(curve, 180).unwrap();
+        assert_eq!(after_left.open_end, "1,-1");
+        assert_eq!(after_left.last_turn, Some(RiverTurn::Left));
+        // Flowing E: a second left would head N, back towards the spring.
+        assert!(!after_left.allows(curve, (1, -1), 90)); // W, N
+        assert!(after_left.allows(curve, (1, -1), 0)); // S, W
+
+        // Straights keep the last turn; the lake closes the river.
+        let straight = after_left.advance(tile_type_to_index("R2"), 90).unwrap();
+        assert_eq!(straight.open_end, "2,-1");
+        assert_eq!(straight.last_turn, Some(RiverTurn::Left));
+        assert_eq!(straight.advance(RIVER_LAKE_IDX, 270), None);
+    }
+}
@@ -7,15 +7,17 @@ use super::tiles::get_rotated_features;
 use super::types::{CarcassonneState, Feature, FeatureType, Position};
 
 /// Score a completed feature. Returns {player_id: points}.
-pub fn score_completed_feature(feature: &Feature) -> HashMap<String, i64> {
+/// With `inns_cathedrals`, roads with an inn score 2 per tile and cities with a
+/// cathedral 3 per tile and pennant.
+pub fn score_completed_feature(feature: &Feature, inns_cathedrals: bool) -> HashMap<String, i64> {
     if feature.meeples.is_empty() {
         return HashMap::new();
     }
 
-    // Count meeples per player
+    // Majority strength per player (a big meeple counts twice)
     let mut meeple_counts: HashMap<String, i64> = HashMap::new();
     for m in &feature.meeples {
-        *meeple_counts.entry(m.player_id.clone()).or_insert(0) += 1;
+        *meeple_counts.entry(m.player_id.clone()).or_insert(0) += m.count as i64;
     }
 
     let max_count = *meeple_counts.values().max().unwrap_or(&0);
@@ -28,7 +30,11 @@ pub fn score_completed_feature(feature: &Feature) -> HashMap<String, i64> {
     let tile_count = feature.tiles.len() as i64;
 
     let points = match feature.feature_type {
+        FeatureType::City if inns_cathedrals && feature.has_cathedral => {
+            tile_count * 3 + feature.pennants as i64 * 3
+        }
         FeatureType::City => tile_count * 2 + feature.pennants as i64 * 2,
+        FeatureType::Road if inns_cathedrals && feature.has_inn => tile_count * 2,
         FeatureType::Road => tile_count,
         FeatureType::Monastery => 9,
         FeatureType::Field => return HashMap::new(),
@@ -37,7 +43,8 @@ pub fn score_completed_feature(feature: &Feature) -> HashMap<String, i64> {
     winners.into_iter().map(|pid| (pid, points)).collect()
 }
 
-/// Score all incomplete features and fields at game end.
+/// Score all incomplete features and fields at game end. With Inns & Cathedrals,
+/// an incomplete road with an inn or city with a cathedral scores nothing.
 pub fn score_end_game(
     state: &CarcassonneState,
 ) -> (HashMap<String, i64>, HashMap<String, HashMap<String, i64>>) {
@@ -55,7 +62,7 @@ pub fn score_end_game(
 
         let mut meeple_counts: HashMap<String, i64> = HashMap::new();
         for m in &feature.meeples {
-            *meeple_counts.entry(m.player_id.clone()).or_insert(0) += 1;
+            *meeple_counts.entry(m.player_id.clone()).or_insert(0) += m.count as i64;
         }
 
         let max_count = *meeple_counts.values().max().unwrap_or(&0);
@@ -68,9 +75,11 @@ pub fn score_end_game(
         let tile_count = feature.tiles.len() as i64;
 
         let (points, category) = match feature.feature_type {
+            FeatureType::City if state.inns_cathedrals && feature.has_cathedral => (0, "cities"),
             FeatureType::City => {
                 (tile_count + feature.pennants as i64, "cities")
             }
+            FeatureType::Road if state.inns_cathedrals && feature.has_inn => (0, "roads"),
             FeatureType::Road => (tile_count, "roads"),
             FeatureType::Monastery => {
                 if feature.tiles.is_empty() {
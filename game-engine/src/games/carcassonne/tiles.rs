@@ -1,19 +1,22 @@
-//! Complete tile catalog for the Carcassonne base game (24 types, 72 tiles).
+//! Complete tile catalog for the Carcassonne base game (24 types, 72 tiles);
+//! expansion tiles (see `river.rs`) are indexed after it.
 //! Mirrors backend/src/games/carcassonne/tiles.py.
 
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
+use super::inns_cathedrals::{INNS_CATHEDRALS_CATALOG, INNS_CATHEDRALS_EXPANSION};
+use super::river::{RIVER_CATALOG, RIVER_EXPANSION};
 use super::types::*;
 
 use EdgeType::{City as C, Field as F, Road as R};
 use FeatureType::{City, Field, Monastery, Road};
 
-fn edges(n: EdgeType, e: EdgeType, s: EdgeType, w: EdgeType) -> [EdgeType; 4] {
+pub(super) fn edges(n: EdgeType, e: EdgeType, s: EdgeType, w: EdgeType) -> [EdgeType; 4] {
     [n, e, s, w]
 }
 
-fn feat(
+pub(super) fn feat(
     ft: FeatureType,
     e: &[&str],
     spots: &[&str],
@@ -25,10 +28,12 @@ fn feat(
         is_monastery: false,
         meeple_spots: spots.iter().map(|s| s.to_string()).collect(),
         adjacent_cities: vec![],
+        has_inn: false,
+        has_cathedral: false,
     }
 }
 
-fn feat_pennant(
+pub(super) fn feat_pennant(
     ft: FeatureType,
     e: &[&str],
     spots: &[&str],
@@ -39,7 +44,7 @@ fn feat_pennant(
     }
 }
 
-fn feat_monastery(spots: &[&str]) -> TileFeature {
+pub(super) fn feat_monastery(spots: &[&str]) -> TileFeature {
     TileFeature {
         feature_type: Monastery,
         edges: vec![],
@@ -47,10 +52,12 @@ fn feat_monastery(spots: &[&str]) -> TileFeature {
         is_monastery: true,
         meeple_spots: spots.iter().map(|s| s.to_string()).collect(),
         adjacent_cities: vec![],
+        has_inn: false,
+        has_cathedral: false,
     }
 }
 
-fn feat_with_adj(
+pub(super) fn feat_with_adj(
     ft: FeatureType,
     e: &[&str],
     spots: &[&str],
@@ -392,9 +399,13 @@ pub static TILE_CATALOG: Lazy<Vec<TileDefinition>> = Lazy::new(|| {
     ]
 });
 
+/// Base game tiles followed by every expansion's tiles.
+fn all_tiles() -> impl Iterator<Item = &'static TileDefinition> {
+    TILE_CATALOG.iter().chain(RIVER_CATALOG.iter()).chain(INNS_CATHEDRALS_CATALOG.iter())
+}
+
 pub static TILE_LOOKUP: Lazy<HashMap<String, &'static TileDefinition>> = Lazy::new(|| {
-    TILE_CATALOG
-        .iter()
+    all_tiles()
         .map(|t| (t.tile_type_id.clone(), t))
         .collect()
 });
@@ -402,8 +413,8 @@ pub static TILE_LOOKUP: Lazy<HashMap<String, &'static TileDefinition>> = Lazy::n
 /// Pre-computed rotated edges for all tile types × 4 rotations.
 /// Indexed by tile type u8 index → \[rotation_index (0-3)\]\[direction_index (N=0,E=1,S=2,W=3)\].
 pub static ROTATED_EDGES: Lazy<Vec<[[EdgeType; 4]; 4]>> = Lazy::new(|| {
-    let mut table = vec![[[EdgeType::Field; 4]; 4]; 24];
-    for tile in TILE_CATALOG.iter() {
+    let mut table = vec![[[EdgeType::Field; 4]; 4]; TILE_TYPE_COUNT];
+    for tile in all_tiles() {
         let idx = tile_type_to_index(&tile.tile_type_id) as usize;
         for rot_idx in 0..4usize {
             for dir_idx in 0..4usize {
@@ -415,24 +426,35 @@ pub static ROTATED_EDGES: Lazy<Vec<[[EdgeType; 4]; 4]>> = Lazy::new(|| {
     table
 });
 
-/// Fast tile definition lookup by u8 index (0–23).
+/// Fast tile definition lookup by u8 index.
 pub static TILE_DEFS: Lazy<Vec<&'static TileDefinition>> = Lazy::new(|| {
-    let mut defs: Vec<Option<&'static TileDefinition>> = vec![None; 24];
-    for t in TILE_CATALOG.iter() {
+    let mut defs: Vec<Option<&'static TileDefinition>> = vec![None; TILE_TYPE_COUNT];
+    for t in all_tiles() {
         let idx = tile_type_to_index(&t.tile_type_id) as usize;
         defs[idx] = Some(t);
     }
     defs.into_iter().map(|d| d.unwrap()).collect()
 });
 
+/// Whether a tile type carries a pennant (on its single pennant city).
+pub fn tile_has_pennant(tile_type_idx: u8) -> bool {
+    TILE_DEFS[tile_type_idx as usize].features.iter().any(|f| f.has_pennant)
+}
+
 pub const STARTING_TILE_ID: &str = "D";
 pub const STARTING_TILE_IDX: u8 = 3; // tile_type_to_index("D")
 
-/// Build the draw bag as u8 tile type indices. Excludes one copy of the starting tile.
-pub fn build_tile_bag(_expansions: Option<&[String]>) -> Vec<u8> {
-    let mut bag = Vec::with_capacity(71);
-    for tile_def in TILE_CATALOG.iter() {
-        let count = if tile_def.tile_type_id == STARTING_TILE_ID {
+/// Build the draw bag as u8 tile type indices: the base game plus any
+/// expansion tiles shuffled in with it (river tiles are drawn separately first).
+/// Excludes one copy of the starting tile unless the river spring starts the game.
+pub fn build_tile_bag(expansions: Option<&[String]>) -> Vec<u8> {
+    let has = |name: &str| expansions.is_some_and(|e| e.iter().any(|x| x == name));
+    let river = has(RIVER_EXPANSION);
+    let extra: &[TileDefinition] =
+        if has(INNS_CATHEDRALS_EXPANSION) { &INNS_CATHEDRALS_CATALOG } else { &[] };
+    let mut bag = Vec::with_capacity(72);
+    for tile_def in TILE_CATALOG.iter().chain(extra) {
+        let count = if tile_def.tile_type_id == STARTING_TILE_ID && !river {
             tile_def.count - 1
         } else {
             tile_def.count
@@ -449,11 +471,11 @@ pub fn build_tile_bag(_expansions: Option<&[String]>) -> Vec<u8> {
 /// Indexed by tile type u8 index → [rotation_index (0-3)].
 /// Returns a borrowed slice — zero allocation on the hot path.
 pub static ROTATED_FEATURES: Lazy<Vec<[Vec<TileFeature>; 4]>> = Lazy::new(|| {
-    let mut table: Vec<[Vec<TileFeature>; 4]> = Vec::with_capacity(24);
-    for _ in 0..24 {
+    let mut table: Vec<[Vec<TileFeature>; 4]> = Vec::with_capacity(TILE_TYPE_COUNT);
+    for _ in 0..TILE_TYPE_COUNT {
         table.push([vec![], vec![], vec![], vec![]]);
     }
-    for tile in TILE_CATALOG.iter() {
+    for tile in all_tiles() {
         let idx = tile_type_to_index(&tile.tile_type_id) as usize;
         for rot_idx in 0..4usize {
             let rotation = rot_idx as u32 * 90;
@@ -475,6 +497,8 @@ pub static ROTATED_FEATURES: Lazy<Vec<[Vec<TileFeature>; 4]>> = Lazy::new(|| {
                         adjacent_cities: feat.adjacent_cities.iter()
                             .map(|s| rotate_meeple_spot(s, rotation))
                             .collect(),
+                        has_inn: feat.has_inn,
+                        has_cathedral: feat.has_cathedral,
                     })
                     .collect();
             }
@@ -515,6 +539,13 @@ mod tests {
     fn test_tile_bag_size() {
         let bag = build_tile_bag(None);
         assert_eq!(bag.len(), 71); // 72 - 1 starting tile
+
+        // The river spring replaces the starting tile
+        let bag = build_tile_bag(Some(&[RIVER_EXPANSION.to_string()]));
+        assert_eq!(bag.len(), 72);
+
+        let bag = build_tile_bag(Some(&[INNS_CATHEDRALS_EXPANSION.to_string()]));
+        assert_eq!(bag.len(), 71 + 8);
     }
 
     #[test]
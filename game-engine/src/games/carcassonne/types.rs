@@ -9,6 +9,8 @@ pub enum EdgeType {
     City,
     Road,
     Field,
+    /// River expansion; only ever matches another river edge.
+    River,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -44,19 +46,30 @@ pub fn direction_index(dir: &str) -> usize {
 
 // --- Tile type ID conversion ---
 
-const TILE_TYPE_STRINGS: [&str; 24] = [
+/// Base game tiles "A"–"X" (0–23), then the river expansion "R1"–"R10" (24–33)
+/// and the Inns & Cathedrals tiles "IC1"–"IC4" (34–37).
+const TILE_TYPE_STRINGS: [&str; TILE_TYPE_COUNT] = [
     "A", "B", "C", "D", "E", "F", "G", "H", "I", "J",
     "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T",
     "U", "V", "W", "X",
+    "R1", "R2", "R3", "R4", "R5", "R6", "R7", "R8", "R9", "R10",
+    "IC1", "IC2", "IC3", "IC4",
 ];
 
-/// Convert tile type ID string (e.g. "A") to u8 index (0–23).
+/// Number of tile types across the base game and expansions.
+pub const TILE_TYPE_COUNT: usize = 38;
+
+/// Convert tile type ID string (e.g. "A", "R3") to u8 index. Unknown
+/// multi-letter IDs map to `u8::MAX` so invariant checks can report them.
 #[inline]
 pub fn tile_type_to_index(id: &str) -> u8 {
-    id.as_bytes()[0] - b'A'
+    if id.len() == 1 {
+        return id.as_bytes()[0] - b'A';
+    }
+    TILE_TYPE_STRINGS.iter().position(|&s| s == id).map_or(u8::MAX, |i| i as u8)
 }
 
-/// Convert tile type u8 index (0–23) to string ID (e.g. "A").
+/// Convert tile type u8 index to string ID (e.g. "A").
 #[inline]
 pub fn tile_index_to_type(idx: u8) -> &'static str {
     TILE_TYPE_STRINGS[idx as usize]
@@ -75,6 +88,12 @@ pub struct TileFeature {
     pub meeple_spots: Vec<String>,
     #[serde(default)]
     pub adjacent_cities: Vec<String>,
+    /// Inns & Cathedrals: an inn on this road segment.
+    #[serde(default)]
+    pub has_inn: bool,
+    /// Inns & Cathedrals: a cathedral in this city segment.
+    #[serde(default)]
+    pub has_cathedral: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,10 +126,13 @@ impl Position {
     }
 
     pub fn from_key(key: &str) -> Self {
-        let mut parts = key.split(',');
-        let x: i32 = parts.next().unwrap().parse().unwrap();
-        let y: i32 = parts.next().unwrap().parse().unwrap();
-        Self { x, y }
+        Self::parse_key(key).unwrap_or_else(|| panic!("Invalid position key: {key}"))
+    }
+
+    /// Parse an "x,y" key, or None if it is malformed.
+    pub fn parse_key(key: &str) -> Option<Self> {
+        let (x, y) = key.split_once(',')?;
+        Some(Self { x: x.parse().ok()?, y: y.parse().ok()? })
     }
 
     pub fn neighbor(self, direction: &str) -> Self {
@@ -153,6 +175,25 @@ pub struct PlacedMeeple {
     pub player_id: String,
     pub position: String,
     pub spot: String,
+    /// Weight in majority checks: 2 for the big meeple, otherwise 1.
+    #[serde(default = "PlacedMeeple::default_count", skip_serializing_if = "PlacedMeeple::is_normal")]
+    pub count: u32,
+}
+
+impl PlacedMeeple {
+    pub const BIG_COUNT: u32 = 2;
+
+    fn default_count() -> u32 {
+        1
+    }
+
+    fn is_normal(count: &u32) -> bool {
+        *count == 1
+    }
+
+    pub fn is_big(&self) -> bool {
+        self.count == Self::BIG_COUNT
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -170,6 +211,30 @@ pub struct Feature {
     pub open_edges: Vec<[String; 2]>,
     #[serde(default, rename = "_merged_from")]
     pub merged_from: Vec<String>,
+    /// Any of the feature's tiles has an inn (roads) or a cathedral (cities).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_inn: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_cathedral: bool,
+}
+
+/// What happens when a drawn tile has no legal placement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnplaceablePolicy {
+    /// Remove the tile from the game and draw again.
+    #[default]
+    Discard,
+    /// Put the tile back at the bottom of the bag and draw again.
+    Bottom,
+    /// End the game as if the bag were empty.
+    EndGame,
+}
+
+impl UnplaceablePolicy {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Full Carcassonne game state (strongly typed, serialized to/from JSON at gRPC boundary).
@@ -184,6 +249,9 @@ pub struct CarcassonneState {
     pub features: HashMap<String, Feature>,
     pub tile_feature_map: HashMap<String, HashMap<String, String>>,
     pub meeple_supply: HashMap<String, i32>,
+    /// Meeples each player started with, from the `meeples_per_player` option.
+    #[serde(default = "default_meeples_per_player", skip_serializing_if = "is_default_meeples_per_player")]
+    pub meeples_per_player: i32,
     pub scores: HashMap<String, i64>,
     pub current_player_index: usize,
     #[serde(default)]
@@ -198,6 +266,38 @@ pub struct CarcassonneState {
     /// Redirect table for merged feature IDs: old_id -> surviving_id.
     #[serde(default)]
     pub feature_redirects: HashMap<String, String>,
+    /// Scores after each score_check, oldest first. None unless the
+    /// `track_score_history` option is set; never carried into MCTS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_history: Option<Vec<HashMap<String, i64>>>,
+    /// Set from the `unplaceable_policy` option.
+    #[serde(default, skip_serializing_if = "UnplaceablePolicy::is_default")]
+    pub unplaceable_policy: UnplaceablePolicy,
+    /// Set from the `ai_view_placement_groups` option.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ai_view_placement_groups: bool,
+    /// Big meeples still in each player's supply (0 or 1). Empty unless
+    /// Inns & Cathedrals is played.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub big_meeple_supply: HashMap<String, i32>,
+    /// Inns & Cathedrals scoring; set when the `expansions` option includes it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inns_cathedrals: bool,
+    /// Open end of the river while river expansion tiles are still being placed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub river: Option<super::river::RiverState>,
+    /// Zobrist hash of the tiles and meeples on the board (see `zobrist`),
+    /// updated as they come and go. Not serialized: recomputed on decode.
+    #[serde(skip)]
+    pub zobrist: u64,
+}
+
+fn default_meeples_per_player() -> i32 {
+    super::meeples::MEEPLES_PER_PLAYER
+}
+
+fn is_default_meeples_per_player(count: &i32) -> bool {
+    *count == super::meeples::MEEPLES_PER_PLAYER
 }
 
 impl CarcassonneState {
@@ -209,10 +309,18 @@ impl CarcassonneState {
     pub fn float_scores(&self) -> std::collections::HashMap<String, f64> {
         self.scores.iter().map(|(k, v)| (k.clone(), *v as f64)).collect()
     }
+
+    /// Append the current scores to `score_history` if tracking is enabled.
+    pub fn record_score_history(&mut self) {
+        if let Some(history) = self.score_history.as_mut() {
+            history.push(self.scores.clone());
+        }
+    }
 }
 
 /// Board with (i32, i32) tuple keys for zero-allocation neighbor lookups.
-/// Custom Serialize/Deserialize maintains "x,y" string key JSON format.
+/// Custom Serialize/Deserialize maintains "x,y" string key JSON format; binary
+/// formats get positions and tile indices as plain numbers.
 #[derive(Debug, Clone)]
 pub struct Board {
     pub tiles: HashMap<(i32, i32), PlacedTile>,
@@ -233,8 +341,30 @@ struct BoardSerde {
     open_positions: Vec<String>,
 }
 
+/// Non-human-readable form: tiles as (x, y, tile index, rotation).
+#[derive(Serialize, Deserialize)]
+struct BoardBinary {
+    tiles: Vec<(i32, i32, u8, u32)>,
+    open_positions: Vec<(i32, i32)>,
+}
+
+/// Tile index `idx` from a binary state, rejecting ones that name no tile type.
+fn check_tile_index<E: serde::de::Error>(idx: u8) -> Result<u8, E> {
+    if (idx as usize) < TILE_TYPE_COUNT {
+        Ok(idx)
+    } else {
+        Err(E::custom(format!("invalid tile index {idx}")))
+    }
+}
+
 impl Serialize for Board {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            let tiles = self.tiles.iter()
+                .map(|(&(x, y), tile)| (x, y, tile.tile_type_id, tile.rotation))
+                .collect();
+            return BoardBinary { tiles, open_positions: self.open_positions.clone() }.serialize(serializer);
+        }
         let serde_board = BoardSerde {
             tiles: self.tiles.iter().map(|(&(x, y), tile)| {
                 (
@@ -255,33 +385,56 @@ impl Serialize for Board {
 
 impl<'de> Deserialize<'de> for Board {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let binary = BoardBinary::deserialize(deserializer)?;
+            let mut open_positions = binary.open_positions;
+            open_positions.sort();
+            return Ok(Board {
+                tiles: binary.tiles.into_iter().map(|(x, y, tile_type_id, rotation)| {
+                    let tile_type_id = check_tile_index(tile_type_id)?;
+                    Ok(((x, y), PlacedTile { tile_type_id, rotation }))
+                }).collect::<Result<_, D::Error>>()?,
+                open_positions,
+            });
+        }
         let serde_board = BoardSerde::deserialize(deserializer)?;
+        let parse = |key: &str| {
+            Position::parse_key(key)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid position key '{key}'")))
+        };
+        let mut open_positions: Vec<(i32, i32)> = serde_board.open_positions.iter().map(|key| {
+            let pos = parse(key)?;
+            Ok((pos.x, pos.y))
+        }).collect::<Result<_, D::Error>>()?;
+        // Kept sorted, as `update_open_positions` relies on
+        open_positions.sort();
         Ok(Board {
             tiles: serde_board.tiles.into_iter().map(|(key, tile)| {
-                let pos = Position::from_key(&key);
-                (
+                let pos = parse(&key)?;
+                Ok((
                     (pos.x, pos.y),
                     PlacedTile {
                         tile_type_id: tile_type_to_index(&tile.tile_type_id),
                         rotation: tile.rotation,
                     },
-                )
-            }).collect(),
-            open_positions: serde_board.open_positions.into_iter().map(|key| {
-                let pos = Position::from_key(&key);
-                (pos.x, pos.y)
-            }).collect(),
+                ))
+            }).collect::<Result<_, D::Error>>()?,
+            open_positions,
         })
     }
 }
 
-// --- Serde helpers for tile_bag (Vec<u8> ↔ Vec<String>) ---
+// --- Serde helpers for tile_bag (Vec<u8> ↔ Vec<String>, indices in binary formats) ---
 
 mod serde_tile_bag {
     use super::tile_index_to_type;
 
     pub fn serialize<S: serde::Serializer>(bag: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeSeq;
+        use serde::Serialize;
+        if !serializer.is_human_readable() {
+            return bag.serialize(serializer);
+        }
         let mut seq = serializer.serialize_seq(Some(bag.len()))?;
         for &idx in bag {
             seq.serialize_element(tile_index_to_type(idx))?;
@@ -291,17 +444,25 @@ mod serde_tile_bag {
 
     pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
         use serde::Deserialize;
+        if !deserializer.is_human_readable() {
+            let bag: Vec<u8> = Vec::deserialize(deserializer)?;
+            return bag.into_iter().map(super::check_tile_index).collect();
+        }
         let strings: Vec<String> = Vec::deserialize(deserializer)?;
         Ok(strings.iter().map(|s| super::tile_type_to_index(s)).collect())
     }
 }
 
-// --- Serde helpers for current_tile (Option<u8> ↔ Option<String>) ---
+// --- Serde helpers for current_tile (Option<u8> ↔ Option<String>, index in binary formats) ---
 
 mod serde_current_tile {
     use super::tile_index_to_type;
 
     pub fn serialize<S: serde::Serializer>(tile: &Option<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::Serialize;
+        if !serializer.is_human_readable() {
+            return tile.serialize(serializer);
+        }
         match tile {
             Some(idx) => serializer.serialize_some(tile_index_to_type(*idx)),
             None => serializer.serialize_none(),
@@ -310,6 +471,10 @@ mod serde_current_tile {
 
     pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
         use serde::Deserialize;
+        if !deserializer.is_human_readable() {
+            let tile: Option<u8> = Option::deserialize(deserializer)?;
+            return tile.map(super::check_tile_index).transpose();
+        }
         let opt: Option<String> = Option::deserialize(deserializer)?;
         Ok(opt.map(|s| super::tile_type_to_index(&s)))
     }
@@ -467,7 +632,7 @@ mod tests {
 
     #[test]
     fn test_tile_type_roundtrip() {
-        for idx in 0..24u8 {
+        for idx in 0..TILE_TYPE_COUNT as u8 {
             let s = tile_index_to_type(idx);
             assert_eq!(tile_type_to_index(s), idx);
         }
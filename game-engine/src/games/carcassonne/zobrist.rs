@@ -0,0 +1,35 @@
+//! Zobrist keys for Carcassonne positions.
+//!
+//! The board is unbounded, so instead of a precomputed table each key is a fixed
+//! hash of what it stands for: (position, tile type, rotation) for a tile and
+//! (position, spot, owner, weight) for a meeple. Meeples are keyed by where they
+//! stand rather than by feature id, since merges relabel features. XOR-ing keys
+//! in and out keeps `CarcassonneState::zobrist` current in O(1) per change.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::types::{CarcassonneState, PlacedMeeple};
+
+pub fn tile_key(x: i32, y: i32, tile_type_idx: u8, rotation: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (0u8, x, y, tile_type_idx, rotation % 360).hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn meeple_key(meeple: &PlacedMeeple) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (1u8, &meeple.position, &meeple.spot, &meeple.player_id, meeple.count).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Zobrist hash of the tiles and meeples on the board, computed from scratch.
+pub fn board_hash(state: &CarcassonneState) -> u64 {
+    let tiles = state
+        .board
+        .tiles
+        .iter()
+        .map(|(&(x, y), tile)| tile_key(x, y, tile.tile_type_id, tile.rotation));
+    let meeples = state.features.values().flat_map(|f| f.meeples.iter().map(meeple_key));
+    tiles.chain(meeples).fold(0, |hash, key| hash ^ key)
+}
@@ -0,0 +1,455 @@
+//! Connect Four — a small perfect-information game for exercising MCTS.
+
+use std::collections::HashMap;
+
+use crate::engine::models::*;
+use crate::engine::payload::{FieldKind, PayloadField};
+use crate::engine::plugin::{TypedGamePlugin, TypedTransitionResult};
+
+use super::types::*;
+
+const DROP_SCHEMA: &[PayloadField] = &[PayloadField::required("column", FieldKind::UInt)];
+
+/// Columns in the order valid actions are listed: center first, as the
+/// strongest moves usually are.
+const COLUMN_ORDER: [usize; COLUMNS] = [3, 2, 4, 1, 5, 0, 6];
+
+pub struct ConnectFourPlugin;
+
+impl TypedGamePlugin for ConnectFourPlugin {
+    type State = ConnectFourState;
+
+    fn game_id(&self) -> &str {
+        "connect_four"
+    }
+    fn display_name(&self) -> &str {
+        "Connect Four"
+    }
+    fn min_players(&self) -> u32 {
+        2
+    }
+    fn max_players(&self) -> u32 {
+        2
+    }
+    fn description(&self) -> &str {
+        "Drop discs into a 7x6 grid; the first to line up four in a row, \
+         column or diagonal wins."
+    }
+    fn disconnect_policy(&self) -> &str {
+        "forfeit_game"
+    }
+
+    fn encode_state(&self, state: &ConnectFourState) -> serde_json::Value {
+        serde_json::to_value(state).expect("serialization should not fail")
+    }
+
+    fn load_state(&self, game_data: &serde_json::Value, _phase: &Phase) -> Result<(), String> {
+        let state = serde_json::from_value::<ConnectFourState>(game_data.clone())
+            .map_err(|e| format!("Invalid ConnectFourState: {e}"))?;
+        if state.columns.len() != COLUMNS || state.columns.iter().any(|c| c.len() > ROWS) {
+            return Err(format!("Invalid ConnectFourState: board must be {COLUMNS}x{ROWS}"));
+        }
+        Ok(())
+    }
+
+    fn create_initial_state(
+        &self,
+        players: &[Player],
+        _config: &GameConfig,
+    ) -> (ConnectFourState, Phase, Vec<Event>) {
+        let player_ids: Vec<String> = players.iter().map(|p| p.player_id.clone()).collect();
+        let state = ConnectFourState::new(player_ids.clone());
+        let phase = make_player_turn_phase(0, &players[0].player_id);
+        let events = vec![Event {
+            event_type: "game_started".into(),
+            player_id: None,
+            payload: serde_json::json!({"players": player_ids}),
+        }];
+        (state, phase, events)
+    }
+
+    fn get_valid_actions(
+        &self,
+        state: &ConnectFourState,
+        phase: &Phase,
+        player_id: &str,
+    ) -> Vec<serde_json::Value> {
+        if phase.name != "player_turn" || !is_current_player(state, player_id) {
+            return vec![];
+        }
+        COLUMN_ORDER
+            .iter()
+            .filter(|&&c| !state.is_column_full(c))
+            .map(|&c| serde_json::json!({"column": c}))
+            .collect()
+    }
+
+    fn validate_action(
+        &self,
+        state: &ConnectFourState,
+        phase: &Phase,
+        action: &Action,
+    ) -> Option<String> {
+        if phase.name != "player_turn" {
+            return Some(format!("No moves allowed in phase {}", phase.name));
+        }
+        if action.action_type != "drop" {
+            return Some(format!("Unknown action type: {}", action.action_type));
+        }
+        if !is_current_player(state, &action.player_id) {
+            return Some(format!("Not {}'s turn", action.player_id));
+        }
+        match drop_column(action) {
+            None => Some("Missing column in payload".into()),
+            Some(c) if c >= COLUMNS => Some(format!("Column {c} is off the board")),
+            Some(c) if state.is_column_full(c) => Some(format!("Column {c} is full")),
+            Some(_) => None,
+        }
+    }
+
+    fn apply_action(
+        &self,
+        state: &ConnectFourState,
+        phase: &Phase,
+        action: &Action,
+        players: &[Player],
+    ) -> Result<TypedTransitionResult<ConnectFourState>, String> {
+        match (phase.name.as_str(), action.action_type.as_str()) {
+            ("player_turn", "drop") => self.apply_drop(state, action, players),
+            _ => Err(format!("Unknown action {} in phase {}", action.action_type, phase.name)),
+        }
+    }
+
+    fn get_player_view(
+        &self,
+        state: &ConnectFourState,
+        _phase: &Phase,
+        _player_id: Option<&str>,
+        _players: &[Player],
+    ) -> serde_json::Value {
+        // No hidden information — return full state
+        self.encode_state(state)
+    }
+
+    fn get_scores(&self, state: &ConnectFourState) -> HashMap<String, f64> {
+        state
+            .player_ids
+            .iter()
+            .enumerate()
+            .map(|(seat, pid)| (pid.clone(), if state.winner == Some(seat) { 1.0 } else { 0.0 }))
+            .collect()
+    }
+
+    fn is_perfect_information(&self) -> bool {
+        true
+    }
+
+    /// A draw is worth 0.5 to both players, keeping value(a) = 1 - value(b).
+    fn value_for(&self, result: &GameResult, player_id: &str) -> f64 {
+        if result.winners.is_empty() {
+            0.5
+        } else if result.winners.iter().any(|w| w == player_id) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn parse_ai_action(
+        &self,
+        response: &serde_json::Value,
+        _phase: &Phase,
+        player_id: &str,
+    ) -> Action {
+        let payload = response
+            .get("action")
+            .and_then(|a| a.get("payload"))
+            .unwrap_or(response)
+            .clone();
+        Action {
+            action_type: "drop".into(),
+            player_id: player_id.into(),
+            payload,
+        }
+    }
+
+    fn payload_schema(&self, action_type: &str) -> Option<&'static [PayloadField]> {
+        match action_type {
+            "drop" => Some(DROP_SCHEMA),
+            _ => None,
+        }
+    }
+
+    fn phase_action_types(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        &[("player_turn", &["drop"])]
+    }
+}
+
+impl ConnectFourPlugin {
+    fn apply_drop(
+        &self,
+        state: &ConnectFourState,
+        action: &Action,
+        players: &[Player],
+    ) -> Result<TypedTransitionResult<ConnectFourState>, String> {
+        let column = drop_column(action)
+            .filter(|&c| c < COLUMNS && !state.is_column_full(c))
+            .ok_or_else(|| format!("Cannot drop into column {}", action.payload["column"]))?;
+        let seat = state.current_player_index;
+        let mut s = state.clone();
+        s.columns[column].push(seat);
+        let row = s.columns[column].len() - 1;
+
+        let mut events = vec![Event {
+            event_type: "disc_dropped".into(),
+            player_id: Some(action.player_id.clone()),
+            payload: serde_json::json!({"column": column, "row": row}),
+        }];
+
+        let reason = if s.connects_at(column, row) {
+            s.winner = Some(seat);
+            Some("four_in_a_row")
+        } else if s.is_full() {
+            Some("board_full")
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            let final_scores = self.get_scores(&s);
+            let winners: Vec<String> = s.winner.map(|w| s.player_ids[w].clone()).into_iter().collect();
+            events.push(Event {
+                event_type: "game_ended".into(),
+                player_id: None,
+                payload: serde_json::json!({
+                    "final_scores": &final_scores,
+                    "winners": &winners,
+                    "reason": reason,
+                }),
+            });
+            return Ok(TypedTransitionResult {
+                state: s,
+                events,
+                next_phase: Phase {
+                    name: "game_over".into(),
+                    auto_resolve: false,
+                    concurrent_mode: None,
+                    expected_actions: vec![],
+                    metadata: serde_json::json!({}),
+                },
+                scores: final_scores.clone(),
+                game_over: Some(GameResult {
+                    winners,
+                    final_scores,
+                    reason: reason.into(),
+                    details: HashMap::new(),
+                }),
+            });
+        }
+
+        let next = (seat + 1) % players.len();
+        s.current_player_index = next;
+        let scores = self.get_scores(&s);
+        Ok(TypedTransitionResult {
+            state: s,
+            events,
+            next_phase: make_player_turn_phase(next, &players[next].player_id),
+            scores,
+            game_over: None,
+        })
+    }
+}
+
+fn is_current_player(state: &ConnectFourState, player_id: &str) -> bool {
+    state.player_ids.get(state.current_player_index).map(String::as_str) == Some(player_id)
+}
+
+fn drop_column(action: &Action) -> Option<usize> {
+    action.payload.get("column")?.as_u64().map(|c| c as usize)
+}
+
+fn make_player_turn_phase(player_index: usize, player_id: &str) -> Phase {
+    Phase {
+        name: "player_turn".into(),
+        concurrent_mode: Some(ConcurrentMode::Sequential),
+        expected_actions: vec![ExpectedAction {
+            player_id: player_id.into(),
+            action_type: "drop".into(),
+            constraints: HashMap::new(),
+            timeout_ms: None,
+        }],
+        auto_resolve: false,
+        metadata: serde_json::json!({"player_index": player_index}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::mcts::{mcts_search, MctsParams};
+    use crate::engine::simulator::{apply_action_and_resolve, SimulationState};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn players() -> Vec<Player> {
+        ["mcts", "other"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| Player {
+                player_id: id.to_string(),
+                display_name: id.to_string(),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    fn new_game() -> SimulationState<ConnectFourState> {
+        let players = players();
+        let config = GameConfig { options: serde_json::json!({}), random_seed: None };
+        let (state, phase, _) = ConnectFourPlugin.create_initial_state(&players, &config);
+        SimulationState {
+            state,
+            phase,
+            scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
+            players: players.into(),
+            game_over: None,
+            history: None,
+        }
+    }
+
+    fn drop(sim: &mut SimulationState<ConnectFourState>, column: usize) {
+        let player_id = sim.state.player_ids[sim.state.current_player_index].clone();
+        let action = Action {
+            action_type: "drop".into(),
+            player_id,
+            payload: serde_json::json!({"column": column}),
+        };
+        assert_eq!(ConnectFourPlugin.validate_action(&sim.state, &sim.phase, &action), None);
+        apply_action_and_resolve(&ConnectFourPlugin, sim, &action).unwrap();
+    }
+
+    fn mcts_column(sim: &SimulationState<ConnectFourState>, seed: u64) -> usize {
+        let params = MctsParams { num_simulations: 1000, seed: Some(seed), ..Default::default() };
+        let (action, _) = mcts_search(
+            &sim.state,
+            &sim.phase,
+            "mcts",
+            &ConnectFourPlugin,
+            &sim.players,
+            &params,
+            None,
+        ).unwrap();
+        action["column"].as_u64().unwrap() as usize
+    }
+
+    #[test]
+    fn test_wins_in_each_direction() {
+        let lines: [&[usize]; 4] = [
+            &[0, 0, 1, 1, 2, 2, 3],          // row
+            &[0, 1, 0, 1, 0, 1, 0],          // column
+            &[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3], // rising diagonal
+            &[3, 2, 2, 1, 1, 0, 1, 0, 0, 6, 0], // falling diagonal
+        ];
+        for moves in lines {
+            let mut sim = new_game();
+            for &c in moves {
+                assert!(sim.game_over.is_none());
+                drop(&mut sim, c);
+            }
+            let result = sim.game_over.as_ref().unwrap();
+            assert_eq!(result.winners, vec!["mcts".to_string()], "{moves:?}");
+            assert_eq!(result.reason, "four_in_a_row");
+        }
+    }
+
+    #[test]
+    fn test_full_board_is_a_draw() {
+        let mut sim = new_game();
+        // Column pairs filled in an order that never lines up four.
+        for pair in [[0, 1], [2, 3], [4, 5]] {
+            for _ in 0..3 {
+                drop(&mut sim, pair[0]);
+                drop(&mut sim, pair[1]);
+            }
+            for _ in 0..3 {
+                drop(&mut sim, pair[1]);
+                drop(&mut sim, pair[0]);
+            }
+        }
+        for _ in 0..ROWS {
+            assert!(sim.game_over.is_none());
+            drop(&mut sim, 6);
+        }
+        let result = sim.game_over.as_ref().unwrap();
+        assert!(result.winners.is_empty());
+        assert_eq!(result.reason, "board_full");
+        assert_eq!(ConnectFourPlugin.value_for(result, "mcts"), 0.5);
+    }
+
+    #[test]
+    fn test_rejects_full_column_and_wrong_player() {
+        let mut sim = new_game();
+        for _ in 0..ROWS {
+            drop(&mut sim, 3);
+        }
+        let full = Action {
+            action_type: "drop".into(),
+            player_id: "mcts".into(),
+            payload: serde_json::json!({"column": 3}),
+        };
+        assert!(ConnectFourPlugin.validate_action(&sim.state, &sim.phase, &full).is_some());
+        let out_of_turn = Action { player_id: "other".into(), payload: serde_json::json!({"column": 0}), ..full };
+        assert!(ConnectFourPlugin.validate_action(&sim.state, &sim.phase, &out_of_turn).is_some());
+        assert!(ConnectFourPlugin.get_valid_actions(&sim.state, &sim.phase, "other").is_empty());
+        assert_eq!(ConnectFourPlugin.get_valid_actions(&sim.state, &sim.phase, "mcts").len(), COLUMNS - 1);
+
+        // Unvalidated drops into a full or missing column fail instead of panicking
+        for payload in [serde_json::json!({"column": 3}), serde_json::json!({"column": 9}), serde_json::json!({})] {
+            let action = Action { action_type: "drop".into(), player_id: "mcts".into(), payload };
+            assert!(ConnectFourPlugin.apply_action(&sim.state, &sim.phase, &action, &sim.players).is_err());
+        }
+    }
+
+    #[test]
+    fn test_mcts_takes_immediate_win() {
+        // Each position leaves "mcts" (to move) one drop from four in a line.
+        let positions: [&[usize]; 3] = [
+            &[1, 1, 2, 2, 3, 3],             // row: 0 or 4
+            &[6, 5, 6, 5, 6, 5],             // column: 6, or lose on 5
+            &[0, 1, 1, 2, 2, 3, 2, 3, 3, 6], // rising diagonal: 3
+        ];
+        for (seed, moves) in positions.into_iter().enumerate() {
+            let mut sim = new_game();
+            for &c in moves {
+                drop(&mut sim, c);
+            }
+            let column = mcts_column(&sim, seed as u64);
+            drop(&mut sim, column);
+            let winners = sim.game_over.map(|r| r.winners).unwrap_or_default();
+            assert_eq!(winners, vec!["mcts".to_string()], "{moves:?}: played {column}");
+        }
+    }
+
+    #[test]
+    fn test_mcts_never_loses_center_opening_to_random() {
+        for seed in 0..3 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut sim = new_game();
+            drop(&mut sim, 3);
+            while sim.game_over.is_none() {
+                let valid = ConnectFourPlugin.get_valid_actions(&sim.state, &sim.phase, "other");
+                let column = valid.choose(&mut rng).unwrap()["column"].as_u64().unwrap() as usize;
+                drop(&mut sim, column);
+                if sim.game_over.is_none() {
+                    let column = mcts_column(&sim, seed);
+                    drop(&mut sim, column);
+                }
+            }
+            let result = sim.game_over.unwrap();
+            assert!(!result.winners.contains(&"other".to_string()), "seed {seed}: lost to random");
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Domain types for Connect Four.
+
+use serde::{Deserialize, Serialize};
+
+pub const COLUMNS: usize = 7;
+pub const ROWS: usize = 6;
+/// Discs in a line needed to win.
+pub const CONNECT: usize = 4;
+
+/// Full Connect Four game state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectFourState {
+    /// Seat index of each disc per column, bottom first.
+    pub columns: Vec<Vec<usize>>,
+    /// Player ids by seat index.
+    pub player_ids: Vec<String>,
+    pub current_player_index: usize,
+    /// Seat index of the winner once someone connects four.
+    #[serde(default)]
+    pub winner: Option<usize>,
+}
+
+impl ConnectFourState {
+    pub fn new(player_ids: Vec<String>) -> Self {
+        Self {
+            columns: vec![Vec::new(); COLUMNS],
+            player_ids,
+            current_player_index: 0,
+            winner: None,
+        }
+    }
+
+    /// Seat index of the disc at (`column`, `row`), row 0 being the bottom.
+    pub fn disc(&self, column: usize, row: usize) -> Option<usize> {
+        self.columns.get(column)?.get(row).copied()
+    }
+
+    pub fn is_column_full(&self, column: usize) -> bool {
+        self.columns[column].len() >= ROWS
+    }
+
+    pub fn is_full(&self) -> bool {
+        (0..COLUMNS).all(|c| self.is_column_full(c))
+    }
+
+    /// Whether the disc at (`column`, `row`) is part of `CONNECT` in a line.
+    pub fn connects_at(&self, column: usize, row: usize) -> bool {
+        let Some(seat) = self.disc(column, row) else {
+            return false;
+        };
+        let same = |c: i64, r: i64| {
+            c >= 0 && r >= 0 && self.disc(c as usize, r as usize) == Some(seat)
+        };
+        [(1, 0), (0, 1), (1, 1), (1, -1)].iter().any(|&(dc, dr)| {
+            let run = |sign: i64| {
+                (1..CONNECT as i64)
+                    .take_while(|&i| same(column as i64 + sign * i * dc, row as i64 + sign * i * dr))
+                    .count()
+            };
+            1 + run(1) + run(-1) >= CONNECT
+        })
+    }
+}
@@ -319,6 +319,9 @@ pub fn is_hex_controlled(board: &Board, q: i32, r: i32, player_id: &str) -> bool
     }
 }
 
+/// Minimum surrounding count needed to resolve a conflict hex.
+pub const RESOLVE_THRESHOLD: u32 = 4;
+
 /// Compute the surrounding count for a conflict hex from a given player's perspective.
 ///
 /// For each of the 6 hex directions:
@@ -350,7 +353,7 @@ pub fn get_resolvable_conflicts(board: &Board, player_id: &str) -> Vec<String> {
         .filter(|(_, &state)| state == HexState::Conflict)
         .filter_map(|(hex_key, _)| {
             let (q, r) = parse_hex_key(hex_key)?;
-            if compute_surrounding_count(board, q, r, player_id) >= 4 {
+            if compute_surrounding_count(board, q, r, player_id) >= RESOLVE_THRESHOLD {
                 Some(hex_key.clone())
             } else {
                 None
@@ -359,6 +362,22 @@ pub fn get_resolvable_conflicts(board: &Board, player_id: &str) -> Vec<String> {
         .collect()
 }
 
+/// Surrounding count of every conflict hex for the given player, highest first
+/// (ties by hex key).
+pub fn conflict_surrounding_counts(board: &Board, player_id: &str) -> Vec<(String, u32)> {
+    let mut counts: Vec<(String, u32)> = board
+        .hex_states
+        .iter()
+        .filter(|(_, &state)| state == HexState::Conflict)
+        .filter_map(|(hex_key, _)| {
+            let (q, r) = parse_hex_key(hex_key)?;
+            Some((hex_key.clone(), compute_surrounding_count(board, q, r, player_id)))
+        })
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
 /// Validate that a player can resolve a specific conflict hex.
 pub fn validate_resolve_conflict(board: &Board, hex_key: &str, player_id: &str) -> Option<String> {
     let state = board.hex_states.get(hex_key).copied().unwrap_or(HexState::Empty);
@@ -370,9 +389,9 @@ pub fn validate_resolve_conflict(board: &Board, hex_key: &str, player_id: &str)
         None => return Some("Invalid hex key format".into()),
     };
     let count = compute_surrounding_count(board, q, r, player_id);
-    if count < 4 {
+    if count < RESOLVE_THRESHOLD {
         return Some(format!(
-            "Insufficient surrounding count for {hex_key}: {count} (need >= 4)"
+            "Insufficient surrounding count for {hex_key}: {count} (need >= {RESOLVE_THRESHOLD})"
         ));
     }
     None
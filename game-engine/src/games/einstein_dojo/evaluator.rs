@@ -0,0 +1,151 @@
+//! Heuristic evaluation function for Ein Stein Dojo MCTS.
+//! Returns a value in [0, 1] representing how good the position is for the player.
+//!
+//! Every signal is expressed in (approximate) points and combined before a single
+//! sigmoid, like the Carcassonne v2 evaluator.
+
+use crate::engine::models::*;
+use crate::engine::plugin::EvalFn;
+use crate::games::einstein_dojo::board::{
+    compute_surrounding_count, get_resolvable_conflicts, parse_hex_key, RESOLVE_THRESHOLD,
+};
+use crate::games::einstein_dojo::scoring::count_scores;
+use crate::games::einstein_dojo::types::EinsteinDojoState;
+
+/// A conflict the player could resolve right now is worth about the hex it claims.
+const RESOLVABLE_CONFLICT_POINTS: f64 = 1.0;
+/// Full control around the main conflict; resolving it ends the game outright.
+const MAIN_CONFLICT_POINTS: f64 = 4.0;
+/// Unplaced tiles and marks as tempo: each can still turn into score.
+const TILE_POINTS: f64 = 0.25;
+const MARK_POINTS: f64 = 0.5;
+/// Point advantage at which the value reaches ~0.73.
+const SCALE: f64 = 3.0;
+
+/// Create the Ein Stein Dojo evaluation function.
+pub fn make_einstein_eval() -> EvalFn<EinsteinDojoState> {
+    Box::new(evaluate)
+}
+
+fn evaluate(state: &EinsteinDojoState, _phase: &Phase, player_id: &str, players: &[Player]) -> f64 {
+    let scores = count_scores(&state.board);
+    let main_conflict = state.main_conflict.as_deref().and_then(parse_hex_key);
+
+    let points = |pid: &str| {
+        let score = scores.get(pid).copied().unwrap_or(0) as f64;
+        let resolvable = get_resolvable_conflicts(&state.board, pid).len() as f64;
+        // Progress toward resolving the main conflict, 1.0 once it is resolvable
+        let main_progress = main_conflict
+            .map(|(q, r)| {
+                let count = compute_surrounding_count(&state.board, q, r, pid);
+                count.min(RESOLVE_THRESHOLD) as f64 / RESOLVE_THRESHOLD as f64
+            })
+            .unwrap_or(0.0);
+        let tiles = state.tiles_remaining.get(pid).copied().unwrap_or(0).max(0) as f64;
+        let marks = state.marks_remaining.get(pid).copied().unwrap_or(0).max(0) as f64;
+
+        score
+            + RESOLVABLE_CONFLICT_POINTS * resolvable
+            + MAIN_CONFLICT_POINTS * main_progress
+            + TILE_POINTS * tiles
+            + MARK_POINTS * marks
+    };
+
+    let Some(best_opp) = players
+        .iter()
+        .filter(|p| p.player_id != player_id)
+        .map(|p| points(&p.player_id))
+        .reduce(f64::max)
+    else {
+        return 0.5;
+    };
+    unit_value(sigmoid(points(player_id) - best_opp, SCALE))
+}
+
+fn sigmoid(x: f64, scale: f64) -> f64 {
+    if x.is_nan() {
+        return 0.5;
+    }
+    1.0 / (1.0 + (-x / scale.max(1e-9)).exp())
+}
+
+fn unit_value(value: f64) -> f64 {
+    if value.is_nan() {
+        0.5
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::TypedGamePlugin;
+    use crate::games::einstein_dojo::plugin::EinsteinDojoPlugin;
+    use crate::games::einstein_dojo::types::HexState;
+
+    fn players() -> Vec<Player> {
+        ["p1", "p2"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| Player {
+                player_id: id.to_string(),
+                display_name: id.to_string(),
+                seat_index: i as i32,
+                is_bot: false,
+                bot_id: None,
+            })
+            .collect()
+    }
+
+    fn initial_state() -> (EinsteinDojoState, Phase) {
+        let config = GameConfig { options: serde_json::json!({}), random_seed: Some(1) };
+        let (state, phase, _) = EinsteinDojoPlugin.create_initial_state(&players(), &config);
+        (state, phase)
+    }
+
+    fn complete_hex(state: &mut EinsteinDojoState, key: &str, owner: &str) {
+        for k in 0..6 {
+            state.board.kite_owners.insert(format!("{key}:{k}"), owner.into());
+        }
+        state.board.hex_states.insert(key.into(), HexState::Complete);
+    }
+
+    #[test]
+    fn test_initial_position_is_even() {
+        let (state, phase) = initial_state();
+        let eval = make_einstein_eval();
+        let v = eval(&state, &phase, "p1", &players());
+        assert!((v - 0.5).abs() < 1e-9, "got {v}");
+    }
+
+    #[test]
+    fn test_near_winning_position_favors_leader() {
+        let (mut state, phase) = initial_state();
+        complete_hex(&mut state, "3,0", "p1");
+        complete_hex(&mut state, "-3,0", "p1");
+        complete_hex(&mut state, "0,3", "p2");
+        // p1 surrounds the main conflict on three sides; one more resolves it.
+        state.board.hex_states.insert("0,0".into(), HexState::Conflict);
+        state.main_conflict = Some("0,0".into());
+        for key in ["1,0", "0,1", "-1,1"] {
+            state.board.hex_marks.insert(key.into(), "p1".into());
+        }
+        state.marks_remaining.insert("p1".into(), 5);
+        state.tiles_remaining.insert("p1".into(), 12);
+        state.tiles_remaining.insert("p2".into(), 13);
+
+        let eval = make_einstein_eval();
+        let leader = eval(&state, &phase, "p1", &players());
+        let trailer = eval(&state, &phase, "p2", &players());
+        assert!(leader > 0.5, "leader got {leader}");
+        assert!(trailer < 0.5, "trailer got {trailer}");
+        assert!((leader + trailer - 1.0).abs() < 1e-9);
+
+        // Completing the surround makes the position even better for the leader.
+        state.board.hex_marks.insert("1,-1".into(), "p1".into());
+        let closer = eval(&state, &phase, "p1", &players());
+        assert!(closer > leader, "{closer} <= {leader}");
+        assert!((0.0..=1.0).contains(&closer));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod board;
+pub mod evaluator;
 pub mod pieces;
 pub mod plugin;
 pub mod scoring;
@@ -3,19 +3,36 @@
 use std::collections::HashMap;
 
 use crate::engine::models::*;
-use crate::engine::plugin::{TypedGamePlugin, TypedTransitionResult};
+use crate::engine::payload::{action_schema, FieldKind, PayloadField};
+use crate::engine::plugin::{metadata_player, EvalFn, TypedGamePlugin, TypedTransitionResult};
+use crate::engine::rng::GameRng;
 
 use super::board::{
-    apply_placement, apply_resolve_conflict, get_all_valid_placements,
+    apply_placement, apply_resolve_conflict, conflict_surrounding_counts, get_all_valid_placements,
     get_resolvable_conflicts, get_valid_mark_hexes, validate_mark_placement,
-    validate_placement, validate_resolve_conflict,
+    validate_placement, validate_resolve_conflict, RESOLVE_THRESHOLD,
 };
+use super::pieces::NUM_ORIENTATIONS;
 use super::scoring::count_scores;
 use super::types::*;
 
 const TILES_PER_PLAYER: i32 = 16;
 const MARKS_PER_PLAYER: i32 = 8;
 
+const PLACE_TILE_SCHEMA: &[PayloadField] = &[
+    PayloadField::required("orientation", FieldKind::UInt),
+    PayloadField::required("anchor_q", FieldKind::Int),
+    PayloadField::required("anchor_r", FieldKind::Int),
+];
+
+const HEX_SCHEMA: &[PayloadField] = &[PayloadField::required("hex", FieldKind::String)];
+
+/// MCTS value of a points win: BASE for a tiebreak win, up to BASE + SPAN for a
+/// margin of POINTS_MARGIN_SCALE or more.
+const POINTS_WIN_BASE: f64 = 0.7;
+const POINTS_WIN_SPAN: f64 = 0.25;
+const POINTS_MARGIN_SCALE: f64 = 5.0;
+
 pub struct EinsteinDojoPlugin;
 
 impl TypedGamePlugin for EinsteinDojoPlugin {
@@ -41,19 +58,29 @@ impl TypedGamePlugin for EinsteinDojoPlugin {
         "forfeit_player"
     }
 
-    fn decode_state(&self, game_data: &serde_json::Value) -> EinsteinDojoState {
-        serde_json::from_value(game_data.clone())
-            .unwrap_or_else(|e| panic!("Failed to decode EinsteinDojoState: {e}"))
+    fn supported_options(&self) -> Vec<OptionSpec> {
+        vec![OptionSpec {
+            name: "track_score_history".into(),
+            option_type: OptionType::Boolean,
+            default: serde_json::json!(false),
+            description: "Record every player's score after each scoring check".into(),
+        }]
     }
 
     fn encode_state(&self, state: &EinsteinDojoState) -> serde_json::Value {
         serde_json::to_value(state).expect("serialization should not fail")
     }
 
+    fn load_state(&self, game_data: &serde_json::Value, _phase: &Phase) -> Result<(), String> {
+        serde_json::from_value::<EinsteinDojoState>(game_data.clone())
+            .map(|_| ())
+            .map_err(|e| format!("Invalid EinsteinDojoState: {e}"))
+    }
+
     fn create_initial_state(
         &self,
         players: &[Player],
-        _config: &GameConfig,
+        config: &GameConfig,
     ) -> (EinsteinDojoState, Phase, Vec<Event>) {
         let tiles_remaining: HashMap<String, i32> = players
             .iter()
@@ -73,6 +100,12 @@ impl TypedGamePlugin for EinsteinDojoPlugin {
             scores,
             current_player_index: 0,
             main_conflict: None,
+            score_history: config
+                .options
+                .get("track_score_history")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+                .then(Vec::new),
         };
 
         let first_player = &players[0];
@@ -197,22 +230,26 @@ impl TypedGamePlugin for EinsteinDojoPlugin {
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TypedTransitionResult<EinsteinDojoState> {
+    ) -> Result<TypedTransitionResult<EinsteinDojoState>, String> {
+        let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
+        if player_index >= players.len() {
+            return Err(format!("player_index {} out of range for {} players", player_index, players.len()));
+        }
         match phase.name.as_str() {
             "player_turn" => match action.action_type.as_str() {
                 "place_tile" => self.apply_place_tile(state, phase, action, players),
                 "place_mark" => self.apply_place_mark(state, phase, action),
                 "resolve_conflict" => self.apply_resolve(state, phase, action, players),
-                _ => panic!("Unknown action type in player_turn: {}", action.action_type),
+                _ => Err(format!("Unknown action type in player_turn: {}", action.action_type)),
             },
             "resolve_chain" => match action.action_type.as_str() {
                 "resolve_conflict" => self.apply_resolve(state, phase, action, players),
-                "skip_resolve" => self.apply_resolve_chain_skip(state, phase),
-                _ => panic!("Unknown action type in resolve_chain: {}", action.action_type),
+                "skip_resolve" => Ok(self.apply_resolve_chain_skip(state, phase)),
+                _ => Err(format!("Unknown action type in resolve_chain: {}", action.action_type)),
             },
-            "score_check" => self.apply_score_check(state, phase, players),
+            "score_check" => Ok(self.apply_score_check(state, phase, players)),
             "choose_main_conflict" => self.apply_choose_main_conflict(state, phase, action),
-            _ => panic!("Unknown phase: {}", phase.name),
+            _ => Err(format!("Unknown phase: {}", phase.name)),
         }
     }
 
@@ -227,10 +264,92 @@ impl TypedGamePlugin for EinsteinDojoPlugin {
         self.encode_state(state)
     }
 
+    /// Surrounding count of every conflict hex for `player_id`, so clients can show
+    /// why a conflict is resolvable and which are closest to the threshold.
+    fn valid_action_details(
+        &self,
+        state: &EinsteinDojoState,
+        _phase: &Phase,
+        player_id: &str,
+    ) -> Option<serde_json::Value> {
+        let conflicts: Vec<_> = conflict_surrounding_counts(&state.board, player_id)
+            .into_iter()
+            .map(|(hex, count)| {
+                serde_json::json!({
+                    "hex": hex,
+                    "surrounding_count": count,
+                    "resolvable": count >= RESOLVE_THRESHOLD,
+                })
+            })
+            .collect();
+        Some(serde_json::json!({
+            "resolve_threshold": RESOLVE_THRESHOLD,
+            "conflicts": conflicts,
+        }))
+    }
+
     fn get_scores(&self, state: &EinsteinDojoState) -> HashMap<String, f64> {
         state.float_scores()
     }
 
+    /// No hidden information; only drops the score history so search doesn't carry it.
+    fn determinize(&self, state: &mut EinsteinDojoState, _rng: &mut GameRng) {
+        state.score_history = None;
+    }
+
+    /// Conflict resolution and scoring stay with the player whose turn it is.
+    fn acting_player(
+        &self,
+        _state: &EinsteinDojoState,
+        phase: &Phase,
+        players: &[Player],
+    ) -> Option<String> {
+        match phase.name.as_str() {
+            "player_turn" | "resolve_chain" | "choose_main_conflict" | "score_check" => {
+                metadata_player(phase, players)
+            }
+            _ => None,
+        }
+    }
+
+    /// Turn actions carry their type in the payload (`place_tile`, `place_mark`, ...).
+    fn action_type_for(&self, phase: &Phase, payload: &serde_json::Value) -> String {
+        match payload.get("action_type").and_then(|v| v.as_str()) {
+            Some(action_type) => action_type.to_string(),
+            None => phase.name.clone(),
+        }
+    }
+
+    /// Resolving the main conflict is a decisive 1.0. A points win is worth less,
+    /// growing with the margin up to 0.95, so MCTS prefers the conflict when both
+    /// are on offer. Losses mirror wins (1 - value).
+    fn value_for(&self, result: &GameResult, player_id: &str) -> f64 {
+        let won = result.winners.iter().any(|w| w == player_id);
+        let win_value = if result.reason == "main_conflict_resolved" {
+            1.0
+        } else {
+            let winner = if won { Some(player_id) } else { result.winners.first().map(String::as_str) };
+            let winner_score = winner.and_then(|w| result.final_scores.get(w)).copied().unwrap_or(0.0);
+            let runner_up = result
+                .final_scores
+                .iter()
+                .filter(|(pid, _)| Some(pid.as_str()) != winner)
+                .map(|(_, &s)| s)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let margin = if runner_up.is_finite() { winner_score - runner_up } else { 0.0 };
+            POINTS_WIN_BASE + POINTS_WIN_SPAN * (margin / POINTS_MARGIN_SCALE).clamp(0.0, 1.0)
+        };
+        if won { win_value } else { 1.0 - win_value }
+    }
+
+    fn eval_profiles(&self) -> Vec<&'static str> {
+        vec!["default"]
+    }
+
+    fn make_eval(&self, profile: &str) -> Option<EvalFn<EinsteinDojoState>> {
+        (profile == "default").then(super::evaluator::make_einstein_eval)
+    }
+
     fn parse_ai_action(
         &self,
         response: &serde_json::Value,
@@ -254,6 +373,35 @@ impl TypedGamePlugin for EinsteinDojoPlugin {
         }
     }
 
+    fn payload_schema(&self, action_type: &str) -> Option<&'static [PayloadField]> {
+        match action_type {
+            "place_tile" => Some(PLACE_TILE_SCHEMA),
+            "place_mark" | "resolve_conflict" | "choose_main_conflict" => Some(HEX_SCHEMA),
+            _ => None,
+        }
+    }
+
+    fn phase_action_types(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        &[
+            ("player_turn", &["place_tile", "place_mark", "resolve_conflict"]),
+            ("resolve_chain", &["resolve_conflict", "skip_resolve"]),
+            ("choose_main_conflict", &["choose_main_conflict"]),
+        ]
+    }
+
+    /// Turn and chain actions name their type in the payload (see `action_type_for`).
+    fn action_schema(&self) -> serde_json::Value {
+        let mut schema = action_schema(self.game_id(), self.phase_action_types(), |t| self.payload_schema(t));
+        for action_type in ["place_tile", "place_mark", "resolve_conflict", "skip_resolve"] {
+            let def = &mut schema["$defs"][action_type];
+            def["properties"]["action_type"] = serde_json::json!({"const": action_type});
+            let mut required = def["required"].as_array().cloned().unwrap_or_default();
+            required.push("action_type".into());
+            def["required"] = required.into();
+        }
+        schema
+    }
+
     fn on_player_forfeit(
         &self,
         state: &EinsteinDojoState,
@@ -399,11 +547,11 @@ impl EinsteinDojoPlugin {
         state: &EinsteinDojoState,
         phase: &Phase,
         action: &Action,
-    ) -> TypedTransitionResult<EinsteinDojoState> {
+    ) -> Result<TypedTransitionResult<EinsteinDojoState>, String> {
         let mut s = state.clone();
         let player_id = &action.player_id;
         let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
-        let hex_key = action.payload["hex"].as_str().unwrap().to_string();
+        let hex_key = payload_hex(action)?;
 
         // Place mark
         s.board.hex_marks.insert(hex_key.clone(), player_id.clone());
@@ -427,13 +575,13 @@ impl EinsteinDojoPlugin {
             metadata: serde_json::json!({"player_index": player_index}),
         };
 
-        TypedTransitionResult {
+        Ok(TypedTransitionResult {
             state: s.clone(),
             events,
             next_phase: score_check_phase,
             scores: s.float_scores(),
             game_over: None,
-        }
+        })
     }
 
     fn apply_place_tile(
@@ -442,14 +590,19 @@ impl EinsteinDojoPlugin {
         phase: &Phase,
         action: &Action,
         _players: &[Player],
-    ) -> TypedTransitionResult<EinsteinDojoState> {
+    ) -> Result<TypedTransitionResult<EinsteinDojoState>, String> {
         let mut s = state.clone();
         let player_id = &action.player_id;
         let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
 
-        let orientation = action.payload["orientation"].as_u64().unwrap() as u8;
-        let anchor_q = action.payload["anchor_q"].as_i64().unwrap() as i32;
-        let anchor_r = action.payload["anchor_r"].as_i64().unwrap() as i32;
+        let (Some(orientation), Some(anchor_q), Some(anchor_r)) = (
+            action.payload["orientation"].as_u64().filter(|&o| o < NUM_ORIENTATIONS as u64),
+            action.payload["anchor_q"].as_i64(),
+            action.payload["anchor_r"].as_i64(),
+        ) else {
+            return Err("place_tile needs an orientation, anchor_q and anchor_r".into());
+        };
+        let (orientation, anchor_q, anchor_r) = (orientation as u8, anchor_q as i32, anchor_r as i32);
 
         let changed_hexes = apply_placement(&mut s.board, player_id, orientation, anchor_q, anchor_r);
 
@@ -490,16 +643,16 @@ impl EinsteinDojoPlugin {
             if new_conflicts.len() == 1 {
                 // Auto-set the single new conflict as main
                 s.main_conflict = Some(new_conflicts[0].clone());
-                return TypedTransitionResult {
+                return Ok(TypedTransitionResult {
                     state: s.clone(),
                     events,
                     next_phase: score_check_phase,
                     scores: s.float_scores(),
                     game_over: None,
-                };
+                });
             } else if new_conflicts.len() > 1 {
                 // Player must choose which conflict is the main one
-                return TypedTransitionResult {
+                return Ok(TypedTransitionResult {
                     state: s.clone(),
                     events,
                     next_phase: Phase {
@@ -519,18 +672,18 @@ impl EinsteinDojoPlugin {
                     },
                     scores: s.float_scores(),
                     game_over: None,
-                };
+                });
             }
         }
 
         // No main conflict logic needed — proceed to score_check
-        TypedTransitionResult {
+        Ok(TypedTransitionResult {
             state: s.clone(),
             events,
             next_phase: score_check_phase,
             scores: s.float_scores(),
             game_over: None,
-        }
+        })
     }
 
     fn apply_score_check(
@@ -551,6 +704,7 @@ impl EinsteinDojoPlugin {
                 score_counts.get(&p.player_id).copied().unwrap_or(0),
             );
         }
+        s.record_score_history();
 
         // Check game end: current player has 0 tiles AND 0 marks
         let tiles_left = s
@@ -609,14 +763,14 @@ impl EinsteinDojoPlugin {
         state: &EinsteinDojoState,
         phase: &Phase,
         action: &Action,
-    ) -> TypedTransitionResult<EinsteinDojoState> {
+    ) -> Result<TypedTransitionResult<EinsteinDojoState>, String> {
         let mut s = state.clone();
-        let chosen_hex = action.payload["hex"].as_str().unwrap().to_string();
+        let chosen_hex = payload_hex(action)?;
         s.main_conflict = Some(chosen_hex.clone());
 
         let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
 
-        TypedTransitionResult {
+        Ok(TypedTransitionResult {
             state: s.clone(),
             events: vec![Event {
                 event_type: "main_conflict_chosen".into(),
@@ -632,7 +786,7 @@ impl EinsteinDojoPlugin {
             },
             scores: s.float_scores(),
             game_over: None,
-        }
+        })
     }
 
     fn validate_resolve_action(
@@ -653,17 +807,17 @@ impl EinsteinDojoPlugin {
         phase: &Phase,
         action: &Action,
         players: &[Player],
-    ) -> TypedTransitionResult<EinsteinDojoState> {
+    ) -> Result<TypedTransitionResult<EinsteinDojoState>, String> {
         let mut s = state.clone();
         let player_id = &action.player_id;
         let player_index = phase.metadata["player_index"].as_u64().unwrap_or(0) as usize;
-        let hex_key = action.payload["hex"].as_str().unwrap().to_string();
+        let hex_key = payload_hex(action)?;
 
         apply_resolve_conflict(&mut s.board, &hex_key, player_id);
 
         // Check for main conflict win
         if s.main_conflict.as_deref() == Some(hex_key.as_str()) {
-            return self.end_game_main_conflict_win(s, player_id, &hex_key, players);
+            return Ok(self.end_game_main_conflict_win(s, player_id, &hex_key, players));
         }
 
         // Recount scores
@@ -684,7 +838,7 @@ impl EinsteinDojoPlugin {
         // Check for more resolvable conflicts (chaining)
         let more_resolvable = get_resolvable_conflicts(&s.board, player_id);
         if more_resolvable.is_empty() {
-            TypedTransitionResult {
+            Ok(TypedTransitionResult {
                 state: s.clone(),
                 events,
                 next_phase: Phase {
@@ -696,9 +850,9 @@ impl EinsteinDojoPlugin {
                 },
                 scores: s.float_scores(),
                 game_over: None,
-            }
+            })
         } else {
-            TypedTransitionResult {
+            Ok(TypedTransitionResult {
                 state: s.clone(),
                 events,
                 next_phase: Phase {
@@ -718,7 +872,7 @@ impl EinsteinDojoPlugin {
                 },
                 scores: s.float_scores(),
                 game_over: None,
-            }
+            })
         }
     }
 
@@ -855,6 +1009,13 @@ impl EinsteinDojoPlugin {
     }
 }
 
+fn payload_hex(action: &Action) -> Result<String, String> {
+    action.payload["hex"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} needs a hex", action.action_type))
+}
+
 fn make_player_turn_phase(player_index: usize, player_id: &str) -> Phase {
     Phase {
         name: "player_turn".into(),
@@ -900,6 +1061,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_malformed_payload_rejected_cleanly() {
+        use crate::engine::plugin::{GamePlugin, JsonAdapter};
+
+        let plugin = JsonAdapter(EinsteinDojoPlugin);
+        let players = test_players();
+        let (game_data, phase, _) = plugin.create_initial_state(&players, &default_config());
+        let mark = |payload| Action {
+            action_type: "place_mark".into(),
+            player_id: "p1".into(),
+            payload,
+        };
+
+        let numeric_hex = mark(serde_json::json!({"hex": 12}));
+        assert_eq!(
+            plugin.validate_action(&game_data, &phase, &numeric_hex).as_deref(),
+            Some("Field 'hex' must be a string, got integer")
+        );
+        assert_eq!(
+            plugin.validate_payload_shape(&mark(serde_json::json!({}))).as_deref(),
+            Some("Missing 'hex' in payload")
+        );
+
+        let tile = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"orientation": "north", "anchor_q": 0, "anchor_r": 0}),
+        };
+        assert!(plugin.validate_payload_shape(&tile).unwrap().contains("'orientation'"));
+
+        // Applied without validation they fail instead of panicking
+        let skip = Action { action_type: "skip_resolve".into(), ..mark(serde_json::json!({})) };
+        for action in [&numeric_hex, &tile, &skip] {
+            assert!(plugin.apply_action(&game_data, &phase, action, &players).is_err());
+        }
+
+        // Well-formed payloads pass the shape check and reach game validation
+        let valid = plugin.get_valid_actions(&game_data, &phase, "p1");
+        let first = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: valid[0].clone(),
+        };
+        assert_eq!(plugin.validate_payload_shape(&first), None);
+        assert_eq!(plugin.validate_action(&game_data, &phase, &first), None);
+    }
+
     #[test]
     fn test_metadata() {
         let plugin = EinsteinDojoPlugin;
@@ -962,7 +1170,7 @@ mod tests {
 
         assert!(plugin.validate_action(&state, &phase, &action).is_none());
 
-        let result = plugin.apply_action(&state, &phase, &action, &players);
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
         assert_eq!(result.state.board.kite_owners.len(), 8);
         assert_eq!(result.state.board.placed_pieces.len(), 1);
         assert_eq!(result.state.tiles_remaining["p1"], 15);
@@ -982,7 +1190,7 @@ mod tests {
             player_id: "p1".into(),
             payload: serde_json::json!({"anchor_q": 0, "anchor_r": 0, "orientation": 0}),
         };
-        let result = plugin.apply_action(&state, &phase, &action, &players);
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
 
         // Score check (auto-resolve, action is ignored)
         let score_action = Action {
@@ -991,13 +1199,100 @@ mod tests {
             payload: serde_json::json!({}),
         };
         let result2 =
-            plugin.apply_action(&result.state, &result.next_phase, &score_action, &players);
+            plugin.apply_action(&result.state, &result.next_phase, &score_action, &players).unwrap();
 
         assert_eq!(result2.next_phase.name, "player_turn");
         assert_eq!(result2.next_phase.expected_actions[0].player_id, "p2");
         assert_eq!(result2.state.current_player_index, 1);
     }
 
+    #[test]
+    fn test_acting_player_per_phase() {
+        let plugin = EinsteinDojoPlugin;
+        let players = test_players();
+        let (state, phase, _) = plugin.create_initial_state(&players, &default_config());
+        let acting = |phase: &Phase| plugin.acting_player(&state, phase, &players);
+        assert_eq!(acting(&phase).as_deref(), Some("p1"));
+
+        let action = Action {
+            action_type: "place_tile".into(),
+            player_id: "p1".into(),
+            payload: serde_json::json!({"anchor_q": 0, "anchor_r": 0, "orientation": 0}),
+        };
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+        assert_eq!(result.next_phase.name, "score_check");
+        assert_eq!(acting(&result.next_phase).as_deref(), Some("p1"));
+
+        let score_action = Action {
+            action_type: "score_check".into(),
+            player_id: "".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = plugin.apply_action(&result.state, &result.next_phase, &score_action, &players).unwrap();
+        assert_eq!(acting(&result.next_phase).as_deref(), Some("p2"));
+
+        for name in ["resolve_chain", "choose_main_conflict"] {
+            let phase = Phase {
+                name: name.into(),
+                auto_resolve: false,
+                concurrent_mode: Some(ConcurrentMode::Sequential),
+                expected_actions: vec![],
+                metadata: serde_json::json!({"player_index": 1}),
+            };
+            assert_eq!(acting(&phase).as_deref(), Some("p2"), "{}", name);
+        }
+
+        let game_over = Phase {
+            name: "game_over".into(),
+            auto_resolve: false,
+            concurrent_mode: None,
+            expected_actions: vec![],
+            metadata: serde_json::json!({"player_index": 0}),
+        };
+        assert_eq!(acting(&game_over), None);
+    }
+
+    #[test]
+    fn test_score_history_records_each_score_check() {
+        let plugin = EinsteinDojoPlugin;
+        let players = test_players();
+        let config = GameConfig {
+            options: serde_json::json!({"track_score_history": true}),
+            random_seed: None,
+        };
+        let (mut state, mut phase, _) = plugin.create_initial_state(&players, &config);
+
+        let mut checks = 0;
+        while checks < 4 {
+            let action = if phase.name == "score_check" {
+                checks += 1;
+                Action {
+                    action_type: "score_check".into(),
+                    player_id: "".into(),
+                    payload: serde_json::json!({}),
+                }
+            } else {
+                let pid = phase.expected_actions[0].player_id.clone();
+                let mut payload = plugin.get_valid_actions(&state, &phase, &pid)[0].clone();
+                let action_type = payload["action_type"].as_str().unwrap().to_string();
+                payload.as_object_mut().unwrap().remove("action_type");
+                Action { action_type, player_id: pid, payload }
+            };
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
+            state = r.state;
+            phase = r.next_phase;
+
+            let history = state.score_history.as_ref().unwrap();
+            assert_eq!(history.len(), checks);
+            if checks > 0 {
+                assert_eq!(history[checks - 1], state.scores, "latest entry is the current score");
+            }
+        }
+
+        plugin.determinize(&mut state, &mut GameRng::from_seed(0));
+        assert!(state.score_history.is_none());
+    }
+
     #[test]
     fn test_full_turn_cycle() {
         let plugin = EinsteinDojoPlugin;
@@ -1010,7 +1305,7 @@ mod tests {
             player_id: "p1".into(),
             payload: serde_json::json!({"anchor_q": 0, "anchor_r": 0, "orientation": 0}),
         };
-        let r = plugin.apply_action(&state, &phase, &action1, &players);
+        let r = plugin.apply_action(&state, &phase, &action1, &players).unwrap();
         state = r.state;
         phase = r.next_phase;
 
@@ -1020,7 +1315,7 @@ mod tests {
             player_id: "".into(),
             payload: serde_json::json!({}),
         };
-        let r = plugin.apply_action(&state, &phase, &sc, &players);
+        let r = plugin.apply_action(&state, &phase, &sc, &players).unwrap();
         state = r.state;
         phase = r.next_phase;
 
@@ -1035,7 +1330,7 @@ mod tests {
             player_id: "p2".into(),
             payload: valid[0].clone(),
         };
-        let r = plugin.apply_action(&state, &phase, &action2, &players);
+        let r = plugin.apply_action(&state, &phase, &action2, &players).unwrap();
         assert_eq!(r.state.tiles_remaining["p2"], 15);
         assert_eq!(r.next_phase.name, "score_check");
     }
@@ -1082,7 +1377,7 @@ mod tests {
                     player_id: "".into(),
                     payload: serde_json::json!({}),
                 };
-                let r = plugin.apply_action(&state, &phase, &sc, &players);
+                let r = plugin.apply_action(&state, &phase, &sc, &players).unwrap();
                 state = r.state;
                 phase = r.next_phase;
                 if r.game_over.is_some() {
@@ -1099,7 +1394,7 @@ mod tests {
                     player_id: current_pid,
                     payload: valid[0].clone(),
                 };
-                let r = plugin.apply_action(&state, &phase, &action, &players);
+                let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
                 state = r.state;
                 phase = r.next_phase;
                 if r.game_over.is_some() {
@@ -1116,7 +1411,7 @@ mod tests {
                     player_id: current_pid,
                     payload: serde_json::json!({}),
                 };
-                let r = plugin.apply_action(&state, &phase, &action, &players);
+                let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
                 state = r.state;
                 phase = r.next_phase;
                 if r.game_over.is_some() {
@@ -1144,7 +1439,7 @@ mod tests {
                 payload: first.clone(),
             };
 
-            let r = plugin.apply_action(&state, &phase, &action, &players);
+            let r = plugin.apply_action(&state, &phase, &action, &players).unwrap();
             state = r.state;
             phase = r.next_phase;
             turns += 1;
@@ -1203,11 +1498,76 @@ mod tests {
         };
         assert!(plugin.validate_action(&state, &phase, &action).is_none());
 
-        let result = plugin.apply_action(&state, &phase, &action, &players);
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
         assert_eq!(result.state.board.hex_states["0,0"], HexState::Resolved);
         assert_eq!(result.state.board.hex_owners["0,0"], "p1");
     }
 
+    #[test]
+    fn test_valid_action_details_report_surrounding_counts() {
+        let plugin = EinsteinDojoPlugin;
+        let players = test_players();
+        let (mut state, _, _) = plugin.create_initial_state(&players, &default_config());
+
+        // Resolvable conflict at (0,0): 4 controlled neighbors
+        state.board.hex_states.insert("0,0".into(), HexState::Conflict);
+        for &(q, r) in &[(1i32, 0i32), (-1, 0), (0, 1), (0, -1)] {
+            state.board.hex_marks.insert(format!("{q},{r}"), "p1".into());
+        }
+        // Conflict at (5,5) with only 2 controlled neighbors
+        state.board.hex_states.insert("5,5".into(), HexState::Conflict);
+        for &(q, r) in &[(6i32, 5i32), (4, 5)] {
+            state.board.hex_marks.insert(format!("{q},{r}"), "p1".into());
+        }
+
+        let phase = make_player_turn_phase(0, "p1");
+        let details = plugin.valid_action_details(&state, &phase, "p1").unwrap();
+        let conflicts = details["conflicts"].as_array().unwrap();
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(conflicts[0]["hex"], "0,0");
+        assert!(conflicts[0]["surrounding_count"].as_u64().unwrap() >= 4);
+        assert_eq!(conflicts[0]["resolvable"], true);
+
+        assert_eq!(conflicts[1]["hex"], "5,5");
+        assert_eq!(conflicts[1]["surrounding_count"], 2);
+        assert_eq!(conflicts[1]["resolvable"], false);
+
+        // Only the resolvable hex is a valid action
+        let resolve_hexes: Vec<_> = plugin
+            .get_valid_actions(&state, &phase, "p1")
+            .into_iter()
+            .filter(|a| a["action_type"] == "resolve_conflict")
+            .map(|a| a["hex"].clone())
+            .collect();
+        assert_eq!(resolve_hexes, vec![serde_json::json!("0,0")]);
+    }
+
+    #[test]
+    fn test_value_for_ranks_conflict_win_above_points_win() {
+        let plugin = EinsteinDojoPlugin;
+        let result = |reason: &str, p1: f64, p2: f64| GameResult {
+            winners: vec!["p1".into()],
+            final_scores: HashMap::from([("p1".to_string(), p1), ("p2".to_string(), p2)]),
+            reason: reason.into(),
+            details: HashMap::new(),
+        };
+        let conflict = result("main_conflict_resolved", 3.0, 4.0);
+        let marginal = result("normal", 5.0, 4.0);
+        let tiebreak = result("normal", 4.0, 4.0);
+        let crushing = result("normal", 12.0, 2.0);
+
+        let v = |r: &GameResult, pid: &str| plugin.value_for(r, pid);
+        assert_eq!(v(&conflict, "p1"), 1.0);
+        assert!(v(&conflict, "p1") > v(&crushing, "p1"));
+        assert!(v(&crushing, "p1") > v(&marginal, "p1"));
+        assert!(v(&marginal, "p1") > v(&tiebreak, "p1"));
+        assert!(v(&tiebreak, "p1") > 0.5);
+        for r in [&conflict, &marginal, &tiebreak, &crushing] {
+            assert!((v(r, "p1") + v(r, "p2") - 1.0).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn test_main_conflict_win() {
         let plugin = EinsteinDojoPlugin;
@@ -1232,7 +1592,7 @@ mod tests {
             player_id: "p1".into(),
             payload: serde_json::json!({"hex": "0,0"}),
         };
-        let result = plugin.apply_action(&state, &phase, &action, &players);
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
         assert!(result.game_over.is_some());
         let game_over = result.game_over.unwrap();
         assert_eq!(game_over.winners, vec!["p1"]);
@@ -1265,7 +1625,7 @@ mod tests {
         };
         assert!(plugin.validate_action(&state, &phase, &action).is_none());
 
-        let result = plugin.apply_action(&state, &phase, &action, &players);
+        let result = plugin.apply_action(&state, &phase, &action, &players).unwrap();
         assert_eq!(result.next_phase.name, "score_check");
     }
 
@@ -1288,6 +1648,7 @@ mod tests {
                 .collect(),
             current_player_index: 0,
             main_conflict: None,
+            score_history: None,
         };
 
         let score_phase = Phase {
@@ -1302,7 +1663,7 @@ mod tests {
             action_type: "score_check".into(),
             player_id: "".into(),
             payload: serde_json::json!({}),
-        }, &players);
+        }, &players).unwrap();
 
         assert!(r.game_over.is_some());
         let game_over = r.game_over.unwrap();
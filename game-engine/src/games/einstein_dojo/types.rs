@@ -59,6 +59,10 @@ pub struct EinsteinDojoState {
     /// Hex key ("q,r") of the main conflict. None until the first conflict is created.
     #[serde(default)]
     pub main_conflict: Option<String>,
+    /// Scores after each score_check, oldest first. None unless the
+    /// `track_score_history` option is set; never carried into MCTS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_history: Option<Vec<HashMap<String, i64>>>,
 }
 
 impl EinsteinDojoState {
@@ -68,4 +72,11 @@ impl EinsteinDojoState {
             .map(|(k, v)| (k.clone(), *v as f64))
             .collect()
     }
+
+    /// Append the current scores to `score_history` if tracking is enabled.
+    pub fn record_score_history(&mut self) {
+        if let Some(history) = self.score_history.as_mut() {
+            history.push(self.scores.clone());
+        }
+    }
 }
@@ -1,4 +1,5 @@
 pub mod carcassonne;
+pub mod connect_four;
 pub mod einstein_dojo;
 
 use std::collections::HashMap;
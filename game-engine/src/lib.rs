@@ -1,2 +1,7 @@
 pub mod engine;
 pub mod games;
+
+#[cfg(feature = "testing")]
+pub mod client;
+#[cfg(feature = "testing")]
+pub mod server;
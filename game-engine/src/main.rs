@@ -1,24 +1,34 @@
+use std::future::Future;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
-use tonic::transport::Server;
+use clap::{Parser, Subcommand};
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
 use tracing_subscriber::EnvFilter;
 
+#[cfg(test)]
+mod client;
 mod engine;
 mod games;
+mod play;
+mod self_test;
 mod server;
 
 use engine::plugin::JsonAdapter;
 use games::carcassonne::plugin::CarcassonnePlugin;
+use games::connect_four::plugin::ConnectFourPlugin;
 use games::einstein_dojo::plugin::EinsteinDojoPlugin;
 use games::GameRegistry;
 use server::proto::game_engine_service_server::GameEngineServiceServer;
-use server::GameEngineServer;
+use server::{GameEngineServer, SearchLimitPolicy};
 
 #[derive(Parser)]
 #[command(name = "meeple-game-engine", about = "Meeple game engine gRPC server")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Port to listen on
     #[arg(short, long, default_value = "50051", env = "MEEPLE_ENGINE_PORT")]
     port: u16,
@@ -26,6 +36,33 @@ struct Cli {
     /// Path to bot_profiles.toml (default: auto-discover)
     #[arg(long, env = "MEEPLE_BOT_PROFILES")]
     profiles: Option<PathBuf>,
+
+    /// Max concurrent MctsSearch/RunArena operations (0 = unlimited)
+    #[arg(long, default_value = "0", env = "MEEPLE_MAX_CONCURRENT_SEARCHES")]
+    max_concurrent_searches: usize,
+
+    /// What to do with heavy requests when the limit is reached
+    #[arg(long, value_enum, default_value = "queue", env = "MEEPLE_SEARCH_LIMIT_POLICY")]
+    search_limit_policy: SearchLimitPolicy,
+
+    /// PEM certificate chain; serve over TLS instead of plaintext (requires --tls-key)
+    #[arg(long, env = "MEEPLE_ENGINE_TLS_CERT")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key matching --tls-cert
+    #[arg(long, env = "MEEPLE_ENGINE_TLS_KEY")]
+    tls_key: Option<PathBuf>,
+
+    /// Before serving, play a short MCTS-vs-random arena for each searchable game
+    /// and exit with an error if a game crashes or MCTS loses
+    #[arg(long)]
+    self_test: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play one local game between two strategies and print the transcript
+    Play(play::PlayArgs),
 }
 
 #[tokio::main]
@@ -36,28 +73,223 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let cli = Cli::parse();
 
+    if let Some(Command::Play(args)) = &cli.command {
+        return play::run(args, &mut std::io::stdout().lock());
+    }
+
     let mut registry = GameRegistry::new();
     registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
     registry.register(Box::new(JsonAdapter(EinsteinDojoPlugin)));
+    registry.register(Box::new(JsonAdapter(ConnectFourPlugin)));
     tracing::info!(
         games = ?registry.list_game_ids(),
         "registered game plugins"
     );
 
+    if cli.self_test {
+        self_test::run(&registry)?;
+    }
+
     let server = if let Some(ref profiles_path) = cli.profiles {
-        GameEngineServer::with_profiles(registry, profiles_path)
-            .map_err(|e| format!("Failed to load profiles: {}", e))?
+        GameEngineServer::with_profiles(registry, profiles_path)?
     } else {
         GameEngineServer::new(registry)
     };
+    let server = server.with_search_limit(cli.max_concurrent_searches, cli.search_limit_policy);
+
+    // Load TLS material before binding so a bad cert/key fails at startup.
+    let builder = server_builder(cli.tls_cert.as_deref(), cli.tls_key.as_deref())?;
 
     let addr: SocketAddr = ([0, 0, 0, 0], cli.port).into();
-    tracing::info!(%addr, "starting gRPC server");
+    tracing::info!(%addr, tls = cli.tls_cert.is_some(), "starting gRPC server");
+    serve(builder, server, TcpIncoming::bind(addr)?, shutdown_signal()).await
+}
 
+/// A server builder, configured for TLS when both a certificate and a key are
+/// given. Errors if only one is, or if they can't be read or don't match.
+fn server_builder(
+    tls_cert: Option<&Path>,
+    tls_key: Option<&Path>,
+) -> Result<Server, Box<dyn std::error::Error>> {
+    let (cert_path, key_path) = match (tls_cert, tls_key) {
+        (None, None) => return Ok(Server::builder()),
+        (Some(cert), Some(key)) => (cert, key),
+        _ => return Err("--tls-cert and --tls-key must be given together".into()),
+    };
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))
+    };
+    let identity = Identity::from_pem(read(cert_path)?, read(key_path)?);
     Server::builder()
-        .add_service(GameEngineServiceServer::new(server))
-        .serve(addr)
+        .tls_config(ServerTlsConfig::new().identity(identity))
+        .map_err(|e| {
+            let cause = std::error::Error::source(&e).map(|s| s.to_string());
+            format!(
+                "invalid TLS certificate/key ({}, {}): {}",
+                cert_path.display(),
+                key_path.display(),
+                cause.unwrap_or_else(|| e.to_string())
+            )
+            .into()
+        })
+}
+
+/// Serve the engine, health and (optionally) reflection services until
+/// `shutdown` resolves, then wait for in-flight requests and the blocking
+/// tasks behind streaming RPCs to finish.
+async fn serve(
+    mut builder: Server,
+    server: GameEngineServer,
+    incoming: TcpIncoming,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stream_tasks = server.stream_tasks();
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<GameEngineServiceServer<GameEngineServer>>()
+        .await;
+
+    let router = builder
+        .add_service(health_service)
+        .add_service(GameEngineServiceServer::new(server));
+    #[cfg(feature = "reflection")]
+    let router = router.add_service(
+        tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(server::proto::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+            .build_v1()?,
+    );
+
+    let tasks = stream_tasks.clone();
+    router
+        .serve_with_incoming_shutdown(incoming, async move {
+            shutdown.await;
+            tracing::info!(
+                active_streams = tasks.len(),
+                "shutting down, draining in-flight requests"
+            );
+            health_reporter
+                .set_not_serving::<GameEngineServiceServer<GameEngineServer>>()
+                .await;
+        })
         .await?;
 
+    // A stream whose client went away keeps its task running; let it finish.
+    stream_tasks.close();
+    stream_tasks.wait().await;
+    tracing::info!("shutdown complete");
     Ok(())
 }
+
+/// Resolves on Ctrl-C, or on SIGTERM where available (container stop).
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!(error = %e, "failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use server::proto::game_engine_service_client::GameEngineServiceClient;
+    use server::proto::{ArenaStrategyConfig, RunArenaRequest};
+
+    #[tokio::test]
+    async fn test_shutdown_drains_running_arena() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(EinsteinDojoPlugin)));
+        let server = GameEngineServer::new(registry);
+        let stream_tasks = server.stream_tasks();
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let serving = serve(Server::builder(), server, TcpIncoming::from(listener), async {
+            let _ = stop_rx.await;
+        });
+
+        let client = async {
+            let mut client = GameEngineServiceClient::connect(format!("http://{addr}"))
+                .await
+                .unwrap();
+            let strategy = |name: &str, strategy_type: &str| ArenaStrategyConfig {
+                name: name.into(),
+                strategy_type: strategy_type.into(),
+                num_simulations: 10,
+                ..Default::default()
+            };
+            let mut stream = client
+                .run_arena(RunArenaRequest {
+                    game_id: "einstein_dojo".into(),
+                    num_games: 3,
+                    base_seed: 1,
+                    strategies: vec![strategy("mcts", "mcts"), strategy("random", "random")],
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+                .into_inner();
+
+            // Shut down while the arena is mid-run.
+            let first = stream.message().await.unwrap().unwrap();
+            assert!(first.final_result.is_none());
+            assert_eq!(stream_tasks.len(), 1);
+            stop_tx.send(()).unwrap();
+
+            let mut last = first;
+            while let Some(update) = stream.message().await.unwrap() {
+                last = update;
+            }
+            last
+        };
+
+        let (result, last) = tokio::time::timeout(
+            std::time::Duration::from_secs(60),
+            async { tokio::join!(serving, client) },
+        )
+        .await
+        .expect("server did not shut down");
+        assert!(result.is_ok());
+        assert_eq!(last.final_result.unwrap().num_games, 3);
+        assert!(stream_tasks.is_empty());
+    }
+
+    #[test]
+    fn test_server_builder_tls_options() {
+        let fixture = |name: &str| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name);
+        let (cert, key, other_key) = (fixture("server.crt"), fixture("server.key"), fixture("other.key"));
+
+        assert!(server_builder(None, None).is_ok());
+        assert!(server_builder(Some(&cert), Some(&key)).is_ok());
+
+        let err = server_builder(Some(&cert), None).unwrap_err().to_string();
+        assert!(err.contains("must be given together"), "{err}");
+        let err = server_builder(Some(&cert), Some(&fixture("missing.key"))).unwrap_err().to_string();
+        assert!(err.contains("failed to read") && err.contains("missing.key"), "{err}");
+        let err = server_builder(Some(&cert), Some(&other_key)).unwrap_err().to_string();
+        assert!(err.contains("invalid TLS certificate/key"), "{err}");
+    }
+}
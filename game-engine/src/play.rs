@@ -0,0 +1,174 @@
+//! `play` subcommand — run one local game between two strategies and print the
+//! transcript. No gRPC; uses the arena's per-game loop.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use clap::{Args, ValueEnum};
+
+use crate::engine::arena::{play_one_game, TranscriptEntry};
+use crate::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
+use crate::engine::mcts::MctsParams;
+use crate::engine::models::{Event, GameConfig, Player};
+use crate::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
+use crate::games::carcassonne::plugin::CarcassonnePlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StrategyKind {
+    Random,
+    Mcts,
+}
+
+#[derive(Debug, Args)]
+pub struct PlayArgs {
+    /// Game to play (only carcassonne is supported)
+    #[arg(long, default_value = "carcassonne")]
+    pub game: String,
+
+    /// Random seed for the game setup
+    #[arg(long, default_value = "42")]
+    pub seed: u64,
+
+    /// Strategy for the first seat
+    #[arg(long, value_enum, default_value = "mcts")]
+    pub p1: StrategyKind,
+
+    /// Strategy for the second seat
+    #[arg(long, value_enum, default_value = "random")]
+    pub p2: StrategyKind,
+
+    /// Number of tiles in the bag (default: full game)
+    #[arg(long)]
+    pub tile_count: Option<u32>,
+
+    /// MCTS simulations per move
+    #[arg(long, default_value = "500")]
+    pub num_simulations: usize,
+}
+
+/// Play the configured game and write the transcript to `out`.
+pub fn run(args: &PlayArgs, out: &mut dyn Write) -> Result<(), Box<dyn std::error::Error>> {
+    if args.game != "carcassonne" {
+        return Err(format!("unsupported game '{}' (supported: carcassonne)", args.game).into());
+    }
+    let plugin = CarcassonnePlugin;
+
+    let params = MctsParams {
+        num_simulations: args.num_simulations,
+        ..Default::default()
+    };
+    let make_strategy = |kind: StrategyKind| -> Box<dyn BotStrategy<CarcassonnePlugin>> {
+        match kind {
+            StrategyKind::Random => Box::new(RandomStrategy),
+            StrategyKind::Mcts => Box::new(MctsStrategy::with_eval(
+                params.clone(),
+                make_carcassonne_eval(DEFAULT_WEIGHTS),
+            )),
+        }
+    };
+    let strategies = [make_strategy(args.p1), make_strategy(args.p2)];
+    let kinds = [args.p1, args.p2];
+
+    let players: Vec<Player> = (0..2)
+        .map(|i| Player {
+            player_id: format!("p{}", i),
+            display_name: format!("{:?}", kinds[i]).to_lowercase(),
+            seat_index: i as i32,
+            is_bot: true,
+            bot_id: None,
+        })
+        .collect();
+    let pid_to_strategy: HashMap<String, &dyn BotStrategy<CarcassonnePlugin>> = players
+        .iter()
+        .zip(&strategies)
+        .map(|(p, s)| (p.player_id.clone(), s.as_ref()))
+        .collect();
+
+    let mut options = serde_json::json!({});
+    if let Some(n) = args.tile_count {
+        options["tile_count"] = n.into();
+    }
+    let config = GameConfig {
+        options,
+        random_seed: Some(args.seed),
+    };
+
+    writeln!(out, "{} seed={}", args.game, args.seed)?;
+    for p in &players {
+        writeln!(out, "  {}: {}", p.player_id, p.display_name)?;
+    }
+
+    let mut transcript = Vec::new();
+    let result = play_one_game(&plugin, &players, &config, &pid_to_strategy, Some(&mut transcript));
+
+    for (i, entry) in transcript.iter().enumerate() {
+        write_entry(out, i + 1, entry)?;
+    }
+
+    match result {
+        Some(gr) => {
+            let mut scores: Vec<_> = gr.final_scores.iter().collect();
+            scores.sort_by(|a, b| a.0.cmp(b.0));
+            let scores: Vec<String> = scores.iter().map(|(pid, s)| format!("{}={}", pid, s)).collect();
+            writeln!(out, "Result: winners={:?} scores: {} ({})", gr.winners, scores.join(" "), gr.reason)?;
+        }
+        None => writeln!(out, "Result: game did not finish")?,
+    }
+    Ok(())
+}
+
+fn write_entry(out: &mut dyn Write, n: usize, entry: &TranscriptEntry) -> std::io::Result<()> {
+    let action = &entry.action;
+    writeln!(out, "{:4}. {} {} {}", n, action.player_id, action.action_type, action.payload)?;
+    for event in &entry.events {
+        write_event(out, event)?;
+    }
+    Ok(())
+}
+
+fn write_event(out: &mut dyn Write, event: &Event) -> std::io::Result<()> {
+    match &event.player_id {
+        Some(pid) => writeln!(out, "        {} [{}] {}", event.event_type, pid, event.payload),
+        None => writeln!(out, "        {} {}", event.event_type, event.payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_prints_transcript() {
+        let args = PlayArgs {
+            game: "carcassonne".into(),
+            seed: 42,
+            p1: StrategyKind::Mcts,
+            p2: StrategyKind::Random,
+            tile_count: Some(5),
+            num_simulations: 20,
+        };
+        let mut out = Vec::new();
+        run(&args, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("carcassonne seed=42"));
+        assert!(text.contains("p0 place_tile"));
+        assert!(text.contains("p1 place_tile"));
+        assert!(text.contains("tile_placed"));
+        assert!(text.lines().last().unwrap().starts_with("Result: winners="), "{}", text);
+    }
+
+    #[test]
+    fn test_play_rejects_unknown_game() {
+        let args = PlayArgs {
+            game: "chess".into(),
+            seed: 1,
+            p1: StrategyKind::Random,
+            p2: StrategyKind::Random,
+            tile_count: Some(5),
+            num_simulations: 10,
+        };
+        let err = run(&args, &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("unsupported game"));
+    }
+}
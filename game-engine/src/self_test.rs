@@ -0,0 +1,201 @@
+//! `--self-test` startup check: a tiny MCTS-vs-random arena per game, so a broken
+//! plugin or search fails the deploy instead of the first client request.
+
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::engine::arena::{run_arena, DEFAULT_MAX_MOVES_PER_GAME};
+use crate::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
+use crate::engine::mcts::MctsParams;
+use crate::engine::plugin::TypedGamePlugin;
+use crate::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
+use crate::games::carcassonne::plugin::CarcassonnePlugin;
+use crate::games::connect_four::plugin::ConnectFourPlugin;
+use crate::games::einstein_dojo::plugin::EinsteinDojoPlugin;
+use crate::games::GameRegistry;
+
+const SELF_TEST_GAMES: usize = 4;
+const SELF_TEST_SEED: u64 = 42;
+
+/// Outcome of the self-test arena for one game.
+#[derive(Debug)]
+pub struct SelfTestOutcome {
+    pub game_id: String,
+    pub mcts_wins: usize,
+    pub random_wins: usize,
+    pub draws: usize,
+    /// Panic message if a game crashed.
+    pub error: Option<String>,
+}
+
+impl SelfTestOutcome {
+    /// No crash, and MCTS won at least as often as random.
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.mcts_wins >= self.random_wins
+    }
+}
+
+/// Short searches: enough to beat random play, fast enough for startup.
+fn self_test_params() -> MctsParams {
+    MctsParams {
+        num_simulations: 50,
+        time_limit_ms: 999999.0,
+        num_determinizations: 2,
+        ..Default::default()
+    }
+}
+
+/// Play `SELF_TEST_GAMES` seat-alternating games of `mcts` against random play.
+pub fn check_game<P: TypedGamePlugin + 'static>(
+    plugin: &P,
+    mcts: MctsStrategy<P>,
+    game_options: serde_json::Value,
+) -> SelfTestOutcome {
+    let mut strategies: HashMap<String, Box<dyn BotStrategy<P>>> = HashMap::new();
+    strategies.insert("mcts".into(), Box::new(mcts));
+    strategies.insert("random".into(), Box::new(RandomStrategy));
+
+    let run = catch_unwind(AssertUnwindSafe(|| {
+        run_arena(plugin, &strategies, SELF_TEST_GAMES, SELF_TEST_SEED, 2, Some(game_options), true, false, DEFAULT_MAX_MOVES_PER_GAME, None)
+    }));
+    let mut outcome = SelfTestOutcome {
+        game_id: plugin.game_id().to_string(),
+        mcts_wins: 0,
+        random_wins: 0,
+        draws: 0,
+        error: None,
+    };
+    match run {
+        Ok(result) => {
+            outcome.mcts_wins = result.wins["mcts"];
+            outcome.random_wins = result.wins["random"];
+            outcome.draws = result.draws;
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "unknown panic".into());
+            outcome.error = Some(message);
+        }
+    }
+    outcome
+}
+
+/// The self-test arena for a registered game. A game without one fails, so a
+/// newly registered game can't skip the check.
+fn check_registered(game_id: &str) -> SelfTestOutcome {
+    match game_id {
+        "carcassonne" => check_game(
+            &CarcassonnePlugin,
+            MctsStrategy::with_eval(self_test_params(), make_carcassonne_eval(DEFAULT_WEIGHTS)),
+            serde_json::json!({"tile_count": 10}),
+        ),
+        // No hidden information, so one determinization is enough.
+        "einstein_dojo" => {
+            let params = MctsParams { num_determinizations: 1, ..self_test_params() };
+            let mcts = match EinsteinDojoPlugin.make_eval("default") {
+                Some(eval_fn) => MctsStrategy::with_eval(params, eval_fn),
+                None => MctsStrategy::new(params),
+            };
+            check_game(&EinsteinDojoPlugin, mcts, serde_json::json!({}))
+        }
+        "connect_four" => check_game(
+            &ConnectFourPlugin,
+            MctsStrategy::new(MctsParams { num_determinizations: 1, ..self_test_params() }),
+            serde_json::json!({}),
+        ),
+        _ => SelfTestOutcome {
+            game_id: game_id.to_string(),
+            mcts_wins: 0,
+            random_wins: 0,
+            draws: 0,
+            error: Some("no self-test arena for this game".into()),
+        },
+    }
+}
+
+/// Self-test every registered game, logging each result. Fails if any game does.
+pub fn run(registry: &GameRegistry) -> Result<(), Box<dyn std::error::Error>> {
+    let mut game_ids = registry.list_game_ids();
+    game_ids.sort();
+    let outcomes: Vec<_> = game_ids.iter().map(|id| check_registered(id)).collect();
+
+    let mut failed = Vec::new();
+    for outcome in &outcomes {
+        if outcome.passed() {
+            tracing::info!(
+                game = %outcome.game_id,
+                mcts_wins = outcome.mcts_wins,
+                random_wins = outcome.random_wins,
+                draws = outcome.draws,
+                "self-test passed"
+            );
+        } else {
+            tracing::error!(
+                game = %outcome.game_id,
+                mcts_wins = outcome.mcts_wins,
+                random_wins = outcome.random_wins,
+                draws = outcome.draws,
+                error = outcome.error.as_deref().unwrap_or(""),
+                "self-test failed"
+            );
+            failed.push(outcome.game_id.as_str());
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("self-test failed for: {}", failed.join(", ")).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::test_games::TicTacToe;
+
+    #[test]
+    fn test_self_test_carcassonne() {
+        let outcome = check_game(
+            &CarcassonnePlugin,
+            MctsStrategy::with_eval(self_test_params(), make_carcassonne_eval(DEFAULT_WEIGHTS)),
+            serde_json::json!({"tile_count": 10}),
+        );
+        assert!(outcome.passed(), "{:?}", outcome);
+        assert_eq!(outcome.mcts_wins + outcome.random_wins + outcome.draws, SELF_TEST_GAMES);
+    }
+
+    #[test]
+    fn test_self_test_tic_tac_toe() {
+        let outcome = check_game(&TicTacToe, MctsStrategy::new(self_test_params()), serde_json::json!({}));
+        assert!(outcome.passed(), "{:?}", outcome);
+        assert_eq!(outcome.game_id, "tic_tac_toe");
+    }
+
+    #[test]
+    fn test_self_test_registered_games() {
+        for game_id in ["einstein_dojo", "connect_four"] {
+            let outcome = check_registered(game_id);
+            assert!(outcome.passed(), "{:?}", outcome);
+            assert_eq!(outcome.game_id, game_id);
+        }
+        assert!(!check_registered("unknown").passed());
+    }
+
+    #[test]
+    fn test_self_test_fails_on_crash() {
+        let outcome = SelfTestOutcome {
+            game_id: "broken".into(),
+            mcts_wins: 4,
+            random_wins: 0,
+            draws: 0,
+            error: Some("boom".into()),
+        };
+        assert!(!outcome.passed());
+        let outcome = SelfTestOutcome { error: None, mcts_wins: 1, random_wins: 3, ..outcome };
+        assert!(!outcome.passed());
+    }
+}
@@ -1,38 +1,69 @@
 //! gRPC server implementation for GameEngineService.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::task::TaskTracker;
 use tonic::{Request, Response, Status};
 
-use crate::engine::arena::run_arena;
+use crate::engine::arena::{
+    run_arena, run_arena_parallel, run_arena_sprt, ArenaResult, SprtParams, DEFAULT_MAX_MOVES_PER_GAME,
+};
 use crate::engine::bot_profiles::{load_default_profiles, load_profiles, BotProfilesFile};
 use crate::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
-use crate::engine::mcts::{mcts_search, MctsParams};
+use crate::engine::mcts::{
+    mcts_search, mcts_search_policy, mcts_search_with_progress, mcts_search_with_stats, FpuMode, MctsParams,
+    MctsProgress, TreeStats,
+};
 use crate::engine::models;
-use crate::engine::plugin::{GamePlugin, TypedGamePlugin};
+use crate::engine::plugin::{EvalFn, GamePlugin, TypedGamePlugin};
 use crate::games::carcassonne::evaluator::{
-    make_carcassonne_eval, make_carcassonne_eval_owned, AGGRESSIVE_WEIGHTS, CONSERVATIVE_WEIGHTS,
-    DEFAULT_WEIGHTS, FIELD_HEAVY_WEIGHTS,
+    make_carcassonne_eval, preset_weights, EvalWeights, DEFAULT_WEIGHTS,
 };
 use crate::games::carcassonne::plugin::CarcassonnePlugin;
 use crate::games::carcassonne::types::CarcassonneState;
+use crate::games::einstein_dojo::plugin::EinsteinDojoPlugin;
+use crate::games::einstein_dojo::types::EinsteinDojoState;
 use crate::games::GameRegistry;
 
 pub mod proto {
     tonic::include_proto!("meeple.game_engine.v1");
+
+    /// Encoded descriptors for the engine protos, served by gRPC reflection.
+    #[cfg(feature = "reflection")]
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        tonic::include_file_descriptor_set!("game_engine_descriptor");
 }
 
 use proto::game_engine_service_server::GameEngineService;
 use proto::*;
 
+/// What to do with a heavy request (MctsSearch, RunArena) when all slots are busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SearchLimitPolicy {
+    /// Wait for a slot to free up.
+    Queue,
+    /// Fail immediately with `resource_exhausted`.
+    Reject,
+}
+
 /// The gRPC service implementation.
 pub struct GameEngineServer {
     registry: Arc<GameRegistry>,
-    profiles: Arc<BotProfilesFile>,
+    /// Swapped whole by ReloadProfiles; readers clone the `Arc` and keep a
+    /// consistent set for the rest of their request.
+    profiles: RwLock<Arc<BotProfilesFile>>,
+    /// The `--profiles` file, if the server was started with one.
+    profiles_path: Option<PathBuf>,
+    /// Bounds concurrent heavy operations; `None` means unlimited.
+    search_limiter: Option<Arc<Semaphore>>,
+    search_limit_policy: SearchLimitPolicy,
+    /// Blocking tasks behind streaming RPCs, which outlive their handler.
+    stream_tasks: TaskTracker,
 }
 
 impl GameEngineServer {
@@ -40,7 +71,11 @@ impl GameEngineServer {
         let profiles = load_default_profiles();
         Self {
             registry: Arc::new(registry),
-            profiles: Arc::new(profiles),
+            profiles: RwLock::new(Arc::new(profiles)),
+            profiles_path: None,
+            search_limiter: None,
+            search_limit_policy: SearchLimitPolicy::Queue,
+            stream_tasks: TaskTracker::new(),
         }
     }
 
@@ -48,15 +83,125 @@ impl GameEngineServer {
         let profiles = load_profiles(profiles_path)?;
         Ok(Self {
             registry: Arc::new(registry),
-            profiles: Arc::new(profiles),
+            profiles: RwLock::new(Arc::new(profiles)),
+            profiles_path: Some(profiles_path.to_path_buf()),
+            search_limiter: None,
+            search_limit_policy: SearchLimitPolicy::Queue,
+            stream_tasks: TaskTracker::new(),
         })
     }
 
+    /// The current bot profiles.
+    fn profiles(&self) -> Arc<BotProfilesFile> {
+        self.profiles.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Allow at most `max_concurrent` heavy operations at once (0 = unlimited).
+    pub fn with_search_limit(mut self, max_concurrent: usize, policy: SearchLimitPolicy) -> Self {
+        self.search_limiter = (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent)));
+        self.search_limit_policy = policy;
+        self
+    }
+
+    /// Tracks the tasks behind `StreamMctsSearch` and `RunArena` streams, so
+    /// shutdown can wait for them after the gRPC server stops.
+    pub fn stream_tasks(&self) -> TaskTracker {
+        self.stream_tasks.clone()
+    }
+
+    /// Take a slot for a heavy operation. The slot is released when the permit drops.
+    async fn acquire_search_permit(&self) -> Result<Option<OwnedSemaphorePermit>, Status> {
+        let Some(limiter) = &self.search_limiter else {
+            return Ok(None);
+        };
+        let permit = match self.search_limit_policy {
+            SearchLimitPolicy::Queue => limiter.clone().acquire_owned().await.ok(),
+            SearchLimitPolicy::Reject => limiter.clone().try_acquire_owned().ok(),
+        };
+        permit
+            .map(Some)
+            .ok_or_else(|| Status::resource_exhausted("too many concurrent searches, retry later"))
+    }
+
     fn get_plugin(&self, game_id: &str) -> Result<&dyn GamePlugin, Status> {
         self.registry
             .get(game_id)
             .ok_or_else(|| Status::not_found(format!("unknown game_id: {}", game_id)))
     }
+
+    /// Reject an eval profile the game doesn't provide. An empty profile means no
+    /// eval function, and "default" is accepted by games without profiles.
+    fn check_eval_profile(&self, game_id: &str, eval_profile: &str) -> Result<(), Status> {
+        if eval_profile.is_empty() {
+            return Ok(());
+        }
+        let profiles = self.get_plugin(game_id)?.eval_profiles();
+        if profiles.contains(&eval_profile) || (profiles.is_empty() && eval_profile == "default") {
+            return Ok(());
+        }
+        Err(Status::invalid_argument(format!(
+            "unknown eval_profile '{}' for {}. Available: {:?}",
+            eval_profile, game_id, profiles
+        )))
+    }
+
+    /// Validate an MctsSearch request and resolve its params and eval function,
+    /// from the named bot profile when set, else from the request fields.
+    fn prepare_search(&self, req: &MctsSearchRequest) -> Result<PreparedSearch, Status> {
+        let phase = req
+            .phase
+            .as_ref()
+            .map(proto_to_phase)
+            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
+        let players = proto_to_players(&req.players);
+        if players.is_empty() {
+            return Err(Status::invalid_argument(
+                "MctsSearch requires non-empty `players` with correct seat ordering"
+            ));
+        }
+        validate_seat_order(&players)?;
+
+        let profiles = self.profiles();
+        let (params, eval_weights, eval_profile) = if !req.bot_profile.is_empty() {
+            let profile = profiles.profiles.get(&req.bot_profile).ok_or_else(|| {
+                Status::invalid_argument(format!(
+                    "unknown bot_profile: '{}'. Available: {:?}",
+                    req.bot_profile,
+                    profiles.profiles.keys().collect::<Vec<_>>()
+                ))
+            })?;
+            (profile.mcts_params_for(&phase.name), profile.eval_weights, profile.effective_eval_profile())
+        } else {
+            let params = ProtoMctsFields::from(req).to_params().map_err(Status::invalid_argument)?;
+            (params, None, req.eval_profile.as_str())
+        };
+        self.check_eval_profile(&req.game_id, eval_profile)?;
+
+        let (game, params) = match req.game_id.as_str() {
+            "carcassonne" => {
+                // Custom weights from the profile take priority over its named preset
+                let eval_fn = match eval_weights {
+                    Some(w) => Some(make_carcassonne_eval(w)),
+                    None => CarcassonnePlugin.make_eval(eval_profile),
+                };
+                let state = decode_typed_state(&CarcassonnePlugin, &req.game_data_json, req.game_data_format())?;
+                (SearchGame::Carcassonne { state, eval_fn }, params)
+            }
+            // No hidden information, so every determinization would search the same tree.
+            "einstein_dojo" => {
+                let state = decode_typed_state(&EinsteinDojoPlugin, &req.game_data_json, req.game_data_format())?;
+                let eval_fn = EinsteinDojoPlugin.make_eval(eval_profile);
+                (SearchGame::EinsteinDojo { state, eval_fn }, MctsParams { num_determinizations: 1, ..params })
+            }
+            _ => {
+                return Err(Status::unimplemented(format!(
+                    "MCTS not available for game: {}",
+                    req.game_id
+                )))
+            }
+        };
+        Ok(PreparedSearch { game, phase, players, params })
+    }
 }
 
 // --- Conversion helpers: protobuf <-> engine types ---
@@ -79,6 +224,22 @@ fn proto_to_players(players: &[Player]) -> Vec<models::Player> {
     players.iter().map(proto_to_player).collect()
 }
 
+/// Reject player lists that are not ordered by seat (`players[i].seat_index == i`).
+///
+/// MCTS and turn rotation index players positionally, so a misordered list
+/// silently produces wrong results in release builds.
+fn validate_seat_order(players: &[models::Player]) -> Result<(), Status> {
+    for (i, p) in players.iter().enumerate() {
+        if p.seat_index != i as i32 {
+            return Err(Status::invalid_argument(format!(
+                "players must be ordered by seat_index: players[{}] ('{}') has seat_index {}",
+                i, p.player_id, p.seat_index
+            )));
+        }
+    }
+    Ok(())
+}
+
 fn proto_to_config(config: &GameConfig) -> models::GameConfig {
     let options = if config.options.is_empty() {
         serde_json::json!({})
@@ -100,7 +261,7 @@ fn proto_to_config(config: &GameConfig) -> models::GameConfig {
     }
 }
 
-fn proto_to_phase(phase: &Phase) -> models::Phase {
+pub(crate) fn proto_to_phase(phase: &Phase) -> models::Phase {
     let expected_actions = phase
         .expected_actions
         .iter()
@@ -154,18 +315,63 @@ fn proto_to_action(action: &Action) -> models::Action {
     }
 }
 
-fn game_data_from_bytes(bytes: &[u8]) -> Result<serde_json::Value, Status> {
-    serde_json::from_slice(bytes)
-        .map_err(|e| Status::invalid_argument(format!("invalid game_data JSON: {}", e)))
+fn game_data_format(format: GameDataFormat) -> models::GameDataFormat {
+    match format {
+        GameDataFormat::Json => models::GameDataFormat::Json,
+        GameDataFormat::Msgpack => models::GameDataFormat::MessagePack,
+    }
+}
+
+fn game_data_from_bytes(
+    plugin: &dyn GamePlugin,
+    bytes: &[u8],
+    format: GameDataFormat,
+) -> Result<serde_json::Value, Status> {
+    plugin.decode_game_data(bytes, game_data_format(format)).map_err(Status::invalid_argument)
 }
 
-fn game_data_to_bytes(value: &serde_json::Value) -> Vec<u8> {
-    serde_json::to_vec(value).unwrap_or_default()
+/// Parse `game_data_json` and check it decodes as the game's state, so a
+/// malformed state is rejected instead of panicking inside the plugin.
+fn decode_game_data(
+    plugin: &dyn GamePlugin,
+    bytes: &[u8],
+    format: GameDataFormat,
+) -> Result<serde_json::Value, Status> {
+    let game_data = game_data_from_bytes(plugin, bytes, format)?;
+    plugin
+        .check_state(&game_data)
+        .map_err(|e| Status::invalid_argument(format!("invalid game_data: {}", e)))?;
+    Ok(game_data)
+}
+
+/// Decode straight to the typed state, for callers that search it directly.
+fn decode_typed_state<P: TypedGamePlugin>(
+    plugin: &P,
+    bytes: &[u8],
+    format: GameDataFormat,
+) -> Result<P::State, Status> {
+    let state = match game_data_format(format) {
+        models::GameDataFormat::Json => models::GameDataFormat::Json
+            .decode(bytes)
+            .and_then(|game_data| plugin.try_decode_state(&game_data)),
+        models::GameDataFormat::MessagePack => plugin.decode_state_binary(bytes),
+    };
+    state.map_err(|e| Status::invalid_argument(format!("invalid game_data: {}", e)))
+}
+
+fn game_data_to_bytes(
+    plugin: &dyn GamePlugin,
+    value: &serde_json::Value,
+    format: GameDataFormat,
+) -> Result<Vec<u8>, Status> {
+    plugin
+        .encode_game_data(value, game_data_format(format))
+        .map_err(|e| Status::internal(format!("cannot encode game_data: {}", e)))
 }
 
 // Engine -> proto conversions
 
-fn phase_to_proto(phase: &models::Phase) -> Phase {
+pub(crate) fn phase_to_proto(phase: &models::Phase) -> Phase {
     let expected_actions = phase
         .expected_actions
         .iter()
@@ -218,17 +424,44 @@ fn game_result_to_proto(gr: &models::GameResult) -> GameResult {
     }
 }
 
-fn transition_to_proto(tr: &models::TransitionResult) -> TransitionResult {
-    TransitionResult {
-        game_data_json: game_data_to_bytes(&tr.game_data),
+fn transition_to_proto(
+    plugin: &dyn GamePlugin,
+    tr: &models::TransitionResult,
+    format: GameDataFormat,
+) -> Result<TransitionResult, Status> {
+    Ok(TransitionResult {
+        game_data_json: game_data_to_bytes(plugin, &tr.game_data, format)?,
         events: tr.events.iter().map(event_to_proto).collect(),
         next_phase: Some(phase_to_proto(&tr.next_phase)),
         scores: tr.scores.clone(),
         game_over: tr.game_over.as_ref().map(game_result_to_proto),
+    })
+}
+
+fn game_info(plugin: &dyn GamePlugin) -> GetGameInfoResponse {
+    GetGameInfoResponse {
+        game_id: plugin.game_id().to_string(),
+        display_name: plugin.display_name().to_string(),
+        min_players: plugin.min_players() as i32,
+        max_players: plugin.max_players() as i32,
+        description: plugin.description().to_string(),
+        disconnect_policy: plugin.disconnect_policy().to_string(),
+        supported_options: plugin
+            .supported_options()
+            .into_iter()
+            .map(|o| GameOptionSpec {
+                name: o.name,
+                r#type: o.option_type.as_str().to_string(),
+                default_json: o.default.to_string(),
+                description: o.description,
+            })
+            .collect(),
     }
 }
 
-fn build_mcts_params(
+/// MCTS fields shared by `MctsSearchRequest` and `ArenaStrategyConfig`.
+/// proto3 scalars have no presence, so 0 means "keep the default".
+struct ProtoMctsFields {
     num_simulations: i32,
     time_limit_ms: f64,
     exploration_constant: f64,
@@ -240,63 +473,345 @@ fn build_mcts_params(
     max_amaf_depth: i32,
     rave_fpu: bool,
     tile_aware_amaf: bool,
-) -> MctsParams {
-    let defaults = MctsParams::default();
-    MctsParams {
-        num_simulations: if num_simulations > 0 {
-            num_simulations as usize
-        } else {
-            defaults.num_simulations
-        },
-        time_limit_ms: if time_limit_ms > 0.0 {
-            time_limit_ms
-        } else {
-            defaults.time_limit_ms
-        },
-        exploration_constant: if exploration_constant > 0.0 {
-            exploration_constant
-        } else {
-            defaults.exploration_constant
-        },
-        num_determinizations: if num_determinizations > 0 {
-            num_determinizations as usize
-        } else {
-            defaults.num_determinizations
-        },
-        pw_c: if pw_c > 0.0 { pw_c } else { defaults.pw_c },
-        pw_alpha: if pw_alpha > 0.0 {
-            pw_alpha
-        } else {
-            defaults.pw_alpha
-        },
-        use_rave,
-        rave_k: if rave_k > 0.0 {
-            rave_k
-        } else {
-            defaults.rave_k
-        },
-        max_amaf_depth: if max_amaf_depth > 0 {
-            max_amaf_depth as usize
-        } else {
-            defaults.max_amaf_depth
-        },
-        rave_fpu,
-        tile_aware_amaf,
-    }
-}
-
-fn resolve_eval_fn(
-    eval_profile: &str,
-) -> Option<
-    Box<dyn Fn(&CarcassonneState, &models::Phase, &str, &[models::Player]) -> f64 + Send + Sync>,
-> {
-    match eval_profile {
-        "aggressive" => Some(make_carcassonne_eval(&AGGRESSIVE_WEIGHTS)),
-        "field_heavy" => Some(make_carcassonne_eval(&FIELD_HEAVY_WEIGHTS)),
-        "conservative" => Some(make_carcassonne_eval(&CONSERVATIVE_WEIGHTS)),
-        "default" => Some(make_carcassonne_eval(&DEFAULT_WEIGHTS)),
-        "" => None,
-        _ => None,
+    final_selection: String,
+}
+
+impl From<&MctsSearchRequest> for ProtoMctsFields {
+    fn from(req: &MctsSearchRequest) -> Self {
+        Self {
+            num_simulations: req.num_simulations,
+            time_limit_ms: req.time_limit_ms,
+            exploration_constant: req.exploration_constant,
+            num_determinizations: req.num_determinizations,
+            pw_c: req.pw_c,
+            pw_alpha: req.pw_alpha,
+            use_rave: req.use_rave,
+            rave_k: req.rave_k,
+            max_amaf_depth: req.max_amaf_depth,
+            rave_fpu: req.rave_fpu,
+            tile_aware_amaf: req.tile_aware_amaf,
+            final_selection: req.final_selection.clone(),
+        }
+    }
+}
+
+impl From<&ArenaStrategyConfig> for ProtoMctsFields {
+    fn from(config: &ArenaStrategyConfig) -> Self {
+        Self {
+            num_simulations: config.num_simulations,
+            time_limit_ms: config.time_limit_ms,
+            exploration_constant: 0.0,
+            num_determinizations: config.num_determinizations,
+            pw_c: config.pw_c,
+            pw_alpha: config.pw_alpha,
+            use_rave: config.use_rave,
+            rave_k: config.rave_k,
+            max_amaf_depth: config.max_amaf_depth,
+            rave_fpu: config.rave_fpu,
+            tile_aware_amaf: config.tile_aware_amaf,
+            final_selection: config.final_selection.clone(),
+        }
+    }
+}
+
+impl ProtoMctsFields {
+    /// Set fields over the defaults; errors on negative counts and values the
+    /// search cannot run with (callers report them as `invalid_argument`).
+    fn to_params(&self) -> Result<MctsParams, String> {
+        let count = |name: &str, v: i32| {
+            usize::try_from(v).map_err(|_| format!("{name} must be positive, got {v}"))
+        };
+        let mut b = MctsParams::builder()
+            .rave(self.use_rave)
+            .fpu_mode(FpuMode::from_rave_fpu(self.rave_fpu))
+            .tile_aware_amaf(self.tile_aware_amaf);
+        if self.num_simulations != 0 {
+            b = b.simulations(count("num_simulations", self.num_simulations)?);
+        }
+        if self.time_limit_ms != 0.0 {
+            b = b.time_limit_ms(self.time_limit_ms);
+        }
+        if self.exploration_constant != 0.0 {
+            b = b.exploration_constant(self.exploration_constant);
+        }
+        if self.num_determinizations != 0 {
+            b = b.determinizations(count("num_determinizations", self.num_determinizations)?);
+        }
+        if self.pw_c != 0.0 {
+            b = b.pw_c(self.pw_c);
+        }
+        if self.pw_alpha != 0.0 {
+            b = b.pw_alpha(self.pw_alpha);
+        }
+        if self.rave_k != 0.0 {
+            b = b.rave_k(self.rave_k);
+        }
+        if self.max_amaf_depth != 0 {
+            b = b.max_amaf_depth(count("max_amaf_depth", self.max_amaf_depth)?);
+        }
+        if !self.final_selection.is_empty() {
+            let selection = self.final_selection.parse()?;
+            b = b.final_selection(selection);
+        }
+        b.build().map_err(|e| format!("invalid MCTS params: {e}"))
+    }
+}
+
+/// Decoded root state of a search, for each game MCTS can play.
+enum SearchGame {
+    Carcassonne {
+        state: CarcassonneState,
+        eval_fn: Option<EvalFn<CarcassonneState>>,
+    },
+    EinsteinDojo {
+        state: EinsteinDojoState,
+        eval_fn: Option<EvalFn<EinsteinDojoState>>,
+    },
+}
+
+/// A validated MctsSearch request with its params and eval resolved.
+struct PreparedSearch {
+    game: SearchGame,
+    phase: models::Phase,
+    players: Vec<models::Player>,
+    params: MctsParams,
+}
+
+#[allow(clippy::type_complexity)]
+fn eval_ref<S>(
+    eval_fn: &Option<EvalFn<S>>,
+) -> Option<&(dyn Fn(&S, &models::Phase, &str, &[models::Player]) -> f64 + Sync)> {
+    eval_fn.as_ref().map(|f| {
+        f.as_ref() as &(dyn Fn(&S, &models::Phase, &str, &[models::Player]) -> f64 + Sync)
+    })
+}
+
+impl PreparedSearch {
+    fn run(
+        &self,
+        player_id: &str,
+        on_progress: Option<&(dyn Fn(MctsProgress) + Sync)>,
+    ) -> Result<(serde_json::Value, usize), String> {
+        let (phase, players, params) = (&self.phase, &self.players, &self.params);
+        match (&self.game, on_progress) {
+            (SearchGame::Carcassonne { state, eval_fn }, Some(report)) => mcts_search_with_progress(
+                state,
+                phase,
+                player_id,
+                &CarcassonnePlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+                report,
+            ),
+            (SearchGame::Carcassonne { state, eval_fn }, None) => mcts_search(
+                state,
+                phase,
+                player_id,
+                &CarcassonnePlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+            ),
+            (SearchGame::EinsteinDojo { state, eval_fn }, Some(report)) => mcts_search_with_progress(
+                state,
+                phase,
+                player_id,
+                &EinsteinDojoPlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+                report,
+            ),
+            (SearchGame::EinsteinDojo { state, eval_fn }, None) => mcts_search(
+                state,
+                phase,
+                player_id,
+                &EinsteinDojoPlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+            ),
+        }
+    }
+
+    fn run_with_stats(&self, player_id: &str) -> Result<(serde_json::Value, usize, Vec<TreeStats>), String> {
+        let (phase, players, params) = (&self.phase, &self.players, &self.params);
+        match &self.game {
+            SearchGame::Carcassonne { state, eval_fn } => mcts_search_with_stats(
+                state,
+                phase,
+                player_id,
+                &CarcassonnePlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+            ),
+            SearchGame::EinsteinDojo { state, eval_fn } => mcts_search_with_stats(
+                state,
+                phase,
+                player_id,
+                &EinsteinDojoPlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+            ),
+        }
+    }
+
+    fn run_policy(
+        &self,
+        player_id: &str,
+        temperature: f64,
+    ) -> Result<(serde_json::Value, Vec<(serde_json::Value, f64)>), String> {
+        let (phase, players, params) = (&self.phase, &self.players, &self.params);
+        match &self.game {
+            SearchGame::Carcassonne { state, eval_fn } => mcts_search_policy(
+                state,
+                phase,
+                player_id,
+                &CarcassonnePlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+                temperature,
+            ),
+            SearchGame::EinsteinDojo { state, eval_fn } => mcts_search_policy(
+                state,
+                phase,
+                player_id,
+                &EinsteinDojoPlugin,
+                players,
+                params,
+                eval_ref(eval_fn),
+                temperature,
+            ),
+        }
+    }
+}
+
+fn progress_update(p: &MctsProgress, t0: Instant, is_final: bool) -> MctsSearchUpdate {
+    MctsSearchUpdate {
+        action_json: serde_json::to_vec(&p.best_action).unwrap_or_default(),
+        visits: p.best_visits as i32,
+        mean_value: p.best_mean_value,
+        iterations_run: p.iterations as i32,
+        determinizations_completed: p.dets_completed as i32,
+        num_determinizations: p.num_determinizations as i32,
+        elapsed_ms: t0.elapsed().as_secs_f64() * 1000.0,
+        is_final,
+    }
+}
+
+/// Explicit weights for an arena strategy: its `eval_weights` overrides applied on top
+/// of its named `eval_profile` (default weights if unnamed). `None` without overrides.
+fn strategy_eval_weights(config: &ArenaStrategyConfig) -> Result<Option<EvalWeights>, String> {
+    if config.eval_weights.is_empty() {
+        return Ok(None);
+    }
+    let base = preset_weights(&config.eval_profile).unwrap_or(&DEFAULT_WEIGHTS);
+    base.with_overrides(&config.eval_weights)
+        .map(Some)
+        .map_err(|e| format!("strategy '{}': {}", config.name, e))
+}
+
+/// Arena strategies by name. `params` and `evals` (the MCTS eval function, if the
+/// game has one) are in `configs` order. Unknown strategy types play randomly.
+fn arena_strategies<P: TypedGamePlugin + 'static>(
+    configs: &[ArenaStrategyConfig],
+    params: Vec<MctsParams>,
+    evals: impl IntoIterator<Item = Option<EvalFn<P::State>>>,
+) -> HashMap<String, Box<dyn BotStrategy<P>>> {
+    configs
+        .iter()
+        .zip(params)
+        .zip(evals)
+        .map(|((config, params), eval_fn)| {
+            let strategy: Box<dyn BotStrategy<P>> = match config.strategy_type.as_str() {
+                "random" => Box::new(RandomStrategy),
+                "mcts" => {
+                    let mut mcts = MctsStrategy::<P>::new(params);
+                    mcts.eval_fn = eval_fn;
+                    Box::new(mcts)
+                }
+                _ => Box::new(RandomStrategy),
+            };
+            (config.name.clone(), strategy)
+        })
+        .collect()
+}
+
+/// Resolve a RunArena SPRT config; the candidate defaults to the first strategy.
+fn sprt_params(config: &SprtConfig, strategies: &[ArenaStrategyConfig]) -> Result<SprtParams, String> {
+    if strategies.len() != 2 {
+        return Err(format!("sprt requires exactly 2 strategies, got {}", strategies.len()));
+    }
+    let candidate = if config.candidate.is_empty() {
+        strategies[0].name.clone()
+    } else {
+        config.candidate.clone()
+    };
+    if !strategies.iter().any(|s| s.name == candidate) {
+        return Err(format!("unknown sprt candidate: '{candidate}'"));
+    }
+    let params = SprtParams {
+        candidate,
+        elo0: config.elo0,
+        elo1: config.elo1,
+        alpha: config.alpha,
+        beta: config.beta,
+    };
+    params.validate().map_err(|e| format!("sprt: {e}"))?;
+    Ok(params)
+}
+
+/// Play the arena `req` asks for: under SPRT when `sprt` is set, else with up to
+/// `parallel_games` games at a time.
+fn play_arena<P: TypedGamePlugin>(
+    plugin: &P,
+    strategies: &HashMap<String, Box<dyn BotStrategy<P>>>,
+    req: &RunArenaRequest,
+    sprt: Option<&SprtParams>,
+    game_options: Option<serde_json::Value>,
+    on_progress: &(dyn Fn(usize, usize) + Sync),
+) -> ArenaResult {
+    let (num_games, base_seed) = (req.num_games as usize, req.base_seed as u64);
+    match sprt {
+        None if req.parallel_games > 1 => run_arena_parallel(
+            plugin,
+            strategies,
+            num_games,
+            base_seed,
+            strategies.len(),
+            game_options,
+            req.alternate_seats,
+            false,
+            DEFAULT_MAX_MOVES_PER_GAME,
+            req.parallel_games as usize,
+            Some(on_progress),
+        ),
+        Some(sprt) => run_arena_sprt(
+            plugin,
+            strategies,
+            sprt,
+            num_games,
+            base_seed,
+            game_options,
+            req.alternate_seats,
+            Some(on_progress),
+        ),
+        None => run_arena(
+            plugin,
+            strategies,
+            num_games,
+            base_seed,
+            strategies.len(),
+            game_options,
+            req.alternate_seats,
+            false,
+            DEFAULT_MAX_MOVES_PER_GAME,
+            Some(on_progress),
+        ),
     }
 }
 
@@ -310,14 +825,7 @@ impl GameEngineService for GameEngineServer {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
 
-        Ok(Response::new(GetGameInfoResponse {
-            game_id: plugin.game_id().to_string(),
-            display_name: plugin.display_name().to_string(),
-            min_players: plugin.min_players() as i32,
-            max_players: plugin.max_players() as i32,
-            description: plugin.description().to_string(),
-            disconnect_policy: plugin.disconnect_policy().to_string(),
-        }))
+        Ok(Response::new(game_info(plugin)))
     }
 
     // --- ListGames ---
@@ -328,14 +836,7 @@ impl GameEngineService for GameEngineServer {
         let mut games = Vec::new();
         for game_id in self.registry.list_game_ids() {
             if let Some(plugin) = self.registry.get(&game_id) {
-                games.push(GetGameInfoResponse {
-                    game_id: plugin.game_id().to_string(),
-                    display_name: plugin.display_name().to_string(),
-                    min_players: plugin.min_players() as i32,
-                    max_players: plugin.max_players() as i32,
-                    description: plugin.description().to_string(),
-                    disconnect_policy: plugin.disconnect_policy().to_string(),
-                });
+                games.push(game_info(plugin));
             }
         }
         Ok(Response::new(ListGamesResponse { games }))
@@ -357,11 +858,14 @@ impl GameEngineService for GameEngineServer {
                 options: serde_json::json!({}),
                 random_seed: None,
             });
+        plugin
+            .validate_config(&config)
+            .map_err(|e| Status::invalid_argument(format!("invalid game config: {}", e)))?;
 
         let (game_data, phase, events) = plugin.create_initial_state(&players, &config);
 
         Ok(Response::new(CreateInitialStateResponse {
-            game_data_json: game_data_to_bytes(&game_data),
+            game_data_json: game_data_to_bytes(plugin, &game_data, req.game_data_format())?,
             phase: Some(phase_to_proto(&phase)),
             events: events.iter().map(event_to_proto).collect(),
         }))
@@ -374,7 +878,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<GetValidActionsResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -386,8 +890,47 @@ impl GameEngineService for GameEngineServer {
             .iter()
             .map(|a| serde_json::to_vec(a).unwrap_or_default())
             .collect();
+        let details_json = if req.include_details {
+            plugin
+                .valid_action_details(&game_data, &phase, &req.player_id)
+                .map(|d| serde_json::to_vec(&d).unwrap_or_default())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
-        Ok(Response::new(GetValidActionsResponse { actions_json }))
+        Ok(Response::new(GetValidActionsResponse { actions_json, details_json }))
+    }
+
+    // --- GetValidActionsBatch ---
+    async fn get_valid_actions_batch(
+        &self,
+        request: Request<GetValidActionsBatchRequest>,
+    ) -> Result<Response<GetValidActionsBatchResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+        let phase = req
+            .phase
+            .as_ref()
+            .map(proto_to_phase)
+            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
+
+        let valid = plugin.get_valid_actions_batch(&game_data, &phase, &req.player_ids);
+        let actions = req
+            .player_ids
+            .into_iter()
+            .zip(valid)
+            .map(|(pid, actions)| {
+                let actions_json = actions
+                    .iter()
+                    .map(|a| serde_json::to_vec(a).unwrap_or_default())
+                    .collect();
+                (pid, PlayerActions { actions_json })
+            })
+            .collect();
+
+        Ok(Response::new(GetValidActionsBatchResponse { actions }))
     }
 
     // --- ValidateAction ---
@@ -397,7 +940,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<ValidateActionResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -420,7 +963,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<ApplyActionResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -432,11 +975,117 @@ impl GameEngineService for GameEngineServer {
             .map(proto_to_action)
             .ok_or_else(|| Status::invalid_argument("action is required"))?;
         let players = proto_to_players(&req.players);
+        validate_seat_order(&players)?;
+        if let Some(err) = plugin.validate_payload_shape(&action) {
+            return Err(Status::invalid_argument(format!("invalid action payload: {}", err)));
+        }
 
-        let result = plugin.apply_action(&game_data, &phase, &action, &players);
+        let result = plugin
+            .apply_action(&game_data, &phase, &action, &players)
+            .map_err(|e| Status::failed_precondition(format!("cannot apply action: {}", e)))?;
 
         Ok(Response::new(ApplyActionResponse {
-            result: Some(transition_to_proto(&result)),
+            result: Some(transition_to_proto(plugin, &result, req.game_data_format())?),
+        }))
+    }
+
+    // --- ValidateAndApply ---
+    async fn validate_and_apply(
+        &self,
+        request: Request<ValidateAndApplyRequest>,
+    ) -> Result<Response<ValidateAndApplyResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+        let phase = req
+            .phase
+            .as_ref()
+            .map(proto_to_phase)
+            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
+        let action = req
+            .action
+            .as_ref()
+            .map(proto_to_action)
+            .ok_or_else(|| Status::invalid_argument("action is required"))?;
+        let players = proto_to_players(&req.players);
+        validate_seat_order(&players)?;
+
+        let outcome = match plugin.validate_action(&game_data, &phase, &action) {
+            Some(err) => validate_and_apply_response::Outcome::Error(err),
+            None => match plugin.apply_action(&game_data, &phase, &action, &players) {
+                Ok(result) => {
+                    validate_and_apply_response::Outcome::Result(transition_to_proto(plugin, &result, req.game_data_format())?)
+                }
+                Err(err) => validate_and_apply_response::Outcome::Error(err),
+            },
+        };
+
+        Ok(Response::new(ValidateAndApplyResponse {
+            outcome: Some(outcome),
+        }))
+    }
+
+    // --- ApplyActionsBatch ---
+    async fn apply_actions_batch(
+        &self,
+        request: Request<ApplyActionsBatchRequest>,
+    ) -> Result<Response<ApplyActionsBatchResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let mut game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+        let mut phase = req
+            .phase
+            .as_ref()
+            .map(proto_to_phase)
+            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
+        let players = proto_to_players(&req.players);
+        validate_seat_order(&players)?;
+
+        let mut result: Option<models::TransitionResult> = None;
+        let mut events = Vec::new();
+        let mut failure = None;
+        for (i, proto_action) in req.actions.iter().enumerate() {
+            if result.as_ref().is_some_and(|r| r.game_over.is_some()) {
+                failure = Some((i, "game is already over".to_string()));
+                break;
+            }
+            let action = proto_to_action(proto_action);
+            if let Some(err) = plugin.validate_action(&game_data, &phase, &action) {
+                failure = Some((i, err));
+                break;
+            }
+            let mut next = match plugin.apply_action(&game_data, &phase, &action, &players) {
+                Ok(next) => next,
+                Err(err) => {
+                    failure = Some((i, err));
+                    break;
+                }
+            };
+            events.append(&mut next.events);
+            if next.scores.is_empty() {
+                if let Some(prev) = result.as_mut() {
+                    next.scores = std::mem::take(&mut prev.scores);
+                }
+            }
+            game_data = next.game_data.clone();
+            phase = next.next_phase.clone();
+            result = Some(next);
+        }
+
+        let result = result
+            .map(|mut r| {
+                r.events = events;
+                transition_to_proto(plugin, &r, req.game_data_format())
+            })
+            .transpose()?;
+        let (failed_index, error) = match failure {
+            Some((i, err)) => (Some(i as i32), Some(err)),
+            None => (None, None),
+        };
+        Ok(Response::new(ApplyActionsBatchResponse {
+            result,
+            failed_index,
+            error,
         }))
     }
 
@@ -447,7 +1096,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<GetPlayerViewResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -473,7 +1122,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<GetSpectatorSummaryResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -494,7 +1143,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<StateToAiViewResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -541,7 +1190,7 @@ impl GameEngineService for GameEngineServer {
     ) -> Result<Response<OnPlayerForfeitResponse>, Status> {
         let req = request.into_inner();
         let plugin = self.get_plugin(&req.game_id)?;
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
         let phase = req
             .phase
             .as_ref()
@@ -552,7 +1201,7 @@ impl GameEngineService for GameEngineServer {
         let result = plugin.on_player_forfeit(&game_data, &phase, &req.player_id, &players);
 
         Ok(Response::new(OnPlayerForfeitResponse {
-            result: result.map(|tr| transition_to_proto(&tr)),
+            result: result.map(|tr| transition_to_proto(plugin, &tr, req.game_data_format())).transpose()?,
         }))
     }
 
@@ -562,92 +1211,11 @@ impl GameEngineService for GameEngineServer {
         request: Request<MctsSearchRequest>,
     ) -> Result<Response<MctsSearchResponse>, Status> {
         let req = request.into_inner();
-        let game_data = game_data_from_bytes(&req.game_data_json)?;
-        let phase = req
-            .phase
-            .as_ref()
-            .map(proto_to_phase)
-            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
-        let players = proto_to_players(&req.players);
-        if players.is_empty() {
-            return Err(Status::invalid_argument(
-                "MctsSearch requires non-empty `players` with correct seat ordering"
-            ));
-        }
-
-        // If bot_profile is set, load params + eval from the named profile
-        let (params, eval_profile_str) = if !req.bot_profile.is_empty() {
-            let profile = self.profiles.profiles.get(&req.bot_profile).ok_or_else(|| {
-                Status::invalid_argument(format!(
-                    "unknown bot_profile: '{}'. Available: {:?}",
-                    req.bot_profile,
-                    self.profiles.profiles.keys().collect::<Vec<_>>()
-                ))
-            })?;
-            (profile.to_mcts_params(), profile.effective_eval_profile().to_string())
-        } else {
-            let params = build_mcts_params(
-                req.num_simulations,
-                req.time_limit_ms,
-                req.exploration_constant,
-                req.num_determinizations,
-                req.pw_c,
-                req.pw_alpha,
-                req.use_rave,
-                req.rave_k,
-                req.max_amaf_depth,
-                req.rave_fpu,
-                req.tile_aware_amaf,
-            );
-            (params, req.eval_profile.clone())
-        };
-
-        // Resolve eval: custom weights from profile take priority, then named preset
-        let custom_weights = if !req.bot_profile.is_empty() {
-            self.profiles.profiles.get(&req.bot_profile).and_then(|p| p.eval_weights)
-        } else {
-            None
-        };
+        let search = self.prepare_search(&req)?;
 
+        let _permit = self.acquire_search_permit().await?;
         let t0 = Instant::now();
-
-        let (action, iterations_run) = match req.game_id.as_str() {
-            "carcassonne" => {
-                let plugin = CarcassonnePlugin;
-                let eval_fn = if let Some(w) = custom_weights {
-                    Some(make_carcassonne_eval_owned(w))
-                } else {
-                    resolve_eval_fn(&eval_profile_str)
-                };
-                let state = plugin.decode_state(&game_data);
-                let eval_ref = eval_fn.as_ref().map(|f| {
-                    f.as_ref()
-                        as &(dyn Fn(
-                            &CarcassonneState,
-                            &models::Phase,
-                            &str,
-                            &[models::Player],
-                        ) -> f64
-                            + Sync)
-                });
-                mcts_search(
-                    &state,
-                    &phase,
-                    &req.player_id,
-                    &plugin,
-                    &players,
-                    &params,
-                    eval_ref,
-                )
-            }
-            _ => {
-                return Err(Status::unimplemented(format!(
-                    "MCTS not available for game: {}",
-                    req.game_id
-                )))
-            }
-        };
-
+        let (action, iterations_run) = search.run(&req.player_id, None).map_err(Status::internal)?;
         let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
 
         Ok(Response::new(MctsSearchResponse {
@@ -657,6 +1225,57 @@ impl GameEngineService for GameEngineServer {
         }))
     }
 
+    // --- StreamMctsSearch (server streaming) ---
+    type StreamMctsSearchStream = ReceiverStream<Result<MctsSearchUpdate, Status>>;
+
+    async fn stream_mcts_search(
+        &self,
+        request: Request<MctsSearchRequest>,
+    ) -> Result<Response<Self::StreamMctsSearchStream>, Status> {
+        let req = request.into_inner();
+        let search = self.prepare_search(&req)?;
+        let permit = self.acquire_search_permit().await?;
+
+        let (tx, rx) = mpsc::channel(32);
+
+        let token = self.stream_tasks.token();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _token = token;
+            let t0 = Instant::now();
+            let last: std::sync::Mutex<Option<MctsProgress>> = std::sync::Mutex::new(None);
+            let on_progress = |p: MctsProgress| {
+                let _ = tx.blocking_send(Ok(progress_update(&p, t0, false)));
+                *last.lock().unwrap_or_else(|e| e.into_inner()) = Some(p);
+            };
+
+            let (action, iterations_run) = match search.run(&req.player_id, Some(&on_progress)) {
+                Ok(result) => result,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(Status::internal(e)));
+                    return;
+                }
+            };
+
+            // Reuse the last snapshot's stats when it agrees with the final pick
+            let last = last.into_inner().unwrap_or_else(|e| e.into_inner());
+            let mut update = match last {
+                Some(p) if p.best_action == action => progress_update(&p, t0, true),
+                _ => MctsSearchUpdate {
+                    action_json: serde_json::to_vec(&action).unwrap_or_default(),
+                    num_determinizations: search.params.num_determinizations as i32,
+                    elapsed_ms: t0.elapsed().as_secs_f64() * 1000.0,
+                    is_final: true,
+                    ..Default::default()
+                },
+            };
+            update.iterations_run = iterations_run as i32;
+            let _ = tx.blocking_send(Ok(update));
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
     // --- RunArena (server streaming) ---
     type RunArenaStream = ReceiverStream<Result<ArenaProgressUpdate, Status>>;
 
@@ -665,81 +1284,92 @@ impl GameEngineService for GameEngineServer {
         request: Request<RunArenaRequest>,
     ) -> Result<Response<Self::RunArenaStream>, Status> {
         let req = request.into_inner();
+        let custom_weights = req
+            .strategies
+            .iter()
+            .map(strategy_eval_weights)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        let strategy_params = req
+            .strategies
+            .iter()
+            .map(|config| {
+                ProtoMctsFields::from(config)
+                    .to_params()
+                    .map_err(|e| format!("strategy '{}': {}", config.name, e))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        for config in &req.strategies {
+            self.check_eval_profile(&req.game_id, &config.eval_profile).map_err(|e| {
+                Status::invalid_argument(format!("strategy '{}': {}", config.name, e.message()))
+            })?;
+        }
+        let sprt = req
+            .sprt
+            .as_ref()
+            .map(|config| sprt_params(config, &req.strategies))
+            .transpose()
+            .map_err(Status::invalid_argument)?;
+        let game_options = if req.game_options.is_empty() {
+            None
+        } else {
+            let map: serde_json::Map<String, serde_json::Value> = req
+                .game_options
+                .iter()
+                .map(|(k, v)| {
+                    let val = serde_json::from_str(v)
+                        .unwrap_or(serde_json::Value::String(v.clone()));
+                    (k.clone(), val)
+                })
+                .collect();
+            Some(serde_json::Value::Object(map))
+        };
+        // Games are created on the blocking task, where a bad option would panic
+        if let Ok(plugin) = self.get_plugin(&req.game_id) {
+            let config = models::GameConfig {
+                options: game_options.clone().unwrap_or_else(|| serde_json::json!({})),
+                random_seed: None,
+            };
+            plugin
+                .validate_config(&config)
+                .map_err(|e| Status::invalid_argument(format!("invalid game options: {}", e)))?;
+        }
+        let permit = self.acquire_search_permit().await?;
 
         let (tx, rx) = mpsc::channel(32);
 
+        let token = self.stream_tasks.token();
         tokio::task::spawn_blocking(move || {
-            let game_options = if req.game_options.is_empty() {
-                None
-            } else {
-                let map: serde_json::Map<String, serde_json::Value> = req
-                    .game_options
-                    .iter()
-                    .map(|(k, v)| {
-                        let val = serde_json::from_str(v)
-                            .unwrap_or(serde_json::Value::String(v.clone()));
-                        (k.clone(), val)
-                    })
-                    .collect();
-                Some(serde_json::Value::Object(map))
-            };
+            // Hold the slot until the whole arena finishes.
+            let _permit = permit;
+            let _token = token;
 
             let tx_progress = tx.clone();
             let num_games = req.num_games as usize;
+            let on_progress = |completed: usize, total: usize| {
+                let _ = tx_progress.blocking_send(Ok(ArenaProgressUpdate {
+                    games_completed: completed as i32,
+                    total_games: total as i32,
+                    final_result: None,
+                }));
+            };
 
             let result = match req.game_id.as_str() {
                 "carcassonne" => {
-                    let plugin = CarcassonnePlugin;
-                    let mut strategies: HashMap<
-                        String,
-                        Box<dyn BotStrategy<CarcassonnePlugin>>,
-                    > = HashMap::new();
-                    for strat_config in &req.strategies {
-                        let strategy: Box<dyn BotStrategy<CarcassonnePlugin>> =
-                            match strat_config.strategy_type.as_str() {
-                                "random" => Box::new(RandomStrategy),
-                                "mcts" => {
-                                    let params = build_mcts_params(
-                                        strat_config.num_simulations,
-                                        strat_config.time_limit_ms,
-                                        0.0,
-                                        strat_config.num_determinizations,
-                                        strat_config.pw_c,
-                                        strat_config.pw_alpha,
-                                        strat_config.use_rave,
-                                        strat_config.rave_k,
-                                        strat_config.max_amaf_depth,
-                                        strat_config.rave_fpu,
-                                        strat_config.tile_aware_amaf,
-                                    );
-                                    let eval_fn =
-                                        resolve_eval_fn(&strat_config.eval_profile);
-                                    Box::new(MctsStrategy::<CarcassonnePlugin> {
-                                        params,
-                                        eval_fn,
-                                    })
-                                }
-                                _ => Box::new(RandomStrategy),
-                            };
-                        strategies.insert(strat_config.name.clone(), strategy);
-                    }
-                    let num_players = strategies.len();
-                    run_arena(
-                        &plugin,
-                        &strategies,
-                        num_games,
-                        req.base_seed as u64,
-                        num_players,
-                        game_options,
-                        req.alternate_seats,
-                        Some(&|completed, total| {
-                            let _ = tx_progress.blocking_send(Ok(ArenaProgressUpdate {
-                                games_completed: completed as i32,
-                                total_games: total as i32,
-                                final_result: None,
-                            }));
-                        }),
-                    )
+                    let evals = req.strategies.iter().zip(&custom_weights).map(|(config, weights)| {
+                        match weights {
+                            Some(w) => Some(make_carcassonne_eval(*w)),
+                            None => CarcassonnePlugin.make_eval(&config.eval_profile),
+                        }
+                    });
+                    let strategies = arena_strategies(&req.strategies, strategy_params, evals);
+                    play_arena(&CarcassonnePlugin, &strategies, &req, sprt.as_ref(), game_options, &on_progress)
+                }
+                "einstein_dojo" => {
+                    let evals = req.strategies.iter().map(|config| EinsteinDojoPlugin.make_eval(&config.eval_profile));
+                    let strategies = arena_strategies(&req.strategies, strategy_params, evals);
+                    play_arena(&EinsteinDojoPlugin, &strategies, &req, sprt.as_ref(), game_options, &on_progress)
                 }
                 _ => {
                     let _ = tx.blocking_send(Err(Status::unimplemented(format!(
@@ -762,6 +1392,8 @@ impl GameEngineService for GameEngineServer {
                         win_rate: result.win_rate(name),
                         ci_95_lo: ci_lo,
                         ci_95_hi: ci_hi,
+                        seat_win_rates: result.seat_win_rates(name),
+                        avg_think_ms: result.avg_think_ms(name),
                     },
                 );
             }
@@ -775,12 +1407,20 @@ impl GameEngineService for GameEngineServer {
                     .map(|(k, v)| (k.clone(), *v as i32))
                     .collect(),
                 draws: result.draws as i32,
+                adjudicated: result.adjudicated as i32,
                 score_stats,
                 total_duration_s,
+                sprt_verdict: result.sprt_verdict.map(|v| v.as_str().to_string()).unwrap_or_default(),
+                sprt_llr: sprt.as_ref().map(|s| s.result_llr(&result)).unwrap_or(0.0),
+                elo_ratings: req
+                    .strategies
+                    .first()
+                    .map(|anchor| result.elo_ratings(&anchor.name))
+                    .unwrap_or_default(),
             };
 
             let _ = tx.blocking_send(Ok(ArenaProgressUpdate {
-                games_completed: num_games as i32,
+                games_completed: result.num_games as i32,
                 total_games: num_games as i32,
                 final_result: Some(final_result),
             }));
@@ -794,8 +1434,12 @@ impl GameEngineService for GameEngineServer {
         &self,
         _request: Request<ListBotProfilesRequest>,
     ) -> Result<Response<ListBotProfilesResponse>, Status> {
+        let loaded = self.profiles();
+        let mut names: Vec<_> = loaded.profiles.keys().collect();
+        names.sort();
         let mut profiles = Vec::new();
-        for (name, profile) in &self.profiles.profiles {
+        for name in names {
+            let profile = &loaded.profiles[name];
             let params = profile.to_mcts_params();
             profiles.push(BotProfileInfo {
                 name: name.clone(),
@@ -806,11 +1450,12 @@ impl GameEngineService for GameEngineServer {
                 num_determinizations: params.num_determinizations as i32,
                 eval_profile: profile.effective_eval_profile().to_string(),
                 use_rave: params.use_rave,
+                mcts_params_json: serde_json::to_vec(&params).unwrap_or_default(),
             });
         }
 
         let mut production_mapping = HashMap::new();
-        let prod = &self.profiles.production;
+        let prod = &loaded.production;
         if let Some(v) = &prod.easy { production_mapping.insert("easy".to_string(), v.clone()); }
         if let Some(v) = &prod.medium { production_mapping.insert("medium".to_string(), v.clone()); }
         if let Some(v) = &prod.hard { production_mapping.insert("hard".to_string(), v.clone()); }
@@ -821,4 +1466,1433 @@ impl GameEngineService for GameEngineServer {
             production_mapping,
         }))
     }
+
+    // --- ListEvalProfiles ---
+    async fn list_eval_profiles(
+        &self,
+        _request: Request<ListEvalProfilesRequest>,
+    ) -> Result<Response<ListEvalProfilesResponse>, Status> {
+        let games = self
+            .registry
+            .list_game_ids()
+            .into_iter()
+            .filter_map(|game_id| {
+                let plugin = self.registry.get(&game_id)?;
+                let names = plugin.eval_profiles().into_iter().map(String::from).collect();
+                Some((game_id, EvalProfiles { names }))
+            })
+            .collect();
+
+        Ok(Response::new(ListEvalProfilesResponse { games }))
+    }
+
+    // --- GetFeatureOutlook ---
+    async fn get_feature_outlook(
+        &self,
+        request: Request<GetFeatureOutlookRequest>,
+    ) -> Result<Response<GetFeatureOutlookResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+
+        let outlook = plugin.feature_outlook(&game_data, &req.player_id).ok_or_else(|| {
+            Status::unimplemented(format!("Feature outlook not available for game: {}", req.game_id))
+        })?;
+        let outlook_json = serde_json::to_vec(&outlook).unwrap_or_default();
+
+        Ok(Response::new(GetFeatureOutlookResponse { outlook_json }))
+    }
+
+    // --- DescribeMeeple ---
+    async fn describe_meeple(
+        &self,
+        request: Request<DescribeMeepleRequest>,
+    ) -> Result<Response<DescribeMeepleResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+
+        let description = plugin.describe_meeple(&game_data, &req.position, &req.spot).ok_or_else(|| {
+            Status::not_found(format!("No meeple spot '{}' at {}", req.spot, req.position))
+        })?;
+        let description_json = serde_json::to_vec(&description).unwrap_or_default();
+
+        Ok(Response::new(DescribeMeepleResponse { description_json }))
+    }
+
+    // --- LoadGame ---
+    async fn load_game(
+        &self,
+        request: Request<LoadGameRequest>,
+    ) -> Result<Response<LoadGameResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = game_data_from_bytes(plugin, &req.game_data_json, req.game_data_format())?;
+        let phase = req
+            .phase
+            .as_ref()
+            .map(proto_to_phase)
+            .ok_or_else(|| Status::invalid_argument("phase is required"))?;
+
+        let game_data = plugin
+            .load_state(&game_data, &phase)
+            .map_err(|e| Status::invalid_argument(format!("cannot load game state: {}", e)))?;
+        Ok(Response::new(LoadGameResponse {
+            game_data_json: game_data_to_bytes(plugin, &game_data, req.game_data_format())?,
+            phase: Some(phase_to_proto(&phase)),
+        }))
+    }
+
+    // --- MctsPolicy ---
+    async fn mcts_policy(
+        &self,
+        request: Request<MctsPolicyRequest>,
+    ) -> Result<Response<MctsPolicyResponse>, Status> {
+        let req = request.into_inner();
+        let search_req = req
+            .search
+            .as_ref()
+            .ok_or_else(|| Status::invalid_argument("search is required"))?;
+        let temperature = match req.temperature {
+            None => 1.0,
+            Some(t) if t >= 0.0 && t.is_finite() => t,
+            Some(t) => {
+                return Err(Status::invalid_argument(format!(
+                    "temperature must be >= 0, got {}",
+                    t
+                )))
+            }
+        };
+        let search = self.prepare_search(search_req)?;
+
+        let _permit = self.acquire_search_permit().await?;
+        let t0 = Instant::now();
+        let (action, policy) = search.run_policy(&search_req.player_id, temperature).map_err(Status::internal)?;
+        let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
+
+        Ok(Response::new(MctsPolicyResponse {
+            action_json: serde_json::to_vec(&action).unwrap_or_default(),
+            policy: policy
+                .into_iter()
+                .map(|(action, probability)| PolicyEntry {
+                    action_json: serde_json::to_vec(&action).unwrap_or_default(),
+                    probability,
+                })
+                .collect(),
+            elapsed_ms,
+        }))
+    }
+
+    async fn mcts_search_debug(
+        &self,
+        request: Request<MctsSearchRequest>,
+    ) -> Result<Response<MctsSearchDebugResponse>, Status> {
+        let req = request.into_inner();
+        let search = self.prepare_search(&req)?;
+
+        let _permit = self.acquire_search_permit().await?;
+        let t0 = Instant::now();
+        let (action, iterations, stats) = search.run_with_stats(&req.player_id).map_err(Status::internal)?;
+        let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
+
+        let stats_json = serde_json::json!({
+            "total_nodes": stats.iter().map(|s| s.total_nodes).sum::<usize>(),
+            "max_depth": stats.iter().map(|s| s.max_depth).max().unwrap_or(0),
+            "leaf_evals": stats.iter().map(|s| s.leaf_evals).sum::<usize>(),
+            "determinizations": stats,
+        });
+        Ok(Response::new(MctsSearchDebugResponse {
+            action_json: serde_json::to_vec(&action).unwrap_or_default(),
+            iterations_run: iterations as i32,
+            elapsed_ms,
+            stats_json: serde_json::to_vec(&stats_json).unwrap_or_default(),
+        }))
+    }
+
+    // --- ReloadProfiles ---
+    async fn reload_profiles(
+        &self,
+        _request: Request<ReloadProfilesRequest>,
+    ) -> Result<Response<ReloadProfilesResponse>, Status> {
+        let path = self
+            .profiles_path
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("server was not started with --profiles"))?;
+        let loaded = load_profiles(path).map_err(Status::failed_precondition)?;
+        let mut profile_names: Vec<String> = loaded.profiles.keys().cloned().collect();
+        profile_names.sort();
+        *self.profiles.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(loaded);
+        tracing::info!(path = %path.display(), count = profile_names.len(), "reloaded bot profiles");
+        Ok(Response::new(ReloadProfilesResponse { profile_names }))
+    }
+
+    // --- GetActionSchema ---
+    async fn get_action_schema(
+        &self,
+        request: Request<GetActionSchemaRequest>,
+    ) -> Result<Response<GetActionSchemaResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        Ok(Response::new(GetActionSchemaResponse {
+            schema_json: serde_json::to_vec(&plugin.action_schema()).unwrap_or_default(),
+        }))
+    }
+
+    // --- ExportNotation ---
+    async fn export_notation(
+        &self,
+        request: Request<ExportNotationRequest>,
+    ) -> Result<Response<ExportNotationResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = decode_game_data(plugin, &req.game_data_json, req.game_data_format())?;
+        let notation = plugin
+            .export_notation(&game_data)
+            .ok_or_else(|| Status::unimplemented(format!("{} has no notation", req.game_id)))?;
+        Ok(Response::new(ExportNotationResponse { notation }))
+    }
+
+    // --- ImportNotation ---
+    async fn import_notation(
+        &self,
+        request: Request<ImportNotationRequest>,
+    ) -> Result<Response<ImportNotationResponse>, Status> {
+        let req = request.into_inner();
+        let plugin = self.get_plugin(&req.game_id)?;
+        let game_data = plugin
+            .import_notation(&req.notation)
+            .ok_or_else(|| Status::unimplemented(format!("{} has no notation", req.game_id)))?
+            .map_err(|e| Status::invalid_argument(format!("invalid notation: {}", e)))?;
+        Ok(Response::new(ImportNotationResponse {
+            game_data_json: game_data_to_bytes(plugin, &game_data, req.game_data_format())?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::plugin::JsonAdapter;
+    use crate::games::carcassonne::evaluator::AGGRESSIVE_WEIGHTS;
+    use crate::games::einstein_dojo::plugin::EinsteinDojoPlugin;
+
+    /// Encodes and decodes the Carcassonne game_data the tests pass around.
+    const CARCASSONNE: JsonAdapter<CarcassonnePlugin> = JsonAdapter(CarcassonnePlugin);
+
+    #[tokio::test]
+    async fn test_search_limit_reject_policy() {
+        let server = make_server().with_search_limit(1, SearchLimitPolicy::Reject);
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let request = || {
+            Request::new(MctsSearchRequest {
+                game_data_json: game_data_json.clone(),
+                phase: Some(phase.clone()),
+                player_id: "p0".into(),
+                players: players.clone(),
+                game_id: "carcassonne".into(),
+                num_simulations: 10,
+                ..Default::default()
+            })
+        };
+
+        // Occupy the only slot, as a long-running search would.
+        let held = server.acquire_search_permit().await.unwrap();
+        assert!(held.is_some());
+        let err = server.mcts_search(request()).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+        let err = server
+            .run_arena(Request::new(RunArenaRequest {
+                game_id: "carcassonne".into(),
+                num_games: 1,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+        // Light RPCs bypass the limiter.
+        let valid = server
+            .get_valid_actions(Request::new(GetValidActionsRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: game_data_json.clone(),
+                phase: Some(phase.clone()),
+                player_id: "p0".into(),
+                include_details: false,
+                ..Default::default()
+            }))
+            .await;
+        assert!(valid.is_ok());
+
+        drop(held);
+        assert!(server.mcts_search(request()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_arena_strategy_eval_weights() {
+        let mcts = |name: &str, eval_weights: HashMap<String, f64>| ArenaStrategyConfig {
+            name: name.into(),
+            strategy_type: "mcts".into(),
+            num_simulations: 5,
+            num_determinizations: 1,
+            eval_profile: "aggressive".into(),
+            eval_weights,
+            ..Default::default()
+        };
+        let tuned = mcts("tuned", HashMap::from([("field_scale".to_string(), 2.0)]));
+        let w = strategy_eval_weights(&tuned).unwrap().unwrap();
+        assert_eq!(w.field_scale, 2.0);
+        assert_eq!(w.score_scale, AGGRESSIVE_WEIGHTS.score_scale);
+        assert!(strategy_eval_weights(&mcts("plain", HashMap::new())).unwrap().is_none());
+
+        let bad = mcts("bad", HashMap::from([("bogus".to_string(), 1.0)]));
+        let err = make_server()
+            .run_arena(Request::new(RunArenaRequest {
+                game_id: "carcassonne".into(),
+                num_games: 1,
+                strategies: vec![tuned, bad],
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("bad"));
+    }
+
+    #[tokio::test]
+    async fn test_run_arena_einstein_dojo() {
+        let strategy = |name: &str, strategy_type: &str| ArenaStrategyConfig {
+            name: name.into(),
+            strategy_type: strategy_type.into(),
+            num_simulations: 10,
+            ..Default::default()
+        };
+        let mut rx = make_server()
+            .run_arena(Request::new(RunArenaRequest {
+                game_id: "einstein_dojo".into(),
+                num_games: 4,
+                base_seed: 3,
+                alternate_seats: true,
+                strategies: vec![strategy("mcts", "mcts"), strategy("random", "random")],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .into_inner();
+        let mut updates = Vec::new();
+        while let Some(update) = rx.recv().await {
+            updates.push(update.unwrap());
+        }
+
+        let (last, progress) = updates.split_last().unwrap();
+        let completed: Vec<i32> = progress.iter().map(|u| u.games_completed).collect();
+        assert_eq!(completed, vec![1, 2, 3, 4]);
+        assert!(progress.iter().all(|u| u.total_games == 4 && u.final_result.is_none()));
+        let result = last.final_result.as_ref().unwrap();
+        assert_eq!(result.num_games, 4);
+        assert_eq!(result.wins.values().sum::<i32>() + result.draws, 4);
+        assert_eq!(result.elo_ratings.len(), 2);
+        assert_eq!(result.elo_ratings["mcts"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_run_arena_sprt_config_rejected() {
+        let random = |name: &str| ArenaStrategyConfig {
+            name: name.into(),
+            strategy_type: "random".into(),
+            ..Default::default()
+        };
+        let sprt = SprtConfig { elo0: 0.0, elo1: 50.0, alpha: 0.05, beta: 0.05, ..Default::default() };
+        let cases = [
+            (vec![random("a"), random("b"), random("c")], sprt.clone(), "exactly 2"),
+            (vec![random("a"), random("b")], SprtConfig { candidate: "z".into(), ..sprt.clone() }, "candidate"),
+            (vec![random("a"), random("b")], SprtConfig { alpha: 0.0, ..sprt }, "alpha"),
+        ];
+        for (strategies, sprt, expected) in cases {
+            let err = make_server()
+                .run_arena(Request::new(RunArenaRequest {
+                    game_id: "carcassonne".into(),
+                    num_games: 2,
+                    strategies,
+                    sprt: Some(sprt),
+                    ..Default::default()
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+            assert!(err.message().contains(expected), "{}", err.message());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_arena_game_options_rejected() {
+        let random = |name: &str| ArenaStrategyConfig {
+            name: name.into(),
+            strategy_type: "random".into(),
+            ..Default::default()
+        };
+        let cases = [
+            ("meeples_per_player", "0", "meeples_per_player"),
+            ("tile_sequence", r#"["D", "Q9"]"#, "unknown tile type"),
+            ("tile_sequence", "D", "must be an array"),
+        ];
+        for (option, value, expected) in cases {
+            let err = make_server()
+                .run_arena(Request::new(RunArenaRequest {
+                    game_id: "carcassonne".into(),
+                    num_games: 2,
+                    strategies: vec![random("a"), random("b")],
+                    game_options: HashMap::from([(option.to_string(), value.to_string())]),
+                    ..Default::default()
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+            assert!(err.message().contains(expected), "{}", err.message());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_mcts_search_matches_unary() {
+        use std::io::Write;
+
+        let mut profiles = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            profiles,
+            "[profiles.seeded]\nnum_simulations = 40\nnum_determinizations = 3\n\
+             seed = 7\neval_profile = \"default\""
+        )
+        .unwrap();
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+        let server = GameEngineServer::with_profiles(registry, profiles.path()).unwrap();
+
+        // Draw a tile so the search starts from a real placement decision.
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let draw = models::Action {
+            action_type: "draw_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = server.get_plugin("carcassonne").unwrap().apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &draw,
+            &proto_to_players(&players),
+        ).unwrap();
+        let request = || {
+            Request::new(MctsSearchRequest {
+                game_data_json: game_data_to_bytes(&CARCASSONNE, &result.game_data, GameDataFormat::Json).unwrap(),
+                phase: Some(phase_to_proto(&result.next_phase)),
+                player_id: "p0".into(),
+                players: players.clone(),
+                game_id: "carcassonne".into(),
+                bot_profile: "seeded".into(),
+                ..Default::default()
+            })
+        };
+
+        let mut rx =
+            server.stream_mcts_search(request()).await.unwrap().into_inner().into_inner();
+        let mut updates = Vec::new();
+        while let Some(update) = rx.recv().await {
+            updates.push(update.unwrap());
+        }
+        let (last, progress) = updates.split_last().unwrap();
+        assert!(last.is_final);
+        assert!(!progress.is_empty());
+        assert!(progress.iter().all(|u| !u.is_final && u.num_determinizations == 3));
+        assert_eq!(progress.last().unwrap().determinizations_completed, 3);
+        assert!(progress
+            .windows(2)
+            .all(|w| w[0].determinizations_completed < w[1].determinizations_completed));
+
+        let unary = server.mcts_search(request()).await.unwrap().into_inner();
+        assert_eq!(last.action_json, unary.action_json);
+        assert_eq!(last.iterations_run, unary.iterations_run);
+    }
+
+    #[tokio::test]
+    async fn test_list_bot_profiles() {
+        use std::io::Write;
+
+        let mut profiles = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            profiles,
+            "[profiles.quick]\nnum_simulations = 40\neval_profile = \"\"\n\
+             [profiles.strong]\nnum_simulations = 900\nuse_rave = true\n\
+             [production]\nhard = \"strong\""
+        )
+        .unwrap();
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+        let server = GameEngineServer::with_profiles(registry, profiles.path()).unwrap();
+
+        let resp = server
+            .list_bot_profiles(Request::new(ListBotProfilesRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        let names: Vec<_> = resp.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["quick", "strong"]);
+        assert_eq!(resp.production_mapping["hard"], "strong");
+        let strong = &resp.profiles[1];
+        assert_eq!((strong.num_simulations, strong.use_rave, strong.eval_profile.as_str()), (900, true, "default"));
+        let params: serde_json::Value = serde_json::from_slice(&strong.mcts_params_json).unwrap();
+        assert_eq!(params["num_simulations"], 900);
+        assert_eq!(params["rave_k"], MctsParams::default().rave_k);
+
+        writeln!(profiles, "[profiles.broken]\nnum_determinizations = 0").unwrap();
+        let err = GameEngineServer::with_profiles(GameRegistry::new(), profiles.path()).err().unwrap();
+        assert!(err.contains("profiles.broken: num_determinizations must be at least 1"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_reload_profiles() {
+        let err = make_server().reload_profiles(Request::new(ReloadProfilesRequest {})).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+
+        let profiles = tempfile::NamedTempFile::new().unwrap();
+        let write_profile = |num_simulations: usize| {
+            std::fs::write(
+                profiles.path(),
+                format!("[profiles.tuned]\nnum_simulations = {num_simulations}\nnum_determinizations = 1\n"),
+            )
+            .unwrap();
+        };
+        write_profile(20);
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+        let server = GameEngineServer::with_profiles(registry, profiles.path()).unwrap();
+
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let draw = models::Action {
+            action_type: "draw_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = server.get_plugin("carcassonne").unwrap().apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &draw,
+            &proto_to_players(&players),
+        ).unwrap();
+        let iterations = || async {
+            let request = Request::new(MctsSearchRequest {
+                game_data_json: game_data_to_bytes(&CARCASSONNE, &result.game_data, GameDataFormat::Json).unwrap(),
+                phase: Some(phase_to_proto(&result.next_phase)),
+                player_id: "p0".into(),
+                players: players.clone(),
+                game_id: "carcassonne".into(),
+                bot_profile: "tuned".into(),
+                ..Default::default()
+            });
+            server.mcts_search(request).await.unwrap().into_inner().iterations_run
+        };
+        assert_eq!(iterations().await, 20);
+
+        write_profile(35);
+        let reload = || server.reload_profiles(Request::new(ReloadProfilesRequest {}));
+        assert_eq!(reload().await.unwrap().into_inner().profile_names, ["tuned"]);
+        assert_eq!(iterations().await, 35);
+
+        // A bad file is rejected and the loaded profiles stay in place
+        std::fs::write(profiles.path(), "[profiles.tuned]\nnum_determinizations = 0\n").unwrap();
+        let err = reload().await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+        assert!(err.message().contains("num_determinizations"), "{}", err.message());
+        assert_eq!(iterations().await, 35);
+    }
+
+    fn make_server() -> GameEngineServer {
+        let mut registry = GameRegistry::new();
+        registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+        registry.register(Box::new(JsonAdapter(EinsteinDojoPlugin)));
+        GameEngineServer::new(registry)
+    }
+
+    fn proto_player(id: &str, seat: i32) -> Player {
+        Player {
+            player_id: id.into(),
+            display_name: id.into(),
+            seat_index: seat,
+            is_bot: false,
+            bot_id: String::new(),
+        }
+    }
+
+    fn initial_state(server: &GameEngineServer, players: &[Player]) -> (Vec<u8>, Phase) {
+        let plugin = server.get_plugin("carcassonne").unwrap();
+        let config = models::GameConfig {
+            options: serde_json::json!({"tile_count": 10}),
+            random_seed: Some(42),
+        };
+        let (game_data, phase, _) =
+            plugin.create_initial_state(&proto_to_players(players), &config);
+        (game_data_to_bytes(&CARCASSONNE, &game_data, GameDataFormat::Json).unwrap(), phase_to_proto(&phase))
+    }
+
+    #[test]
+    fn test_validate_seat_order() {
+        let ordered = proto_to_players(&[proto_player("p0", 0), proto_player("p1", 1)]);
+        assert!(validate_seat_order(&ordered).is_ok());
+
+        let swapped = proto_to_players(&[proto_player("p1", 1), proto_player("p0", 0)]);
+        let err = validate_seat_order(&swapped).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("players[0]"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_game_data_rejected() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (_, phase) = initial_state(&server, &players);
+        let garbage = serde_json::to_vec(&serde_json::json!({"board": 42})).unwrap();
+
+        let err = server
+            .get_valid_actions(Request::new(GetValidActionsRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: garbage.clone(),
+                phase: Some(phase.clone()),
+                player_id: "p0".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("invalid game_data"), "{}", err.message());
+
+        let err = server
+            .validate_and_apply(Request::new(ValidateAndApplyRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: garbage.clone(),
+                phase: Some(phase.clone()),
+                action: Some(Action {
+                    action_type: "draw_tile".into(),
+                    player_id: "p0".into(),
+                    payload_json: Vec::new(),
+                }),
+                players: players.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let err = server
+            .mcts_search(Request::new(MctsSearchRequest {
+                game_data_json: garbage,
+                phase: Some(phase),
+                player_id: "p0".into(),
+                players,
+                game_id: "carcassonne".into(),
+                num_simulations: 10,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_unappliable_action_fails_precondition() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+
+        // The game opens on an auto draw; a meeple can't be placed before any tile
+        let err = server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(Phase { name: "place_meeple".into(), ..phase }),
+                action: Some(Action {
+                    action_type: "place_meeple".into(),
+                    player_id: "p0".into(),
+                    payload_json: serde_json::to_vec(&serde_json::json!({"meeple_spot": "city_N"})).unwrap(),
+                }),
+                players,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_message_pack_game_data() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let msgpack = GameDataFormat::Msgpack as i32;
+
+        let created = server
+            .create_initial_state(Request::new(CreateInitialStateRequest {
+                game_id: "carcassonne".into(),
+                players: players.clone(),
+                config: None,
+                game_data_format: msgpack,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let game_data = game_data_from_bytes(&CARCASSONNE, &created.game_data_json, GameDataFormat::Msgpack).unwrap();
+        assert!(game_data["board"]["tiles"].get("0,0").is_some());
+        // The typed state's encoding, not the JSON document's
+        let state = CarcassonnePlugin.decode_state_binary(&created.game_data_json).unwrap();
+        assert_eq!(CarcassonnePlugin.encode_state(&state), game_data);
+        assert!(models::GameDataFormat::MessagePack.decode(&created.game_data_json).unwrap() != game_data);
+
+        let apply = |game_data_format| {
+            server.apply_action(Request::new(ApplyActionRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: created.game_data_json.clone(),
+                phase: created.phase.clone(),
+                action: Some(Action {
+                    action_type: "draw_tile".into(),
+                    player_id: "p0".into(),
+                    payload_json: Vec::new(),
+                }),
+                players: players.clone(),
+                game_data_format,
+            }))
+        };
+        // The response state comes back in the request's format
+        let result = apply(msgpack).await.unwrap().into_inner().result.unwrap();
+        let drawn = game_data_from_bytes(&CARCASSONNE, &result.game_data_json, GameDataFormat::Msgpack).unwrap();
+        assert!(drawn["current_tile"].is_string());
+
+        let err = apply(GameDataFormat::Json as i32).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_misordered_players_rejected() {
+        let server = make_server();
+        let ordered = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let misordered = vec![proto_player("p1", 1), proto_player("p0", 0)];
+        let (game_data_json, phase) = initial_state(&server, &ordered);
+
+        let err = server
+            .mcts_search(Request::new(MctsSearchRequest {
+                game_data_json: game_data_json.clone(),
+                phase: Some(phase.clone()),
+                player_id: "p0".into(),
+                players: misordered.clone(),
+                game_id: "carcassonne".into(),
+                num_simulations: 10,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let err = server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(phase),
+                action: Some(Action {
+                    action_type: "draw_tile".into(),
+                    player_id: "p0".into(),
+                    payload_json: Vec::new(),
+                }),
+                players: misordered,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_payload_rejected_before_apply() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let plugin = server.get_plugin("einstein_dojo").unwrap();
+        let config = models::GameConfig { options: serde_json::json!({}), random_seed: None };
+        let (game_data, phase, _) = plugin.create_initial_state(&proto_to_players(&players), &config);
+
+        // Would panic in apply_place_mark if it reached the plugin
+        let err = server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: "einstein_dojo".into(),
+                game_data_json: game_data_to_bytes(&CARCASSONNE, &game_data, GameDataFormat::Json).unwrap(),
+                phase: Some(phase_to_proto(&phase)),
+                action: Some(Action {
+                    action_type: "place_mark".into(),
+                    player_id: "p0".into(),
+                    payload_json: br#"{"hex": 7}"#.to_vec(),
+                }),
+                players,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("'hex' must be a string"), "{}", err.message());
+    }
+
+    #[tokio::test]
+    async fn test_feature_outlook_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, _) = initial_state(&server, &players);
+
+        // No meeples on the board yet
+        let resp = server
+            .get_feature_outlook(Request::new(GetFeatureOutlookRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                player_id: "p0".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let outlook: serde_json::Value = serde_json::from_slice(&resp.outlook_json).unwrap();
+        assert_eq!(outlook, serde_json::json!([]));
+
+        let plugin = server.get_plugin("einstein_dojo").unwrap();
+        let config = models::GameConfig { options: serde_json::json!({}), random_seed: None };
+        let (game_data, _, _) = plugin.create_initial_state(&proto_to_players(&players), &config);
+        let err = server
+            .get_feature_outlook(Request::new(GetFeatureOutlookRequest {
+                game_id: "einstein_dojo".into(),
+                game_data_json: game_data_to_bytes(&CARCASSONNE, &game_data, GameDataFormat::Json).unwrap(),
+                player_id: "p0".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn test_describe_meeple_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, _) = initial_state(&server, &players);
+        let describe = |position: &str, spot: &str| {
+            server.describe_meeple(Request::new(DescribeMeepleRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: game_data_json.clone(),
+                position: position.into(),
+                spot: spot.into(),
+                ..Default::default()
+            }))
+        };
+
+        // The starting tile's road is unclaimed
+        let resp = describe("0,0", "road_EW").await.unwrap().into_inner();
+        let description: serde_json::Value = serde_json::from_slice(&resp.description_json).unwrap();
+        assert_eq!(description["feature_type"], "road");
+        assert!(description["meeple"].is_null());
+        assert_eq!(description["leaders"], serde_json::json!([]));
+
+        let err = describe("5,5", "road_EW").await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_game_info_reports_supported_options() {
+        let server = make_server();
+        let info = server
+            .get_game_info(Request::new(GetGameInfoRequest { game_id: "carcassonne".into() }))
+            .await
+            .unwrap()
+            .into_inner();
+        let tile_count = info
+            .supported_options
+            .iter()
+            .find(|o| o.name == "tile_count")
+            .expect("tile_count should be reported");
+        assert_eq!(tile_count.r#type, "integer");
+        assert_eq!(tile_count.default_json, "null");
+
+        let games = server.list_games(Request::new(ListGamesRequest {})).await.unwrap().into_inner();
+        let carcassonne = games.games.iter().find(|g| g.game_id == "carcassonne").unwrap();
+        assert_eq!(carcassonne.supported_options, info.supported_options);
+        let einstein = games.games.iter().find(|g| g.game_id == "einstein_dojo").unwrap();
+        let names: Vec<_> = einstein.supported_options.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["track_score_history"]);
+    }
+
+    #[tokio::test]
+    async fn test_action_schema_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let schema = |game_id: &str| {
+            let req = GetActionSchemaRequest { game_id: game_id.into() };
+            let server = &server;
+            async move {
+                let resp = server.get_action_schema(Request::new(req)).await.unwrap().into_inner();
+                serde_json::from_slice::<serde_json::Value>(&resp.schema_json).unwrap()
+            }
+        };
+        let phase_validator = |schema: &serde_json::Value, phase: &str| {
+            let mut root = schema.clone();
+            root["$ref"] = serde_json::json!(format!("#/phases/{phase}"));
+            jsonschema::validator_for(&root).unwrap()
+        };
+        let valid_actions = |game_id: &str, game_data_json: Vec<u8>, phase: Phase, player_id: &str| {
+            let req = GetValidActionsRequest {
+                game_id: game_id.into(),
+                game_data_json,
+                phase: Some(phase),
+                player_id: player_id.into(),
+                ..Default::default()
+            };
+            let server = &server;
+            async move {
+                let resp = server.get_valid_actions(Request::new(req)).await.unwrap().into_inner();
+                resp.actions_json
+                    .iter()
+                    .map(|a| serde_json::from_slice::<serde_json::Value>(a).unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Carcassonne: draw, then check the tile placements and meeple choices
+        let carcassonne = schema("carcassonne").await;
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let drawn = server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(phase),
+                action: Some(Action { action_type: "draw_tile".into(), player_id: "p0".into(), payload_json: vec![] }),
+                players: players.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .result
+            .unwrap();
+        let place_tile = drawn.next_phase.clone().unwrap();
+        assert_eq!(place_tile.name, "place_tile");
+        let placements = valid_actions("carcassonne", drawn.game_data_json.clone(), place_tile.clone(), "p0").await;
+        let validator = phase_validator(&carcassonne, "place_tile");
+        assert!(!placements.is_empty());
+        for placement in &placements {
+            assert!(validator.is_valid(placement), "{placement}");
+        }
+        assert!(!validator.is_valid(&serde_json::json!({"x": 1, "y": 0})));
+        assert!(!validator.is_valid(&serde_json::json!({"x": 1, "y": 0, "rotation": -90})));
+
+        let placed = server
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: drawn.game_data_json,
+                phase: Some(place_tile),
+                action: Some(Action {
+                    action_type: "place_tile".into(),
+                    player_id: "p0".into(),
+                    payload_json: serde_json::to_vec(&placements[0]).unwrap(),
+                }),
+                players: players.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .result
+            .unwrap();
+        let place_meeple = placed.next_phase.unwrap();
+        let meeples = valid_actions("carcassonne", placed.game_data_json, place_meeple.clone(), "p0").await;
+        let validator = phase_validator(&carcassonne, &place_meeple.name);
+        for meeple in &meeples {
+            assert!(validator.is_valid(meeple), "{meeple}");
+        }
+        assert!(!validator.is_valid(&serde_json::json!({"meeple_spot": 3})));
+
+        // Ein Stein Dojo turn actions carry their action_type
+        let einstein = schema("einstein_dojo").await;
+        let created = server
+            .create_initial_state(Request::new(CreateInitialStateRequest {
+                game_id: "einstein_dojo".into(),
+                players: players.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let turn = created.phase.unwrap();
+        let validator = phase_validator(&einstein, &turn.name);
+        let actions = valid_actions("einstein_dojo", created.game_data_json, turn, "p0").await;
+        assert!(!actions.is_empty());
+        for action in &actions {
+            assert!(validator.is_valid(action), "{action}");
+        }
+        assert!(!validator.is_valid(&serde_json::json!({"action_type": "skip_resolve"})));
+        assert!(phase_validator(&einstein, "resolve_chain").is_valid(&serde_json::json!({"action_type": "skip_resolve"})));
+
+        let err = server
+            .get_action_schema(Request::new(GetActionSchemaRequest { game_id: "chess".into() }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_notation_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, _) = initial_state(&server, &players);
+
+        let notation = server
+            .export_notation(Request::new(ExportNotationRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: game_data_json.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .notation;
+        assert!(notation.starts_with("D:0,0:0 - "), "{notation}");
+
+        let imported = server
+            .import_notation(Request::new(ImportNotationRequest {
+                game_id: "carcassonne".into(),
+                notation: notation.clone(),
+                game_data_format: GameDataFormat::Msgpack.into(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let plugin = server.get_plugin("carcassonne").unwrap();
+        let original = game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap();
+        let roundtrip = game_data_from_bytes(&CARCASSONNE, &imported.game_data_json, GameDataFormat::Msgpack).unwrap();
+        for key in ["board", "scores", "meeple_supply", "tile_bag"] {
+            assert_eq!(roundtrip[key], original[key], "{key}");
+        }
+        assert_eq!(plugin.export_notation(&roundtrip), Some(notation.clone()));
+
+        let err = server
+            .import_notation(Request::new(ImportNotationRequest {
+                game_id: "carcassonne".into(),
+                notation: "not a position".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let err = server
+            .import_notation(Request::new(ImportNotationRequest {
+                game_id: "einstein_dojo".into(),
+                notation,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn test_load_game_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let load = |game_data_json: Vec<u8>| {
+            server.load_game(Request::new(LoadGameRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(phase.clone()),
+                ..Default::default()
+            }))
+        };
+
+        let loaded = load(game_data_json.clone()).await.unwrap().into_inner();
+        assert_eq!(loaded.game_data_json, game_data_json);
+        assert_eq!(loaded.phase, Some(phase.clone()));
+
+        // Fields left to their defaults come back filled in
+        let mut game_data: serde_json::Value = serde_json::from_slice(&game_data_json).unwrap();
+        let mut sparse = game_data.clone();
+        sparse.as_object_mut().unwrap().retain(|k, _| k != "rng_state" && k != "forfeited_players");
+        let loaded = load(serde_json::to_vec(&sparse).unwrap()).await.unwrap().into_inner();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&loaded.game_data_json).unwrap(), game_data);
+
+        game_data["meeple_supply"]["p0"] = serde_json::json!(9);
+        let err = load(game_data_to_bytes(&CARCASSONNE, &game_data, GameDataFormat::Json).unwrap()).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("INV3"), "{}", err.message());
+    }
+
+    #[tokio::test]
+    async fn test_eval_profiles_come_from_plugins() {
+        let server = make_server();
+        let resp = server
+            .list_eval_profiles(Request::new(ListEvalProfilesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.games["carcassonne"].names, CarcassonnePlugin.eval_profiles());
+        assert_eq!(resp.games["carcassonne"].names[0], "default");
+        assert_eq!(resp.games["einstein_dojo"].names, vec!["default"]);
+
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let err = server
+            .mcts_search(Request::new(MctsSearchRequest {
+                game_data_json,
+                phase: Some(phase),
+                player_id: "p0".into(),
+                players,
+                game_id: "carcassonne".into(),
+                num_simulations: 10,
+                eval_profile: "reckless".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("unknown eval_profile 'reckless'"), "{}", err.message());
+        assert!(err.message().contains("field_heavy"), "{}", err.message());
+
+        let err = server
+            .run_arena(Request::new(RunArenaRequest {
+                game_id: "carcassonne".into(),
+                num_games: 1,
+                strategies: vec![ArenaStrategyConfig {
+                    name: "a".into(),
+                    strategy_type: "mcts".into(),
+                    eval_profile: "reckless".into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().starts_with("strategy 'a': unknown eval_profile"), "{}", err.message());
+    }
+
+    #[tokio::test]
+    async fn test_mcts_search_debug_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let draw = models::Action {
+            action_type: "draw_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = server.get_plugin("carcassonne").unwrap().apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &draw,
+            &proto_to_players(&players),
+        ).unwrap();
+        let resp = server
+            .mcts_search_debug(Request::new(MctsSearchRequest {
+                game_data_json: game_data_to_bytes(&CARCASSONNE, &result.game_data, GameDataFormat::Json).unwrap(),
+                phase: Some(phase_to_proto(&result.next_phase)),
+                player_id: "p0".into(),
+                players: players.clone(),
+                game_id: "carcassonne".into(),
+                num_simulations: 40,
+                num_determinizations: 2,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let stats: serde_json::Value = serde_json::from_slice(&resp.stats_json).unwrap();
+        let dets = stats["determinizations"].as_array().unwrap();
+        assert_eq!(dets.len(), 2);
+        let per_det_nodes: u64 = dets.iter().map(|d| d["total_nodes"].as_u64().unwrap()).sum();
+        assert_eq!(stats["total_nodes"].as_u64(), Some(per_det_nodes));
+        let root_children = dets[0]["root_child_visits"].as_array().unwrap();
+        assert!(!root_children.is_empty());
+        assert!(root_children[0][0].is_string() && root_children[0][1].is_u64());
+        assert_eq!(resp.iterations_run, 40);
+        assert!(!resp.action_json.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_valid_actions_batch() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let draw = models::Action {
+            action_type: "draw_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = server.get_plugin("carcassonne").unwrap().apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &draw,
+            &proto_to_players(&players),
+        ).unwrap();
+        assert_eq!(result.next_phase.name, "place_tile");
+        let game_data_json = game_data_to_bytes(&CARCASSONNE, &result.game_data, GameDataFormat::Json).unwrap();
+        let phase = phase_to_proto(&result.next_phase);
+
+        let resp = server
+            .get_valid_actions_batch(Request::new(GetValidActionsBatchRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: game_data_json.clone(),
+                phase: Some(phase.clone()),
+                player_ids: vec!["p0".into(), "p1".into()],
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(resp.actions["p1"].actions_json.is_empty());
+        let single = server
+            .get_valid_actions(Request::new(GetValidActionsRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(phase),
+                player_id: "p0".into(),
+                include_details: false,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!single.actions_json.is_empty());
+        assert_eq!(resp.actions["p0"].actions_json, single.actions_json);
+    }
+
+    #[tokio::test]
+    async fn test_validate_and_apply() {
+        use validate_and_apply_response::Outcome;
+
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let request = |game_data_json: Vec<u8>, phase: Phase, action_type: &str, payload| {
+            Request::new(ValidateAndApplyRequest {
+                game_id: "carcassonne".into(),
+                game_data_json,
+                phase: Some(phase),
+                action: Some(Action {
+                    action_type: action_type.into(),
+                    player_id: "p0".into(),
+                    payload_json: serde_json::to_vec(&payload).unwrap(),
+                }),
+                players: players.clone(),
+                ..Default::default()
+            })
+        };
+
+        let resp = server
+            .validate_and_apply(request(game_data_json, phase, "draw_tile", serde_json::json!({})))
+            .await
+            .unwrap()
+            .into_inner();
+        let Some(Outcome::Result(drawn)) = resp.outcome else {
+            panic!("expected a transition, got {:?}", resp.outcome);
+        };
+        assert_eq!(drawn.next_phase.as_ref().unwrap().name, "place_tile");
+
+        let resp = server
+            .validate_and_apply(request(
+                drawn.game_data_json.clone(),
+                drawn.next_phase.clone().unwrap(),
+                "place_tile",
+                serde_json::json!({"x": 50, "y": 50, "rotation": 0}),
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+        let Some(Outcome::Error(err)) = resp.outcome else {
+            panic!("expected a validation error, got {:?}", resp.outcome);
+        };
+        assert!(err.contains("Cannot place tile"), "{err}");
+
+        // Nothing was applied: the drawn state still accepts a legal placement.
+        let placement = server
+            .get_plugin("carcassonne")
+            .unwrap()
+            .get_valid_actions(
+                &game_data_from_bytes(&CARCASSONNE, &drawn.game_data_json, GameDataFormat::Json).unwrap(),
+                &proto_to_phase(drawn.next_phase.as_ref().unwrap()),
+                "p0",
+            )
+            .into_iter()
+            .next()
+            .unwrap();
+        let resp = server
+            .validate_and_apply(request(
+                drawn.game_data_json,
+                drawn.next_phase.unwrap(),
+                "place_tile",
+                placement,
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+        let Some(Outcome::Result(placed)) = resp.outcome else {
+            panic!("expected a transition, got {:?}", resp.outcome);
+        };
+        assert_eq!(placed.next_phase.unwrap().name, "place_meeple");
+    }
+
+    #[tokio::test]
+    async fn test_apply_actions_batch_full_turn() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+
+        // Work out a legal placement for whatever tile p0 draws.
+        let plugin = server.get_plugin("carcassonne").unwrap();
+        let drawn = plugin.apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &models::Action {
+                action_type: "draw_tile".into(),
+                player_id: "p0".into(),
+                payload: serde_json::json!({}),
+            },
+            &proto_to_players(&players),
+        ).unwrap();
+        let placement = plugin
+            .get_valid_actions(&drawn.game_data, &drawn.next_phase, "p0")
+            .into_iter()
+            .next()
+            .unwrap();
+
+        let action = |action_type: &str, payload: serde_json::Value| Action {
+            action_type: action_type.into(),
+            player_id: "p0".into(),
+            payload_json: serde_json::to_vec(&payload).unwrap(),
+        };
+        let request = |actions: Vec<Action>| {
+            Request::new(ApplyActionsBatchRequest {
+                game_id: "carcassonne".into(),
+                game_data_json: game_data_json.clone(),
+                phase: Some(phase.clone()),
+                actions,
+                players: players.clone(),
+                ..Default::default()
+            })
+        };
+
+        let resp = server
+            .apply_actions_batch(request(vec![
+                action("draw_tile", serde_json::json!({})),
+                action("place_tile", placement),
+                action("place_meeple", serde_json::json!({"skip": true})),
+                action("score_check", serde_json::json!({})),
+            ]))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.failed_index, None);
+        let result = resp.result.unwrap();
+        let next_phase = result.next_phase.unwrap();
+        assert_eq!(next_phase.name, "draw_tile");
+        assert_eq!(next_phase.metadata["player_index"], "1");
+        let event_types: Vec<_> = result.events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(event_types[0], "tile_drawn");
+        assert!(event_types.contains(&"tile_placed"));
+        assert!(event_types.contains(&"meeple_skipped"));
+
+        let resp = server
+            .apply_actions_batch(request(vec![
+                action("draw_tile", serde_json::json!({})),
+                action("place_tile", serde_json::json!({"x": 50, "y": 50, "rotation": 0})),
+                action("place_meeple", serde_json::json!({"skip": true})),
+            ]))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.failed_index, Some(1));
+        assert!(resp.error.is_some());
+        assert_eq!(resp.result.unwrap().next_phase.unwrap().name, "place_tile");
+    }
+
+    #[tokio::test]
+    async fn test_mcts_search_einstein_dojo() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let plugin = server.get_plugin("einstein_dojo").unwrap();
+        let config = models::GameConfig { options: serde_json::json!({}), random_seed: Some(7) };
+        let (game_data, phase, _) = plugin.create_initial_state(&proto_to_players(&players), &config);
+        let search = MctsSearchRequest {
+            game_id: "einstein_dojo".into(),
+            game_data_json: game_data_to_bytes(&CARCASSONNE, &game_data, GameDataFormat::Json).unwrap(),
+            phase: Some(phase_to_proto(&phase)),
+            player_id: "p0".into(),
+            players: players.clone(),
+            num_simulations: 50,
+            num_determinizations: 4,
+            ..Default::default()
+        };
+        let resp = server.mcts_search(Request::new(search.clone())).await.unwrap().into_inner();
+
+        assert_eq!(resp.iterations_run, 50);
+        let action: serde_json::Value = serde_json::from_slice(&resp.action_json).unwrap();
+        assert_eq!(action["action_type"], "place_tile");
+        let valid = plugin.get_valid_actions(&game_data, &phase, "p0");
+        assert!(valid.contains(&action));
+
+        // No hidden information: the requested determinizations collapse to one tree.
+        let debug = server.mcts_search_debug(Request::new(search)).await.unwrap().into_inner();
+        let stats: serde_json::Value = serde_json::from_slice(&debug.stats_json).unwrap();
+        assert_eq!(stats["determinizations"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mcts_policy_rpc() {
+        let server = make_server();
+        let players = vec![proto_player("p0", 0), proto_player("p1", 1)];
+        let (game_data_json, phase) = initial_state(&server, &players);
+        let draw = models::Action {
+            action_type: "draw_tile".into(),
+            player_id: "p0".into(),
+            payload: serde_json::json!({}),
+        };
+        let result = server.get_plugin("carcassonne").unwrap().apply_action(
+            &game_data_from_bytes(&CARCASSONNE, &game_data_json, GameDataFormat::Json).unwrap(),
+            &proto_to_phase(&phase),
+            &draw,
+            &proto_to_players(&players),
+        ).unwrap();
+        let search = MctsSearchRequest {
+            game_data_json: game_data_to_bytes(&CARCASSONNE, &result.game_data, GameDataFormat::Json).unwrap(),
+            phase: Some(phase_to_proto(&result.next_phase)),
+            player_id: "p0".into(),
+            players: players.clone(),
+            game_id: "carcassonne".into(),
+            num_simulations: 60,
+            num_determinizations: 2,
+            ..Default::default()
+        };
+        let policy = |temperature: Option<f64>| {
+            server.mcts_policy(Request::new(MctsPolicyRequest {
+                search: Some(search.clone()),
+                temperature,
+            }))
+        };
+
+        let resp = policy(None).await.unwrap().into_inner();
+        assert!(!resp.policy.is_empty());
+        assert_eq!(resp.policy[0].action_json, resp.action_json);
+        let total: f64 = resp.policy.iter().map(|e| e.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // Temperature 0 is argmax: everything on the returned move
+        let resp = policy(Some(0.0)).await.unwrap().into_inner();
+        assert_eq!(resp.policy[0].action_json, resp.action_json);
+        assert_eq!(resp.policy[0].probability, 1.0);
+        assert!(resp.policy[1..].iter().all(|e| e.probability == 0.0));
+
+        let err = policy(Some(-1.0)).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
 }
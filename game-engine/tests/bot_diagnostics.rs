@@ -8,11 +8,12 @@
 
 use std::collections::HashMap;
 
-use meeple_game_engine::engine::arena::run_arena;
+use meeple_game_engine::engine::arena::{run_arena, DEFAULT_MAX_MOVES_PER_GAME};
 use meeple_game_engine::engine::bot_strategy::{BotStrategy, MctsStrategy, RandomStrategy};
 use meeple_game_engine::engine::mcts::{mcts_search, MctsParams};
 use meeple_game_engine::engine::models::*;
 use meeple_game_engine::engine::plugin::TypedGamePlugin;
+use meeple_game_engine::engine::rng::GameRng;
 use meeple_game_engine::engine::simulator::{apply_action_and_resolve, SimulationState};
 use meeple_game_engine::games::carcassonne::evaluator::{make_carcassonne_eval, DEFAULT_WEIGHTS};
 use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
@@ -26,7 +27,7 @@ fn mcts_vs_random() {
     let plugin = CarcassonnePlugin;
     let num_games = 20;
 
-    let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+    let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
     let mcts = MctsStrategy::<CarcassonnePlugin>::with_eval(
         MctsParams {
             num_simulations: 500,
@@ -51,6 +52,8 @@ fn mcts_vs_random() {
         2,
         None,
         true,
+        false,
+        DEFAULT_MAX_MOVES_PER_GAME,
         Some(&|done, total| {
             eprintln!("  game {}/{}", done, total);
         }),
@@ -156,12 +159,14 @@ fn mcts_wrong_player_order() {
                 options: serde_json::json!({}),
             };
             let (state, phase, _) = plugin.create_initial_state(&correct_players, &config);
+            let mut rng = GameRng::from_seed(seed);
             let mut sim = SimulationState {
                 state,
                 phase,
-                players: correct_players.clone(),
+                players: correct_players.clone().into(),
                 scores: correct_players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
                 game_over: None,
+                history: None,
             };
 
             resolve_auto(&plugin, &mut sim);
@@ -177,25 +182,25 @@ fn mcts_wrong_player_order() {
                 } else { break; };
 
                 let chosen = if acting_pid == mcts_pid {
-                    let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+                    let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
                     let eval_ref: Option<&(dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Sync)> =
                         Some(eval_fn.as_ref());
                     // Pass mcts_players (correct or wrong order) to MCTS
                     let (action, _) = mcts_search(
                         &sim.state, &sim.phase, &acting_pid, &plugin,
                         mcts_players, &params, eval_ref,
-                    );
+                    ).unwrap();
                     action
                 } else {
                     let valid = plugin.get_valid_actions(&sim.state, &sim.phase, &acting_pid);
                     if valid.is_empty() { break; }
                     use rand::seq::SliceRandom;
-                    valid.choose(&mut rand::thread_rng()).cloned().unwrap()
+                    valid.choose(&mut rng).cloned().unwrap()
                 };
 
                 let action_type = sim.phase.expected_actions[0].action_type.clone();
                 let action = Action { action_type, player_id: acting_pid, payload: chosen };
-                apply_action_and_resolve(&plugin, &mut sim, &action);
+                apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
             }
 
             let ms = sim.scores.get(mcts_pid).copied().unwrap_or(0.0);
@@ -266,12 +271,14 @@ fn mcts_vs_random_json_roundtrip() {
             options: serde_json::json!({}),
         };
         let (state, phase, _) = plugin.create_initial_state(&players, &config);
+        let mut rng = GameRng::from_seed(seed);
         let mut sim = SimulationState {
             state,
             phase,
-            players: players.clone(),
+            players: players.clone().into(),
             scores: players.iter().map(|p| (p.player_id.clone(), 0.0)).collect(),
             game_over: None,
+            history: None,
         };
 
         // Auto-resolve initial phases
@@ -298,7 +305,7 @@ fn mcts_vs_random_json_roundtrip() {
                 let decoded_state: CarcassonneState =
                     serde_json::from_value(json_state).expect("JSON round-trip failed");
 
-                let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+                let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
                 let eval_ref: Option<
                     &(dyn Fn(&CarcassonneState, &Phase, &str, &[Player]) -> f64 + Sync),
                 > = Some(eval_fn.as_ref());
@@ -311,7 +318,7 @@ fn mcts_vs_random_json_roundtrip() {
                     &players,
                     &params,
                     eval_ref,
-                );
+                ).unwrap();
                 action
             } else {
                 // Random opponent
@@ -320,7 +327,7 @@ fn mcts_vs_random_json_roundtrip() {
                     break;
                 }
                 use rand::seq::SliceRandom;
-                valid.choose(&mut rand::thread_rng()).cloned().unwrap()
+                valid.choose(&mut rng).cloned().unwrap()
             };
 
             let action_type = sim.phase.expected_actions[0].action_type.clone();
@@ -329,7 +336,7 @@ fn mcts_vs_random_json_roundtrip() {
                 player_id: acting_pid,
                 payload: chosen,
             };
-            apply_action_and_resolve(&plugin, &mut sim, &action);
+            apply_action_and_resolve(&plugin, &mut sim, &action).unwrap();
         }
 
         let ms = sim.scores.get(&mcts_pid).copied().unwrap_or(0.0);
@@ -388,6 +395,6 @@ fn resolve_auto(plugin: &CarcassonnePlugin, state: &mut SimulationState<Carcasso
             player_id: pid,
             payload: serde_json::json!({}),
         };
-        apply_action_and_resolve(plugin, state, &synthetic);
+        apply_action_and_resolve(plugin, state, &synthetic).unwrap();
     }
 }
@@ -67,7 +67,7 @@ fn resolve_auto(plugin: &CarcassonnePlugin, sim: &mut SimulationState<<Carcasson
             player_id: pid,
             payload: serde_json::json!({}),
         };
-        apply_action_and_resolve(plugin, sim, &synthetic);
+        apply_action_and_resolve(plugin, sim, &synthetic).unwrap();
     }
 }
 
@@ -123,9 +123,10 @@ fn test_replay_python_game_trace() {
     let mut sim = SimulationState {
         state: initial_state,
         phase: initial_phase,
-        players: players.clone(),
+        players: players.clone().into(),
         scores: scores_map,
         game_over: None,
+        history: None,
     };
 
     let turns = trace["turns"].as_array().expect("turns should be array");
@@ -233,7 +234,7 @@ fn test_replay_python_game_trace() {
             player_id: expected_pid.to_string(),
             payload: chosen_tile,
         };
-        apply_action_and_resolve(&plugin, &mut sim, &tile_action);
+        apply_action_and_resolve(&plugin, &mut sim, &tile_action).unwrap();
 
         if sim.game_over.is_some() {
             // Game ended during tile placement (shouldn't happen but handle it)
@@ -275,7 +276,7 @@ fn test_replay_python_game_trace() {
             player_id: expected_pid.to_string(),
             payload: chosen_meeple,
         };
-        apply_action_and_resolve(&plugin, &mut sim, &meeple_action);
+        apply_action_and_resolve(&plugin, &mut sim, &meeple_action).unwrap();
         // Auto-resolve: score_check -> draw_tile -> place_tile
 
         // --- Compare scores ---
@@ -301,7 +302,7 @@ fn test_replay_python_game_trace() {
             turn_data["eval_p0"].as_f64(),
             turn_data["eval_p1"].as_f64(),
         ) {
-            let eval_fn = make_carcassonne_eval(&DEFAULT_WEIGHTS);
+            let eval_fn = make_carcassonne_eval(DEFAULT_WEIGHTS);
             let rust_eval_p0 = eval_fn(&sim.state, &sim.phase, "p0", &players);
             let rust_eval_p1 = eval_fn(&sim.state, &sim.phase, "p1", &players);
 
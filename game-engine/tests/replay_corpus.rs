@@ -0,0 +1,47 @@
+//! Regression corpus: replay every recorded game log in `tests/fixtures/replay_corpus/`
+//! and check the engine still reproduces the recorded final scores.
+//!
+//! To add a case, drop a `GameLog` JSON file (e.g. from a bug report) into the
+//! corpus directory. Run with:
+//!     cargo test --test replay_corpus -- --nocapture
+
+use std::path::PathBuf;
+
+use meeple_game_engine::engine::plugin::JsonAdapter;
+use meeple_game_engine::engine::replay::{check_game_log, load_corpus};
+use meeple_game_engine::games::carcassonne::plugin::CarcassonnePlugin;
+use meeple_game_engine::games::einstein_dojo::plugin::EinsteinDojoPlugin;
+use meeple_game_engine::games::GameRegistry;
+
+fn make_registry() -> GameRegistry {
+    let mut registry = GameRegistry::new();
+    registry.register(Box::new(JsonAdapter(CarcassonnePlugin)));
+    registry.register(Box::new(JsonAdapter(EinsteinDojoPlugin)));
+    registry
+}
+
+#[test]
+fn replay_corpus_reproduces_recorded_scores() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay_corpus");
+    let corpus = load_corpus(&dir).expect("failed to load replay corpus");
+    assert!(!corpus.is_empty(), "replay corpus at {:?} is empty", dir);
+
+    let registry = make_registry();
+    let mut failures = Vec::new();
+    for (path, log) in &corpus {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let result = match registry.get(&log.game_id) {
+            Some(plugin) => check_game_log(plugin, log).map(|_| ()),
+            None => Err(format!("unknown game_id '{}'", log.game_id)),
+        };
+        match result {
+            Ok(()) => println!("  ok   {} ({} actions)", name, log.actions.len()),
+            Err(e) => {
+                println!("  FAIL {}: {}", name, e);
+                failures.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "replay corpus failures:\n{}", failures.join("\n"));
+}